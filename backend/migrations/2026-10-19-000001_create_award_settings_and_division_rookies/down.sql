@@ -0,0 +1,2 @@
+DROP TABLE division_rookies;
+DROP TABLE award_settings;
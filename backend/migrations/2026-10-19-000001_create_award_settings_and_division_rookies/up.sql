@@ -0,0 +1,18 @@
+CREATE TABLE award_settings (
+    did                         UUID          NOT NULL PRIMARY KEY REFERENCES divisions(did) ON DELETE CASCADE,
+    individual_scorers_cutoff   INTEGER       NOT NULL DEFAULT 10,
+    quiz_outs_cutoff            INTEGER       NOT NULL DEFAULT 10,
+    team_placements_cutoff      INTEGER       NOT NULL DEFAULT 3,
+    rookies_cutoff              INTEGER       NOT NULL DEFAULT 5,
+    include_perfect_games       BOOLEAN       NOT NULL DEFAULT TRUE,
+    questions_per_game          INTEGER       NOT NULL DEFAULT 20,      -- regulation questions; a game counts once all are quizzed
+    created_at                  TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    updated_at                  TIMESTAMPTZ   NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE division_rookies (
+    did          UUID          NOT NULL REFERENCES divisions(did) ON DELETE CASCADE,
+    quizzerid    UUID          NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at   TIMESTAMPTZ   NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (did, quizzerid)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE certificate_templates (
+    tid          UUID           NOT NULL PRIMARY KEY REFERENCES tournaments(tid) ON DELETE CASCADE,
+    name         VARCHAR(64)    NOT NULL,
+    svg          TEXT           NOT NULL,
+    created_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+DROP TABLE bracket_seats;
+DROP TABLE bracket_nodes;
+DROP TABLE brackets;
@@ -0,0 +1,34 @@
+CREATE TABLE brackets (
+    bracketid    UUID           PRIMARY KEY DEFAULT gen_random_uuid(),
+    did          UUID           NOT NULL REFERENCES divisions(did) ON DELETE CASCADE,
+    name         VARCHAR(64)    NOT NULL,
+    format       VARCHAR(32)    NOT NULL,    -- power_match, single_elimination, double_elimination or top_two
+    team_size    INTEGER        NOT NULL DEFAULT 2,
+    created_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW(),
+    UNIQUE (did, name)
+);
+
+-- one game of a bracket; the game row is created once every seat is known
+CREATE TABLE bracket_nodes (
+    nodeid       UUID           PRIMARY KEY DEFAULT gen_random_uuid(),
+    bracketid    UUID           NOT NULL REFERENCES brackets(bracketid) ON DELETE CASCADE,
+    side         VARCHAR(16)    NOT NULL,    -- winners, losers, final or power
+    round_num    INTEGER        NOT NULL,
+    position     INTEGER        NOT NULL,
+    gid          UUID           REFERENCES games(gid) ON DELETE SET NULL,
+    created_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ    NOT NULL DEFAULT NOW(),
+    UNIQUE (bracketid, side, round_num, position)
+);
+
+-- a seat is filled either by a seed from the standings or by a place in an earlier node
+CREATE TABLE bracket_seats (
+    nodeid       UUID           NOT NULL REFERENCES bracket_nodes(nodeid) ON DELETE CASCADE,
+    seat         INTEGER        NOT NULL,
+    seed         INTEGER,
+    teamid       UUID           REFERENCES teams(teamid) ON DELETE SET NULL,
+    from_nodeid  UUID           REFERENCES bracket_nodes(nodeid) ON DELETE CASCADE,
+    from_place   INTEGER,
+    PRIMARY KEY (nodeid, seat)
+);
@@ -0,0 +1,19 @@
+DROP TABLE schedules;
+
+CREATE TABLE schedules (
+    sid BIGSERIAL UNIQUE PRIMARY KEY,
+    tid BIGINT NOT NULL,
+    roundtime timestamptz NOT NULL,
+    tournament varchar(32) NOT NULL,
+    division varchar(32) NOT NULL,
+    room varchar(32) NOT NULL,
+    round varchar(32) NOT NULL,
+    team1 varchar(32),
+    team2 varchar(32),
+    team3 varchar(32),
+    quizmaster varchar(32),
+    contentjudge varchar(32),
+    scorekeeper varchar(32),
+    stats varchar(1024),
+    UNIQUE (tid, roundtime, room)
+);
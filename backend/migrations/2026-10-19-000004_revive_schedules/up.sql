@@ -0,0 +1,26 @@
+-- The schedules table still used BIGINT ids from before tournaments moved to UUIDs. It was never
+-- written by the backend, so it's recreated rather than migrated. Imported rows are kept here
+-- until they're confirmed; gid then points at the game created from the row.
+
+DROP TABLE IF EXISTS schedules;
+
+CREATE TABLE schedules (
+    sid UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tid UUID NOT NULL REFERENCES tournaments(tid) ON DELETE CASCADE,
+    roundtime TIMESTAMPTZ,                      -- when the quiz should start; defaults to the round's start time
+    tournament VARCHAR(32) NOT NULL DEFAULT '', -- the names as they appear in the imported file
+    division VARCHAR(32) NOT NULL,
+    room VARCHAR(32) NOT NULL,
+    round VARCHAR(64) NOT NULL,
+    team1 VARCHAR(128),
+    team2 VARCHAR(128),
+    team3 VARCHAR(128),
+    quizmaster VARCHAR(128),
+    contentjudge VARCHAR(128),
+    scorekeeper VARCHAR(128),
+    stats VARCHAR(1024),
+    gid UUID REFERENCES games(gid) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (tid, division, round, room)
+);
@@ -0,0 +1,12 @@
+-- A calendar feed is a secret URL that serves one team's, user's or room's games as iCalendar,
+-- so calendar apps can subscribe without logging in.
+
+CREATE TABLE calendar_feeds (
+    feedid UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token TEXT NOT NULL UNIQUE,                 -- the secret part of the feed's URL
+    scope VARCHAR(16) NOT NULL,                 -- team, user or room
+    subject_id UUID NOT NULL,                   -- the team, user or room
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (created_by, scope, subject_id)
+);
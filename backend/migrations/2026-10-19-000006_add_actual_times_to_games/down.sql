@@ -0,0 +1,4 @@
+ALTER TABLE games
+    DROP COLUMN actual_start_time,
+    DROP COLUMN actual_end_time,
+    DROP COLUMN start_offset_minutes;
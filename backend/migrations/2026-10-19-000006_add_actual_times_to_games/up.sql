@@ -0,0 +1,7 @@
+-- When a game actually ran, taken from the first and last events the room sent to /scoreevent,
+-- and how far the game has been moved from its round's scheduled start when only its room slips.
+
+ALTER TABLE games
+    ADD COLUMN actual_start_time TIMESTAMPTZ,
+    ADD COLUMN actual_end_time TIMESTAMPTZ,
+    ADD COLUMN start_offset_minutes INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+ALTER TABLE award_settings
+    DROP COLUMN bye_score,
+    DROP COLUMN bye_counts_as_win,
+    DROP COLUMN forfeit_loss_score,
+    DROP COLUMN forfeit_win_score;
+
+DROP TABLE game_outcomes;
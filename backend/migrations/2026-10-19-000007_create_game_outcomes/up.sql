@@ -0,0 +1,19 @@
+-- A game outcome is decided by an admin instead of by the game's event stream: a forfeit, a bye
+-- or a no contest. Award settings say how forfeits and byes count in the standings.
+
+CREATE TABLE game_outcomes (
+    gid UUID PRIMARY KEY REFERENCES games(gid) ON DELETE CASCADE,
+    outcome VARCHAR(16) NOT NULL,               -- forfeit, bye or no_contest
+    reason VARCHAR(32) NOT NULL,                -- no_show, withdrawn, ineligible, misconduct, technical, odd_team_count or other
+    teamid UUID REFERENCES teams(teamid) ON DELETE CASCADE,    -- the team that forfeited or received the bye
+    note TEXT NOT NULL DEFAULT '',
+    recorded_by UUID REFERENCES users(id) ON DELETE SET NULL, -- none once that user is deleted
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE award_settings
+    ADD COLUMN forfeit_win_score INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN forfeit_loss_score INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN bye_counts_as_win BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN bye_score INTEGER NOT NULL DEFAULT 0;
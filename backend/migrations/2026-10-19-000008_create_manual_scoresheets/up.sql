@@ -0,0 +1,11 @@
+-- A paper scoresheet entered after the fact, when the room's QuizMachine couldn't record the game.
+-- Its events are generated from the sheet and stored in gameevents with md5digest 'manual'.
+
+CREATE TABLE manual_scoresheets (
+    gid UUID PRIMARY KEY REFERENCES games(gid) ON DELETE CASCADE,
+    entered_by UUID REFERENCES users(id) ON DELETE SET NULL, -- none once that user is deleted
+    scoresheet TEXT NOT NULL,                   -- the sheet as submitted, in JSON
+    events INTEGER NOT NULL,                    -- how many gameevents were generated from it
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,15 @@
+-- The history of admin repairs to games: moving a game to another room or round, and merging the
+-- events of one game into another. A merged game is deleted, so its id is kept without a reference.
+
+CREATE TABLE game_audits (
+    auditid UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tid UUID NOT NULL REFERENCES tournaments(tid) ON DELETE CASCADE,
+    gid UUID NOT NULL,                          -- the game that was moved, or merged into
+    source_gid UUID,                            -- the game merged away
+    action VARCHAR(16) NOT NULL,                -- move or merge
+    detail TEXT NOT NULL,                       -- what changed, in JSON
+    performed_by UUID REFERENCES users(id) ON DELETE SET NULL, -- none once that user is deleted
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX game_audits_gid_idx ON game_audits (gid);
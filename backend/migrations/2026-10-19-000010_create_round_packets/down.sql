@@ -0,0 +1 @@
+DROP TABLE round_packets;
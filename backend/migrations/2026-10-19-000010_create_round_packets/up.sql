@@ -0,0 +1,13 @@
+-- How each round's question packet was made. The packet's questions are in the round's
+-- scheduled_question_* slots; the alternates for overtime are kept here, in order, as JSON.
+
+CREATE TABLE round_packets (
+    roundid UUID PRIMARY KEY REFERENCES rounds(roundid) ON DELETE CASCADE,
+    rules TEXT,                                 -- the generator's rules in JSON, none if only entered by hand
+    seed BIGINT,                                -- regenerating with the same rules and seed picks the same packet
+    alternates TEXT NOT NULL DEFAULT '[]',      -- question ids in JSON
+    adjusted BOOLEAN NOT NULL DEFAULT FALSE,    -- changed by hand since it was generated
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
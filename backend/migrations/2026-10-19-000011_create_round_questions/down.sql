@@ -0,0 +1,51 @@
+-- Questions past the twentieth regular one, and overtime questions, are lost.
+
+ALTER TABLE rounds ADD COLUMN scheduled_question_one_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_two_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_three_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_four_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_five_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_six_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_seven_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_eight_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_nine_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_ten_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_eleven_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_twelve_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_thirteen_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_fourteen_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_fifteen_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_sixteen_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_seventeen_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_eighteen_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_nineteen_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE rounds ADD COLUMN scheduled_question_twenty_id UUID REFERENCES questionsandanswers(id);
+ALTER TABLE round_packets ADD COLUMN alternates TEXT NOT NULL DEFAULT '[]';
+
+UPDATE rounds r SET scheduled_question_one_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 1;
+UPDATE rounds r SET scheduled_question_two_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 2;
+UPDATE rounds r SET scheduled_question_three_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 3;
+UPDATE rounds r SET scheduled_question_four_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 4;
+UPDATE rounds r SET scheduled_question_five_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 5;
+UPDATE rounds r SET scheduled_question_six_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 6;
+UPDATE rounds r SET scheduled_question_seven_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 7;
+UPDATE rounds r SET scheduled_question_eight_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 8;
+UPDATE rounds r SET scheduled_question_nine_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 9;
+UPDATE rounds r SET scheduled_question_ten_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 10;
+UPDATE rounds r SET scheduled_question_eleven_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 11;
+UPDATE rounds r SET scheduled_question_twelve_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 12;
+UPDATE rounds r SET scheduled_question_thirteen_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 13;
+UPDATE rounds r SET scheduled_question_fourteen_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 14;
+UPDATE rounds r SET scheduled_question_fifteen_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 15;
+UPDATE rounds r SET scheduled_question_sixteen_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 16;
+UPDATE rounds r SET scheduled_question_seventeen_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 17;
+UPDATE rounds r SET scheduled_question_eighteen_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 18;
+UPDATE rounds r SET scheduled_question_nineteen_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 19;
+UPDATE rounds r SET scheduled_question_twenty_id = rq.questionid FROM round_questions rq WHERE rq.roundid = r.roundid AND rq.role = 'regular' AND rq.position = 20;
+
+UPDATE round_packets p SET alternates = (
+    SELECT COALESCE(json_agg(rq.questionid ORDER BY rq.position), '[]'::json)::text
+    FROM round_questions rq WHERE rq.roundid = p.roundid AND rq.role = 'alternate'
+);
+
+DROP TABLE round_questions;
@@ -0,0 +1,97 @@
+-- The questions of a round, in order, replace the twenty scheduled_question_* columns of rounds and
+-- the alternates kept with round_packets. A question's role is regular (read in the round),
+-- alternate (read when a question is thrown out) or overtime.
+
+CREATE TABLE round_questions (
+    roundid UUID NOT NULL REFERENCES rounds(roundid) ON DELETE CASCADE,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('regular', 'alternate', 'overtime')),
+    position INTEGER NOT NULL CHECK (position >= 1),
+    questionid UUID NOT NULL REFERENCES questionsandanswers(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (roundid, role, position),
+    UNIQUE (roundid, questionid)
+);
+
+CREATE INDEX round_questions_questionid_idx ON round_questions (questionid);
+
+-- a question in two slots of a round keeps the first
+INSERT INTO round_questions (roundid, role, position, questionid)
+SELECT roundid, 'regular', ROW_NUMBER() OVER (PARTITION BY roundid ORDER BY slot), questionid
+FROM (
+    SELECT DISTINCT ON (roundid, questionid) roundid, slot, questionid
+    FROM (
+        SELECT roundid, 1 AS slot, scheduled_question_one_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 2 AS slot, scheduled_question_two_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 3 AS slot, scheduled_question_three_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 4 AS slot, scheduled_question_four_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 5 AS slot, scheduled_question_five_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 6 AS slot, scheduled_question_six_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 7 AS slot, scheduled_question_seven_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 8 AS slot, scheduled_question_eight_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 9 AS slot, scheduled_question_nine_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 10 AS slot, scheduled_question_ten_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 11 AS slot, scheduled_question_eleven_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 12 AS slot, scheduled_question_twelve_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 13 AS slot, scheduled_question_thirteen_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 14 AS slot, scheduled_question_fourteen_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 15 AS slot, scheduled_question_fifteen_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 16 AS slot, scheduled_question_sixteen_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 17 AS slot, scheduled_question_seventeen_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 18 AS slot, scheduled_question_eighteen_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 19 AS slot, scheduled_question_nineteen_id AS questionid FROM rounds
+        UNION ALL
+        SELECT roundid, 20 AS slot, scheduled_question_twenty_id AS questionid FROM rounds
+    ) AS slots
+    WHERE questionid IS NOT NULL
+    ORDER BY roundid, questionid, slot
+) AS used;
+
+INSERT INTO round_questions (roundid, role, position, questionid)
+SELECT a.roundid, 'alternate', ROW_NUMBER() OVER (PARTITION BY a.roundid ORDER BY a.ord), a.questionid
+FROM (
+    SELECT p.roundid, e.value::uuid AS questionid, e.ord
+    FROM round_packets p
+    CROSS JOIN LATERAL jsonb_array_elements_text(p.alternates::jsonb) WITH ORDINALITY AS e(value, ord)
+) AS a
+JOIN questionsandanswers qa ON qa.id = a.questionid
+WHERE NOT EXISTS (SELECT 1 FROM round_questions rq WHERE rq.roundid = a.roundid AND rq.questionid = a.questionid);
+
+ALTER TABLE round_packets DROP COLUMN alternates;
+ALTER TABLE rounds DROP COLUMN scheduled_question_one_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_two_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_three_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_four_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_five_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_six_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_seven_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_eight_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_nine_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_ten_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_eleven_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_twelve_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_thirteen_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_fourteen_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_fifteen_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_sixteen_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_seventeen_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_eighteen_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_nineteen_id;
+ALTER TABLE rounds DROP COLUMN scheduled_question_twenty_id;
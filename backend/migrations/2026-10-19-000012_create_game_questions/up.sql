@@ -0,0 +1,14 @@
+-- The question from the question bank read for each question number of a game. It's taken from
+-- the round's packet as the game is played, or set by hand when an alternate was read instead.
+
+CREATE TABLE game_questions (
+    gid UUID NOT NULL REFERENCES games(gid) ON DELETE CASCADE,
+    question INTEGER NOT NULL,                  -- the game's question number, as in gameevents
+    questionid UUID NOT NULL REFERENCES questionsandanswers(id),
+    source VARCHAR(16) NOT NULL,                -- packet or manual
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (gid, question)
+);
+
+CREATE INDEX game_questions_questionid_idx ON game_questions (questionid);
@@ -0,0 +1,2 @@
+-- The scripture columns stay normalized: the names and spacing they had before aren't kept.
+DROP INDEX questionsandanswers_book_chapter_idx;
@@ -0,0 +1,90 @@
+-- Puts the question bank's scripture columns in the canonical form the scripture parser writes:
+-- book names written out (aliases like '1 Cor', 'I Corinthians' and 'Psalm' as in the list of
+-- books), verses without spaces ('16 - 18' as '16-18'), and each beginning verse the first of its
+-- verses. The aliases are the ones in src/models/scripture.rs.
+
+CREATE TEMPORARY TABLE book_aliases (
+    alias VARCHAR(32) PRIMARY KEY,
+    book VARCHAR(32) NOT NULL
+);
+
+INSERT INTO book_aliases (alias, book) VALUES
+    ('genesis', 'Genesis'), ('exodus', 'Exodus'), ('leviticus', 'Leviticus'), ('numbers', 'Numbers'),
+    ('deuteronomy', 'Deuteronomy'), ('joshua', 'Joshua'), ('judges', 'Judges'), ('ruth', 'Ruth'),
+    ('1 samuel', '1 Samuel'), ('2 samuel', '2 Samuel'), ('1 kings', '1 Kings'), ('2 kings', '2 Kings'),
+    ('1 chronicles', '1 Chronicles'), ('2 chronicles', '2 Chronicles'), ('ezra', 'Ezra'), ('nehemiah', 'Nehemiah'),
+    ('esther', 'Esther'), ('job', 'Job'), ('psalms', 'Psalms'), ('proverbs', 'Proverbs'),
+    ('ecclesiastes', 'Ecclesiastes'), ('song of songs', 'Song of Songs'), ('isaiah', 'Isaiah'), ('jeremiah', 'Jeremiah'),
+    ('lamentations', 'Lamentations'), ('ezekiel', 'Ezekiel'), ('daniel', 'Daniel'), ('hosea', 'Hosea'),
+    ('joel', 'Joel'), ('amos', 'Amos'), ('obadiah', 'Obadiah'), ('jonah', 'Jonah'),
+    ('micah', 'Micah'), ('nahum', 'Nahum'), ('habakkuk', 'Habakkuk'), ('zephaniah', 'Zephaniah'),
+    ('haggai', 'Haggai'), ('zechariah', 'Zechariah'), ('malachi', 'Malachi'), ('matthew', 'Matthew'),
+    ('mark', 'Mark'), ('luke', 'Luke'), ('john', 'John'), ('acts', 'Acts'),
+    ('romans', 'Romans'), ('1 corinthians', '1 Corinthians'), ('2 corinthians', '2 Corinthians'), ('galatians', 'Galatians'),
+    ('ephesians', 'Ephesians'), ('philippians', 'Philippians'), ('colossians', 'Colossians'), ('1 thessalonians', '1 Thessalonians'),
+    ('2 thessalonians', '2 Thessalonians'), ('1 timothy', '1 Timothy'), ('2 timothy', '2 Timothy'), ('titus', 'Titus'),
+    ('philemon', 'Philemon'), ('hebrews', 'Hebrews'), ('james', 'James'), ('1 peter', '1 Peter'),
+    ('2 peter', '2 Peter'), ('1 john', '1 John'), ('2 john', '2 John'), ('3 john', '3 John'),
+    ('jude', 'Jude'), ('revelation', 'Revelation'), ('gen', 'Genesis'), ('ge', 'Genesis'),
+    ('gn', 'Genesis'), ('exod', 'Exodus'), ('exo', 'Exodus'), ('ex', 'Exodus'),
+    ('lev', 'Leviticus'), ('le', 'Leviticus'), ('lv', 'Leviticus'), ('num', 'Numbers'),
+    ('nu', 'Numbers'), ('nm', 'Numbers'), ('deut', 'Deuteronomy'), ('dt', 'Deuteronomy'),
+    ('de', 'Deuteronomy'), ('josh', 'Joshua'), ('jos', 'Joshua'), ('judg', 'Judges'),
+    ('jdg', 'Judges'), ('ru', 'Ruth'), ('rth', 'Ruth'), ('1 sam', '1 Samuel'),
+    ('1 sa', '1 Samuel'), ('2 sam', '2 Samuel'), ('2 sa', '2 Samuel'), ('1 kgs', '1 Kings'),
+    ('1 ki', '1 Kings'), ('2 kgs', '2 Kings'), ('2 ki', '2 Kings'), ('1 chr', '1 Chronicles'),
+    ('1 chron', '1 Chronicles'), ('2 chr', '2 Chronicles'), ('2 chron', '2 Chronicles'), ('ezr', 'Ezra'),
+    ('neh', 'Nehemiah'), ('est', 'Esther'), ('esth', 'Esther'), ('jb', 'Job'),
+    ('psalm', 'Psalms'), ('ps', 'Psalms'), ('psa', 'Psalms'), ('pss', 'Psalms'),
+    ('prov', 'Proverbs'), ('pr', 'Proverbs'), ('prv', 'Proverbs'), ('eccl', 'Ecclesiastes'),
+    ('ecc', 'Ecclesiastes'), ('qoh', 'Ecclesiastes'), ('song', 'Song of Songs'), ('song of solomon', 'Song of Songs'),
+    ('sos', 'Song of Songs'), ('canticles', 'Song of Songs'), ('isa', 'Isaiah'), ('is', 'Isaiah'),
+    ('jer', 'Jeremiah'), ('lam', 'Lamentations'), ('ezek', 'Ezekiel'), ('eze', 'Ezekiel'),
+    ('dan', 'Daniel'), ('dn', 'Daniel'), ('hos', 'Hosea'), ('jl', 'Joel'),
+    ('am', 'Amos'), ('obad', 'Obadiah'), ('ob', 'Obadiah'), ('jon', 'Jonah'),
+    ('mic', 'Micah'), ('nah', 'Nahum'), ('hab', 'Habakkuk'), ('zeph', 'Zephaniah'),
+    ('hag', 'Haggai'), ('zech', 'Zechariah'), ('mal', 'Malachi'), ('matt', 'Matthew'),
+    ('mt', 'Matthew'), ('mk', 'Mark'), ('mrk', 'Mark'), ('lk', 'Luke'),
+    ('luk', 'Luke'), ('jn', 'John'), ('jhn', 'John'), ('ac', 'Acts'),
+    ('rom', 'Romans'), ('ro', 'Romans'), ('1 cor', '1 Corinthians'), ('1 co', '1 Corinthians'),
+    ('2 cor', '2 Corinthians'), ('2 co', '2 Corinthians'), ('gal', 'Galatians'), ('eph', 'Ephesians'),
+    ('phil', 'Philippians'), ('php', 'Philippians'), ('col', 'Colossians'), ('1 thess', '1 Thessalonians'),
+    ('1 th', '1 Thessalonians'), ('2 thess', '2 Thessalonians'), ('2 th', '2 Thessalonians'), ('1 tim', '1 Timothy'),
+    ('1 ti', '1 Timothy'), ('2 tim', '2 Timothy'), ('2 ti', '2 Timothy'), ('tit', 'Titus'),
+    ('philem', 'Philemon'), ('phm', 'Philemon'), ('heb', 'Hebrews'), ('jas', 'James'),
+    ('jm', 'James'), ('1 pet', '1 Peter'), ('1 pe', '1 Peter'), ('2 pet', '2 Peter'),
+    ('2 pe', '2 Peter'), ('1 jn', '1 John'), ('1 jo', '1 John'), ('2 jn', '2 John'),
+    ('2 jo', '2 John'), ('3 jn', '3 John'), ('3 jo', '3 John'), ('jud', 'Jude'),
+    ('rev', 'Revelation'), ('re', 'Revelation'), ('revelations', 'Revelation');
+
+WITH normalized AS (
+    SELECT id,
+        regexp_replace(
+            regexp_replace(
+                regexp_replace(
+                    regexp_replace(
+                        lower(regexp_replace(trim(replace(book, '.', ' ')), '\s+', ' ', 'g')),
+                        '^(iii|third|3rd) ', '3 '),
+                    '^(ii|second|2nd) ', '2 '),
+                '^(i|first|1st) ', '1 '),
+            '^([123])([a-z])', '\1 \2') AS name
+    FROM questionsandanswers
+)
+UPDATE questionsandanswers q
+SET book = a.book
+FROM normalized n
+JOIN book_aliases a ON a.alias = n.name
+WHERE q.id = n.id AND q.book <> a.book;
+
+UPDATE questionsandanswers
+SET verses = regexp_replace(translate(verses, '–—', '--'), '\s+', '', 'g')
+WHERE verses ~ '[\s–—]';
+
+UPDATE questionsandanswers
+SET beginning_verse = (regexp_match(verses, '^(\d+)'))[1]::int
+WHERE verses ~ '^\d+' AND beginning_verse <> (regexp_match(verses, '^(\d+)'))[1]::int;
+
+DROP TABLE book_aliases;
+
+-- for finding the questions touching a range of scripture
+CREATE INDEX questionsandanswers_book_chapter_idx ON questionsandanswers (book, chapter, beginning_verse);
@@ -0,0 +1 @@
+DROP TABLE packet_exports;
@@ -0,0 +1,13 @@
+-- Each download of a round's packet, as a QuizMachine question file or in the question bank's
+-- import layout, so a tournament can see who had the questions before the round started.
+
+CREATE TABLE packet_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    roundid UUID NOT NULL REFERENCES rounds(roundid) ON DELETE CASCADE,
+    exported_by UUID REFERENCES users(id) ON DELETE SET NULL, -- none once that user is deleted
+    scheduled_start_time TIMESTAMPTZ,           -- the round's, when it was exported
+    questions TEXT NOT NULL,                    -- the question ids in the file, in order, in JSON
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX packet_exports_roundid_idx ON packet_exports (roundid);
@@ -0,0 +1,7 @@
+ALTER TABLE divisions DROP COLUMN competition_lvl, DROP COLUMN quiz_season;
+ALTER TABLE tournaments DROP COLUMN quiz_season;
+ALTER TABLE questionsandanswers
+    DROP CONSTRAINT questionsandanswers_competition_lvl_fkey,
+    DROP CONSTRAINT questionsandanswers_quiz_season_fkey;
+DROP TABLE competition_levels;
+DROP TABLE quiz_seasons;
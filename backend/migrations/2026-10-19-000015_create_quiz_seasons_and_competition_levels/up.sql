@@ -0,0 +1,50 @@
+-- Quiz seasons and competition levels as their own tables. The question bank keeps its quiz_season
+-- and competition_lvl numbers, which now refer to them, and divisions and tournaments can name the
+-- season they quiz and divisions the level they're for.
+
+CREATE TABLE quiz_seasons (
+    season INTEGER PRIMARY KEY,                 -- the number the question bank uses, e.g. 1 for John
+    name VARCHAR(64) NOT NULL,
+    year INTEGER,                               -- the year it's quizzed, e.g. 2026 for 2026-27, when set
+    material VARCHAR(255) NOT NULL DEFAULT '',  -- books and chapters as a reference, e.g. "1 Corinthians 1-16; 2 Corinthians 1-13"
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The rotation runs from John (1) through 1 & 2 Corinthians (8); the seasons between are added
+-- when they're set up.
+INSERT INTO quiz_seasons (season, name, material) VALUES
+    (1, 'John', 'John 1-21'),
+    (8, '1 & 2 Corinthians', '1 Corinthians 1-16; 2 Corinthians 1-13');
+
+INSERT INTO quiz_seasons (season, name)
+SELECT DISTINCT quiz_season, 'Season ' || quiz_season FROM questionsandanswers
+ON CONFLICT DO NOTHING;
+
+CREATE TABLE competition_levels (
+    level INTEGER PRIMARY KEY,                  -- the number the question bank uses; higher is harder
+    name VARCHAR(32) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO competition_levels (level, name) VALUES
+    (0, 'Practice'),
+    (1, 'Novice'),
+    (2, 'Experienced'),
+    (3, 'Senior');
+
+INSERT INTO competition_levels (level, name)
+SELECT DISTINCT competition_lvl, 'Level ' || competition_lvl FROM questionsandanswers
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE questionsandanswers
+    ADD CONSTRAINT questionsandanswers_quiz_season_fkey FOREIGN KEY (quiz_season) REFERENCES quiz_seasons(season),
+    ADD CONSTRAINT questionsandanswers_competition_lvl_fkey FOREIGN KEY (competition_lvl) REFERENCES competition_levels(level);
+
+ALTER TABLE tournaments
+    ADD COLUMN quiz_season INTEGER REFERENCES quiz_seasons(season);
+
+ALTER TABLE divisions
+    ADD COLUMN quiz_season INTEGER REFERENCES quiz_seasons(season),         -- the tournament's when not set
+    ADD COLUMN competition_lvl INTEGER REFERENCES competition_levels(level);
@@ -0,0 +1,24 @@
+-- A content judge's case for each challenge or appeal: who raised it on which question, the
+-- argument, the ruling and why. The event stream only records the outcome ('C-', 'A+', 'A-', or a
+-- 'DE' for a challenge that was upheld); a case points at that event once it's in the stream. A
+-- ruling stays when the question it was on is deleted from the bank, or the user who judged it is
+-- deleted; it just no longer says which.
+
+CREATE TABLE rulings (
+    rulingid UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    gid UUID NOT NULL REFERENCES games(gid) ON DELETE CASCADE,
+    question INTEGER NOT NULL,                  -- the game's question number, as in gameevents
+    questionid UUID REFERENCES questionsandanswers(id) ON DELETE SET NULL, -- the question read, when it's known
+    kind VARCHAR(16) NOT NULL,                  -- challenge or appeal
+    teamid UUID NOT NULL REFERENCES teams(teamid),
+    argument TEXT NOT NULL,
+    ruling VARCHAR(16) NOT NULL DEFAULT 'pending', -- pending, accepted or overruled
+    rationale TEXT NOT NULL DEFAULT '',
+    event_question INTEGER,                     -- the game event recording the ruling
+    event_num INTEGER,
+    judged_by UUID REFERENCES users(id) ON DELETE SET NULL, -- none once that user is deleted
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX rulings_gid_idx ON rulings (gid);
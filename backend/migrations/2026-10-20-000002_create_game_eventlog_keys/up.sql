@@ -0,0 +1,16 @@
+-- The client key, division, room and round a game's eventlog entries were sent with before the game
+-- was moved, or before another game was merged into it. QuizMachine keeps sending those, so both
+-- the eventlog lookup and new events follow them to the game.
+
+CREATE TABLE game_eventlog_keys (
+    tid UUID NOT NULL REFERENCES tournaments(tid) ON DELETE CASCADE,
+    clientkey VARCHAR(64) NOT NULL,
+    division VARCHAR(48) NOT NULL,
+    room VARCHAR(48) NOT NULL,
+    round VARCHAR(48) NOT NULL,
+    gid UUID NOT NULL REFERENCES games(gid) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tid, clientkey, division, room, round)
+);
+
+CREATE INDEX game_eventlog_keys_gid_idx ON game_eventlog_keys (gid);
@@ -0,0 +1,2 @@
+DELETE FROM roles WHERE name = 'question_writer';
+DELETE FROM permissions WHERE resource = 'question';
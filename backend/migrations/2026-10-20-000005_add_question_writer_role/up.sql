@@ -0,0 +1,20 @@
+-- The question bank's permissions and the question_writer role, for databases set up before them.
+-- Members read the bank, question writers also write it and see its answers, super users do all.
+INSERT INTO permissions (name, resource, action) VALUES
+    ('question:create', 'question', 'create'),
+    ('question:read', 'question', 'read'),
+    ('question:update', 'question', 'update'),
+    ('question:delete', 'question', 'delete')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO roles (name, description) VALUES
+    ('question_writer', 'Write and edit the question bank, and see its answers (assign alongside member)')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO roles_permissions (role_id, permission_id)
+SELECT roles.id, permissions.id
+FROM roles, permissions
+WHERE (roles.name = 'member' AND permissions.name = 'question:read')
+   OR (roles.name = 'question_writer' AND permissions.name IN ('question:create', 'question:update', 'question:delete'))
+   OR (roles.name = 'super_user' AND permissions.resource = 'question')
+ON CONFLICT DO NOTHING;
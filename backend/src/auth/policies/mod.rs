@@ -5,6 +5,8 @@ pub mod round;
 pub mod room;
 pub mod game;
 pub mod team;
+pub mod question;
+pub mod quizzer;
 
 use uuid::Uuid;
 
@@ -25,6 +27,7 @@ impl UserContext {
 }
 
 pub trait Policy<Resource> {
+    fn can_read(&self, _resource: &Resource) -> bool { true }
     fn can_create(&self, _resource: &Resource) -> bool { true }
     fn can_update(&self, resource: &Resource) -> bool;
     fn can_delete(&self, resource: &Resource) -> bool;
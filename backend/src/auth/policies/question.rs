@@ -0,0 +1,27 @@
+use crate::auth::{is_rbac_and_abac_authorized, policies::{Policy, PolicyContext, UserContext}};
+use crate::models::{permission::{AppAction, AppResource}, role::AppRole};
+
+/// The question bank belongs to no tournament, so a user's question permissions alone decide what
+/// they can do with it. Only question writers and users who can edit the bank see the answers.
+pub struct QuestionPolicyResource;
+
+impl Policy<QuestionPolicyResource> for PolicyContext<QuestionPolicyResource> {
+    fn can_update(&self, _resource: &QuestionPolicyResource) -> bool { true }
+    fn can_delete(&self, _resource: &QuestionPolicyResource) -> bool { true }
+}
+
+impl PolicyContext<QuestionPolicyResource> {
+    pub fn new(user_ctx: &UserContext) -> Self {
+        Self { user_ctx: user_ctx.clone(), resource: QuestionPolicyResource }
+    }
+
+    pub fn is_authorized(&self, action: AppAction) -> bool {
+        let permission = format!["{}:{}", AppResource::Question.as_str(), action.as_str()];
+        is_rbac_and_abac_authorized(self, &permission, AppResource::Question.as_str()).is_ok()
+    }
+
+    pub fn can_view_answers(&self) -> bool {
+        self.user_ctx.roles.iter().any(|r| r == AppRole::QuestionWriter.as_str())
+            || self.is_authorized(AppAction::Update)
+    }
+}
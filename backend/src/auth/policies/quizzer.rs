@@ -0,0 +1,28 @@
+use uuid::Uuid;
+use crate::auth::policies::{Policy, PolicyContext};
+use crate::models::tournament::Tournament;
+
+/// Carries the data needed to evaluate who may read a quizzer's own records, such as their
+/// strengths: the quizzer, their coaches (`user_is_coach`), and the owner and admins of a
+/// tournament the quizzer played in (`tournament`, when `quizzer_is_in_tournament`).
+/// `user_is_tournament_admin` should be pre-loaded from the `tournaments_admins` table.
+pub struct QuizzerPolicyResource {
+    pub quizzer_id: Uuid,
+    pub user_is_coach: bool,
+    pub tournament: Option<Tournament>,
+    pub user_is_tournament_admin: bool,
+    pub quizzer_is_in_tournament: bool,
+}
+
+impl Policy<QuizzerPolicyResource> for PolicyContext<QuizzerPolicyResource> {
+    fn can_read(&self, resource: &QuizzerPolicyResource) -> bool {
+        let runs_tournament = resource.tournament.as_ref().is_some_and(|t| {
+            self.user_ctx.user_id == t.owner_id || resource.user_is_tournament_admin
+        });
+        self.user_ctx.user_id == resource.quizzer_id
+            || resource.user_is_coach
+            || (runs_tournament && resource.quizzer_is_in_tournament)
+    }
+    fn can_update(&self, _resource: &QuizzerPolicyResource) -> bool { false }
+    fn can_delete(&self, _resource: &QuizzerPolicyResource) -> bool { false }
+}
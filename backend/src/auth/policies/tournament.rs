@@ -11,3 +11,25 @@ impl Policy<Tournament> for PolicyContext<Tournament> {
         || self.user_ctx.roles.iter().any(|r| r == AppRole::SuperUser.as_str())
     }
 }
+
+/// Carries the data needed to evaluate the policies of what a tournament's owner and admins review,
+/// such as its challenge and appeal cases, which only they can read.
+/// `user_is_tournament_admin` should be pre-loaded from the `tournaments_admins` table.
+pub struct TournamentReviewPolicyResource {
+    pub tournament: Tournament,
+    pub user_is_tournament_admin: bool,
+}
+
+impl Policy<TournamentReviewPolicyResource> for PolicyContext<TournamentReviewPolicyResource> {
+    fn can_read(&self, resource: &TournamentReviewPolicyResource) -> bool {
+        self.user_ctx.user_id == resource.tournament.owner_id
+            || resource.user_is_tournament_admin
+    }
+    fn can_update(&self, resource: &TournamentReviewPolicyResource) -> bool {
+        self.user_ctx.user_id == resource.tournament.owner_id
+            || resource.user_is_tournament_admin
+    }
+    fn can_delete(&self, resource: &TournamentReviewPolicyResource) -> bool {
+        self.user_ctx.user_id == resource.tournament.owner_id
+    }
+}
@@ -1,6 +1,6 @@
 use crate::database;
 use crate::schema::{
-    activation_tokens, apicalllog, computers, create_tournament_applicants, divisions, equipment, equipmentregistrations, equipmentsets, extensioncords, gameevents, games, interfaceboxes, jumppads, microphonerecorders, password_reset_tokens, permissions, projectors, roles, roles_permissions, rooms, rosters, rosters_coaches, rosters_quizzers, rounds, statsgroups, teams, tournamentgroups, tournamentgroups_tournaments, tournaments, tournaments_admins, user_sessions, users, users_roles
+    activation_tokens, apicalllog, award_settings, brackets, calendar_feeds, certificate_templates, computers, create_tournament_applicants, division_rookies, divisions, equipment, equipmentregistrations, equipmentsets, eventlogs, extensioncords, game_audits, game_eventlog_keys, game_outcomes, game_questions, gameevents, games, interfaceboxes, jumppads, manual_scoresheets, microphonerecorders, packet_exports, password_reset_tokens, permissions, projectors, questionsandanswers, roles, roles_permissions, rooms, rosters, rosters_coaches, rosters_quizzers, round_packets, round_questions, rounds, rulings, schedules, statsgroups, teams, tournamentgroups, tournamentgroups_tournaments, tournaments, tournaments_admins, user_sessions, users, users_roles
 };
 use diesel::prelude::*;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};  // , MigrationHarness};
@@ -44,6 +44,50 @@ pub fn clean_database(conn: &mut database::Connection) {
         .execute(conn)
         .expect("Failed to clean gameevents");
 
+    diesel::delete(game_questions::table)
+        .execute(conn)
+        .expect("Failed to clean game_questions");
+
+    diesel::delete(rulings::table)
+        .execute(conn)
+        .expect("Failed to clean rulings");
+
+    diesel::delete(packet_exports::table)
+        .execute(conn)
+        .expect("Failed to clean packet_exports");
+
+    diesel::delete(game_outcomes::table)
+        .execute(conn)
+        .expect("Failed to clean game_outcomes");
+
+    diesel::delete(manual_scoresheets::table)
+        .execute(conn)
+        .expect("Failed to clean manual_scoresheets");
+
+    diesel::delete(game_audits::table)
+        .execute(conn)
+        .expect("Failed to clean game_audits");
+
+    diesel::delete(game_eventlog_keys::table)
+        .execute(conn)
+        .expect("Failed to clean game_eventlog_keys");
+
+    diesel::delete(brackets::table)
+        .execute(conn)
+        .expect("Failed to clean brackets");
+
+    diesel::delete(schedules::table)
+        .execute(conn)
+        .expect("Failed to clean schedules");
+
+    diesel::delete(calendar_feeds::table)
+        .execute(conn)
+        .expect("Failed to clean calendar_feeds");
+
+    diesel::delete(eventlogs::table)
+        .execute(conn)
+        .expect("Failed to clean eventlogs");
+
     diesel::delete(permissions::table)
         .execute(conn)
         .expect("Failed to clean permissions");
@@ -124,10 +168,22 @@ pub fn clean_database(conn: &mut database::Connection) {
         .execute(conn)
         .expect("Failed to clean teams");
 
+    diesel::delete(round_questions::table)
+        .execute(conn)
+        .expect("Failed to clean round_questions");
+
+    diesel::delete(round_packets::table)
+        .execute(conn)
+        .expect("Failed to clean round_packets");
+
     diesel::delete(rounds::table)
         .execute(conn)
         .expect("Failed to clean rounds");
 
+    diesel::delete(questionsandanswers::table)
+        .execute(conn)
+        .expect("Failed to clean questionsandanswers");
+
     diesel::delete(rooms::table)
         .execute(conn)
         .expect("Failed to clean rooms");
@@ -147,6 +203,18 @@ pub fn clean_database(conn: &mut database::Connection) {
     diesel::delete(activation_tokens::table)
         .execute(conn)
         .expect("Failed to clean activation tokens");
+
+    diesel::delete(division_rookies::table)
+        .execute(conn)
+        .expect("Failed to clean division_rookies");
+
+    diesel::delete(award_settings::table)
+        .execute(conn)
+        .expect("Failed to clean award_settings");
+
+    diesel::delete(certificate_templates::table)
+        .execute(conn)
+        .expect("Failed to clean certificate_templates");
     
     diesel::delete(divisions::table)
         .execute(conn)
@@ -42,6 +42,7 @@ fn add_super_user(db: &mut database::Connection) {
 ///
 ///  member             → :read on every resource
 ///  tournament_manager → tournament:create/update/delete only (member covers :read)
+///  question_writer    → question:create/update/delete (member covers :read)
 ///  super_user         → full CRUD on every resource
 fn init_roles_and_permissions(db: &mut database::Connection) {
     use std::collections::HashMap;
@@ -94,6 +95,14 @@ fn init_roles_and_permissions(db: &mut database::Connection) {
         .filter(|id| Some(*id) != tour_create_id)
         .collect();
 
+    let question_writer_permissions: Vec<Uuid> = [
+            AppAction::Create.as_str(),
+            AppAction::Update.as_str(),
+            AppAction::Delete.as_str(),
+        ].iter()
+        .filter_map(|action| perm_ids.get(&(AppResource::Question.as_str(), *action)).copied())
+        .collect();
+
     // ── Build one role row per AppRole variant ────────────────────────────────
     for app_role in AppRole::iter() {
         let role = RoleBuilder::new(app_role.as_str())
@@ -107,6 +116,8 @@ fn init_roles_and_permissions(db: &mut database::Connection) {
             // tournament_manager: create/update/delete on all tournament-managed resources (including TournamentGroups)
             AppRole::TournamentManager => tour_manager_permissions.clone(),
             AppRole::TournamentAdmin => tour_admin_permissions.clone(),
+            // question_writer: create/update/delete on the question bank, which also shows its answers
+            AppRole::QuestionWriter => question_writer_permissions.clone(),
             // super_user: full CRUD on everything
             AppRole::SuperUser => all_ids.clone(),
         };
@@ -0,0 +1,414 @@
+use std::collections::{HashMap, HashSet};
+use crate::database;
+use crate::models::{self, award_setting::AwardSettings, common::normalize_name, game::Game, gameevent::{GameEvent, GameResult}};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// A game counts toward awards only once it is finalized: it is not ignored, its event stream
+// calculates cleanly, every regulation question of its division's ruleset has been quizzed and
+// there is a single winner.
+// A game with a recorded outcome (forfeit or bye) counts by that outcome instead of its events.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CountedGame {
+    pub game: Game,
+    pub result: GameResult,
+    pub teamids: Vec<Option<Uuid>>,  // same order as result.teams; None if the team could not be matched to the Game's teams
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SkippedGame {
+    pub gid: Uuid,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameResults {
+    pub counted: Vec<CountedGame>,
+    pub skipped: Vec<SkippedGame>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuizzerTally {
+    pub quizzer_name: String,
+    pub team_name: String,
+    pub teamid: Option<Uuid>,
+    pub games_played: i32,
+    pub points: i32,
+    pub correct_tossups: i32,
+    pub errors_on_tossups: i32,
+    pub quiz_outs: i32,
+    pub perfect_games: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TeamTally {
+    pub team_name: String,
+    pub teamid: Option<Uuid>,
+    pub games_played: i32,
+    pub wins: i32,
+    pub total_score: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuizzerAward {
+    pub place: i32,
+    pub is_tied: bool,
+    pub value: i32,  // what the category ranks by (points, perfect games or quiz-outs)
+    pub quizzer: QuizzerTally,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TeamAward {
+    pub place: i32,
+    pub is_tied: bool,
+    pub team: TeamTally,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DivisionAwards {
+    pub did: Uuid,
+    pub dname: String,
+    pub settings: AwardSettings,
+    pub games_counted: i32,
+    pub games_skipped: Vec<SkippedGame>,
+    pub individual_scorers: Vec<QuizzerAward>,
+    pub perfect_games: Vec<QuizzerAward>,
+    pub quiz_outs: Vec<QuizzerAward>,
+    pub team_placements: Vec<TeamAward>,
+    pub rookies: Vec<QuizzerAward>,
+}
+
+pub fn read_all_counted_games_of_division(db: &mut database::Connection, division_id: Uuid) -> QueryResult<Vec<Game>> {
+    use crate::schema::games::dsl::*;
+    games
+        .filter(divisionid.eq(division_id))
+        .filter(ignore.eq(false))
+        .order(created_at.asc())
+        .load::<Game>(db)
+}
+
+pub fn calculate_game_results(db: &mut database::Connection, games: Vec<Game>) -> QueryResult<GameResults> {
+    let game_ids: Vec<Uuid> = games.iter().map(|g| g.gid).collect();
+    let mut events_by_game: HashMap<Uuid, Vec<GameEvent>> = HashMap::new();
+    for game_event in models::gameevent::read_all_gameevents_of_games(db, &game_ids)? {
+        events_by_game.entry(game_event.gid).or_default().push(game_event);
+    }
+
+    let outcomes = models::game_outcome::read_all_outcomes_of_games(db, &game_ids)?;
+    let mut settings: HashMap<Uuid, AwardSettings> = HashMap::new();
+
+    let mut team_names: HashMap<Uuid, String> = HashMap::new();
+    let mut counted = vec![];
+    let mut skipped = vec![];
+    for game in games {
+        for team_id in [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)].into_iter().flatten() {
+            if let std::collections::hash_map::Entry::Vacant(entry) = team_names.entry(team_id) {
+                entry.insert(models::team::read(db, team_id).map(|t| t.name).unwrap_or_default());
+            }
+        }
+
+        if let std::collections::hash_map::Entry::Vacant(entry) = settings.entry(game.divisionid) {
+            entry.insert(models::award_setting::read_or_default(db, game.divisionid)?);
+        }
+        if let Some(outcome) = outcomes.get(&game.gid) {
+            match models::game_outcome::decided_result(&game, outcome, &settings[&game.divisionid], &team_names) {
+                Ok((result, teamids)) => counted.push(CountedGame { game, result, teamids }),
+                Err(reason) => skipped.push(SkippedGame { gid: game.gid, reasons: vec![reason] }),
+            }
+            continue;
+        }
+
+        let game_events = events_by_game.remove(&game.gid).unwrap_or_default();
+        if game_events.is_empty() {
+            skipped.push(SkippedGame { gid: game.gid, reasons: vec!["Game has no events.".to_string()] });
+            continue;
+        }
+        let result = match models::gameevent::calculate_game_result(game.gid, game_events, settings[&game.divisionid].questions_per_game) {
+            Ok(r) => r,
+            Err(reasons) => {
+                skipped.push(SkippedGame { gid: game.gid, reasons });
+                continue;
+            }
+        };
+        if !result.is_final {
+            skipped.push(SkippedGame { gid: game.gid, reasons: vec![format!["Game is not finalized (last question: {}).", result.last_question]] });
+            continue;
+        }
+
+        let teamids = result.teams
+            .iter()
+            .map(|t| match_team_of_game(&game, &team_names, t.team, &t.name))
+            .collect();
+        counted.push(CountedGame { game, result, teamids });
+    }
+    Ok(GameResults { counted, skipped })
+}
+
+fn match_team_of_game(game: &Game, team_names: &HashMap<Uuid, String>, team_idx: i32, event_team_name: &str) -> Option<Uuid> {
+    let candidates = [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)];
+    // prefer the name the scorekeeper entered; fall back to the seat the team occupied
+    for team_id in candidates.iter().flatten() {
+        if team_names.get(team_id).is_some_and(|name| normalize_name(name) == normalize_name(event_team_name)) {
+            return Some(*team_id);
+        }
+    }
+    match team_idx {
+        0 => Some(game.leftteamid),
+        1 => Some(game.centerteamid.unwrap_or(game.rightteamid)),
+        2 => Some(game.rightteamid),
+        _ => None,
+    }
+}
+
+fn team_key(teamid: Option<Uuid>, team_name: &str) -> String {
+    match teamid {
+        Some(id) => id.to_string(),
+        None => normalize_name(team_name),
+    }
+}
+
+pub fn tally_quizzers(counted: &[CountedGame], team_names: &HashMap<Uuid, String>) -> Vec<QuizzerTally> {
+    let mut tallies: HashMap<(String, String), QuizzerTally> = HashMap::new();
+    let mut order: Vec<(String, String)> = vec![];
+    for counted_game in counted.iter() {
+        for (team, teamid) in counted_game.result.teams.iter().zip(counted_game.teamids.iter()) {
+            let team_name = teamid.and_then(|id| team_names.get(&id).cloned()).unwrap_or(team.name.clone());
+            for quizzer in team.quizzers.iter() {
+                let key = (team_key(*teamid, &team.name), normalize_name(&quizzer.name));
+                let tally = tallies.entry(key.clone()).or_insert_with(|| {
+                    order.push(key.clone());
+                    QuizzerTally {
+                        quizzer_name: quizzer.name.clone(),
+                        team_name: team_name.clone(),
+                        teamid: *teamid,
+                        games_played: 0,
+                        points: 0,
+                        correct_tossups: 0,
+                        errors_on_tossups: 0,
+                        quiz_outs: 0,
+                        perfect_games: 0,
+                    }
+                });
+                tally.games_played += 1;
+                tally.points += quizzer.points;
+                tally.correct_tossups += quizzer.correct_tossups;
+                tally.errors_on_tossups += quizzer.errors_on_tossups;
+                if quizzer.quizzed_out {
+                    tally.quiz_outs += 1;
+                }
+                if quizzer.is_perfect_game() {
+                    tally.perfect_games += 1;
+                }
+            }
+        }
+    }
+    order.into_iter().filter_map(|key| tallies.remove(&key)).collect()
+}
+
+pub fn tally_teams(counted: &[CountedGame], team_names: &HashMap<Uuid, String>) -> Vec<TeamTally> {
+    let mut tallies: HashMap<String, TeamTally> = HashMap::new();
+    let mut order: Vec<String> = vec![];
+    for counted_game in counted.iter() {
+        for (team, teamid) in counted_game.result.teams.iter().zip(counted_game.teamids.iter()) {
+            let key = team_key(*teamid, &team.name);
+            let tally = tallies.entry(key.clone()).or_insert_with(|| {
+                order.push(key.clone());
+                TeamTally {
+                    team_name: teamid.and_then(|id| team_names.get(&id).cloned()).unwrap_or(team.name.clone()),
+                    teamid: *teamid,
+                    games_played: 0,
+                    wins: 0,
+                    total_score: 0,
+                }
+            });
+            tally.games_played += 1;
+            tally.total_score += team.score;
+            if team.rank == 1 {
+                tally.wins += 1;
+            }
+        }
+    }
+    order.into_iter().filter_map(|key| tallies.remove(&key)).collect()
+}
+
+/// Sorts by `sort_key` (highest first) and assigns competition placements (1, 2, 2, 4, ...).
+/// Everyone whose placement is within `cutoff` is kept, so a tie at the cutoff can return more
+/// than `cutoff` entries. A cutoff of 0 or less keeps nothing.
+pub fn place_with_ties<T, K: Ord + Copy>(mut items: Vec<T>, sort_key: impl Fn(&T) -> K, cutoff: i32) -> Vec<(i32, bool, T)> {
+    items.sort_by_key(|item| std::cmp::Reverse(sort_key(item)));
+    let keys: Vec<K> = items.iter().map(&sort_key).collect();
+    let mut placed = vec![];
+    for (idx, item) in items.into_iter().enumerate() {
+        let place = keys.iter().filter(|&&k| k > keys[idx]).count() as i32 + 1;
+        if place > cutoff {
+            break;
+        }
+        let is_tied = keys.iter().filter(|&&k| k == keys[idx]).count() > 1;
+        placed.push((place, is_tied, item));
+    }
+    placed
+}
+
+fn quizzer_awards(quizzers: Vec<QuizzerTally>, value: impl Fn(&QuizzerTally) -> i32, cutoff: i32) -> Vec<QuizzerAward> {
+    place_with_ties(quizzers, |q| value(q), cutoff)
+        .into_iter()
+        .map(|(place, is_tied, quizzer)| QuizzerAward { place, is_tied, value: value(&quizzer), quizzer })
+        .collect()
+}
+
+fn read_team_names_of_division(db: &mut database::Connection, division_id: Uuid) -> QueryResult<HashMap<Uuid, String>> {
+    use crate::schema::teams::dsl::*;
+    let names: Vec<(Uuid, String)> = teams
+        .filter(did.eq(division_id))
+        .select((teamid, name))
+        .load::<(Uuid, String)>(db)?;
+    Ok(names.into_iter().collect())
+}
+
+/// The (team, name) pairs a division's rookies quiz under: a rookie is matched through the seats of
+/// the teams they're on, so a quizzer elsewhere who shares their name isn't a rookie.
+fn read_rookie_seats_of_division(db: &mut database::Connection, division_id: Uuid) -> QueryResult<HashSet<(Uuid, String)>> {
+    use crate::schema::teams::dsl::*;
+    let rookie_ids: HashSet<Uuid> = models::division_rookie::read_all_rookies_of_division(db, division_id)?
+        .into_iter()
+        .map(|r| r.quizzerid)
+        .collect();
+    let mut seats = HashSet::new();
+    for team in teams.filter(did.eq(division_id)).load::<models::team::Team>(db)? {
+        let seated = [team.quizzer_one_id, team.quizzer_two_id, team.quizzer_three_id, team.quizzer_four_id, team.quizzer_five_id, team.quizzer_six_id];
+        for quizzer_id in seated.into_iter().flatten().filter(|q| rookie_ids.contains(q)) {
+            if let Ok(user) = models::user::read(db, quizzer_id) {
+                // scorekeepers type either the first name or the full name
+                seats.insert((team.teamid, normalize_name(&user.fname)));
+                seats.insert((team.teamid, normalize_name(&format!["{} {}", user.fname, user.lname])));
+            }
+        }
+    }
+    Ok(seats)
+}
+
+fn is_rookie(quizzer: &QuizzerTally, rookie_seats: &HashSet<(Uuid, String)>) -> bool {
+    quizzer.teamid.is_some_and(|team_id| rookie_seats.contains(&(team_id, normalize_name(&quizzer.quizzer_name))))
+}
+
+pub fn compute_division_awards(db: &mut database::Connection, division_id: Uuid) -> QueryResult<DivisionAwards> {
+    let division = models::division::read(db, division_id)?;
+    let settings = models::award_setting::read_or_default(db, division_id)?;
+
+    let games = read_all_counted_games_of_division(db, division_id)?;
+    let results = calculate_game_results(db, games)?;
+
+    let team_names = read_team_names_of_division(db, division_id)?;
+
+    let quizzers = tally_quizzers(&results.counted, &team_names);
+    let teams = tally_teams(&results.counted, &team_names);
+    let rookie_seats = read_rookie_seats_of_division(db, division_id)?;
+
+    let individual_scorers = quizzer_awards(quizzers.clone(), |q| q.points, settings.individual_scorers_cutoff);
+    let perfect_games = if settings.include_perfect_games {
+        let with_perfects: Vec<QuizzerTally> = quizzers.iter().filter(|q| q.perfect_games > 0).cloned().collect();
+        quizzer_awards(with_perfects, |q| q.perfect_games, i32::MAX)
+    } else {
+        vec![]
+    };
+    let with_quiz_outs: Vec<QuizzerTally> = quizzers.iter().filter(|q| q.quiz_outs > 0).cloned().collect();
+    let quiz_outs = quizzer_awards(with_quiz_outs, |q| q.quiz_outs, settings.quiz_outs_cutoff);
+    let rookies_only: Vec<QuizzerTally> = quizzers.iter().filter(|q| is_rookie(q, &rookie_seats)).cloned().collect();
+    let rookies = quizzer_awards(rookies_only, |q| q.points, settings.rookies_cutoff);
+    let team_placements = place_with_ties(teams, |t| (t.wins, t.total_score), settings.team_placements_cutoff)
+        .into_iter()
+        .map(|(place, is_tied, team)| TeamAward { place, is_tied, team })
+        .collect();
+
+    Ok(DivisionAwards {
+        did: division.did,
+        dname: division.dname,
+        settings,
+        games_counted: results.counted.len() as i32,
+        games_skipped: results.skipped,
+        individual_scorers,
+        perfect_games,
+        quiz_outs,
+        team_placements,
+        rookies,
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn place_label(place: i32, is_tied: bool) -> String {
+    if is_tied { format!["T-{}", place] } else { place.to_string() }
+}
+
+fn quizzer_awards_table(title: &str, value_label: &str, awards: &[QuizzerAward]) -> String {
+    let mut html = format!["<h2>{}</h2>\n", escape_html(title)];
+    if awards.is_empty() {
+        html.push_str("<p>None</p>\n");
+        return html;
+    }
+    html.push_str(&format!["<table>\n<tr><th>Place</th><th>Quizzer</th><th>Team</th><th>{}</th></tr>\n", escape_html(value_label)]);
+    for award in awards.iter() {
+        html.push_str(&format![
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            place_label(award.place, award.is_tied),
+            escape_html(&award.quizzer.quizzer_name),
+            escape_html(&award.quizzer.team_name),
+            award.value
+        ]);
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+pub fn to_printable_html(awards: &DivisionAwards) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!["<title>Awards: {}</title>\n", escape_html(&awards.dname)]);
+    html.push_str("<style>body{font-family:serif;margin:2em;}table{border-collapse:collapse;margin-bottom:1.5em;}th,td{border:1px solid #444;padding:4px 10px;text-align:left;}h2{page-break-after:avoid;}</style>\n");
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!["<h1>Awards: {}</h1>\n", escape_html(&awards.dname)]);
+    html.push_str(&format!["<p>Games counted: {}. Games not counted: {}. \"T-\" marks a tie.</p>\n", awards.games_counted, awards.games_skipped.len()]);
+
+    if awards.settings.team_placements_cutoff > 0 {
+        html.push_str("<h2>Team Placements</h2>\n");
+        if awards.team_placements.is_empty() {
+            html.push_str("<p>None</p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>Place</th><th>Team</th><th>Wins</th><th>Total Score</th></tr>\n");
+            for award in awards.team_placements.iter() {
+                html.push_str(&format![
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    place_label(award.place, award.is_tied),
+                    escape_html(&award.team.team_name),
+                    award.team.wins,
+                    award.team.total_score
+                ]);
+            }
+            html.push_str("</table>\n");
+        }
+    }
+    if awards.settings.individual_scorers_cutoff > 0 {
+        html.push_str(&quizzer_awards_table(&format!["Top {} Individual Scorers", awards.settings.individual_scorers_cutoff], "Points", &awards.individual_scorers));
+    }
+    if awards.settings.include_perfect_games {
+        html.push_str(&quizzer_awards_table("Perfect Games", "Perfect Games", &awards.perfect_games));
+    }
+    if awards.settings.quiz_outs_cutoff > 0 {
+        html.push_str(&quizzer_awards_table("Most Quiz-Outs", "Quiz-Outs", &awards.quiz_outs));
+    }
+    if awards.settings.rookies_cutoff > 0 {
+        html.push_str(&quizzer_awards_table("Rookie Leaderboard", "Points", &awards.rookies));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
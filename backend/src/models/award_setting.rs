@@ -0,0 +1,323 @@
+use crate::database;
+use diesel::*;
+use diesel::{QueryResult,AsChangeset,Insertable,Identifiable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use chrono::{DateTime,Utc};
+use uuid::Uuid;
+use crate::models::gameevent::DEFAULT_QUESTIONS_PER_GAME;
+
+pub const DEFAULT_INDIVIDUAL_SCORERS_CUTOFF: i32 = 10;
+pub const DEFAULT_QUIZ_OUTS_CUTOFF: i32 = 10;
+pub const DEFAULT_TEAM_PLACEMENTS_CUTOFF: i32 = 3;
+pub const DEFAULT_ROOKIES_CUTOFF: i32 = 5;
+pub const DEFAULT_FORFEIT_WIN_SCORE: i32 = 0;
+pub const DEFAULT_FORFEIT_LOSS_SCORE: i32 = 0;
+pub const DEFAULT_BYE_SCORE: i32 = 0;
+
+pub struct AwardSettingsBuilder {
+    did: Uuid,
+    individual_scorers_cutoff: Option<i32>,
+    quiz_outs_cutoff: Option<i32>,
+    team_placements_cutoff: Option<i32>,
+    rookies_cutoff: Option<i32>,
+    include_perfect_games: Option<bool>,
+    forfeit_win_score: Option<i32>,
+    forfeit_loss_score: Option<i32>,
+    bye_counts_as_win: Option<bool>,
+    bye_score: Option<i32>,
+    questions_per_game: Option<i32>,
+}
+
+impl AwardSettingsBuilder {
+    pub fn new(did: Uuid) -> Self {
+        AwardSettingsBuilder {
+            did,
+            individual_scorers_cutoff: None,
+            quiz_outs_cutoff: None,
+            team_placements_cutoff: None,
+            rookies_cutoff: None,
+            include_perfect_games: None,
+            forfeit_win_score: None,
+            forfeit_loss_score: None,
+            bye_counts_as_win: None,
+            bye_score: None,
+            questions_per_game: None,
+        }
+    }
+    pub fn new_default(did: Uuid) -> Self {
+        AwardSettingsBuilder {
+            did,
+            individual_scorers_cutoff: Some(DEFAULT_INDIVIDUAL_SCORERS_CUTOFF),
+            quiz_outs_cutoff: Some(DEFAULT_QUIZ_OUTS_CUTOFF),
+            team_placements_cutoff: Some(DEFAULT_TEAM_PLACEMENTS_CUTOFF),
+            rookies_cutoff: Some(DEFAULT_ROOKIES_CUTOFF),
+            include_perfect_games: Some(true),
+            forfeit_win_score: Some(DEFAULT_FORFEIT_WIN_SCORE),
+            forfeit_loss_score: Some(DEFAULT_FORFEIT_LOSS_SCORE),
+            bye_counts_as_win: Some(true),
+            bye_score: Some(DEFAULT_BYE_SCORE),
+            questions_per_game: Some(DEFAULT_QUESTIONS_PER_GAME),
+        }
+    }
+    pub fn set_individual_scorers_cutoff(mut self, val: i32) -> Self {
+        self.individual_scorers_cutoff = Some(val);
+        self
+    }
+    pub fn set_quiz_outs_cutoff(mut self, val: i32) -> Self {
+        self.quiz_outs_cutoff = Some(val);
+        self
+    }
+    pub fn set_team_placements_cutoff(mut self, val: i32) -> Self {
+        self.team_placements_cutoff = Some(val);
+        self
+    }
+    pub fn set_rookies_cutoff(mut self, val: i32) -> Self {
+        self.rookies_cutoff = Some(val);
+        self
+    }
+    pub fn set_include_perfect_games(mut self, val: bool) -> Self {
+        self.include_perfect_games = Some(val);
+        self
+    }
+    pub fn set_forfeit_win_score(mut self, val: i32) -> Self {
+        self.forfeit_win_score = Some(val);
+        self
+    }
+    pub fn set_forfeit_loss_score(mut self, val: i32) -> Self {
+        self.forfeit_loss_score = Some(val);
+        self
+    }
+    pub fn set_bye_counts_as_win(mut self, val: bool) -> Self {
+        self.bye_counts_as_win = Some(val);
+        self
+    }
+    pub fn set_bye_score(mut self, val: i32) -> Self {
+        self.bye_score = Some(val);
+        self
+    }
+    pub fn set_questions_per_game(mut self, val: i32) -> Self {
+        self.questions_per_game = Some(val);
+        self
+    }
+    fn validate_all_are_some(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.individual_scorers_cutoff.is_none() {
+            errors.push("individual_scorers_cutoff is required".to_string());
+        }
+        if self.quiz_outs_cutoff.is_none() {
+            errors.push("quiz_outs_cutoff is required".to_string());
+        }
+        if self.team_placements_cutoff.is_none() {
+            errors.push("team_placements_cutoff is required".to_string());
+        }
+        if self.rookies_cutoff.is_none() {
+            errors.push("rookies_cutoff is required".to_string());
+        }
+        if self.include_perfect_games.is_none() {
+            errors.push("include_perfect_games is required".to_string());
+        }
+        if self.forfeit_win_score.is_none() {
+            errors.push("forfeit_win_score is required".to_string());
+        }
+        if self.forfeit_loss_score.is_none() {
+            errors.push("forfeit_loss_score is required".to_string());
+        }
+        if self.bye_counts_as_win.is_none() {
+            errors.push("bye_counts_as_win is required".to_string());
+        }
+        if self.bye_score.is_none() {
+            errors.push("bye_score is required".to_string());
+        }
+        if self.questions_per_game.is_none() {
+            errors.push("questions_per_game is required".to_string());
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(())
+    }
+    pub fn build(self) -> Result<NewAwardSettings, Vec<String>> {
+        match self.validate_all_are_some() {
+            Err(e) => {
+                Err(e)
+            },
+            Ok(_) => {
+                Ok(
+                    NewAwardSettings {
+                        did: self.did,
+                        individual_scorers_cutoff: self.individual_scorers_cutoff.unwrap(),
+                        quiz_outs_cutoff: self.quiz_outs_cutoff.unwrap(),
+                        team_placements_cutoff: self.team_placements_cutoff.unwrap(),
+                        rookies_cutoff: self.rookies_cutoff.unwrap(),
+                        include_perfect_games: self.include_perfect_games.unwrap(),
+                        forfeit_win_score: self.forfeit_win_score.unwrap(),
+                        forfeit_loss_score: self.forfeit_loss_score.unwrap(),
+                        bye_counts_as_win: self.bye_counts_as_win.unwrap(),
+                        bye_score: self.bye_score.unwrap(),
+                        questions_per_game: self.questions_per_game.unwrap(),
+                    }
+                )
+            }
+        }
+    }
+    pub fn build_and_insert(self, db: &mut database::Connection) -> QueryResult<AwardSettings> {
+        let new_settings = self.build();
+        match new_settings {
+            Ok(settings) => create(db, &settings),
+            Err(e) => {
+                let message = e.join(", ");
+                Err(diesel::result::Error::QueryBuilderError(message.into()))
+            }
+        }
+    }
+}
+
+// Cutoffs are placements, not row counts: everyone tied at the cutoff placement is included.
+// A cutoff of 0 turns the award category off. The forfeit and bye settings give the score a team
+// is credited with for a game decided by a recorded outcome rather than by its event stream.
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Queryable,
+    Selectable,
+    Identifiable,
+    ToSchema
+)]
+#[diesel(table_name = crate::schema::award_settings)]
+#[diesel(primary_key(did))]
+pub struct AwardSettings {
+    pub did: Uuid,
+    pub individual_scorers_cutoff: i32,
+    pub quiz_outs_cutoff: i32,
+    pub team_placements_cutoff: i32,
+    pub rookies_cutoff: i32,
+    pub include_perfect_games: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub forfeit_win_score: i32,
+    pub forfeit_loss_score: i32,
+    pub bye_counts_as_win: bool,
+    pub bye_score: i32,
+    pub questions_per_game: i32,                // regulation questions in a game of the division's ruleset
+}
+
+#[derive(
+    Insertable,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone
+)]
+#[diesel(table_name = crate::schema::award_settings)]
+pub struct NewAwardSettings {
+    pub did: Uuid,
+    pub individual_scorers_cutoff: i32,
+    pub quiz_outs_cutoff: i32,
+    pub team_placements_cutoff: i32,
+    pub rookies_cutoff: i32,
+    pub include_perfect_games: bool,
+    pub forfeit_win_score: i32,
+    pub forfeit_loss_score: i32,
+    pub bye_counts_as_win: bool,
+    pub bye_score: i32,
+    pub questions_per_game: i32,
+}
+
+// Settings that haven't been saved, stamped as of now.
+impl From<NewAwardSettings> for AwardSettings {
+    fn from(item: NewAwardSettings) -> Self {
+        let now = Utc::now();
+        AwardSettings {
+            did: item.did,
+            individual_scorers_cutoff: item.individual_scorers_cutoff,
+            quiz_outs_cutoff: item.quiz_outs_cutoff,
+            team_placements_cutoff: item.team_placements_cutoff,
+            rookies_cutoff: item.rookies_cutoff,
+            include_perfect_games: item.include_perfect_games,
+            created_at: now,
+            updated_at: now,
+            forfeit_win_score: item.forfeit_win_score,
+            forfeit_loss_score: item.forfeit_loss_score,
+            bye_counts_as_win: item.bye_counts_as_win,
+            bye_score: item.bye_score,
+            questions_per_game: item.questions_per_game,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, AsChangeset)]
+#[diesel(table_name = crate::schema::award_settings)]
+pub struct AwardSettingsChangeset {
+    pub individual_scorers_cutoff: Option<i32>,
+    pub quiz_outs_cutoff: Option<i32>,
+    pub team_placements_cutoff: Option<i32>,
+    pub rookies_cutoff: Option<i32>,
+    pub include_perfect_games: Option<bool>,
+    pub forfeit_win_score: Option<i32>,
+    pub forfeit_loss_score: Option<i32>,
+    pub bye_counts_as_win: Option<bool>,
+    pub bye_score: Option<i32>,
+    pub questions_per_game: Option<i32>,
+}
+
+pub fn create(db: &mut database::Connection, item: &NewAwardSettings) -> QueryResult<AwardSettings> {
+    use crate::schema::award_settings::dsl::*;
+    insert_into(award_settings).values(item).get_result::<AwardSettings>(db)
+}
+
+pub fn exists(db: &mut database::Connection, division_id: Uuid) -> bool {
+    use crate::schema::award_settings::dsl::*;
+    award_settings
+        .find(division_id)
+        .get_result::<AwardSettings>(db)
+        .is_ok()
+}
+
+pub fn read(db: &mut database::Connection, division_id: Uuid) -> QueryResult<AwardSettings> {
+    use crate::schema::award_settings::dsl::*;
+    award_settings.filter(did.eq(division_id)).first::<AwardSettings>(db)
+}
+
+/// The division's settings, or the defaults when none were saved. Reading never writes a row.
+pub fn read_or_default(db: &mut database::Connection, division_id: Uuid) -> QueryResult<AwardSettings> {
+    match read(db, division_id) {
+        Err(diesel::result::Error::NotFound) => {
+            let defaults = AwardSettingsBuilder::new_default(division_id).build().map_err(|e| diesel::result::Error::QueryBuilderError(e.join(", ").into()))?;
+            Ok(AwardSettings::from(defaults))
+        },
+        result => result,
+    }
+}
+
+pub fn read_or_create_default(db: &mut database::Connection, division_id: Uuid) -> QueryResult<AwardSettings> {
+    if exists(db, division_id) {
+        return read(db, division_id);
+    }
+    AwardSettingsBuilder::new_default(division_id).build_and_insert(db)
+}
+
+pub fn update(db: &mut database::Connection, division_id: Uuid, item: &AwardSettingsChangeset) -> QueryResult<AwardSettings> {
+    use crate::schema::award_settings::dsl::*;
+
+    for cutoff in [item.individual_scorers_cutoff, item.quiz_outs_cutoff, item.team_placements_cutoff, item.rookies_cutoff] {
+        if cutoff.is_some_and(|c| c < 0) {
+            return Err(diesel::result::Error::QueryBuilderError("Award cutoffs cannot be negative".into()));
+        }
+    }
+    if item.questions_per_game.is_some_and(|q| q < 1) {
+        return Err(diesel::result::Error::QueryBuilderError("questions_per_game must be at least 1".into()));
+    }
+
+    // Settings rows are created lazily, the first time a division's awards are configured.
+    read_or_create_default(db, division_id)?;
+
+    diesel::update(award_settings.filter(did.eq(division_id)))
+        .set((
+            item,
+            updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(db)
+}
@@ -0,0 +1,1068 @@
+use std::collections::{HashMap, HashSet};
+use crate::database;
+use crate::models::{self, award::{self, CountedGame}, game::{Game, GameBuilder}, game_outcome::{GameOutcome, OutcomeKind}, room::Room};
+use crate::models::round_robin::{all_pages, busy_teams_of_round, occupied_rooms_of_round};
+use crate::models::common::invalid;
+use diesel::prelude::*;
+use diesel::*;
+use diesel::{QueryResult,Insertable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+pub const FORMATS: [&str; 4] = ["power_match", "single_elimination", "double_elimination", "top_two"];
+
+pub struct BracketBuilder {
+    did: Option<Uuid>,
+    name: Option<String>,
+    format: Option<String>,
+    team_size: Option<i32>,
+}
+
+impl BracketBuilder {
+    pub fn new(did: Uuid) -> Self {
+        Self {
+            did: Some(did),
+            name: None,
+            format: None,
+            team_size: None,
+        }
+    }
+    pub fn new_default(did: Uuid) -> Self {
+        Self {
+            did: Some(did),
+            name: Some("Bracket".to_string()),
+            format: Some("single_elimination".to_string()),
+            team_size: Some(2),
+        }
+    }
+    pub fn set_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+    pub fn set_format(mut self, format: &str) -> Self {
+        self.format = Some(format.to_string());
+        self
+    }
+    pub fn set_team_size(mut self, team_size: i32) -> Self {
+        self.team_size = Some(team_size);
+        self
+    }
+    fn validate_all_are_some(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.did.is_none() {
+            errors.push("did is required".to_string());
+        }
+        if self.name.is_none() {
+            errors.push("name is required".to_string());
+        }
+        if self.format.is_none() {
+            errors.push("format is required".to_string());
+        }
+        if self.team_size.is_none() {
+            errors.push("team_size is required".to_string());
+        }
+        if let (Some(format), Some(team_size)) = (&self.format, self.team_size)
+            && let Err(e) = validate_format(format, team_size) {
+            errors.push(e);
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(())
+    }
+    pub fn build(self) -> Result<NewBracket, Vec<String>> {
+        match self.validate_all_are_some() {
+            Err(e) => {
+                Err(e)
+            },
+            Ok(_) => {
+                Ok(
+                    NewBracket {
+                        did: self.did.unwrap(),
+                        name: self.name.unwrap(),
+                        format: self.format.unwrap(),
+                        team_size: self.team_size.unwrap(),
+                    }
+                )
+            }
+        }
+    }
+    pub fn build_and_insert(self, db: &mut database::Connection) -> QueryResult<Bracket> {
+        let new_bracket = self.build();
+        create(db, &new_bracket.unwrap())
+    }
+}
+
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Queryable,
+    Selectable,
+    Identifiable,
+    ToSchema
+)]
+#[diesel(table_name = crate::schema::brackets)]
+#[diesel(primary_key(bracketid))]
+pub struct Bracket {
+    pub bracketid: Uuid,
+    pub did: Uuid,                              // id of the associated division
+    pub name: String,
+    pub format: String,                         // one of FORMATS
+    pub team_size: i32,                         // teams per game: 2, or 3 for top_two
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(
+    Insertable,
+    Serialize,
+    Deserialize,
+    Debug
+)]
+#[diesel(table_name = crate::schema::brackets)]
+pub struct NewBracket {
+    pub did: Uuid,
+    pub name: String,
+    pub format: String,
+    pub team_size: i32,
+}
+
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Queryable,
+    Selectable,
+    Identifiable,
+    ToSchema
+)]
+#[diesel(table_name = crate::schema::bracket_nodes)]
+#[diesel(primary_key(nodeid))]
+pub struct BracketNode {
+    pub nodeid: Uuid,
+    pub bracketid: Uuid,
+    pub side: String,                           // winners, losers, final or power
+    pub round_num: i32,                         // 1-based within its side
+    pub position: i32,                          // 0-based within its round
+    pub gid: Option<Uuid>,                      // the game, once it has been scheduled
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(
+    Insertable,
+    Serialize,
+    Deserialize,
+    Debug
+)]
+#[diesel(table_name = crate::schema::bracket_nodes)]
+pub struct NewBracketNode {
+    pub bracketid: Uuid,
+    pub side: String,
+    pub round_num: i32,
+    pub position: i32,
+}
+
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Queryable,
+    Selectable,
+    Insertable,
+    ToSchema
+)]
+#[diesel(table_name = crate::schema::bracket_seats)]
+#[diesel(primary_key(nodeid, seat))]
+pub struct BracketSeat {
+    pub nodeid: Uuid,
+    pub seat: i32,                              // 0 = left, then center and right
+    pub seed: Option<i32>,                      // place in the standings the team was seeded from
+    pub teamid: Option<Uuid>,                   // set for seeded seats; None with a seed is a bye
+    pub from_nodeid: Option<Uuid>,              // otherwise the team comes from this node ...
+    pub from_place: Option<i32>,                // ... finishing in this place (1 = winner)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BracketRequest {
+    pub name: String,
+    pub format: String,
+    pub team_size: Option<i32>,                 // defaults to 3 for top_two, 2 otherwise
+    pub teams: Option<i32>,                     // how many teams to seed from the top of the standings; all by default
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AdvanceRequest {
+    pub roundid: Uuid,                          // the division round the new games are played in
+    pub room_ids: Option<Vec<Uuid>>,            // defaults to every room of the tournament
+    pub quizmasterid: Option<Uuid>,             // used for rooms that don't have their own quizmaster
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Standing {
+    pub place: i32,
+    pub is_tied: bool,
+    pub teamid: Uuid,
+    pub name: String,
+    pub games_played: i32,
+    pub wins: i32,
+    pub total_score: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BracketSeatView {
+    pub seat: i32,
+    pub seed: Option<i32>,
+    pub teamid: Option<Uuid>,
+    pub team_name: Option<String>,
+    pub from_nodeid: Option<Uuid>,
+    pub from_place: Option<i32>,
+    pub place: Option<i32>,                     // where the team finished in this node's game
+}
+
+// A node's children are the nodes whose winner it receives. Teams coming from any other place
+// (losers dropping into the losers side, runners-up in top_two) are only referenced by
+// `from_nodeid`, which keeps every node under exactly one parent.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BracketNodeView {
+    pub nodeid: Uuid,
+    pub side: String,
+    pub round_num: i32,
+    pub position: i32,
+    pub gid: Option<Uuid>,
+    pub status: String,                         // waiting, ready, scheduled, finished or bye
+    pub seats: Vec<BracketSeatView>,
+    #[schema(no_recursion)]
+    pub children: Vec<BracketNodeView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BracketTree {
+    pub bracket: Bracket,
+    pub roots: Vec<BracketNodeView>,
+    pub champion: Option<Uuid>,
+    pub champion_name: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AdvanceReport {
+    pub created: Vec<Game>,
+    pub warnings: Vec<String>,
+    pub tree: BracketTree,
+}
+
+pub fn validate_format(format: &str, team_size: i32) -> Result<(), String> {
+    match format {
+        "top_two" if team_size != 3 => Err("top_two brackets need three-team games".to_string()),
+        "single_elimination" | "double_elimination" if team_size != 2 => Err(format!["{} brackets need two-team games", format]),
+        "power_match" if team_size != 2 && team_size != 3 => Err("team_size must be 2 or 3".to_string()),
+        f if !FORMATS.contains(&f) => Err(format!["format must be one of {}", FORMATS.join(", ")]),
+        _ => Ok(()),
+    }
+}
+
+pub fn create(db: &mut database::Connection, item: &NewBracket) -> QueryResult<Bracket> {
+    use crate::schema::brackets::dsl::*;
+    insert_into(brackets).values(item).get_result::<Bracket>(db)
+}
+
+pub fn read(db: &mut database::Connection, item_id: Uuid) -> QueryResult<Bracket> {
+    use crate::schema::brackets::dsl::*;
+    brackets.filter(bracketid.eq(item_id)).first::<Bracket>(db)
+}
+
+pub fn read_all_brackets_of_division(db: &mut database::Connection, division_id: Uuid) -> QueryResult<Vec<Bracket>> {
+    use crate::schema::brackets::dsl::*;
+    brackets
+        .filter(did.eq(division_id))
+        .order(created_at.asc())
+        .load::<Bracket>(db)
+}
+
+pub fn delete(db: &mut database::Connection, item_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::brackets::dsl::*;
+    diesel::delete(brackets.filter(bracketid.eq(item_id))).execute(db)
+}
+
+pub fn read_all_nodes_of_bracket(db: &mut database::Connection, bracket_id: Uuid) -> QueryResult<Vec<BracketNode>> {
+    use crate::schema::bracket_nodes::dsl::*;
+    bracket_nodes
+        .filter(bracketid.eq(bracket_id))
+        .order((round_num.asc(), position.asc()))
+        .load::<BracketNode>(db)
+}
+
+pub fn read_all_seats_of_bracket(db: &mut database::Connection, bracket_id: Uuid) -> QueryResult<Vec<BracketSeat>> {
+    use crate::schema::bracket_nodes;
+    use crate::schema::bracket_seats::dsl::*;
+    bracket_seats
+        .inner_join(bracket_nodes::table.on(bracket_nodes::nodeid.eq(nodeid)))
+        .filter(bracket_nodes::bracketid.eq(bracket_id))
+        .select(BracketSeat::as_select())
+        .order((nodeid.asc(), seat.asc()))
+        .load::<BracketSeat>(db)
+}
+
+fn set_game_of_node(db: &mut database::Connection, node_id: Uuid, game_id: Uuid) -> QueryResult<BracketNode> {
+    use crate::schema::bracket_nodes::dsl::*;
+    diesel::update(bracket_nodes.filter(nodeid.eq(node_id)))
+        .set((gid.eq(Some(game_id)), updated_at.eq(Utc::now())))
+        .get_result::<BracketNode>(db)
+}
+
+// Games played in elimination brackets don't change the standings they were seeded from.
+fn read_elimination_game_ids(db: &mut database::Connection, division_id: Uuid) -> QueryResult<HashSet<Uuid>> {
+    use crate::schema::{bracket_nodes, brackets};
+    let game_ids = bracket_nodes::table
+        .inner_join(brackets::table)
+        .filter(brackets::did.eq(division_id))
+        .filter(brackets::format.ne("power_match"))
+        .select(bracket_nodes::gid)
+        .load::<Option<Uuid>>(db)?;
+    Ok(game_ids.into_iter().flatten().collect())
+}
+
+/// Teams ranked by wins, then total score, over the division's finalized games. Teams that haven't
+/// played a counted game share the last place.
+pub fn read_standings(db: &mut database::Connection, division_id: Uuid) -> QueryResult<Vec<Standing>> {
+    let excluded = read_elimination_game_ids(db, division_id)?;
+    let games: Vec<Game> = award::read_all_counted_games_of_division(db, division_id)?
+        .into_iter()
+        .filter(|g| !excluded.contains(&g.gid))
+        .collect();
+    let results = award::calculate_game_results(db, games)?;
+
+    let mut teams = models::team::read_all_teams_of_division(db, division_id, &all_pages())?;
+    teams.sort_by(|a, b| a.name.cmp(&b.name));
+    let team_names: HashMap<Uuid, String> = teams.iter().map(|t| (t.teamid, t.name.clone())).collect();
+
+    let mut tallies: Vec<award::TeamTally> = award::tally_teams(&results.counted, &team_names)
+        .into_iter()
+        .filter(|t| t.teamid.is_some_and(|id| team_names.contains_key(&id)))
+        .collect();
+    tallies.sort_by(|a, b| a.team_name.cmp(&b.team_name));
+
+    let mut standings: Vec<Standing> = award::place_with_ties(tallies, |t| (t.wins, t.total_score), i32::MAX)
+        .into_iter()
+        .map(|(place, is_tied, t)| Standing {
+            place,
+            is_tied,
+            teamid: t.teamid.unwrap(),
+            name: t.team_name,
+            games_played: t.games_played,
+            wins: t.wins,
+            total_score: t.total_score,
+        })
+        .collect();
+    let unplayed: Vec<&models::team::Team> = teams.iter().filter(|t| !standings.iter().any(|s| s.teamid == t.teamid)).collect();
+    let unplayed_place = standings.len() as i32 + 1;
+    for team in unplayed.iter() {
+        standings.push(Standing {
+            place: unplayed_place,
+            is_tied: unplayed.len() > 1,
+            teamid: team.teamid,
+            name: team.name.clone(),
+            games_played: 0,
+            wins: 0,
+            total_score: 0,
+        });
+    }
+    Ok(standings)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeatSource {
+    Seed(usize),                // 1-based place in the standings
+    Place(usize, i32),          // index of an earlier planned node, and the place taken from it
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedNode {
+    pub side: &'static str,
+    pub round_num: i32,
+    pub position: i32,
+    pub seats: Vec<SeatSource>,
+}
+
+/// Seeds in bracket order, so that 1 and 2 can only meet in the final: [1, 4, 2, 3] for four.
+pub fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![1];
+    while order.len() < size {
+        let n = order.len() * 2;
+        order = order.iter().flat_map(|s| [*s, n + 1 - s]).collect();
+    }
+    order
+}
+
+// The winners side of an elimination bracket, padded to a power of two. Seeds past the number of
+// teams are byes. Returns the nodes and the node indices of each round.
+fn plan_winners_side(team_count: usize, last_side: &'static str) -> (Vec<PlannedNode>, Vec<Vec<usize>>) {
+    let size = team_count.next_power_of_two().max(2);
+    let order = seed_order(size);
+    let round_count = size.trailing_zeros() as i32;
+    let mut nodes = vec![];
+    let mut rounds: Vec<Vec<usize>> = vec![];
+    for round_num in 1..=round_count {
+        let count = size >> round_num;
+        let side = if round_num == round_count { last_side } else { "winners" };
+        let mut current = vec![];
+        for position in 0..count {
+            let seats = match rounds.last() {
+                None => vec![SeatSource::Seed(order[2 * position]), SeatSource::Seed(order[2 * position + 1])],
+                Some(previous) => vec![SeatSource::Place(previous[2 * position], 1), SeatSource::Place(previous[2 * position + 1], 1)],
+            };
+            current.push(nodes.len());
+            nodes.push(PlannedNode { side, round_num, position: position as i32, seats });
+        }
+        rounds.push(current);
+    }
+    (nodes, rounds)
+}
+
+pub fn plan_single_elimination(team_count: usize) -> Vec<PlannedNode> {
+    plan_winners_side(team_count, "final").0
+}
+
+/// Losers drop into the losers side, where odd rounds pair its own winners and even rounds bring
+/// in the losers of the next winners round (in reverse order, to avoid early rematches). The
+/// winners of both sides meet in the final.
+pub fn plan_double_elimination(team_count: usize) -> Vec<PlannedNode> {
+    let (mut nodes, winners) = plan_winners_side(team_count.max(4), "winners");
+    let mut previous: Vec<usize> = vec![];
+    for round_num in 1..=2 * (winners.len() as i32 - 1) {
+        let pairs: Vec<(SeatSource, SeatSource)> = if round_num == 1 {
+            winners[0].chunks(2).map(|p| (SeatSource::Place(p[0], 2), SeatSource::Place(p[1], 2))).collect()
+        } else if round_num % 2 == 0 {
+            let dropping = &winners[round_num as usize / 2];
+            previous
+                .iter()
+                .enumerate()
+                .map(|(idx, node)| (SeatSource::Place(*node, 1), SeatSource::Place(dropping[dropping.len() - 1 - idx], 2)))
+                .collect()
+        } else {
+            previous.chunks(2).map(|p| (SeatSource::Place(p[0], 1), SeatSource::Place(p[1], 1))).collect()
+        };
+        let mut current = vec![];
+        for (position, (a, b)) in pairs.into_iter().enumerate() {
+            current.push(nodes.len());
+            nodes.push(PlannedNode { side: "losers", round_num, position: position as i32, seats: vec![a, b] });
+        }
+        previous = current;
+    }
+    let winners_final = *winners.last().unwrap().first().unwrap();
+    nodes.push(PlannedNode {
+        side: "final",
+        round_num: 1,
+        position: 0,
+        seats: vec![SeatSource::Place(winners_final, 1), SeatSource::Place(previous[0], 1)],
+    });
+    nodes
+}
+
+// Deals `count` items into `groups` groups, back and forth, so the strongest share out evenly.
+fn snake(count: usize, groups: usize) -> Vec<Vec<usize>> {
+    let mut dealt = vec![vec![]; groups];
+    for idx in 0..count {
+        let (row, col) = (idx / groups, idx % groups);
+        let group = if row % 2 == 0 { col } else { groups - 1 - col };
+        dealt[group].push(idx);
+    }
+    dealt
+}
+
+/// Three-team games where the top two advance (only the winner, if a game has just two teams).
+/// Rounds continue until three or fewer teams are left, who play the final.
+pub fn plan_top_two(team_count: usize) -> Vec<PlannedNode> {
+    let mut nodes = vec![];
+    let mut sources: Vec<SeatSource> = (1..=team_count).map(SeatSource::Seed).collect();
+    let mut round_num = 1;
+    while sources.len() > 3 {
+        let mut winners = vec![];
+        let mut runners_up = vec![];
+        for (position, members) in snake(sources.len(), sources.len().div_ceil(3)).iter().enumerate() {
+            if members.len() == 3 {
+                runners_up.push(SeatSource::Place(nodes.len(), 2));
+            }
+            winners.push(SeatSource::Place(nodes.len(), 1));
+            nodes.push(PlannedNode {
+                side: "winners",
+                round_num,
+                position: position as i32,
+                seats: members.iter().map(|m| sources[*m].clone()).collect(),
+            });
+        }
+        // the best runner-up goes against the weakest winner
+        runners_up.reverse();
+        sources = winners;
+        sources.extend(runners_up);
+        round_num += 1;
+    }
+    nodes.push(PlannedNode { side: "final", round_num: 1, position: 0, seats: sources });
+    nodes
+}
+
+/// Groups teams in standings order for a power-matched round: 1-2, 3-4, ... or 1-2-3, 4-5-6, ...
+/// With three-team games a leftover of one or two teams becomes two-team games. The second value
+/// is the team left without a game, if any.
+pub fn power_groups(team_count: usize, team_size: usize) -> (Vec<Vec<usize>>, Option<usize>) {
+    let mut sizes = vec![];
+    if team_size == 2 {
+        sizes.extend(std::iter::repeat_n(2, team_count / 2));
+    } else {
+        match team_count % 3 {
+            1 if team_count >= 4 => {
+                sizes.extend(std::iter::repeat_n(3, team_count / 3 - 1));
+                sizes.extend([2, 2]);
+            },
+            2 => {
+                sizes.extend(std::iter::repeat_n(3, team_count / 3));
+                sizes.push(2);
+            },
+            _ => sizes.extend(std::iter::repeat_n(3, team_count / 3)),
+        }
+    }
+    let mut groups = vec![];
+    let mut next = 0;
+    for size in sizes {
+        groups.push((next..next + size).collect());
+        next += size;
+    }
+    let bye = if next < team_count { Some(next) } else { None };
+    (groups, bye)
+}
+
+fn insert_planned_nodes(db: &mut database::Connection, bracket: &Bracket, planned: &[PlannedNode], seeds: &[Uuid]) -> QueryResult<()> {
+    let mut node_ids = vec![];
+    for node in planned.iter() {
+        let new_node = NewBracketNode {
+            bracketid: bracket.bracketid,
+            side: node.side.to_string(),
+            round_num: node.round_num,
+            position: node.position,
+        };
+        let inserted = {
+            use crate::schema::bracket_nodes::dsl::*;
+            insert_into(bracket_nodes).values(&new_node).get_result::<BracketNode>(db)?
+        };
+        node_ids.push(inserted.nodeid);
+    }
+    let mut seats = vec![];
+    for (node, node_id) in planned.iter().zip(node_ids.iter()) {
+        for (idx, source) in node.seats.iter().enumerate() {
+            seats.push(match source {
+                SeatSource::Seed(seed) => BracketSeat {
+                    nodeid: *node_id,
+                    seat: idx as i32,
+                    seed: Some(*seed as i32),
+                    teamid: seeds.get(seed - 1).copied(),
+                    from_nodeid: None,
+                    from_place: None,
+                },
+                SeatSource::Place(from, place) => BracketSeat {
+                    nodeid: *node_id,
+                    seat: idx as i32,
+                    seed: None,
+                    teamid: None,
+                    from_nodeid: Some(node_ids[*from]),
+                    from_place: Some(*place),
+                },
+            });
+        }
+    }
+    insert_into(crate::schema::bracket_seats::table).values(&seats).execute(db)?;
+    Ok(())
+}
+
+/// Creates a bracket seeded from the division's current standings. Elimination formats lay out
+/// every node up front; power-matched brackets get their rounds one at a time from `advance`.
+pub fn create_bracket(db: &mut database::Connection, division_id: Uuid, request: &BracketRequest) -> QueryResult<BracketTree> {
+    let division = models::division::read(db, division_id)?;
+    let team_size = request.team_size.unwrap_or(if request.format == "top_two" { 3 } else { 2 });
+    let new_bracket = BracketBuilder::new(division.did)
+        .set_name(&request.name)
+        .set_format(&request.format)
+        .set_team_size(team_size)
+        .build()
+        .map_err(|e| invalid(e.join(", ")))?;
+
+    let mut seeds: Vec<Uuid> = read_standings(db, division_id)?.iter().map(|s| s.teamid).collect();
+    if let Some(teams) = request.teams {
+        seeds.truncate(teams.max(0) as usize);
+    }
+    if seeds.len() < 2 {
+        return Err(invalid(format!["A bracket needs at least two teams, not {}", seeds.len()]));
+    }
+
+    let planned = match request.format.as_str() {
+        "single_elimination" => plan_single_elimination(seeds.len()),
+        "double_elimination" => plan_double_elimination(seeds.len()),
+        "top_two" => plan_top_two(seeds.len()),
+        _ => vec![],
+    };
+
+    let bracket = db.transaction::<_, diesel::result::Error, _>(|conn| {
+        let bracket = create(conn, &new_bracket)?;
+        insert_planned_nodes(conn, &bracket, &planned, &seeds)?;
+        Ok(bracket)
+    })?;
+    read_tree(db, bracket.bracketid)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Slot {
+    Team(Uuid),
+    Empty,          // a bye: nobody will ever fill this seat
+    Unknown,        // waiting on an earlier game
+}
+
+#[derive(Debug, Clone)]
+struct NodeState {
+    status: &'static str,
+    seats: Vec<Slot>,
+    places: Vec<Slot>,                  // places[0] is the winner; only set once decided
+    team_places: HashMap<Uuid, i32>,
+}
+
+impl NodeState {
+    fn is_decided(&self) -> bool {
+        self.status == "finished" || self.status == "bye"
+    }
+}
+
+// How the bracket's games ended: counted results, and the outcomes recorded for some of them along
+// with the standings places that order the teams an outcome leaves level.
+struct Decisions {
+    results: HashMap<Uuid, CountedGame>,
+    outcomes: HashMap<Uuid, GameOutcome>,
+    standings: HashMap<Uuid, i32>,
+}
+
+// A bye or forfeit decides a bracket game whatever the award settings say: the team that received
+// the bye wins, the team that forfeited finishes last, and the others keep their standings order
+// (then seat order) between them. A no contest decides nothing; the game has to be replayed.
+fn places_of_outcome(outcome: &GameOutcome, teams: &[Uuid], standings: &HashMap<Uuid, i32>) -> Option<Vec<Uuid>> {
+    let kind = OutcomeKind::from_param(&outcome.outcome).ok()?;
+    let mut order = teams.to_vec();
+    order.sort_by_key(|id| standings.get(id).copied().unwrap_or(i32::MAX));
+    let idx = order.iter().position(|id| Some(*id) == outcome.teamid)?;
+    let team = order.remove(idx);
+    match kind {
+        OutcomeKind::Bye => order.insert(0, team),
+        OutcomeKind::Forfeit => order.push(team),
+        OutcomeKind::NoContest => return None,
+    }
+    Some(order)
+}
+
+fn node_state(seats: &[BracketSeat], states: &HashMap<Uuid, NodeState>, gid: Option<Uuid>, decisions: &Decisions) -> NodeState {
+    let slots: Vec<Slot> = seats
+        .iter()
+        .map(|seat| match (seat.from_nodeid, seat.from_place) {
+            (Some(from), Some(place)) => match states.get(&from) {
+                Some(state) if state.is_decided() => state.places.get(place as usize - 1).cloned().unwrap_or(Slot::Empty),
+                _ => Slot::Unknown,
+            },
+            _ => seat.teamid.map(Slot::Team).unwrap_or(Slot::Empty),
+        })
+        .collect();
+    let mut state = NodeState { status: "waiting", seats: slots.clone(), places: vec![], team_places: HashMap::new() };
+    if slots.contains(&Slot::Unknown) {
+        return state;
+    }
+    let teams: Vec<Uuid> = slots.iter().filter_map(|s| if let Slot::Team(id) = s { Some(*id) } else { None }).collect();
+    if teams.len() <= 1 {
+        state.status = "bye";
+        state.places = vec![teams.first().map(|id| Slot::Team(*id)).unwrap_or(Slot::Empty)];
+        if let Some(id) = teams.first() {
+            state.team_places.insert(*id, 1);
+        }
+        return state;
+    }
+    let Some(game_id) = gid else {
+        state.status = "ready";
+        return state;
+    };
+    if let Some(outcome) = decisions.outcomes.get(&game_id) {
+        let Some(order) = places_of_outcome(outcome, &teams, &decisions.standings) else {
+            state.status = "scheduled";
+            return state;
+        };
+        state.status = "finished";
+        for (idx, id) in order.iter().enumerate() {
+            state.team_places.insert(*id, idx as i32 + 1);
+        }
+        state.places = order.into_iter().map(Slot::Team).collect();
+        return state;
+    }
+    let Some(counted) = decisions.results.get(&game_id) else {
+        state.status = "scheduled";
+        return state;
+    };
+    state.status = "finished";
+    for (team, teamid) in counted.result.teams.iter().zip(counted.teamids.iter()) {
+        if let Some(id) = teamid {
+            state.team_places.insert(*id, team.rank);
+        }
+    }
+    state.places = (1..=teams.len() as i32)
+        .map(|place| {
+            let at_place: Vec<Uuid> = state.team_places.iter().filter(|(_, p)| **p == place).map(|(id, _)| *id).collect();
+            if at_place.len() == 1 { Slot::Team(at_place[0]) } else { Slot::Unknown }
+        })
+        .collect();
+    state
+}
+
+fn side_order(side: &str) -> i32 {
+    match side {
+        "winners" | "power" => 0,
+        "losers" => 1,
+        _ => 2,
+    }
+}
+
+struct LoadedBracket {
+    bracket: Bracket,
+    nodes: Vec<BracketNode>,
+    seats: HashMap<Uuid, Vec<BracketSeat>>,
+    states: HashMap<Uuid, NodeState>,
+    outcomes: HashMap<Uuid, GameOutcome>,
+}
+
+fn load_bracket(db: &mut database::Connection, bracket_id: Uuid) -> QueryResult<LoadedBracket> {
+    let bracket = read(db, bracket_id)?;
+    let mut nodes = read_all_nodes_of_bracket(db, bracket_id)?;
+    nodes.sort_by_key(|n| (side_order(&n.side), n.round_num, n.position));
+    let mut seats: HashMap<Uuid, Vec<BracketSeat>> = HashMap::new();
+    for seat in read_all_seats_of_bracket(db, bracket_id)? {
+        seats.entry(seat.nodeid).or_default().push(seat);
+    }
+
+    let mut games = vec![];
+    for game_id in nodes.iter().filter_map(|n| n.gid) {
+        games.push(models::game::read(db, game_id)?);
+    }
+    let game_ids: Vec<Uuid> = games.iter().map(|g| g.gid).collect();
+    let outcomes = models::game_outcome::read_all_outcomes_of_games(db, &game_ids)?;
+    let standings: HashMap<Uuid, i32> = if outcomes.is_empty() {
+        HashMap::new()
+    } else {
+        read_standings(db, bracket.did)?.into_iter().map(|s| (s.teamid, s.place)).collect()
+    };
+    let results: HashMap<Uuid, CountedGame> = award::calculate_game_results(db, games)?
+        .counted
+        .into_iter()
+        .map(|c| (c.game.gid, c))
+        .collect();
+    let decisions = Decisions { results, outcomes, standings };
+
+    // a node can only be decided after the nodes feeding it, so repeat until nothing changes
+    let mut states: HashMap<Uuid, NodeState> = HashMap::new();
+    loop {
+        let decided = states.values().filter(|s| s.is_decided()).count();
+        for node in nodes.iter() {
+            let node_seats = seats.get(&node.nodeid).map(|s| s.as_slice()).unwrap_or(&[]);
+            let state = node_state(node_seats, &states, node.gid, &decisions);
+            states.insert(node.nodeid, state);
+        }
+        if states.values().filter(|s| s.is_decided()).count() == decided {
+            break;
+        }
+    }
+    Ok(LoadedBracket { bracket, nodes, seats, states, outcomes: decisions.outcomes })
+}
+
+fn node_view(loaded: &LoadedBracket, node: &BracketNode, team_names: &HashMap<Uuid, String>) -> BracketNodeView {
+    let state = &loaded.states[&node.nodeid];
+    let seats = loaded.seats.get(&node.nodeid).cloned().unwrap_or_default();
+    let seat_views = seats
+        .iter()
+        .zip(state.seats.iter())
+        .map(|(seat, slot)| {
+            let teamid = if let Slot::Team(id) = slot { Some(*id) } else { None };
+            BracketSeatView {
+                seat: seat.seat,
+                seed: seat.seed,
+                teamid,
+                team_name: teamid.and_then(|id| team_names.get(&id).cloned()),
+                from_nodeid: seat.from_nodeid,
+                from_place: seat.from_place,
+                place: teamid.and_then(|id| state.team_places.get(&id).copied()),
+            }
+        })
+        .collect();
+    let children = seats
+        .iter()
+        .filter(|s| s.from_place == Some(1))
+        .filter_map(|s| loaded.nodes.iter().find(|n| Some(n.nodeid) == s.from_nodeid))
+        .map(|child| node_view(loaded, child, team_names))
+        .collect();
+    BracketNodeView {
+        nodeid: node.nodeid,
+        side: node.side.clone(),
+        round_num: node.round_num,
+        position: node.position,
+        gid: node.gid,
+        status: state.status.to_string(),
+        seats: seat_views,
+        children,
+    }
+}
+
+fn tree_of(db: &mut database::Connection, loaded: &LoadedBracket) -> QueryResult<BracketTree> {
+    let team_names: HashMap<Uuid, String> = models::team::read_all_teams_of_division(db, loaded.bracket.did, &all_pages())?
+        .into_iter()
+        .map(|t| (t.teamid, t.name))
+        .collect();
+
+    let winner_feeds: HashSet<Uuid> = loaded.seats
+        .values()
+        .flatten()
+        .filter(|s| s.from_place == Some(1))
+        .filter_map(|s| s.from_nodeid)
+        .collect();
+    let roots: Vec<BracketNodeView> = loaded.nodes
+        .iter()
+        .filter(|n| !winner_feeds.contains(&n.nodeid))
+        .map(|n| node_view(loaded, n, &team_names))
+        .collect();
+
+    let champion = loaded.nodes
+        .iter()
+        .find(|n| n.side == "final")
+        .and_then(|n| loaded.states[&n.nodeid].places.first())
+        .and_then(|slot| if let Slot::Team(id) = slot { Some(*id) } else { None });
+
+    let mut warnings = vec![];
+    for node in loaded.nodes.iter() {
+        let state = &loaded.states[&node.nodeid];
+        if state.status == "finished" && state.places.contains(&Slot::Unknown) {
+            warnings.push(format!["The {} round {} game {} ended in a tie; break it before the bracket can go on", node.side, node.round_num, node.position + 1]);
+        }
+        if state.status == "scheduled" && node.gid.and_then(|id| loaded.outcomes.get(&id)).is_some() {
+            warnings.push(format!["The {} round {} game {} was a no contest; replay it before the bracket can go on", node.side, node.round_num, node.position + 1]);
+        }
+    }
+
+    Ok(BracketTree {
+        bracket: loaded.bracket.clone(),
+        roots,
+        champion,
+        champion_name: champion.and_then(|id| team_names.get(&id).cloned()),
+        warnings,
+    })
+}
+
+pub fn read_tree(db: &mut database::Connection, bracket_id: Uuid) -> QueryResult<BracketTree> {
+    let loaded = load_bracket(db, bracket_id)?;
+    tree_of(db, &loaded)
+}
+
+// Adds the next power-matched round once every game of the previous one is decided.
+fn add_power_round(db: &mut database::Connection, loaded: &LoadedBracket, warnings: &mut Vec<String>) -> QueryResult<()> {
+    if loaded.nodes.iter().any(|n| !loaded.states[&n.nodeid].is_decided()) {
+        return Ok(());
+    }
+    let standings = read_standings(db, loaded.bracket.did)?;
+    let (groups, bye) = power_groups(standings.len(), loaded.bracket.team_size as usize);
+    if let Some(idx) = bye {
+        warnings.push(format!["{} has no game this round", standings[idx].name]);
+    }
+    let round_num = loaded.nodes.iter().map(|n| n.round_num).max().unwrap_or(0) + 1;
+    let planned: Vec<PlannedNode> = groups
+        .iter()
+        .enumerate()
+        .map(|(position, group)| PlannedNode {
+            side: "power",
+            round_num,
+            position: position as i32,
+            seats: group.iter().map(|idx| SeatSource::Seed(idx + 1)).collect(),
+        })
+        .collect();
+    let seeds: Vec<Uuid> = standings.iter().map(|s| s.teamid).collect();
+    insert_planned_nodes(db, &loaded.bracket, &planned, &seeds)
+}
+
+fn teams_of_node(state: &NodeState) -> Vec<Uuid> {
+    state.seats.iter().filter_map(|s| if let Slot::Team(id) = s { Some(*id) } else { None }).collect()
+}
+
+/// Schedules every node whose teams are all known into free rooms of the given round. Nodes with a
+/// team that already has a game in that round are left for a later round. For a
+/// power-matched bracket, the next round is paired from the standings first, once the previous
+/// round is finished.
+pub fn advance(db: &mut database::Connection, bracket_id: Uuid, request: &AdvanceRequest) -> QueryResult<AdvanceReport> {
+    let bracket = read(db, bracket_id)?;
+    let round = models::round::read(db, request.roundid)?;
+    if round.did != bracket.did {
+        return Err(invalid("The round belongs to another division".to_string()));
+    }
+    let division = models::division::read(db, bracket.did)?;
+
+    let mut warnings = vec![];
+    let mut loaded = load_bracket(db, bracket_id)?;
+    if bracket.format == "power_match" {
+        let node_count = loaded.nodes.len();
+        add_power_round(db, &loaded, &mut warnings)?;
+        if read_all_nodes_of_bracket(db, bracket_id)?.len() != node_count {
+            loaded = load_bracket(db, bracket_id)?;
+        }
+    }
+
+    let ready: Vec<&BracketNode> = loaded.nodes.iter().filter(|n| loaded.states[&n.nodeid].status == "ready").collect();
+    if ready.is_empty() {
+        warnings.push("No games are ready to be scheduled".to_string());
+    }
+    let busy = busy_teams_of_round(db, round.roundid)?;
+    let mut ready_now = vec![];
+    for node in ready {
+        let conflicts: Vec<Uuid> = teams_of_node(&loaded.states[&node.nodeid]).into_iter().filter(|t| busy.contains(t)).collect();
+        if conflicts.is_empty() {
+            ready_now.push(node);
+            continue;
+        }
+        for team_id in conflicts {
+            let team = models::team::read(db, team_id)?;
+            warnings.push(format!["{} already has a game in round {}", team.name, round.name]);
+        }
+    }
+    let ready = ready_now;
+
+    let mut rooms: Vec<Room> = models::room::read_all_rooms_of_tournament(db, division.tid, &all_pages())?;
+    if let Some(room_ids) = &request.room_ids {
+        rooms.retain(|r| room_ids.contains(&r.roomid));
+    }
+    let occupied = occupied_rooms_of_round(db, round.roundid)?;
+    let free_rooms: Vec<&Room> = rooms.iter().filter(|r| !occupied.contains(&r.roomid)).collect();
+    if ready.len() > free_rooms.len() {
+        warnings.push(format!["{} games did not fit into the free rooms of round {}", ready.len() - free_rooms.len(), round.name]);
+    }
+    let assignments: Vec<(&BracketNode, &Room)> = ready.into_iter().zip(free_rooms).collect();
+    if let Some((_, room)) = assignments.iter().find(|(_, room)| room.quizmaster_id.or(request.quizmasterid).is_none()) {
+        return Err(invalid(format!["No quizmaster for room {}; pass quizmasterid", room.name]));
+    }
+
+    let created = db.transaction::<_, diesel::result::Error, _>(|conn| {
+        let mut created = vec![];
+        for (node, room) in assignments.iter() {
+            let teams = teams_of_node(&loaded.states[&node.nodeid]);
+            let new_game = GameBuilder::new_default(room.roomid, round.roundid)
+                .set_tournamentid(Some(division.tid))
+                .set_divisionid(Some(division.did))
+                .set_leftteamid(teams[0])
+                .set_centerteamid(if teams.len() == 3 { Some(teams[1]) } else { None })
+                .set_rightteamid(*teams.last().unwrap())
+                .set_quizmasterid(room.quizmaster_id.or(request.quizmasterid).unwrap())
+                .set_contentjudgeid(room.contentjudge_id)
+                .build()
+                .map_err(|e| invalid(e.join(", ")))?;
+            let game = models::game::create(conn, &new_game)?;
+            set_game_of_node(conn, node.nodeid, game.gid)?;
+            created.push(game);
+        }
+        Ok(created)
+    })?;
+
+    let mut tree = read_tree(db, bracket_id)?;
+    warnings.append(&mut tree.warnings);
+    tree.warnings = warnings.clone();
+    Ok(AdvanceReport { created, warnings, tree })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeds_of(node: &PlannedNode) -> Vec<usize> {
+        node.seats.iter().filter_map(|s| if let SeatSource::Seed(seed) = s { Some(*seed) } else { None }).collect()
+    }
+
+    fn outcome(kind: &str, teamid: Uuid) -> GameOutcome {
+        GameOutcome {
+            gid: Uuid::new_v4(),
+            outcome: kind.to_string(),
+            reason: "no_show".to_string(),
+            teamid: Some(teamid),
+            note: String::new(),
+            recorded_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn outcomes_place_every_team() {
+        let teams: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let standings: HashMap<Uuid, i32> = HashMap::from([(teams[0], 3), (teams[1], 1), (teams[2], 2)]);
+
+        // a bye in a two-team game still sends the other team on as the loser
+        let bye = outcome("bye", teams[1]);
+        assert_eq!(places_of_outcome(&bye, &teams[..2], &standings), Some(vec![teams[1], teams[0]]));
+
+        // a forfeit in a three-team game leaves the others in standings order
+        let forfeit = outcome("forfeit", teams[1]);
+        assert_eq!(places_of_outcome(&forfeit, &teams, &standings), Some(vec![teams[2], teams[0], teams[1]]));
+
+        let no_contest = GameOutcome { teamid: None, ..outcome("no_contest", teams[0]) };
+        assert_eq!(places_of_outcome(&no_contest, &teams, &standings), None);
+    }
+
+    #[test]
+    fn seed_order_keeps_top_seeds_apart() {
+        assert_eq!(seed_order(4), vec![1, 4, 2, 3]);
+        assert_eq!(seed_order(8), vec![1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn single_elimination_gives_byes_to_top_seeds() {
+        let nodes = plan_single_elimination(6);
+        assert_eq!(nodes.len(), 7);
+        assert_eq!(seeds_of(&nodes[0]), vec![1, 8]);
+        assert_eq!(nodes.iter().filter(|n| n.side == "final").count(), 1);
+        assert_eq!(nodes.last().unwrap().seats, vec![SeatSource::Place(4, 1), SeatSource::Place(5, 1)]);
+    }
+
+    #[test]
+    fn double_elimination_drops_every_loser_once() {
+        let nodes = plan_double_elimination(8);
+        // 7 winners, 6 losers and the final
+        assert_eq!(nodes.len(), 14);
+        let winners: Vec<usize> = (0..nodes.len()).filter(|i| nodes[*i].side == "winners").collect();
+        for node in winners.iter() {
+            let drops = nodes.iter().flat_map(|n| n.seats.iter()).filter(|s| **s == SeatSource::Place(*node, 2)).count();
+            assert_eq!(drops, 1, "winners node {}", node);
+        }
+        let final_node = nodes.last().unwrap();
+        assert_eq!(final_node.side, "final");
+        assert_eq!(final_node.seats[0], SeatSource::Place(6, 1));
+    }
+
+    #[test]
+    fn top_two_advances_two_from_each_full_game() {
+        let nodes = plan_top_two(9);
+        // 9 teams -> 3 games -> 6 teams -> 2 games -> 4 teams -> 2 games -> 2 teams -> final
+        let first_round: Vec<&PlannedNode> = nodes.iter().filter(|n| n.round_num == 1 && n.side == "winners").collect();
+        assert_eq!(first_round.len(), 3);
+        assert_eq!(seeds_of(first_round[0]), vec![1, 6, 7]);
+        let second_round: Vec<&PlannedNode> = nodes.iter().filter(|n| n.round_num == 2).collect();
+        assert_eq!(second_round.len(), 2);
+        assert!(second_round.iter().all(|n| n.seats.len() == 3));
+        let final_node = nodes.last().unwrap();
+        assert_eq!(final_node.side, "final");
+        assert!(final_node.seats.len() >= 2 && final_node.seats.len() <= 3);
+    }
+
+    #[test]
+    fn power_groups_split_leftovers_into_pairs() {
+        assert_eq!(power_groups(7, 3), (vec![vec![0, 1, 2], vec![3, 4], vec![5, 6]], None));
+        assert_eq!(power_groups(8, 3), (vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7]], None));
+        assert_eq!(power_groups(5, 2), (vec![vec![0, 1], vec![2, 3]], Some(4)));
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::{BTreeMap, HashMap};
+use crate::database;
+use crate::models::{self, conflict::user_name, division::Division, game::Game, room::Room, round::Round, round_robin::all_pages, team::Team, tournament::Tournament, user::User};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Games last this long in a feed; rounds only record when they start.
+pub const GAME_MINUTES: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarScope {
+    Team,
+    User,
+    Room,
+}
+
+impl CalendarScope {
+    pub fn from_param(value: &str) -> Result<Self, String> {
+        match value {
+            "team" => Ok(CalendarScope::Team),
+            "user" => Ok(CalendarScope::User),
+            "room" => Ok(CalendarScope::Room),
+            _ => Err(format!["'{}' is not a calendar scope; use team, user or room", value]),
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalendarScope::Team => "team",
+            CalendarScope::User => "user",
+            CalendarScope::Room => "room",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::calendar_feeds)]
+#[diesel(primary_key(feedid))]
+pub struct CalendarFeed {
+    pub feedid: Uuid,
+    pub token: String,                          // the secret part of the feed's URL
+    pub scope: String,                          // team, user or room
+    pub subject_id: Uuid,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable)]
+#[diesel(table_name = crate::schema::calendar_feeds)]
+pub struct NewCalendarFeed {
+    pub token: String,
+    pub scope: String,
+    pub subject_id: Uuid,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CalendarFeedRequest {
+    pub scope: String,                          // team, user or room
+    pub subject_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct CalendarFeedWithUrl {
+    #[serde(flatten)]
+    pub feed: CalendarFeed,
+    pub url: String,                            // relative to the server, e.g. /api/calendar/<token>.ics
+}
+
+impl From<CalendarFeed> for CalendarFeedWithUrl {
+    fn from(feed: CalendarFeed) -> Self {
+        let url = format!["/api/calendar/{}.ics", feed.token];
+        CalendarFeedWithUrl { feed, url }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub stamp: DateTime<Utc>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub summary: String,
+    pub location: String,
+    pub description: String,
+}
+
+/// Feeds are unique per creator, scope and subject, so asking again returns the same URL.
+pub fn find_or_create(db: &mut database::Connection, user_id: Uuid, feed_scope: CalendarScope, subject: Uuid) -> QueryResult<(CalendarFeed, bool)> {
+    use crate::schema::calendar_feeds::dsl::*;
+    let existing = calendar_feeds
+        .filter(created_by.eq(user_id))
+        .filter(scope.eq(feed_scope.as_str()))
+        .filter(subject_id.eq(subject))
+        .first::<CalendarFeed>(db)
+        .optional()?;
+    if let Some(feed) = existing {
+        return Ok((feed, false));
+    }
+    let new_feed = NewCalendarFeed {
+        token: Uuid::new_v4().simple().to_string(),
+        scope: feed_scope.as_str().to_string(),
+        subject_id: subject,
+        created_by: user_id,
+    };
+    let feed = diesel::insert_into(calendar_feeds).values(&new_feed).get_result::<CalendarFeed>(db)?;
+    Ok((feed, true))
+}
+
+pub fn read(db: &mut database::Connection, item_id: Uuid) -> QueryResult<CalendarFeed> {
+    use crate::schema::calendar_feeds::dsl::*;
+    calendar_feeds.filter(feedid.eq(item_id)).first::<CalendarFeed>(db)
+}
+
+pub fn read_by_token(db: &mut database::Connection, tok: &str) -> QueryResult<CalendarFeed> {
+    use crate::schema::calendar_feeds::dsl::*;
+    calendar_feeds.filter(token.eq(tok)).first::<CalendarFeed>(db)
+}
+
+pub fn read_all_feeds_of_user(db: &mut database::Connection, user_id: Uuid) -> QueryResult<Vec<CalendarFeed>> {
+    use crate::schema::calendar_feeds::dsl::*;
+    calendar_feeds
+        .filter(created_by.eq(user_id))
+        .order(created_at.asc())
+        .load::<CalendarFeed>(db)
+}
+
+pub fn delete(db: &mut database::Connection, item_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::calendar_feeds::dsl::*;
+    diesel::delete(calendar_feeds.filter(feedid.eq(item_id))).execute(db)
+}
+
+/// Checks that the team, user or room exists and returns its name, which names the calendar.
+pub fn subject_name(db: &mut database::Connection, scope: CalendarScope, subject: Uuid) -> QueryResult<String> {
+    match scope {
+        CalendarScope::Team => Ok(models::team::read(db, subject)?.name),
+        CalendarScope::User => Ok(user_name(&models::user::read(db, subject)?)),
+        CalendarScope::Room => Ok(models::room::read(db, subject)?.name),
+    }
+}
+
+fn games_of_teams(db: &mut database::Connection, team_ids: &[Uuid]) -> QueryResult<Vec<Game>> {
+    use crate::schema::games::dsl::*;
+    games
+        .filter(leftteamid.eq_any(team_ids).or(centerteamid.eq_any(team_ids)).or(rightteamid.eq_any(team_ids)))
+        .load::<Game>(db)
+}
+
+// The games in the feed, each with the parts the subject plays in it (only used for users).
+fn games_with_roles(db: &mut database::Connection, scope: CalendarScope, subject: Uuid) -> QueryResult<BTreeMap<Uuid, (Game, Vec<&'static str>)>> {
+    let mut found: BTreeMap<Uuid, (Game, Vec<&'static str>)> = BTreeMap::new();
+    let mut add = |games: Vec<Game>, role: &'static str| {
+        for game in games {
+            let entry = found.entry(game.gid).or_insert_with(|| (game, vec![]));
+            if !role.is_empty() && !entry.1.contains(&role) {
+                entry.1.push(role);
+            }
+        }
+    };
+    match scope {
+        CalendarScope::Team => add(games_of_teams(db, &[subject])?, ""),
+        CalendarScope::Room => {
+            use crate::schema::games::dsl::*;
+            add(games.filter(roomid.eq(subject)).load::<Game>(db)?, "");
+        },
+        CalendarScope::User => {
+            let quizzer_teams: Vec<Uuid> = models::team::read_all_teams_where_user_is_quizzer(db, subject, &all_pages())?.iter().map(|t| t.teamid).collect();
+            let coach_teams: Vec<Uuid> = models::team::read_all_teams_where_user_is_coach(db, subject, &all_pages())?.iter().map(|t| t.teamid).collect();
+            add(games_of_teams(db, &quizzer_teams)?, "quizzer");
+            add(games_of_teams(db, &coach_teams)?, "coach");
+            add(models::game::read_all_games_where_user_is_quizmaster(db, subject, &all_pages())?, "quizmaster");
+            add(models::game::read_all_games_where_user_is_contentjudge(db, subject, &all_pages())?, "content judge");
+        },
+    }
+    found.retain(|_, (game, _)| !game.ignore);
+    Ok(found)
+}
+
+/// One event per game whose round has a scheduled start time, ordered by start.
+pub fn read_events(db: &mut database::Connection, scope: CalendarScope, subject: Uuid) -> QueryResult<Vec<CalendarEvent>> {
+    use crate::schema::{divisions, rooms, rounds, teams, tournaments, users};
+
+    let found = games_with_roles(db, scope, subject)?;
+    let round_ids: Vec<Uuid> = found.values().map(|(g, _)| g.roundid).collect();
+    let room_ids: Vec<Uuid> = found.values().map(|(g, _)| g.roomid).collect();
+    let division_ids: Vec<Uuid> = found.values().map(|(g, _)| g.divisionid).collect();
+    let tournament_ids: Vec<Uuid> = found.values().map(|(g, _)| g.tournamentid).collect();
+    let team_ids: Vec<Uuid> = found
+        .values()
+        .flat_map(|(g, _)| [Some(g.leftteamid), g.centerteamid, Some(g.rightteamid)])
+        .flatten()
+        .collect();
+    let official_ids: Vec<Uuid> = found.values().flat_map(|(g, _)| [Some(g.quizmasterid), g.contentjudgeid]).flatten().collect();
+
+    let rounds: HashMap<Uuid, Round> = rounds::table.filter(rounds::roundid.eq_any(round_ids)).load::<Round>(db)?.into_iter().map(|r| (r.roundid, r)).collect();
+    let rooms: HashMap<Uuid, Room> = rooms::table.filter(rooms::roomid.eq_any(room_ids)).load::<Room>(db)?.into_iter().map(|r| (r.roomid, r)).collect();
+    let divisions: HashMap<Uuid, Division> = divisions::table.filter(divisions::did.eq_any(division_ids)).load::<Division>(db)?.into_iter().map(|d| (d.did, d)).collect();
+    let tournaments: HashMap<Uuid, Tournament> = tournaments::table.filter(tournaments::tid.eq_any(tournament_ids)).load::<Tournament>(db)?.into_iter().map(|t| (t.tid, t)).collect();
+    let team_names: HashMap<Uuid, String> = teams::table.filter(teams::teamid.eq_any(team_ids)).load::<Team>(db)?.into_iter().map(|t| (t.teamid, t.name)).collect();
+    let official_names: HashMap<Uuid, String> = users::table.filter(users::id.eq_any(official_ids)).load::<User>(db)?.iter().map(|u| (u.id, user_name(u))).collect();
+
+    let mut events = vec![];
+    for (game, roles) in found.values() {
+        let Some(round) = rounds.get(&game.roundid) else { continue };
+        let Some(start) = models::timeline::projected_start(round, game) else { continue };
+        let room = rooms.get(&game.roomid);
+        let teams: Vec<String> = [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)]
+            .into_iter()
+            .flatten()
+            .map(|t| team_names.get(&t).cloned().unwrap_or_default())
+            .collect();
+        let location = match room {
+            Some(r) if !r.building.is_empty() => format!["{}, {}", r.name, r.building],
+            Some(r) => r.name.clone(),
+            None => String::new(),
+        };
+
+        let mut description = vec![];
+        if let Some(tournament) = tournaments.get(&game.tournamentid) {
+            description.push(tournament.tname.clone());
+        }
+        if let Some(division) = divisions.get(&game.divisionid) {
+            description.push(format!["Division: {}", division.dname]);
+        }
+        if let Some(quizmaster) = official_names.get(&game.quizmasterid) {
+            description.push(format!["Quizmaster: {}", quizmaster]);
+        }
+        if let Some(contentjudge) = game.contentjudgeid.and_then(|id| official_names.get(&id)) {
+            description.push(format!["Content judge: {}", contentjudge]);
+        }
+        if !roles.is_empty() {
+            description.push(format!["Your part: {}", roles.join(", ")]);
+        }
+
+        events.push(CalendarEvent {
+            uid: format!["{}@qview", game.gid],
+            stamp: game.updated_at.max(round.updated_at),
+            start,
+            end: start + Duration::minutes(GAME_MINUTES),
+            summary: format!["{}: {}", round.name, teams.join(" vs ")],
+            location,
+            description: description.join("\n"),
+        });
+    }
+    events.sort_by(|a, b| (a.start, &a.location).cmp(&(b.start, &b.location)));
+    Ok(events)
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+// Content lines longer than 75 octets continue on the next line after a space (RFC 5545 3.1).
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+pub fn to_ics(calendar_name: &str, events: &[CalendarEvent]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//QuizStuff//QView//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!["X-WR-CALNAME:{}", escape_text(calendar_name)],
+    ];
+    for event in events.iter() {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!["UID:{}", event.uid]);
+        lines.push(format!["DTSTAMP:{}", format_time(&event.stamp)]);
+        lines.push(format!["DTSTART:{}", format_time(&event.start)]);
+        lines.push(format!["DTEND:{}", format_time(&event.end)]);
+        lines.push(format!["SUMMARY:{}", escape_text(&event.summary)]);
+        if !event.location.is_empty() {
+            lines.push(format!["LOCATION:{}", escape_text(&event.location)]);
+        }
+        lines.push(format!["DESCRIPTION:{}", escape_text(&event.description)]);
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold_line(l)).collect()
+}
+
+/// The calendar a feed serves. Fails if the feed's team, user or room has been deleted.
+pub fn feed_ics(db: &mut database::Connection, feed: &CalendarFeed) -> QueryResult<String> {
+    let scope = CalendarScope::from_param(&feed.scope).map_err(|e| diesel::result::Error::QueryBuilderError(e.into()))?;
+    let name = subject_name(db, scope, feed.subject_id)?;
+    let events = read_events(db, scope, feed.subject_id)?;
+    Ok(to_ics(&name, &events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn to_ics_escapes_and_folds() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 1, 9, 0, 0).unwrap();
+        let event = CalendarEvent {
+            uid: "1@qview".to_string(),
+            stamp: start,
+            start,
+            end: start + Duration::minutes(GAME_MINUTES),
+            summary: "Round 1: Eagles, Gold vs Hawks; Red".to_string(),
+            location: "Room 1".to_string(),
+            description: format!["Line one\n{}", "x".repeat(100)],
+        };
+
+        let ics = to_ics("Team A", &[event]);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20260601T090000Z\r\n"));
+        assert!(ics.contains("DTEND:20260601T093000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Round 1: Eagles\\, Gold vs Hawks\\; Red\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!["DESCRIPTION:Line one\\n{}\r\n", "x".repeat(100)]));
+    }
+}
@@ -0,0 +1,268 @@
+use std::io::{Cursor, Write};
+use crate::models::{award::{DivisionAwards, QuizzerAward}, tournament::Tournament};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Placeholders in a template are replaced with XML-escaped values when a certificate is rendered.
+pub const DEFAULT_SVG_TEMPLATE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="11in" height="8.5in" viewBox="0 0 792 612">
+  <rect x="0" y="0" width="792" height="612" fill="#ffffff"/>
+  <rect x="24" y="24" width="744" height="564" fill="none" stroke="#1f3a5f" stroke-width="6"/>
+  <rect x="36" y="36" width="720" height="540" fill="none" stroke="#1f3a5f" stroke-width="1.5"/>
+  <text x="396" y="130" text-anchor="middle" font-family="serif" font-size="40" font-weight="bold" fill="#1f3a5f">Certificate of Achievement</text>
+  <text x="396" y="190" text-anchor="middle" font-family="serif" font-size="18" fill="#333333">This certificate is presented to</text>
+  <text x="396" y="255" text-anchor="middle" font-family="serif" font-size="38" font-weight="bold" fill="#000000">{{recipient}}</text>
+  <text x="396" y="290" text-anchor="middle" font-family="serif" font-size="18" fill="#333333">{{team}}</text>
+  <text x="396" y="350" text-anchor="middle" font-family="serif" font-size="28" font-weight="bold" fill="#1f3a5f">{{placement}}</text>
+  <text x="396" y="385" text-anchor="middle" font-family="serif" font-size="20" fill="#333333">{{award}}</text>
+  <text x="396" y="450" text-anchor="middle" font-family="serif" font-size="20" fill="#000000">{{tname}} &#8212; {{dname}}</text>
+  <text x="396" y="480" text-anchor="middle" font-family="serif" font-size="16" fill="#333333">{{venue}}, {{fromdate}}</text>
+</svg>
+"##;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Certificate {
+    pub recipient: String,   // quizzer or team name
+    pub team: String,        // the quizzer's team; empty for team awards
+    pub award: String,       // category, e.g. "Top Individual Scorers"
+    pub placement: String,   // e.g. "1st Place" or "Tied for 2nd Place"
+    pub tname: String,
+    pub fromdate: String,
+    pub venue: String,
+    pub dname: String,
+}
+
+pub enum CertificateFormat {
+    Svg,
+    Pdf,
+}
+
+impl CertificateFormat {
+    pub fn from_param(format: &Option<String>) -> Result<CertificateFormat, String> {
+        match format.as_deref().map(|f| f.to_lowercase()) {
+            None => Ok(CertificateFormat::Svg),
+            Some(f) if f == "svg" => Ok(CertificateFormat::Svg),
+            Some(f) if f == "pdf" => Ok(CertificateFormat::Pdf),
+            Some(f) => Err(format!["Unsupported certificate format '{}'; expected svg or pdf", f]),
+        }
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CertificateFormat::Svg => "svg",
+            CertificateFormat::Pdf => "pdf",
+        }
+    }
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            CertificateFormat::Svg => "image/svg+xml",
+            CertificateFormat::Pdf => "application/pdf",
+        }
+    }
+}
+
+pub fn ordinal(n: i32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!["{}{}", n, suffix]
+}
+
+pub fn placement_text(place: i32, is_tied: bool) -> String {
+    if is_tied {
+        format!["Tied for {} Place", ordinal(place)]
+    } else {
+        format!["{} Place", ordinal(place)]
+    }
+}
+
+fn new_certificate(recipient: &str, team: &str, award: &str, placement: String, tournament: &Tournament, dname: &str) -> Certificate {
+    Certificate {
+        recipient: recipient.to_string(),
+        team: team.to_string(),
+        award: award.to_string(),
+        placement,
+        tname: tournament.tname.clone(),
+        fromdate: tournament.fromdate.format("%B %-d, %Y").to_string(),
+        venue: tournament.venue.clone(),
+        dname: dname.to_string(),
+    }
+}
+
+fn quizzer_certificates(certificates: &mut Vec<Certificate>, award: &str, awards: &[QuizzerAward], tournament: &Tournament, dname: &str) {
+    for a in awards.iter() {
+        certificates.push(new_certificate(
+            &a.quizzer.quizzer_name,
+            &a.quizzer.team_name,
+            award,
+            placement_text(a.place, a.is_tied),
+            tournament,
+            dname,
+        ));
+    }
+}
+
+/// One certificate per award entry, in the same category order as the printable awards page.
+/// Perfect games are not ranked, so they are not given a placement.
+pub fn certificates_of_division_awards(awards: &DivisionAwards, tournament: &Tournament) -> Vec<Certificate> {
+    let mut certificates = Vec::new();
+    for a in awards.team_placements.iter() {
+        certificates.push(new_certificate(
+            &a.team.team_name,
+            "",
+            "Team Placement",
+            placement_text(a.place, a.is_tied),
+            tournament,
+            &awards.dname,
+        ));
+    }
+    quizzer_certificates(&mut certificates, "Top Individual Scorers", &awards.individual_scorers, tournament, &awards.dname);
+    for a in awards.perfect_games.iter() {
+        let games = if a.value == 1 { "1 Perfect Game".to_string() } else { format!["{} Perfect Games", a.value] };
+        certificates.push(new_certificate(&a.quizzer.quizzer_name, &a.quizzer.team_name, "Perfect Games", games, tournament, &awards.dname));
+    }
+    quizzer_certificates(&mut certificates, "Most Quiz-Outs", &awards.quiz_outs, tournament, &awards.dname);
+    quizzer_certificates(&mut certificates, "Rookie Leaderboard", &awards.rookies, tournament, &awards.dname);
+    certificates
+}
+
+/// A made-up certificate used to preview a tournament's template.
+pub fn sample_certificate(tournament: &Tournament) -> Certificate {
+    new_certificate("Sample Quizzer", "Sample Team", "Top Individual Scorers", placement_text(1, false), tournament, "Sample Division")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn render_svg(template: &str, certificate: &Certificate) -> String {
+    template
+        .replace("{{recipient}}", &escape_xml(&certificate.recipient))
+        .replace("{{team}}", &escape_xml(&certificate.team))
+        .replace("{{award}}", &escape_xml(&certificate.award))
+        .replace("{{placement}}", &escape_xml(&certificate.placement))
+        .replace("{{tname}}", &escape_xml(&certificate.tname))
+        .replace("{{fromdate}}", &escape_xml(&certificate.fromdate))
+        .replace("{{venue}}", &escape_xml(&certificate.venue))
+        .replace("{{dname}}", &escape_xml(&certificate.dname))
+}
+
+// PDF string literals use the built-in fonts' WinAnsiEncoding; anything outside Latin-1 is replaced.
+fn escape_pdf_text(s: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    for c in s.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(c as u8);
+            },
+            c if (c as u32) >= 0x20 && (c as u32) < 0x7f => out.push(c as u8),
+            c if (c as u32) >= 0xa0 && (c as u32) <= 0xff => out.push(c as u32 as u8),
+            _ => out.push(b'?'),
+        }
+    }
+    out
+}
+
+// Helvetica has no metrics table here, so centering uses an average glyph width.
+fn centered_text(content: &mut Vec<u8>, font: &str, size: f32, y: f32, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let width = text.chars().count() as f32 * size * 0.5;
+    let x = ((792.0 - width) / 2.0).max(40.0);
+    content.extend_from_slice(format!["BT /{} {} Tf {:.1} {:.1} Td (", font, size, x, y].as_bytes());
+    content.extend_from_slice(&escape_pdf_text(text));
+    content.extend_from_slice(b") Tj ET\n");
+}
+
+/// Renders the built-in certificate layout as a single landscape letter page. Uploaded SVG templates
+/// only apply to SVG output; PDF output is produced without any external renderer.
+pub fn render_pdf(certificate: &Certificate) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(b"0.12 0.23 0.37 RG 6 w 24 24 744 564 re S 1.5 w 36 36 720 540 re S\n");
+    content.extend_from_slice(b"0.12 0.23 0.37 rg\n");
+    centered_text(&mut content, "F2", 34.0, 482.0, "Certificate of Achievement");
+    content.extend_from_slice(b"0.2 0.2 0.2 rg\n");
+    centered_text(&mut content, "F1", 16.0, 422.0, "This certificate is presented to");
+    content.extend_from_slice(b"0 0 0 rg\n");
+    centered_text(&mut content, "F2", 32.0, 357.0, &certificate.recipient);
+    centered_text(&mut content, "F1", 16.0, 322.0, &certificate.team);
+    content.extend_from_slice(b"0.12 0.23 0.37 rg\n");
+    centered_text(&mut content, "F2", 24.0, 262.0, &certificate.placement);
+    content.extend_from_slice(b"0.2 0.2 0.2 rg\n");
+    centered_text(&mut content, "F1", 18.0, 227.0, &certificate.award);
+    content.extend_from_slice(b"0 0 0 rg\n");
+    centered_text(&mut content, "F1", 18.0, 162.0, &format!["{} - {}", certificate.tname, certificate.dname]);
+    centered_text(&mut content, "F1", 14.0, 132.0, &format!["{}, {}", certificate.venue, certificate.fromdate]);
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /MediaBox [0 0 792 612] /Resources << /Font << /F1 4 0 R /F2 5 0 R >> >> /Contents 6 0 R >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    let mut stream = format!["<< /Length {} >>\nstream\n", content.len()].into_bytes();
+    stream.extend_from_slice(&content);
+    stream.extend_from_slice(b"endstream");
+    objects.push(stream);
+
+    let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!["{} 0 obj\n", i + 1].as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref_offset = pdf.len();
+    pdf.extend_from_slice(format!["xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1].as_bytes());
+    for offset in offsets.iter() {
+        pdf.extend_from_slice(format!["{:010} 00000 n \n", offset].as_bytes());
+    }
+    pdf.extend_from_slice(format!["trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref_offset].as_bytes());
+    pdf
+}
+
+pub fn render(format: &CertificateFormat, template: &str, certificate: &Certificate) -> Vec<u8> {
+    match format {
+        CertificateFormat::Svg => render_svg(template, certificate).into_bytes(),
+        CertificateFormat::Pdf => render_pdf(certificate),
+    }
+}
+
+fn sanitize_file_name(s: &str) -> String {
+    let cleaned: String = s
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    cleaned.trim_matches('_').to_string()
+}
+
+pub fn file_name(index: usize, certificate: &Certificate, format: &CertificateFormat) -> String {
+    format![
+        "{:02}-{}-{}.{}",
+        index + 1,
+        sanitize_file_name(&certificate.award),
+        sanitize_file_name(&certificate.recipient),
+        format.extension()
+    ]
+}
+
+/// Renders every certificate and bundles them into a single zip archive.
+pub fn build_archive(format: &CertificateFormat, template: &str, certificates: &[Certificate]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (i, certificate) in certificates.iter().enumerate() {
+        archive.start_file(file_name(i, certificate, format), options)?;
+        archive.write_all(&render(format, template, certificate))?;
+    }
+    Ok(archive.finish()?.into_inner())
+}
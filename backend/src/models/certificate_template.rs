@@ -0,0 +1,375 @@
+use crate::database;
+use diesel::*;
+use diesel::{QueryResult,AsChangeset,Insertable,Identifiable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use chrono::{DateTime,Utc};
+use uuid::Uuid;
+
+pub const MAX_TEMPLATE_BYTES: usize = 512 * 1024;
+
+pub struct CertificateTemplateBuilder {
+    tid: Uuid,
+    name: Option<String>,
+    svg: Option<String>,
+}
+
+impl CertificateTemplateBuilder {
+    pub fn new(tid: Uuid) -> Self {
+        CertificateTemplateBuilder {
+            tid,
+            name: None,
+            svg: None,
+        }
+    }
+    pub fn new_default(tid: Uuid) -> Self {
+        CertificateTemplateBuilder {
+            tid,
+            name: Some("Default".to_string()),
+            svg: Some(crate::models::certificate::DEFAULT_SVG_TEMPLATE.to_string()),
+        }
+    }
+    pub fn set_name(mut self, val: &str) -> Self {
+        self.name = Some(val.to_string());
+        self
+    }
+    pub fn set_svg(mut self, val: &str) -> Self {
+        self.svg = Some(val.to_string());
+        self
+    }
+    fn validate_all_are_some(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.name.is_none() {
+            errors.push("name is required".to_string());
+        }
+        match &self.svg {
+            None => errors.push("svg is required".to_string()),
+            Some(svg) => {
+                if let Err(e) = validate_svg(svg) {
+                    errors.push(e);
+                }
+            },
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(())
+    }
+    pub fn build(self) -> Result<NewCertificateTemplate, Vec<String>> {
+        match self.validate_all_are_some() {
+            Err(e) => {
+                Err(e)
+            },
+            Ok(_) => {
+                Ok(
+                    NewCertificateTemplate {
+                        tid: self.tid,
+                        name: self.name.unwrap(),
+                        svg: self.svg.unwrap(),
+                    }
+                )
+            }
+        }
+    }
+    pub fn build_and_insert(self, db: &mut database::Connection) -> QueryResult<CertificateTemplate> {
+        let new_template = self.build();
+        match new_template {
+            Ok(template) => create(db, &template),
+            Err(e) => {
+                let message = e.join(", ");
+                Err(diesel::result::Error::QueryBuilderError(message.into()))
+            }
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Queryable,
+    Selectable,
+    Identifiable,
+    ToSchema
+)]
+#[diesel(table_name = crate::schema::certificate_templates)]
+#[diesel(primary_key(tid))]
+pub struct CertificateTemplate {
+    pub tid: Uuid,
+    pub name: String,
+    pub svg: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(
+    Insertable,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone
+)]
+#[diesel(table_name = crate::schema::certificate_templates)]
+pub struct NewCertificateTemplate {
+    pub tid: Uuid,
+    pub name: String,
+    pub svg: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, AsChangeset)]
+#[diesel(table_name = crate::schema::certificate_templates)]
+pub struct CertificateTemplateChangeset {
+    pub name: String,
+    pub svg: String,
+}
+
+// The elements a certificate needs: shapes, text, paint servers and grouping. Everything else,
+// including scripts, animation, embedded documents, stylesheets and images, is rejected.
+const ALLOWED_ELEMENTS: [&str; 26] = [
+    "svg", "g", "defs", "title", "desc", "symbol", "use", "marker",
+    "rect", "circle", "ellipse", "line", "polyline", "polygon", "path",
+    "text", "tspan", "textpath",
+    "lineargradient", "radialgradient", "stop", "pattern", "clippath", "mask", "filter", "fegaussianblur",
+];
+
+const SVG_NAMESPACES: [&str; 2] = ["http://www.w3.org/2000/svg", "http://www.w3.org/1999/xlink"];
+
+/// Templates are served to browsers and rendered offline, so they must be inert and
+/// self-contained: only the elements in `ALLOWED_ELEMENTS` are accepted, event handler (`on*`)
+/// attributes are rejected, and links and `url(...)` references may only point into the document.
+pub fn validate_svg(svg: &str) -> Result<(), String> {
+    if svg.len() > MAX_TEMPLATE_BYTES {
+        return Err(format!["Template is larger than the {} byte limit", MAX_TEMPLATE_BYTES]);
+    }
+    let mut rest = svg;
+    let mut is_first = true;
+    while let Some(idx) = rest.find('<') {
+        rest = &rest[idx..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = skip_past(comment, "-->")?;
+            continue;
+        }
+        if rest.starts_with("<?xml") {
+            rest = skip_past(&rest[2..], "?>")?;
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            return Err("Template must not contain a DOCTYPE, entity declarations, CDATA or processing instructions".to_string());
+        }
+        if let Some(closing) = rest.strip_prefix("</") {
+            rest = skip_past(closing, ">")?;
+            continue;
+        }
+        let (name, attributes, after) = parse_tag(&rest[1..])?;
+        let name = name.to_lowercase();
+        if is_first && name != "svg" {
+            return Err("Template is not an SVG document".to_string());
+        }
+        is_first = false;
+        if !ALLOWED_ELEMENTS.contains(&name.as_str()) {
+            return Err(format!["Template must not use the <{}> element", name]);
+        }
+        for (attribute, value) in attributes {
+            check_attribute(&attribute.to_lowercase(), &decode_references(value)?)?;
+        }
+        rest = after;
+    }
+    if is_first {
+        return Err("Template is not an SVG document".to_string());
+    }
+    Ok(())
+}
+
+fn skip_past<'a>(text: &'a str, end: &str) -> Result<&'a str, String> {
+    match text.find(end) {
+        Some(idx) => Ok(&text[idx + end.len()..]),
+        None => Err("Template is not well-formed".to_string()),
+    }
+}
+
+// An opening tag's name, its attributes and what follows the tag.
+type Tag<'a> = (&'a str, Vec<(&'a str, &'a str)>, &'a str);
+
+/// Splits an opening tag (after its '<') into its name, its attributes and what follows the tag.
+fn parse_tag(text: &str) -> Result<Tag<'_>, String> {
+    let malformed = || "Template is not well-formed".to_string();
+    let name_end = text.find(|c: char| c.is_whitespace() || c == '/' || c == '>').ok_or_else(malformed)?;
+    let name = &text[..name_end];
+    if name.is_empty() || name.contains(':') {
+        return Err(format!["Template must not use the <{}> element", name]);
+    }
+    let mut attributes = vec![];
+    let mut rest = &text[name_end..];
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>").or_else(|| rest.strip_prefix('>')) {
+            return Ok((name, attributes, after));
+        }
+        let attribute_end = rest.find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/').ok_or_else(malformed)?;
+        let attribute = &rest[..attribute_end];
+        if attribute.is_empty() {
+            return Err(malformed());
+        }
+        rest = rest[attribute_end..].trim_start();
+        let Some(after_equals) = rest.strip_prefix('=') else {
+            attributes.push((attribute, ""));
+            continue;
+        };
+        rest = after_equals.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'').ok_or_else(malformed)?;
+        let value_end = rest[1..].find(quote).ok_or_else(malformed)?;
+        attributes.push((attribute, &rest[1..1 + value_end]));
+        rest = &rest[value_end + 2..];
+    }
+}
+
+/// Decodes the character references in an attribute value, so that `javascript&#58;` reads as
+/// what a browser would see.
+fn decode_references(value: &str) -> Result<String, String> {
+    let mut decoded = String::new();
+    let mut rest = value;
+    while let Some(idx) = rest.find('&') {
+        decoded.push_str(&rest[..idx]);
+        let end = rest[idx..].find(';').ok_or_else(|| "Template has an unterminated character reference".to_string())?;
+        let reference = &rest[idx + 1..idx + end];
+        let character = match reference {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match reference.strip_prefix("#x").or_else(|| reference.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => reference.strip_prefix('#').and_then(|dec| dec.parse::<u32>().ok()).and_then(char::from_u32),
+            },
+        };
+        decoded.push(character.ok_or_else(|| format!["Template uses an unknown character reference '&{};'", reference])?);
+        rest = &rest[idx + end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+fn check_attribute(attribute: &str, value: &str) -> Result<(), String> {
+    if attribute.starts_with("on") {
+        return Err(format!["Template must not use event handler attributes (found '{}')", attribute]);
+    }
+    if attribute == "xmlns" || attribute.starts_with("xmlns:") {
+        if !SVG_NAMESPACES.contains(&value) {
+            return Err(format!["Template must not declare the namespace '{}'", value]);
+        }
+        return Ok(());
+    }
+    if attribute.contains(':') && !["xlink:href", "xml:space", "xml:lang"].contains(&attribute) {
+        return Err(format!["Template must not use the '{}' attribute", attribute]);
+    }
+    // browsers ignore whitespace and control characters inside a URL's scheme
+    let squeezed: String = value.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect::<String>().to_lowercase();
+    if (attribute == "href" || attribute == "xlink:href") && !squeezed.starts_with('#') {
+        return Err(format!["Template must not reference external resources or scripts (found '{}')", value]);
+    }
+    for scheme in ["javascript:", "vbscript:", "data:", "expression(", "@import"] {
+        if squeezed.contains(scheme) {
+            return Err(format!["Template must not reference external resources or scripts (found '{}')", scheme]);
+        }
+    }
+    if squeezed.match_indices("url(").any(|(idx, _)| !squeezed[idx + 4..].trim_start_matches(['"', '\'']).starts_with('#')) {
+        return Err(format!["Template must not reference external resources or scripts (found '{}')", value]);
+    }
+    Ok(())
+}
+
+pub fn create(db: &mut database::Connection, item: &NewCertificateTemplate) -> QueryResult<CertificateTemplate> {
+    use crate::schema::certificate_templates::dsl::*;
+    insert_into(certificate_templates).values(item).get_result::<CertificateTemplate>(db)
+}
+
+pub fn exists(db: &mut database::Connection, tournament_id: Uuid) -> bool {
+    use crate::schema::certificate_templates::dsl::*;
+    certificate_templates
+        .find(tournament_id)
+        .get_result::<CertificateTemplate>(db)
+        .is_ok()
+}
+
+pub fn read(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<CertificateTemplate> {
+    use crate::schema::certificate_templates::dsl::*;
+    certificate_templates.filter(tid.eq(tournament_id)).first::<CertificateTemplate>(db)
+}
+
+/// Returns the tournament's uploaded SVG template, or the built-in one if none was uploaded.
+pub fn read_svg_or_default(db: &mut database::Connection, tournament_id: Uuid) -> String {
+    match read(db, tournament_id) {
+        Ok(template) => template.svg,
+        Err(_) => crate::models::certificate::DEFAULT_SVG_TEMPLATE.to_string(),
+    }
+}
+
+pub fn create_or_update(db: &mut database::Connection, tournament_id: Uuid, item: &CertificateTemplateChangeset) -> QueryResult<CertificateTemplate> {
+    use crate::schema::certificate_templates::dsl::*;
+
+    if let Err(e) = validate_svg(&item.svg) {
+        return Err(diesel::result::Error::QueryBuilderError(e.into()));
+    }
+
+    if !exists(db, tournament_id) {
+        return CertificateTemplateBuilder::new(tournament_id)
+            .set_name(&item.name)
+            .set_svg(&item.svg)
+            .build_and_insert(db);
+    }
+
+    diesel::update(certificate_templates.filter(tid.eq(tournament_id)))
+        .set((
+            item,
+            updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(db)
+}
+
+pub fn delete(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::certificate_templates::dsl::*;
+    diesel::delete(certificate_templates.filter(tid.eq(tournament_id))).execute(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn svg(body: &str) -> String {
+        format!["<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\">{}</svg>", body]
+    }
+
+    #[test]
+    fn validate_svg_accepts_inert_templates() {
+        assert!(validate_svg(crate::models::certificate::DEFAULT_SVG_TEMPLATE).is_ok());
+        assert!(validate_svg(&svg("<defs><linearGradient id=\"g\"><stop offset=\"0\"/></linearGradient></defs><rect fill=\"url(#g)\"/><use xlink:href=\"#g\"/>")).is_ok());
+        assert!(validate_svg(&format!["<?xml version=\"1.0\"?><!-- a certificate -->{}", svg("<text>A &amp; B</text>")]).is_ok());
+    }
+
+    #[test]
+    fn validate_svg_rejects_scripts_and_handlers() {
+        for body in [
+            "<script>alert(1)</script>",
+            "<foreignObject><div/></foreignObject>",
+            "<rect onload=\"alert(1)\"/>",
+            "<rect ONCLICK='alert(1)'/>",
+            "<use href=\"javascript:alert(1)\"/>",
+            "<use xlink:href=\"java&#x09;script&#58;alert(1)\"/>",
+            "<use href=\"data:image/svg+xml;base64,PHN2Zy8+\"/>",
+            "<animate attributeName=\"href\" to=\"javascript:alert(1)\"/>",
+            "<set attributeName=\"onmouseover\" to=\"alert(1)\"/>",
+            "<image href=\"https://example.com/logo.png\"/>",
+            "<rect fill=\"url(https://example.com/paint)\"/>",
+            "<rect style=\"fill: url( 'data:,x')\"/>",
+            "<x:script xmlns:x=\"http://www.w3.org/2000/svg\"/>",
+        ] {
+            assert!(validate_svg(&svg(body)).is_err(), "{} was accepted", body);
+        }
+        assert!(validate_svg("<!DOCTYPE svg [<!ENTITY x \"y\">]><svg/>").is_err());
+        assert!(validate_svg("<svg xmlns=\"http://www.w3.org/1999/xhtml\"/>").is_err());
+        assert!(validate_svg("<html><svg/></html>").is_err());
+        assert!(validate_svg("just text").is_err());
+    }
+}
@@ -31,3 +31,39 @@ pub struct SearchDateParams {
 pub struct TournamentParam {
     pub tid: Uuid,
 }
+
+#[derive(serde::Deserialize)]
+pub struct CertificateParams {
+    pub format: Option<String>,  // "svg" (default) or "pdf"
+}
+
+#[derive(serde::Deserialize)]
+pub struct ExportParams {
+    pub format: Option<String>,  // "csv" (default) or "xlsx"
+    pub report: Option<String>,  // games, standings, quizzers, events or challenges; all of them when omitted (xlsx only)
+}
+
+#[derive(serde::Deserialize)]
+pub struct EventlogExportParams {
+    pub source: Option<String>,  // "eventlogs" (default) or "gameevents"
+}
+
+#[derive(serde::Deserialize)]
+pub struct RebuildParams {
+    pub apply: Option<bool>,  // dry run unless true
+}
+
+/// A validation failure, which the services answer with a 400.
+pub fn invalid(message: String) -> diesel::result::Error {
+    diesel::result::Error::QueryBuilderError(message.into())
+}
+
+/// A name as it's compared with names typed by a scorekeeper: lowercase, single-spaced.
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
+
+/// Escapes the LIKE wildcards in user input, so that it only matches itself.
+pub fn escape_like(input: &str) -> String {
+    input.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
@@ -0,0 +1,92 @@
+use crate::database;
+use crate::models::common::invalid;
+use diesel::prelude::*;
+use diesel::*;
+use diesel::{QueryResult,AsChangeset,Insertable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+// A level of competition, e.g. Novice or Experienced. Its number is the competition_lvl of the
+// question bank: 0 is for practice and higher numbers are for higher levels.
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::competition_levels)]
+#[diesel(primary_key(level))]
+pub struct CompetitionLevel {
+    pub level: i32,                             // the competition_lvl of the question bank
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable, ToSchema)]
+#[diesel(table_name = crate::schema::competition_levels)]
+pub struct NewCompetitionLevel {
+    pub level: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, AsChangeset, ToSchema)]
+#[diesel(table_name = crate::schema::competition_levels)]
+pub struct CompetitionLevelChangeset {
+    pub name: Option<String>,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.len() > 32 {
+        return Err("name must be between 1 and 32 characters".to_string());
+    }
+    Ok(())
+}
+
+pub fn create(db: &mut database::Connection, item: &NewCompetitionLevel) -> QueryResult<CompetitionLevel> {
+    use crate::schema::competition_levels::dsl::*;
+    if item.level < 0 {
+        return Err(invalid("level can't be negative".to_string()));
+    }
+    validate_name(&item.name).map_err(invalid)?;
+    insert_into(competition_levels).values(item).get_result::<CompetitionLevel>(db)
+}
+
+pub fn exists(db: &mut database::Connection, item_level: i32) -> bool {
+    use crate::schema::competition_levels::dsl::*;
+    competition_levels
+        .find(item_level)
+        .get_result::<CompetitionLevel>(db)
+        .is_ok()
+}
+
+/// Fails with a message for the caller when there's no such level.
+pub fn check_exists(db: &mut database::Connection, item_level: i32) -> QueryResult<()> {
+    if exists(db, item_level) { Ok(()) } else { Err(invalid(format!["There is no competition level {}", item_level])) }
+}
+
+pub fn read(db: &mut database::Connection, item_level: i32) -> QueryResult<CompetitionLevel> {
+    use crate::schema::competition_levels::dsl::*;
+    competition_levels.find(item_level).first::<CompetitionLevel>(db)
+}
+
+pub fn read_all(db: &mut database::Connection) -> QueryResult<Vec<CompetitionLevel>> {
+    use crate::schema::competition_levels::dsl::*;
+    competition_levels.order(level.asc()).load::<CompetitionLevel>(db)
+}
+
+pub fn update(db: &mut database::Connection, item_level: i32, item: &CompetitionLevelChangeset) -> QueryResult<CompetitionLevel> {
+    use crate::schema::competition_levels::dsl::*;
+    if let Some(new_name) = &item.name {
+        validate_name(new_name).map_err(invalid)?;
+    }
+    diesel::update(competition_levels.find(item_level))
+        .set((
+            item,
+            updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(db)
+}
+
+/// Deletes a level nothing refers to; questions and divisions keep theirs.
+pub fn delete(db: &mut database::Connection, item_level: i32) -> QueryResult<usize> {
+    use crate::schema::competition_levels::dsl::*;
+    diesel::delete(competition_levels.find(item_level)).execute(db)
+}
@@ -0,0 +1,296 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::database;
+use crate::models::{game::Game, room::Room, team::Team, user::User};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Games happen at the same time when their rounds share a scheduled start time. Rounds without a
+// start time can only be compared with themselves. Ignored games are left out.
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Slot {
+    Time(DateTime<Utc>),
+    Round(Uuid),
+}
+
+#[derive(Debug, Clone)]
+pub struct SlotGame {
+    pub gid: Uuid,
+    pub slot: Slot,
+    pub roomid: Uuid,
+    pub teams: Vec<Uuid>,
+    pub officials: Vec<Uuid>,           // quizmaster, then content judge if there is one
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub kind: &'static str,
+    pub slot: Slot,
+    pub subject: Uuid,
+    pub gids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ScheduleConflict {
+    pub kind: String,                           // team, room, official, quizzer or room_official
+    pub scheduled_start_time: Option<DateTime<Utc>>,
+    pub roundid: Option<Uuid>,                  // set instead when the round has no start time
+    pub subject_id: Uuid,                       // the team, room or user booked twice
+    pub subject_name: String,
+    pub gids: Vec<Uuid>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ConflictReport {
+    pub tid: Uuid,
+    pub games_checked: i32,
+    pub conflicts: Vec<ScheduleConflict>,
+}
+
+// The response to game create and update: the game's own fields, plus the conflicts it is part of.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameWithConflicts {
+    #[serde(flatten)]
+    pub game: Game,
+    pub conflicts: Vec<ScheduleConflict>,
+}
+
+fn booked_twice(kind: &'static str, slot: &Slot, bookings: BTreeMap<Uuid, Vec<Uuid>>, conflicts: &mut Vec<Conflict>) {
+    for (subject, mut gids) in bookings {
+        gids.sort();
+        gids.dedup();
+        if gids.len() > 1 {
+            conflicts.push(Conflict { kind, slot: slot.clone(), subject, gids });
+        }
+    }
+}
+
+/// Finds everything booked twice in one slot: teams, rooms, officials (as quizmaster or content
+/// judge), quizzers on the rosters of two teams that are both playing, and users who are the
+/// standing official of two rooms that both have a game.
+pub fn find_conflicts(
+    games: &[SlotGame],
+    team_quizzers: &HashMap<Uuid, Vec<Uuid>>,
+    room_officials: &HashMap<Uuid, Vec<Uuid>>,
+) -> Vec<Conflict> {
+    let mut by_slot: BTreeMap<&Slot, Vec<&SlotGame>> = BTreeMap::new();
+    for game in games.iter() {
+        by_slot.entry(&game.slot).or_default().push(game);
+    }
+
+    let mut conflicts = vec![];
+    for (slot, slot_games) in by_slot {
+        let mut teams: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+        let mut rooms: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+        let mut officials: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+        let mut quizzers: BTreeMap<Uuid, Vec<(Uuid, Uuid)>> = BTreeMap::new();
+        let mut standing_officials: BTreeMap<Uuid, HashSet<Uuid>> = BTreeMap::new();
+        for game in slot_games.iter() {
+            rooms.entry(game.roomid).or_default().push(game.gid);
+            for team in game.teams.iter() {
+                teams.entry(*team).or_default().push(game.gid);
+                for quizzer in team_quizzers.get(team).into_iter().flatten() {
+                    quizzers.entry(*quizzer).or_default().push((*team, game.gid));
+                }
+            }
+            for official in game.officials.iter() {
+                officials.entry(*official).or_default().push(game.gid);
+            }
+            for official in room_officials.get(&game.roomid).into_iter().flatten() {
+                standing_officials.entry(*official).or_default().insert(game.roomid);
+            }
+        }
+        booked_twice("team", slot, teams, &mut conflicts);
+        booked_twice("room", slot, rooms, &mut conflicts);
+        booked_twice("official", slot, officials, &mut conflicts);
+
+        for (quizzer, entries) in quizzers {
+            let quizzer_teams: HashSet<Uuid> = entries.iter().map(|(team, _)| *team).collect();
+            if quizzer_teams.len() > 1 {
+                let mut gids: Vec<Uuid> = entries.iter().map(|(_, gid)| *gid).collect();
+                gids.sort();
+                gids.dedup();
+                conflicts.push(Conflict { kind: "quizzer", slot: slot.clone(), subject: quizzer, gids });
+            }
+        }
+        for (official, official_rooms) in standing_officials {
+            if official_rooms.len() > 1 {
+                let mut gids: Vec<Uuid> = slot_games.iter().filter(|g| official_rooms.contains(&g.roomid)).map(|g| g.gid).collect();
+                gids.sort();
+                conflicts.push(Conflict { kind: "room_official", slot: slot.clone(), subject: official, gids });
+            }
+        }
+    }
+    conflicts
+}
+
+fn read_slot_games_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<SlotGame>> {
+    use crate::schema::{games, rounds};
+    let rows = games::table
+        .inner_join(rounds::table)
+        .filter(games::tournamentid.eq(tournament_id))
+        .filter(games::ignore.eq(false))
+        .select((Game::as_select(), rounds::scheduled_start_time))
+        .load::<(Game, Option<DateTime<Utc>>)>(db)?;
+    Ok(rows
+        .into_iter()
+        .map(|(game, start_time)| SlotGame {
+            gid: game.gid,
+            slot: start_time.map(Slot::Time).unwrap_or(Slot::Round(game.roundid)),
+            roomid: game.roomid,
+            teams: [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)].into_iter().flatten().collect(),
+            officials: [Some(game.quizmasterid), game.contentjudgeid].into_iter().flatten().collect(),
+        })
+        .collect())
+}
+
+pub(crate) fn quizzers_of_team(team: &Team) -> Vec<Uuid> {
+    [
+        team.quizzer_one_id,
+        team.quizzer_two_id,
+        team.quizzer_three_id,
+        team.quizzer_four_id,
+        team.quizzer_five_id,
+        team.quizzer_six_id,
+    ].into_iter().flatten().collect()
+}
+
+pub(crate) fn user_name(user: &User) -> String {
+    format!["{} {}", user.fname, user.lname].trim().to_string()
+}
+
+fn describe(conflict: &Conflict, name: &str) -> String {
+    let when = match &conflict.slot {
+        Slot::Time(time) => format!["at {}", time.format("%Y-%m-%d %H:%M UTC")],
+        Slot::Round(_) => "in the same round".to_string(),
+    };
+    let count = conflict.gids.len();
+    match conflict.kind {
+        "team" => format!["{} plays {} games {}", name, count, when],
+        "room" => format!["{} hosts {} games {}", name, count, when],
+        "official" => format!["{} officiates {} games {}", name, count, when],
+        "quizzer" => format!["{} is on the roster of teams playing {} games {}", name, count, when],
+        _ => format!["{} is the standing official of rooms with {} games {}", name, count, when],
+    }
+}
+
+pub fn check_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<ConflictReport> {
+    let slot_games = read_slot_games_of_tournament(db, tournament_id)?;
+
+    let team_ids: HashSet<Uuid> = slot_games.iter().flat_map(|g| g.teams.iter().copied()).collect();
+    let teams: Vec<Team> = {
+        use crate::schema::teams::dsl::*;
+        teams.filter(teamid.eq_any(team_ids)).load::<Team>(db)?
+    };
+    let rooms: Vec<Room> = {
+        use crate::schema::rooms::dsl::*;
+        rooms.filter(tid.eq(tournament_id)).load::<Room>(db)?
+    };
+    let team_quizzers: HashMap<Uuid, Vec<Uuid>> = teams.iter().map(|t| (t.teamid, quizzers_of_team(t))).collect();
+    let room_officials: HashMap<Uuid, Vec<Uuid>> = rooms
+        .iter()
+        .map(|r| (r.roomid, [r.quizmaster_id, r.contentjudge_id].into_iter().flatten().collect()))
+        .collect();
+
+    let found = find_conflicts(&slot_games, &team_quizzers, &room_officials);
+
+    let user_ids: HashSet<Uuid> = found.iter().filter(|c| c.kind != "team" && c.kind != "room").map(|c| c.subject).collect();
+    let users: Vec<User> = {
+        use crate::schema::users::dsl::*;
+        users.filter(id.eq_any(user_ids)).load::<User>(db)?
+    };
+    let mut names: HashMap<Uuid, String> = HashMap::new();
+    names.extend(teams.iter().map(|t| (t.teamid, t.name.clone())));
+    names.extend(rooms.iter().map(|r| (r.roomid, r.name.clone())));
+    names.extend(users.iter().map(|u| (u.id, user_name(u))));
+
+    let conflicts = found
+        .iter()
+        .map(|c| {
+            let subject_name = names.get(&c.subject).cloned().unwrap_or_default();
+            ScheduleConflict {
+                kind: c.kind.to_string(),
+                scheduled_start_time: if let Slot::Time(time) = c.slot { Some(time) } else { None },
+                roundid: if let Slot::Round(round) = c.slot { Some(round) } else { None },
+                subject_id: c.subject,
+                message: describe(c, &subject_name),
+                subject_name,
+                gids: c.gids.clone(),
+            }
+        })
+        .collect();
+
+    Ok(ConflictReport { tid: tournament_id, games_checked: slot_games.len() as i32, conflicts })
+}
+
+pub fn check_game(db: &mut database::Connection, game: &Game) -> QueryResult<Vec<ScheduleConflict>> {
+    let report = check_tournament(db, game.tournamentid)?;
+    Ok(report.conflicts.into_iter().filter(|c| c.gids.contains(&game.gid)).collect())
+}
+
+/// Conflicts are only warnings, so a failure to check them doesn't fail the create or update.
+pub fn with_conflicts(db: &mut database::Connection, game: Game) -> GameWithConflicts {
+    let conflicts = check_game(db, &game).unwrap_or_default();
+    GameWithConflicts { game, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn slot_game(slot: &Slot, roomid: Uuid, teams: Vec<Uuid>, officials: Vec<Uuid>) -> SlotGame {
+        SlotGame { gid: Uuid::new_v4(), slot: slot.clone(), roomid, teams, officials }
+    }
+
+    #[test]
+    fn find_conflicts_works() {
+        let nine = Slot::Time(Utc.with_ymd_and_hms(2026, 6, 1, 9, 0, 0).unwrap());
+        let ten = Slot::Time(Utc.with_ymd_and_hms(2026, 6, 1, 10, 0, 0).unwrap());
+        let (room_1, room_2) = (Uuid::new_v4(), Uuid::new_v4());
+        let teams: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let (qm_1, qm_2, quizzer) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let games = vec![
+            slot_game(&nine, room_1, vec![teams[0], teams[1]], vec![qm_1]),
+            slot_game(&nine, room_2, vec![teams[2], teams[3]], vec![qm_2, qm_1]),
+            slot_game(&ten, room_1, vec![teams[0], teams[2]], vec![qm_1]),
+            slot_game(&ten, room_1, vec![teams[4], teams[2]], vec![qm_2]),
+        ];
+        let team_quizzers = HashMap::from([(teams[1], vec![quizzer]), (teams[3], vec![quizzer])]);
+        let room_officials = HashMap::new();
+
+        let conflicts = find_conflicts(&games, &team_quizzers, &room_officials);
+        let kinds: Vec<(&str, Uuid)> = conflicts.iter().map(|c| (c.kind, c.subject)).collect();
+        assert_eq!(conflicts.len(), 4, "{:?}", kinds);
+        assert!(kinds.contains(&("official", qm_1)));
+        assert!(kinds.contains(&("quizzer", quizzer)));
+        assert!(kinds.contains(&("team", teams[2])));
+        assert!(kinds.contains(&("room", room_1)));
+        assert!(conflicts.iter().filter(|c| c.slot == ten).all(|c| c.gids == {
+            let mut gids = vec![games[2].gid, games[3].gid];
+            gids.sort();
+            gids
+        }));
+    }
+
+    #[test]
+    fn standing_officials_of_two_busy_rooms_conflict() {
+        let nine = Slot::Round(Uuid::new_v4());
+        let (room_1, room_2, official) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let games = vec![
+            slot_game(&nine, room_1, vec![Uuid::new_v4(), Uuid::new_v4()], vec![Uuid::new_v4()]),
+            slot_game(&nine, room_2, vec![Uuid::new_v4(), Uuid::new_v4()], vec![Uuid::new_v4()]),
+        ];
+        let room_officials = HashMap::from([(room_1, vec![official]), (room_2, vec![official])]);
+        let conflicts = find_conflicts(&games, &HashMap::new(), &room_officials);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, "room_official");
+        assert_eq!(conflicts[0].gids.len(), 2);
+    }
+}
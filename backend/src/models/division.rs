@@ -1,5 +1,6 @@
 
 use crate::database;
+use crate::models;
 use diesel::*;
 use diesel::{QueryResult,AsChangeset,Insertable,Identifiable};
 use serde::{Deserialize, Serialize};
@@ -13,7 +14,9 @@ pub struct DivisionBuilder {
     dname: Option<String>,
     breadcrumb: Option<String>,
     is_public: Option<bool>,
-    shortinfo: Option<String>
+    shortinfo: Option<String>,
+    quiz_season: Option<i32>,
+    competition_lvl: Option<i32>,
 }
 
 impl DivisionBuilder {
@@ -23,7 +26,9 @@ impl DivisionBuilder {
             dname: None,
             breadcrumb: None,
             is_public: None,
-            shortinfo: None
+            shortinfo: None,
+            quiz_season: None,
+            competition_lvl: None,
         }
     }
 
@@ -34,7 +39,9 @@ impl DivisionBuilder {
             dname: Some(dname.to_string()),
             breadcrumb: Some("/test/post/for/division/1".to_string()),
             is_public: Some(false),
-            shortinfo: Some("Experienced (but still young).".to_string())
+            shortinfo: Some("Experienced (but still young).".to_string()),
+            quiz_season: None,
+            competition_lvl: None,
         }
     }
 
@@ -63,6 +70,16 @@ impl DivisionBuilder {
         self
     }
 
+    pub fn set_quiz_season(mut self, val: i32) -> Self {
+        self.quiz_season = Some(val);
+        self
+    }
+
+    pub fn set_competition_lvl(mut self, val: i32) -> Self {
+        self.competition_lvl = Some(val);
+        self
+    }
+
     fn validate_all_are_some(&self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
@@ -98,7 +115,9 @@ impl DivisionBuilder {
                         dname: self.dname.unwrap(),
                         breadcrumb: self.breadcrumb.unwrap(),
                         is_public: self.is_public.unwrap(),
-                        shortinfo: self.shortinfo.unwrap()
+                        shortinfo: self.shortinfo.unwrap(),
+                        quiz_season: self.quiz_season,
+                        competition_lvl: self.competition_lvl,
                     }
                 )
             }
@@ -132,7 +151,9 @@ pub struct Division {
     pub is_public: bool,
     pub shortinfo : String,
     pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>
+    pub updated_at: DateTime<Utc>,
+    pub quiz_season: Option<i32>,               // the season quizzed, the tournament's when none
+    pub competition_lvl: Option<i32>,           // the level of competition, e.g. 1 for Novice
 }
 
 #[derive(
@@ -147,7 +168,9 @@ pub struct NewDivision {
     pub dname: String,
     pub breadcrumb: String,
     pub is_public: bool,
-    pub shortinfo: String
+    pub shortinfo: String,
+    pub quiz_season: Option<i32>,
+    pub competition_lvl: Option<i32>,
 }
 
 // #[tsync::tsync]
@@ -158,11 +181,25 @@ pub struct DivisionChangeset {
     pub dname: Option<String>,
     pub breadcrumb: Option<String>,
     pub is_public: Option<bool>,
-    pub shortinfo: Option<String>
+    pub shortinfo: Option<String>,
+    pub quiz_season: Option<i32>,
+    pub competition_lvl: Option<i32>,
+}
+
+// The season and level a division names must exist.
+fn check_season_and_level(db: &mut database::Connection, season: Option<i32>, level: Option<i32>) -> QueryResult<()> {
+    if let Some(season) = season {
+        models::quiz_season::check_exists(db, season)?;
+    }
+    if let Some(level) = level {
+        models::competition_level::check_exists(db, level)?;
+    }
+    Ok(())
 }
 
 pub fn create(db: &mut database::Connection, item: &NewDivision) -> QueryResult<Division> {
     use crate::schema::divisions::dsl::*;
+    check_season_and_level(db, item.quiz_season, item.competition_lvl)?;
     insert_into(divisions).values(item).get_result::<Division>(db)
 }
 
@@ -217,6 +254,7 @@ pub fn read_all_divisions_of_tournament(
 
 pub fn update(db: &mut database::Connection, item_id: Uuid, item: &DivisionChangeset) -> QueryResult<Division> {
     use crate::schema::divisions::dsl::*;
+    check_season_and_level(db, item.quiz_season, item.competition_lvl)?;
     diesel::update(divisions.filter(did.eq(item_id)))
         .set((
             item,
@@ -0,0 +1,132 @@
+use crate::database;
+use crate::models;
+use diesel::prelude::*;
+use diesel::*;
+use diesel::{QueryResult,Insertable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+use chrono::{Utc,DateTime};
+
+pub struct DivisionRookieBuilder {
+    did: Uuid,
+    quizzerid: Uuid,
+}
+
+impl DivisionRookieBuilder {
+    pub fn new(did: Uuid, quizzerid: Uuid) -> Self {
+        Self {
+            did,
+            quizzerid,
+        }
+    }
+    pub fn new_default(did: Uuid, quizzerid: Uuid) -> Self {
+        Self {
+            did,
+            quizzerid,
+        }
+    }
+    pub fn build(self) -> Result<NewDivisionRookie, Vec<String>> {
+        Ok(
+            NewDivisionRookie {
+                did: self.did,
+                quizzerid: self.quizzerid,
+            }
+        )
+    }
+    pub fn build_and_insert(self, db: &mut database::Connection) -> QueryResult<DivisionRookie> {
+        let new_division_rookie = self.build();
+        create(db, &new_division_rookie.unwrap())
+    }
+}
+
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Queryable,
+    Selectable,
+    Identifiable,
+    ToSchema
+)]
+#[diesel(table_name = crate::schema::division_rookies)]
+#[diesel(primary_key(did, quizzerid))]
+pub struct DivisionRookie {
+    pub did: Uuid,
+    pub quizzerid: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(
+    Insertable,
+    Serialize,
+    Deserialize,
+    Debug
+)]
+#[diesel(table_name = crate::schema::division_rookies)]
+pub struct NewDivisionRookie {
+    pub did: Uuid,
+    pub quizzerid: Uuid,
+}
+
+/// A rookie has to be on a team in the division. A quizzer who was on a team in an earlier year's
+/// season isn't a rookie; only divisions whose season has a year are checked for that.
+fn check_eligible(db: &mut database::Connection, item: &NewDivisionRookie) -> QueryResult<()> {
+    use crate::schema::teams::dsl::*;
+    if !models::team::is_seated_in_division(db, item.did, item.quizzerid)? {
+        return Err(diesel::result::Error::QueryBuilderError(
+            "The quizzer isn't on a team in this division".into(),
+        ));
+    }
+    let Some(this_year) = models::quiz_season::season_of_division(db, item.did)?.and_then(|s| s.year) else {
+        return Ok(());
+    };
+    let quizzer = Some(item.quizzerid);
+    let earlier_divisions: Vec<Uuid> = teams
+        .filter(did.ne(item.did))
+        .filter(quizzer_one_id.eq(quizzer)
+            .or(quizzer_two_id.eq(quizzer))
+            .or(quizzer_three_id.eq(quizzer))
+            .or(quizzer_four_id.eq(quizzer))
+            .or(quizzer_five_id.eq(quizzer))
+            .or(quizzer_six_id.eq(quizzer)))
+        .select(did)
+        .distinct()
+        .load::<Uuid>(db)?;
+    for division_id in earlier_divisions {
+        if let Some(season) = models::quiz_season::season_of_division(db, division_id)?
+            && season.year.is_some_and(|year| year < this_year)
+        {
+            return Err(diesel::result::Error::QueryBuilderError(
+                format!["The quizzer quizzed in {} and isn't a rookie", season.name].into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn create(db: &mut database::Connection, item: &NewDivisionRookie) -> QueryResult<DivisionRookie> {
+    use crate::schema::division_rookies::dsl::*;
+    check_eligible(db, item)?;
+    insert_into(division_rookies)
+        .values(item)
+        .get_result::<DivisionRookie>(db)
+}
+
+pub fn read_all_rookies_of_division(db: &mut database::Connection, division_id: Uuid) -> QueryResult<Vec<DivisionRookie>> {
+    use crate::schema::division_rookies::dsl::*;
+    division_rookies
+        .filter(did.eq(division_id))
+        .order(created_at.asc())
+        .load::<DivisionRookie>(db)
+}
+
+pub fn delete(db: &mut database::Connection, division_id: Uuid, quizzer_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::division_rookies::dsl::*;
+    diesel::delete(
+        division_rookies
+            .filter(did.eq(division_id))
+            .filter(quizzerid.eq(quizzer_id))
+    ).execute(db)
+}
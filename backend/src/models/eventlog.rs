@@ -1,6 +1,9 @@
+use std::collections::HashMap;
 use chrono::{DateTime, Utc};
-use diesel::{AsChangeset,Insertable,Identifiable,Queryable,RunQueryDsl,insert_into};
+use diesel::{AsChangeset,ExpressionMethods,Insertable,Identifiable,QueryDsl,Queryable,OptionalExtension,RunQueryDsl,insert_into};
 use crate::database;
+use crate::models::{self, game::Game, gameevent::GameEvent};
+use uuid::Uuid;
 use diesel::QueryResult;
 use serde::{Deserialize, Serialize};
 use crate::models::common::*;
@@ -139,3 +142,297 @@ pub fn create(db: &mut database::Connection, item: &EventlogChangeset) -> QueryR
     insert_into(eventlogs).values(item).get_result::<Eventlog>(db)
 }
 
+
+// QMServer wrote its on-disk eventlog as comma separated values quoted with single quotes, one
+// header line followed by one line per event, in this column order.
+pub const QUIZMACHINE_COLUMNS: [&str; 14] = [
+    "clientkey", "tournament", "division", "room", "round", "question", "eventnum",
+    "name", "team", "quizzer", "event", "parm1", "parm2", "clientts",
+];
+
+// Client timestamps are written the way QuizMachine wrote them, e.g. 2025-05-23-14.05.09.000000
+pub const QUIZMACHINE_TS_FORMAT: &str = "%Y-%m-%d-%H.%M.%S.%6f";
+
+// Mirrors the record read back by the replay tool.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QuizMachineRecord {
+    pub clientkey: String,
+    pub tournament: String,
+    pub division: String,
+    pub room: String,
+    pub round: String,
+    pub question: String,
+    pub eventnum: String,
+    pub name: String,
+    pub team: String,
+    pub quizzer: String,
+    pub event: String,
+    pub parm1: String,
+    pub parm2: String,
+    pub clientts: String,
+}
+
+pub enum EventlogSource {
+    Eventlogs,
+    GameEvents,
+}
+
+impl EventlogSource {
+    pub fn from_param(source: &Option<String>) -> Result<EventlogSource, String> {
+        match source.as_deref().map(|s| s.to_lowercase()) {
+            None => Ok(EventlogSource::Eventlogs),
+            Some(s) if s == "eventlogs" => Ok(EventlogSource::Eventlogs),
+            Some(s) if s == "gameevents" => Ok(EventlogSource::GameEvents),
+            Some(s) => Err(format!["Unknown eventlog source '{}'; expected eventlogs or gameevents", s]),
+        }
+    }
+}
+
+pub enum EventlogScope {
+    Tournament(Uuid),
+    Room(Uuid),
+    Game(Uuid),
+}
+
+impl EventlogScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventlogScope::Tournament(_) => "tournament",
+            EventlogScope::Room(_) => "room",
+            EventlogScope::Game(_) => "game",
+        }
+    }
+    pub fn id(&self) -> Uuid {
+        match self {
+            EventlogScope::Tournament(id) | EventlogScope::Room(id) | EventlogScope::Game(id) => *id,
+        }
+    }
+}
+
+fn format_clientts(ts: DateTime<Utc>) -> String {
+    ts.format(QUIZMACHINE_TS_FORMAT).to_string()
+}
+
+/// `ts` holds the client's epoch seconds as sent; entries where it can't be parsed fall back to the
+/// time the server received them.
+pub fn clientts_of_eventlog(entry: &Eventlog) -> DateTime<Utc> {
+    entry.ts
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .unwrap_or(entry.created_at)
+}
+
+pub fn quizmachine_record_of_eventlog(entry: &Eventlog) -> QuizMachineRecord {
+    QuizMachineRecord {
+        clientkey: entry.clientkey.clone(),
+        tournament: entry.tournament.clone(),
+        division: entry.division.clone(),
+        room: entry.room.clone(),
+        round: entry.round.clone(),
+        question: entry.question.to_string(),
+        eventnum: entry.eventnum.to_string(),
+        name: entry.name.clone(),
+        team: entry.team.to_string(),
+        quizzer: entry.quizzer.to_string(),
+        event: entry.event.clone(),
+        parm1: entry.parm1.clone(),
+        parm2: entry.parm2.clone(),
+        clientts: format_clientts(clientts_of_eventlog(entry)),
+    }
+}
+
+pub fn quizmachine_record_of_gameevent(game: &Game, game_event: &GameEvent) -> QuizMachineRecord {
+    QuizMachineRecord {
+        clientkey: game.clientkey.clone(),
+        tournament: game.tournamentid.to_string(),
+        division: game.divisionid.to_string(),
+        room: game.roomid.to_string(),
+        round: game.roundid.to_string(),
+        question: game_event.question.to_string(),
+        eventnum: game_event.eventnum.to_string(),
+        name: game_event.name.clone(),
+        team: game_event.team.to_string(),
+        quizzer: game_event.quizzer.to_string(),
+        event: game_event.event.clone(),
+        parm1: game_event.parm1.clone(),
+        parm2: game_event.parm2.clone(),
+        clientts: format_clientts(game_event.clientts),
+    }
+}
+
+// Eventlog entries identify the tournament, division, room and round by the ids the client sent.
+fn read_all_eventlogs_of_scope(db: &mut database::Connection, scope: &EventlogScope) -> QueryResult<Vec<Eventlog>> {
+    use crate::schema::eventlogs::dsl::*;
+    match scope {
+        EventlogScope::Tournament(id) => eventlogs.filter(tournament.eq(id.to_string())).order(evid.asc()).load::<Eventlog>(db),
+        EventlogScope::Room(id) => eventlogs.filter(room.eq(id.to_string())).order(evid.asc()).load::<Eventlog>(db),
+        EventlogScope::Game(id) => {
+            let game = models::game::read(db, *id)?;
+            read_all_eventlogs_of_game(db, &game)
+        },
+    }
+}
+
+/// What QuizMachine sends to say which game an entry is for, besides the tournament: the client
+/// key, division, room and round.
+pub type EventlogKey = (String, String, String, String);
+
+pub fn eventlog_key_of_game(game: &Game) -> EventlogKey {
+    (game.clientkey.clone(), game.divisionid.to_string(), game.roomid.to_string(), game.roundid.to_string())
+}
+
+/// Records that the entries sent with `game`'s current key belong to the game `owner`, before
+/// `game` is moved (then it is its own owner) or merged into `owner`.
+pub fn record_eventlog_key(db: &mut database::Connection, game: &Game, owner: Uuid) -> QueryResult<usize> {
+    use crate::schema::game_eventlog_keys::dsl::*;
+    let (key_clientkey, key_division, key_room, key_round) = eventlog_key_of_game(game);
+    insert_into(game_eventlog_keys)
+        .values((
+            tid.eq(game.tournamentid),
+            clientkey.eq(key_clientkey),
+            division.eq(key_division),
+            room.eq(key_room),
+            round.eq(key_round),
+            gid.eq(owner),
+        ))
+        .on_conflict((tid, clientkey, division, room, round))
+        .do_update()
+        .set(gid.eq(owner))
+        .execute(db)
+}
+
+/// The game an earlier key now belongs to, if the game sent with it was moved or merged.
+pub fn read_game_of_eventlog_key(db: &mut database::Connection, tournament_id: Uuid, key: &EventlogKey) -> QueryResult<Option<Uuid>> {
+    use crate::schema::game_eventlog_keys::dsl::*;
+    game_eventlog_keys
+        .filter(tid.eq(tournament_id))
+        .filter(clientkey.eq(&key.0))
+        .filter(division.eq(&key.1))
+        .filter(room.eq(&key.2))
+        .filter(round.eq(&key.3))
+        .select(gid)
+        .first::<Uuid>(db)
+        .optional()
+}
+
+/// Every key a game's entries were sent with: its current one and the ones recorded when it was
+/// moved or had games merged into it.
+pub fn read_eventlog_keys_of_game(db: &mut database::Connection, game: &Game) -> QueryResult<Vec<EventlogKey>> {
+    use crate::schema::game_eventlog_keys::dsl::*;
+    let mut keys = vec![eventlog_key_of_game(game)];
+    keys.extend(
+        game_eventlog_keys
+            .filter(gid.eq(game.gid))
+            .select((clientkey, division, room, round))
+            .load::<EventlogKey>(db)?,
+    );
+    Ok(keys)
+}
+
+/// A game's eventlog entries are the ones of its tournament sent with one of its client keys and
+/// the division, room and round that went with it, in arrival order. A client sends every game it
+/// runs with the same key, so the key alone doesn't identify the game; moves and merges are
+/// followed through the keys recorded for the game.
+pub fn read_all_eventlogs_of_game(db: &mut database::Connection, game: &Game) -> QueryResult<Vec<Eventlog>> {
+    use crate::schema::eventlogs::dsl::*;
+    let keys = read_eventlog_keys_of_game(db, game)?;
+    let clientkeys: Vec<&String> = keys.iter().map(|k| &k.0).collect();
+    Ok(eventlogs
+        .filter(tournament.eq(game.tournamentid.to_string()))
+        .filter(clientkey.eq_any(clientkeys))
+        .order(evid.asc())
+        .load::<Eventlog>(db)?
+        .into_iter()
+        .filter(|e| keys.iter().any(|k| k.0 == e.clientkey && k.1 == e.division && k.2 == e.room && k.3 == e.round))
+        .collect())
+}
+
+pub fn read_all_eventlogs_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<Eventlog>> {
+    read_all_eventlogs_of_scope(db, &EventlogScope::Tournament(tournament_id))
+}
+
+fn read_all_games_of_scope(db: &mut database::Connection, scope: &EventlogScope) -> QueryResult<Vec<Game>> {
+    use crate::schema::games::dsl::*;
+    match scope {
+        EventlogScope::Tournament(id) => games.filter(tournamentid.eq(id)).order(created_at.asc()).load::<Game>(db),
+        EventlogScope::Room(id) => games.filter(roomid.eq(id)).order(created_at.asc()).load::<Game>(db),
+        EventlogScope::Game(id) => games.filter(gid.eq(id)).load::<Game>(db),
+    }
+}
+
+/// Reads the events of a tournament, room or game as QuizMachine eventlog records, either from the
+/// raw `eventlogs` (everything that was received, in arrival order) or from the `gameevents` of the
+/// scope's games (one record per event, in game, question and event order).
+pub fn read_quizmachine_records(db: &mut database::Connection, scope: &EventlogScope, source: &EventlogSource) -> QueryResult<Vec<QuizMachineRecord>> {
+    match source {
+        EventlogSource::Eventlogs => Ok(read_all_eventlogs_of_scope(db, scope)?
+            .iter()
+            .map(quizmachine_record_of_eventlog)
+            .collect()),
+        EventlogSource::GameEvents => {
+            let games = read_all_games_of_scope(db, scope)?;
+            let game_ids: Vec<Uuid> = games.iter().map(|g| g.gid).collect();
+            let mut events_by_game: HashMap<Uuid, Vec<GameEvent>> = HashMap::new();
+            for game_event in models::gameevent::read_all_gameevents_of_games(db, &game_ids)? {
+                events_by_game.entry(game_event.gid).or_default().push(game_event);
+            }
+            let mut records = vec![];
+            for game in games.iter() {
+                for game_event in events_by_game.remove(&game.gid).unwrap_or_default().iter() {
+                    records.push(quizmachine_record_of_gameevent(game, game_event));
+                }
+            }
+            Ok(records)
+        },
+    }
+}
+
+pub fn to_quizmachine_csv(records: &[QuizMachineRecord]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b',')
+        .quote(b'\'')
+        .has_headers(false)
+        .from_writer(vec![]);
+    writer.write_record(QUIZMACHINE_COLUMNS).map_err(|e| e.to_string())?;
+    for record in records.iter() {
+        writer.serialize(record).map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_quizmachine_csv_reads_back_like_replay() {
+        let record = QuizMachineRecord {
+            clientkey: "key1".to_string(),
+            tournament: "t".to_string(),
+            division: "d".to_string(),
+            room: "r".to_string(),
+            round: "1".to_string(),
+            question: "3".to_string(),
+            eventnum: "2".to_string(),
+            name: "O'Neil, Jr.".to_string(),
+            team: "1".to_string(),
+            quizzer: "4".to_string(),
+            event: "TC".to_string(),
+            parm1: "".to_string(),
+            parm2: "".to_string(),
+            clientts: "2025-05-23-14.05.09.000000".to_string(),
+        };
+        let bytes = to_quizmachine_csv(std::slice::from_ref(&record)).unwrap();
+
+        // the replay tool reads eventlogs with these settings
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b',')
+            .trim(csv::Trim::All)
+            .quote(b'\'')
+            .from_reader(bytes.as_slice());
+        let records: Vec<QuizMachineRecord> = reader.deserialize().collect::<Result<_, _>>().unwrap();
+        assert_eq!(records, vec![record]);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashSet};
+use crate::database;
+use crate::models::{self, eventlog::{self, Eventlog}, game::Game, gameevent::{GameEvent, NewGameEvent}};
+use diesel::prelude::*;
+use diesel::{insert_into, QueryResult};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// The scoreevent handler writes `eventlogs`, then `games`, then `gameevents` without a transaction,
+// so a failure part way through leaves them disagreeing. The eventlog is authoritative: it holds
+// everything that was received, and a resent event (same question and event number) replaces the
+// earlier one, exactly as the gameevent upsert does.
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct EventSummary {
+    pub question: i32,
+    pub eventnum: i32,
+    pub name: String,
+    pub event: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct FieldDifference {
+    pub field: String,
+    pub eventlog: String,
+    pub gameevent: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct EventConflict {
+    pub question: i32,
+    pub eventnum: i32,
+    pub differences: Vec<FieldDifference>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameConsistencyReport {
+    pub gid: Uuid,
+    pub eventlog_events: i32,               // distinct (question, eventnum) pairs in the eventlog
+    pub gameevents: i32,
+    pub missing: Vec<EventSummary>,         // in the eventlog but not in gameevents
+    pub extra: Vec<EventSummary>,           // in gameevents but not in the eventlog
+    pub conflicting: Vec<EventConflict>,    // in both, with different values
+    pub is_consistent: bool,
+}
+
+// Eventlog entries of a tournament that don't belong to any of its games.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct OrphanedEventlogs {
+    pub clientkey: String,
+    pub division: String,
+    pub room: String,
+    pub round: String,
+    pub entries: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TournamentConsistencyReport {
+    pub tid: Uuid,
+    pub games_checked: i32,
+    pub games_inconsistent: i32,
+    pub games: Vec<GameConsistencyReport>,
+    pub orphaned_eventlogs: Vec<OrphanedEventlogs>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameRebuild {
+    pub report: GameConsistencyReport,      // the state before the rebuild
+    pub action: String,                     // "unchanged", "rebuilt", "would rebuild" or "skipped: ..."
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RebuildReport {
+    pub applied: bool,
+    pub games: Vec<GameRebuild>,
+}
+
+/// The gameevents a game should have according to its eventlog, keyed by (question, eventnum).
+pub fn expected_gameevents(game_id: Uuid, entries: &[Eventlog]) -> BTreeMap<(i32, i32), NewGameEvent> {
+    let mut expected = BTreeMap::new();
+    for entry in entries.iter() {
+        expected.insert((entry.question, entry.eventnum), NewGameEvent {
+            gid: game_id,
+            question: entry.question,
+            eventnum: entry.eventnum,
+            name: entry.name.clone(),
+            team: entry.team,
+            quizzer: entry.quizzer,
+            event: entry.event.clone(),
+            parm1: entry.parm1.clone(),
+            parm2: entry.parm2.clone(),
+            clientts: eventlog::clientts_of_eventlog(entry),
+            serverts: entry.created_at,
+            md5digest: entry.md5digest.clone(),
+        });
+    }
+    expected
+}
+
+fn difference(differences: &mut Vec<FieldDifference>, field: &str, eventlog: String, gameevent: String) {
+    if eventlog != gameevent {
+        differences.push(FieldDifference { field: field.to_string(), eventlog, gameevent });
+    }
+}
+
+fn compare_events(expected: &NewGameEvent, actual: &GameEvent) -> Vec<FieldDifference> {
+    let mut differences = vec![];
+    difference(&mut differences, "name", expected.name.clone(), actual.name.clone());
+    difference(&mut differences, "team", expected.team.to_string(), actual.team.to_string());
+    difference(&mut differences, "quizzer", expected.quizzer.to_string(), actual.quizzer.to_string());
+    difference(&mut differences, "event", expected.event.clone(), actual.event.clone());
+    difference(&mut differences, "parm1", expected.parm1.clone(), actual.parm1.clone());
+    difference(&mut differences, "parm2", expected.parm2.clone(), actual.parm2.clone());
+    // clients send whole seconds
+    difference(&mut differences, "clientts", expected.clientts.timestamp().to_string(), actual.clientts.timestamp().to_string());
+    difference(&mut differences, "md5digest", expected.md5digest.clone(), actual.md5digest.clone());
+    differences
+}
+
+pub fn compare_game(game_id: Uuid, entries: &[Eventlog], actual: &[GameEvent]) -> GameConsistencyReport {
+    let expected = expected_gameevents(game_id, entries);
+    let mut missing = vec![];
+    let mut extra = vec![];
+    let mut conflicting = vec![];
+
+    let mut seen = HashSet::new();
+    for game_event in actual.iter() {
+        let key = (game_event.question, game_event.eventnum);
+        seen.insert(key);
+        match expected.get(&key) {
+            None => extra.push(EventSummary {
+                question: game_event.question,
+                eventnum: game_event.eventnum,
+                name: game_event.name.clone(),
+                event: game_event.event.clone(),
+            }),
+            Some(expected_event) => {
+                let differences = compare_events(expected_event, game_event);
+                if !differences.is_empty() {
+                    conflicting.push(EventConflict { question: key.0, eventnum: key.1, differences });
+                }
+            },
+        }
+    }
+    for (key, expected_event) in expected.iter() {
+        if !seen.contains(key) {
+            missing.push(EventSummary {
+                question: expected_event.question,
+                eventnum: expected_event.eventnum,
+                name: expected_event.name.clone(),
+                event: expected_event.event.clone(),
+            });
+        }
+    }
+    extra.sort_by_key(|e| (e.question, e.eventnum));
+
+    let is_consistent = missing.is_empty() && extra.is_empty() && conflicting.is_empty();
+    GameConsistencyReport {
+        gid: game_id,
+        eventlog_events: expected.len() as i32,
+        gameevents: actual.len() as i32,
+        missing,
+        extra,
+        conflicting,
+        is_consistent,
+    }
+}
+
+fn read_all_gameevents_of_game(db: &mut database::Connection, game_id: Uuid) -> QueryResult<Vec<GameEvent>> {
+    models::gameevent::read_all_gameevents_of_games(db, &[game_id])
+}
+
+pub fn check_game(db: &mut database::Connection, game: &Game) -> QueryResult<GameConsistencyReport> {
+    let entries = eventlog::read_all_eventlogs_of_game(db, game)?;
+    let actual = read_all_gameevents_of_game(db, game.gid)?;
+    Ok(compare_game(game.gid, &entries, &actual))
+}
+
+fn read_all_games_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<Game>> {
+    use crate::schema::games::dsl::*;
+    games
+        .filter(tournamentid.eq(tournament_id))
+        .order(created_at.asc())
+        .load::<Game>(db)
+}
+
+pub fn check_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<TournamentConsistencyReport> {
+    let tournament_games = read_all_games_of_tournament(db, tournament_id)?;
+    let mut games = vec![];
+    for game in tournament_games.iter() {
+        games.push(check_game(db, game)?);
+    }
+
+    let mut game_keys: HashSet<eventlog::EventlogKey> = HashSet::new();
+    for game in tournament_games.iter() {
+        game_keys.extend(eventlog::read_eventlog_keys_of_game(db, game)?);
+    }
+    let mut orphans: BTreeMap<(String, String, String, String), i32> = BTreeMap::new();
+    for entry in eventlog::read_all_eventlogs_of_tournament(db, tournament_id)? {
+        let key = (entry.clientkey, entry.division, entry.room, entry.round);
+        if !game_keys.contains(&key) {
+            *orphans.entry(key).or_default() += 1;
+        }
+    }
+    let orphaned_eventlogs = orphans
+        .into_iter()
+        .map(|((clientkey, division, room, round), entries)| OrphanedEventlogs { clientkey, division, room, round, entries })
+        .collect();
+
+    Ok(TournamentConsistencyReport {
+        tid: tournament_id,
+        games_checked: games.len() as i32,
+        games_inconsistent: games.iter().filter(|g| !g.is_consistent).count() as i32,
+        games,
+        orphaned_eventlogs,
+    })
+}
+
+/// Replaces a game's gameevents with the ones its eventlog calls for. In dry-run mode nothing is
+/// written and the action says what would happen. Games without any eventlog entries are left
+/// alone, since their events may have been entered some other way.
+pub fn rebuild_game(db: &mut database::Connection, game: &Game, apply: bool) -> QueryResult<GameRebuild> {
+    let entries = eventlog::read_all_eventlogs_of_game(db, game)?;
+    let actual = read_all_gameevents_of_game(db, game.gid)?;
+    let report = compare_game(game.gid, &entries, &actual);
+
+    // a scoresheet entered by hand supersedes whatever QuizMachine logged for the game
+    let action = if crate::models::scoresheet::is_manual(db, game.gid)? {
+        "skipped: entered manually".to_string()
+    } else if crate::models::game_merge::has_merged(db, game.gid)? {
+        // the eventlog only has the events QuizMachine sent for this game, not the merged ones
+        "skipped: merged".to_string()
+    } else if entries.is_empty() {
+        "skipped: no eventlog entries".to_string()
+    } else if report.is_consistent {
+        "unchanged".to_string()
+    } else if !apply {
+        "would rebuild".to_string()
+    } else {
+        let expected: Vec<NewGameEvent> = expected_gameevents(game.gid, &entries).into_values().collect();
+        db.transaction::<_, diesel::result::Error, _>(|conn| {
+            use crate::schema::gameevents::dsl::*;
+            diesel::delete(gameevents.filter(gid.eq(game.gid))).execute(conn)?;
+            insert_into(gameevents).values(&expected).execute(conn)?;
+            Ok(())
+        })?;
+        "rebuilt".to_string()
+    };
+
+    Ok(GameRebuild { report, action })
+}
+
+pub fn rebuild_games(db: &mut database::Connection, games: &[Game], apply: bool) -> QueryResult<RebuildReport> {
+    let mut rebuilds = vec![];
+    for game in games.iter() {
+        rebuilds.push(rebuild_game(db, game, apply)?);
+    }
+    Ok(RebuildReport { applied: apply, games: rebuilds })
+}
+
+pub fn rebuild_tournament(db: &mut database::Connection, tournament_id: Uuid, apply: bool) -> QueryResult<RebuildReport> {
+    let games = read_all_games_of_tournament(db, tournament_id)?;
+    rebuild_games(db, &games, apply)
+}
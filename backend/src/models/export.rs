@@ -0,0 +1,539 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use crate::database;
+use crate::models::{self, award::{self, CountedGame}, game::Game, game_outcome::GameOutcome, gameevent::{GameEvent, GameResult, TeamGameResult}, ruling::Ruling};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use uuid::Uuid;
+
+// The report names double as CSV file names and XLSX sheet names.
+pub const REPORTS: [&str; 5] = ["games", "standings", "quizzers", "events", "challenges"];
+
+pub enum ExportScope {
+    Tournament(Uuid),
+    Division(Uuid),
+    Round(Uuid),
+    StatsGroup(Uuid),
+}
+
+impl ExportScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportScope::Tournament(_) => "tournament",
+            ExportScope::Division(_) => "division",
+            ExportScope::Round(_) => "round",
+            ExportScope::StatsGroup(_) => "statsgroup",
+        }
+    }
+    pub fn id(&self) -> Uuid {
+        match self {
+            ExportScope::Tournament(id) | ExportScope::Division(id) | ExportScope::Round(id) | ExportScope::StatsGroup(id) => *id,
+        }
+    }
+}
+
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn from_param(format: &Option<String>) -> Result<ExportFormat, String> {
+        match format.as_deref().map(|f| f.to_lowercase()) {
+            None => Ok(ExportFormat::Csv),
+            Some(f) if f == "csv" => Ok(ExportFormat::Csv),
+            Some(f) if f == "xlsx" => Ok(ExportFormat::Xlsx),
+            Some(f) => Err(format!["Unsupported export format '{}'; expected csv or xlsx", f]),
+        }
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+/// Picks the reports to export. A CSV file holds a single table, so it needs an explicit report;
+/// a workbook gets one sheet per report when none is named.
+pub fn reports_from_param(report: &Option<String>, format: &ExportFormat) -> Result<Vec<&'static str>, String> {
+    match report.as_deref().map(|r| r.to_lowercase()) {
+        Some(r) => match REPORTS.iter().find(|name| **name == r) {
+            Some(name) => Ok(vec![*name]),
+            None => Err(format!["Unknown report '{}'; expected one of {}", r, REPORTS.join(", ")]),
+        },
+        None => match format {
+            ExportFormat::Csv => Err(format!["A report is required for csv; expected one of {}", REPORTS.join(", ")]),
+            ExportFormat::Xlsx => Ok(REPORTS.to_vec()),
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Empty,
+}
+
+impl Cell {
+    fn to_csv_field(&self) -> String {
+        match self {
+            Cell::Text(s) => s.clone(),
+            Cell::Int(n) => n.to_string(),
+            Cell::Float(n) => format!["{:.2}", n],
+            Cell::Bool(b) => b.to_string(),
+            Cell::Empty => String::new(),
+        }
+    }
+}
+
+fn text(s: &str) -> Cell {
+    Cell::Text(s.to_string())
+}
+
+fn opt_text(s: Option<&String>) -> Cell {
+    match s {
+        Some(s) => Cell::Text(s.clone()),
+        None => Cell::Empty,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+// Everything a report needs, read once for the whole export.
+struct ExportData {
+    games: Vec<Game>,
+    events: Vec<GameEvent>,
+    results: HashMap<Uuid, Result<GameResult, Vec<String>>>,
+    counted: Vec<CountedGame>,
+    outcomes: HashMap<Uuid, GameOutcome>,
+    rulings: Vec<Ruling>,
+    team_names: HashMap<Uuid, String>,
+    division_names: HashMap<Uuid, String>,
+    round_names: HashMap<Uuid, String>,
+    room_names: HashMap<Uuid, String>,
+}
+
+pub fn read_all_games_of_scope(db: &mut database::Connection, scope: &ExportScope) -> QueryResult<Vec<Game>> {
+    use crate::schema::games::dsl::*;
+    match scope {
+        ExportScope::Tournament(id) => games.filter(tournamentid.eq(id)).order(created_at.asc()).load::<Game>(db),
+        ExportScope::Division(id) => games.filter(divisionid.eq(id)).order(created_at.asc()).load::<Game>(db),
+        ExportScope::Round(id) => games.filter(roundid.eq(id)).order(created_at.asc()).load::<Game>(db),
+        ExportScope::StatsGroup(id) => {
+            use crate::schema::games_statsgroups::dsl::{games_statsgroups, gameid, statsgroupid};
+            let game_ids: Vec<Uuid> = games_statsgroups
+                .filter(statsgroupid.eq(id))
+                .select(gameid)
+                .load::<Uuid>(db)?;
+            games.filter(gid.eq_any(game_ids)).order(created_at.asc()).load::<Game>(db)
+        },
+    }
+}
+
+fn read_export_data(db: &mut database::Connection, scope: &ExportScope) -> QueryResult<ExportData> {
+    let games = read_all_games_of_scope(db, scope)?;
+    let game_ids: Vec<Uuid> = games.iter().map(|g| g.gid).collect();
+    let events = models::gameevent::read_all_gameevents_of_games(db, &game_ids)?;
+
+    let mut events_by_game: HashMap<Uuid, Vec<GameEvent>> = HashMap::new();
+    for game_event in events.iter() {
+        events_by_game.entry(game_event.gid).or_default().push(game_event.clone());
+    }
+    // a game is final after its division's regulation questions, as in the awards
+    let mut questions_per_game: HashMap<Uuid, i32> = HashMap::new();
+    for game in games.iter() {
+        if let std::collections::hash_map::Entry::Vacant(entry) = questions_per_game.entry(game.divisionid) {
+            entry.insert(models::award_setting::read_or_default(db, game.divisionid)?.questions_per_game);
+        }
+    }
+    let mut results = HashMap::new();
+    for game in games.iter() {
+        if let Some(game_events) = events_by_game.remove(&game.gid) {
+            results.insert(game.gid, models::gameevent::calculate_game_result(game.gid, game_events, questions_per_game[&game.divisionid]));
+        }
+    }
+
+    // standings and quizzer statistics follow the award rules: finalized, non-ignored games only
+    let counted_games: Vec<Game> = games.iter().filter(|g| !g.ignore).cloned().collect();
+    let counted = award::calculate_game_results(db, counted_games)?.counted;
+
+    // a forfeit or bye is reported with the result it stands for, not with whatever its events say
+    let outcomes = models::game_outcome::read_all_outcomes_of_games(db, &game_ids)?;
+    for counted_game in counted.iter().filter(|c| outcomes.contains_key(&c.game.gid)) {
+        results.insert(counted_game.game.gid, Ok(counted_game.result.clone()));
+    }
+
+    let rulings = models::ruling::read_all_of_games(db, &game_ids)?;
+
+    let mut team_names = HashMap::new();
+    let mut division_names = HashMap::new();
+    let mut round_names = HashMap::new();
+    let mut room_names = HashMap::new();
+    for game in games.iter() {
+        for team_id in [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)].into_iter().flatten() {
+            if let std::collections::hash_map::Entry::Vacant(entry) = team_names.entry(team_id) {
+                entry.insert(models::team::read(db, team_id).map(|t| t.name).unwrap_or_default());
+            }
+        }
+        if let std::collections::hash_map::Entry::Vacant(entry) = division_names.entry(game.divisionid) {
+            entry.insert(models::division::read(db, game.divisionid).map(|d| d.dname).unwrap_or_default());
+        }
+        if let std::collections::hash_map::Entry::Vacant(entry) = round_names.entry(game.roundid) {
+            entry.insert(models::round::read(db, game.roundid).map(|r| r.name).unwrap_or_default());
+        }
+        if let std::collections::hash_map::Entry::Vacant(entry) = room_names.entry(game.roomid) {
+            entry.insert(models::room::read(db, game.roomid).map(|r| r.name).unwrap_or_default());
+        }
+    }
+
+    Ok(ExportData { games, events, results, counted, outcomes, rulings, team_names, division_names, round_names, room_names })
+}
+
+// The event stream numbers teams by seat: left, then center (three-team games only), then right.
+fn team_result_of_seat<'a>(result: &'a GameResult, game: &Game, seat: usize) -> Option<&'a TeamGameResult> {
+    let team_idx = match (seat, game.centerteamid.is_some()) {
+        (0, _) => 0,
+        (1, true) => 1,
+        (1, false) => return None,
+        (_, true) => 2,
+        (_, false) => 1,
+    };
+    result.teams.iter().find(|t| t.team == team_idx)
+}
+
+fn games_table(data: &ExportData) -> Table {
+    let columns = vec![
+        "game_id", "division", "round", "room", "ignored", "status", "last_question",
+        "left_team", "left_score", "center_team", "center_score", "right_team", "right_score",
+        "winner", "notes",
+    ];
+    let mut rows = vec![];
+    for game in data.games.iter() {
+        let seats = [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)];
+        let mut row = vec![
+            text(&game.gid.to_string()),
+            opt_text(data.division_names.get(&game.divisionid)),
+            opt_text(data.round_names.get(&game.roundid)),
+            opt_text(data.room_names.get(&game.roomid)),
+            Cell::Bool(game.ignore),
+        ];
+        match data.results.get(&game.gid) {
+            None => {
+                row.extend([text("not started"), Cell::Empty]);
+                for team_id in seats.iter() {
+                    row.push(opt_text(team_id.and_then(|id| data.team_names.get(&id))));
+                    row.push(Cell::Empty);
+                }
+                row.extend([Cell::Empty, Cell::Empty]);
+            },
+            Some(Err(reasons)) => {
+                row.extend([text("invalid"), Cell::Empty]);
+                for team_id in seats.iter() {
+                    row.push(opt_text(team_id.and_then(|id| data.team_names.get(&id))));
+                    row.push(Cell::Empty);
+                }
+                row.extend([Cell::Empty, text(&reasons.join(" "))]);
+            },
+            Some(Ok(result)) => {
+                row.push(text(if result.is_final { "final" } else { "in progress" }));
+                row.push(Cell::Int(result.last_question as i64));
+                let mut winner = Cell::Empty;
+                for (seat, team_id) in seats.iter().enumerate() {
+                    let team_name = opt_text(team_id.and_then(|id| data.team_names.get(&id)));
+                    let team_result = team_result_of_seat(result, game, seat);
+                    if result.is_final && team_result.is_some_and(|t| t.rank == 1) {
+                        winner = team_name.clone();
+                    }
+                    row.push(team_name);
+                    row.push(team_result.map(|t| Cell::Int(t.score as i64)).unwrap_or(Cell::Empty));
+                }
+                row.extend([winner, Cell::Empty]);
+            },
+        }
+        if let Some(outcome) = data.outcomes.get(&game.gid) {
+            row[5] = text(&outcome.outcome);
+            if let Some(notes) = row.last_mut() {
+                *notes = text(&outcome.describe());
+            }
+        }
+        rows.push(row);
+    }
+    Table { name: "games".to_string(), columns, rows }
+}
+
+fn counted_games_by_division(data: &ExportData) -> Vec<(String, Vec<CountedGame>)> {
+    let mut order: Vec<Uuid> = vec![];
+    let mut by_division: HashMap<Uuid, Vec<CountedGame>> = HashMap::new();
+    for counted_game in data.counted.iter() {
+        let division_id = counted_game.game.divisionid;
+        if !by_division.contains_key(&division_id) {
+            order.push(division_id);
+        }
+        by_division.entry(division_id).or_default().push(counted_game.clone());
+    }
+    order
+        .into_iter()
+        .map(|id| (data.division_names.get(&id).cloned().unwrap_or_default(), by_division.remove(&id).unwrap_or_default()))
+        .collect()
+}
+
+fn standings_table(data: &ExportData) -> Table {
+    let columns = vec![
+        "division", "place", "is_tied", "team", "team_id", "games_played", "wins", "losses",
+        "total_score", "average_score",
+    ];
+    let mut rows = vec![];
+    for (dname, counted) in counted_games_by_division(data) {
+        let teams = award::tally_teams(&counted, &data.team_names);
+        for (place, is_tied, team) in award::place_with_ties(teams, |t| (t.wins, t.total_score), i32::MAX) {
+            rows.push(vec![
+                text(&dname),
+                Cell::Int(place as i64),
+                Cell::Bool(is_tied),
+                text(&team.team_name),
+                team.teamid.map(|id| text(&id.to_string())).unwrap_or(Cell::Empty),
+                Cell::Int(team.games_played as i64),
+                Cell::Int(team.wins as i64),
+                Cell::Int((team.games_played - team.wins) as i64),
+                Cell::Int(team.total_score as i64),
+                Cell::Float(team.total_score as f64 / team.games_played.max(1) as f64),
+            ]);
+        }
+    }
+    Table { name: "standings".to_string(), columns, rows }
+}
+
+fn quizzers_table(data: &ExportData) -> Table {
+    let columns = vec![
+        "division", "quizzer", "team", "team_id", "games_played", "points", "average_points",
+        "correct_tossups", "errors_on_tossups", "quiz_outs", "perfect_games",
+    ];
+    let mut rows = vec![];
+    for (dname, counted) in counted_games_by_division(data) {
+        let mut quizzers = award::tally_quizzers(&counted, &data.team_names);
+        quizzers.sort_by_key(|q| std::cmp::Reverse(q.points));
+        for quizzer in quizzers {
+            rows.push(vec![
+                text(&dname),
+                text(&quizzer.quizzer_name),
+                text(&quizzer.team_name),
+                quizzer.teamid.map(|id| text(&id.to_string())).unwrap_or(Cell::Empty),
+                Cell::Int(quizzer.games_played as i64),
+                Cell::Int(quizzer.points as i64),
+                Cell::Float(quizzer.points as f64 / quizzer.games_played.max(1) as f64),
+                Cell::Int(quizzer.correct_tossups as i64),
+                Cell::Int(quizzer.errors_on_tossups as i64),
+                Cell::Int(quizzer.quiz_outs as i64),
+                Cell::Int(quizzer.perfect_games as i64),
+            ]);
+        }
+    }
+    Table { name: "quizzers".to_string(), columns, rows }
+}
+
+fn events_table(data: &ExportData) -> Table {
+    let columns = vec![
+        "game_id", "question", "eventnum", "name", "team", "quizzer", "event", "parm1", "parm2",
+        "clientts", "serverts",
+    ];
+    let rows = data.events
+        .iter()
+        .map(|e| vec![
+            text(&e.gid.to_string()),
+            Cell::Int(e.question as i64),
+            Cell::Int(e.eventnum as i64),
+            text(&e.name),
+            Cell::Int(e.team as i64),
+            Cell::Int(e.quizzer as i64),
+            text(&e.event),
+            text(&e.parm1),
+            text(&e.parm2),
+            text(&e.clientts.to_rfc3339()),
+            text(&e.serverts.to_rfc3339()),
+        ])
+        .collect();
+    Table { name: "events".to_string(), columns, rows }
+}
+
+// The challenges and appeals each team raised, and how many overturned the call.
+fn challenges_table(data: &ExportData) -> Table {
+    let columns = vec![
+        "team", "team_id", "challenges", "challenges_overturned", "appeals", "appeals_overturned",
+    ];
+    let rows = models::ruling::summarize(&data.rulings, &data.team_names)
+        .into_iter()
+        .map(|s| vec![
+            text(&s.team_name),
+            text(&s.teamid.to_string()),
+            Cell::Int(s.challenges as i64),
+            Cell::Int(s.challenges_overturned as i64),
+            Cell::Int(s.appeals as i64),
+            Cell::Int(s.appeals_overturned as i64),
+        ])
+        .collect();
+    Table { name: "challenges".to_string(), columns, rows }
+}
+
+pub fn build_tables(db: &mut database::Connection, scope: &ExportScope, reports: &[&str]) -> QueryResult<Vec<Table>> {
+    let data = read_export_data(db, scope)?;
+    Ok(reports
+        .iter()
+        .filter_map(|report| match *report {
+            "games" => Some(games_table(&data)),
+            "standings" => Some(standings_table(&data)),
+            "quizzers" => Some(quizzers_table(&data)),
+            "events" => Some(events_table(&data)),
+            "challenges" => Some(challenges_table(&data)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Writes a single table as CSV. The byte order mark lets spreadsheet programs detect UTF-8, so
+/// accented and non-Latin names survive being opened by double-click.
+pub fn to_csv(table: &Table) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(vec![0xEF, 0xBB, 0xBF]);
+    writer.write_record(&table.columns).map_err(|e| e.to_string())?;
+    for row in table.rows.iter() {
+        writer.write_record(row.iter().map(|c| c.to_csv_field())).map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+fn escape_xml(s: &str) -> String {
+    s.chars()
+        .filter(|c| matches!(c, '\t' | '\n' | '\r') || *c >= ' ')
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn column_letter(mut idx: usize) -> String {
+    let mut letters = vec![];
+    loop {
+        letters.push((b'A' + (idx % 26) as u8) as char);
+        if idx < 26 {
+            break;
+        }
+        idx = idx / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+fn xlsx_cell(reference: &str, cell: &Cell) -> String {
+    match cell {
+        Cell::Text(s) => format!["<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>", reference, escape_xml(s)],
+        Cell::Int(n) => format!["<c r=\"{}\"><v>{}</v></c>", reference, n],
+        Cell::Float(n) => format!["<c r=\"{}\"><v>{}</v></c>", reference, n],
+        Cell::Bool(b) => format!["<c r=\"{}\" t=\"b\"><v>{}</v></c>", reference, if *b { 1 } else { 0 }],
+        Cell::Empty => String::new(),
+    }
+}
+
+fn xlsx_sheet(table: &Table) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+    xml.push_str("<worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>");
+    let header: Vec<Cell> = table.columns.iter().map(|c| text(c)).collect();
+    for (row_idx, row) in std::iter::once(&header).chain(table.rows.iter()).enumerate() {
+        xml.push_str(&format!["<row r=\"{}\">", row_idx + 1]);
+        for (col_idx, cell) in row.iter().enumerate() {
+            xml.push_str(&xlsx_cell(&format!["{}{}", column_letter(col_idx), row_idx + 1], cell));
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// Writes the tables as an Office Open XML workbook, one sheet per table. Strings are stored
+/// inline, so no shared-string table or stylesheet is needed.
+pub fn to_xlsx(tables: &[Table]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut content_types = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"><Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/><Default Extension=\"xml\" ContentType=\"application/xml\"/><Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>");
+    let mut workbook = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>");
+    let mut workbook_rels = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">");
+    for (idx, table) in tables.iter().enumerate() {
+        let n = idx + 1;
+        content_types.push_str(&format!["<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>", n]);
+        // sheet names are limited to 31 characters
+        let sheet_name: String = table.name.chars().take(31).collect();
+        workbook.push_str(&format!["<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>", escape_xml(&sheet_name), n, n]);
+        workbook_rels.push_str(&format!["<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet{}.xml\"/>", n, n]);
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str("</Relationships>");
+    let root_rels = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\"><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>";
+
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    archive.start_file("[Content_Types].xml", options)?;
+    archive.write_all(content_types.as_bytes())?;
+    archive.start_file("_rels/.rels", options)?;
+    archive.write_all(root_rels.as_bytes())?;
+    archive.start_file("xl/workbook.xml", options)?;
+    archive.write_all(workbook.as_bytes())?;
+    archive.start_file("xl/_rels/workbook.xml.rels", options)?;
+    archive.write_all(workbook_rels.as_bytes())?;
+    for (idx, table) in tables.iter().enumerate() {
+        archive.start_file(format!["xl/worksheets/sheet{}.xml", idx + 1], options)?;
+        archive.write_all(xlsx_sheet(table).as_bytes())?;
+    }
+    Ok(archive.finish()?.into_inner())
+}
+
+/// Renders the requested reports in the requested format, returning the file name and bytes.
+pub fn export(db: &mut database::Connection, scope: &ExportScope, format: &ExportFormat, reports: &[&str]) -> Result<(String, Vec<u8>), String> {
+    let tables = build_tables(db, scope, reports).map_err(|e| e.to_string())?;
+    let report_name = if reports.len() == 1 { reports[0] } else { "results" };
+    let file_name = format!["{}-{}-{}.{}", report_name, scope.as_str(), scope.id(), format.extension()];
+    let bytes = match format {
+        ExportFormat::Csv => to_csv(&tables[0])?,
+        ExportFormat::Xlsx => to_xlsx(&tables).map_err(|e| e.to_string())?,
+    };
+    Ok((file_name, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_letter_works() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(26), "AA");
+        assert_eq!(column_letter(27), "AB");
+        assert_eq!(column_letter(701), "ZZ");
+        assert_eq!(column_letter(702), "AAA");
+    }
+
+    #[test]
+    fn to_csv_keeps_utf8_names() {
+        let table = Table {
+            name: "quizzers".to_string(),
+            columns: vec!["quizzer", "points"],
+            rows: vec![vec![text("Zoë Ñúñez, Jr."), Cell::Int(120)]],
+        };
+        let bytes = to_csv(&table).unwrap();
+        assert!(bytes.starts_with(&[0xEF, 0xBB, 0xBF]));
+        let csv = String::from_utf8(bytes[3..].to_vec()).unwrap();
+        assert_eq!(csv, "quizzer,points\n\"Zoë Ñúñez, Jr.\",120\n");
+    }
+}
@@ -210,7 +210,10 @@ pub struct Game {
     pub contentjudgeid: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-    pub clientkey: String
+    pub clientkey: String,
+    pub actual_start_time: Option<DateTime<Utc>>,   // first event received from the room
+    pub actual_end_time: Option<DateTime<Utc>>,     // last event received from the room
+    pub start_offset_minutes: i32,                  // how far the game is moved from its round's start
 }
 
 #[derive(
@@ -282,6 +285,17 @@ impl GameChangeset {
     }
 }
 
+/// The team's seat in the game's events: left, center, then right. Without a center team the right
+/// team sits in seat 1.
+pub fn seat_of_team(game: &Game, team_id: Uuid) -> Option<i32> {
+    match team_id {
+        t if t == game.leftteamid => Some(0),
+        t if Some(t) == game.centerteamid => Some(1),
+        t if t == game.rightteamid => Some(game.centerteamid.map_or(1, |_| 2)),
+        _ => None,
+    }
+}
+
 pub fn create(db: &mut database::Connection, item: &NewGame) -> QueryResult<Game> {
     use crate::schema::games::dsl::*;
 
@@ -374,6 +388,29 @@ pub fn create_update(db: &mut database::Connection, item: &GameChangeset) -> Que
         .get_result::<Game>(db)
 }
 
+/// Like `create_update`, except that events QuizMachine still sends with the key of a game that
+/// was moved or merged go to the game they belong to now, instead of recreating it where it was.
+pub fn create_update_of_eventlog_key(db: &mut database::Connection, item: &GameChangeset, key: &models::eventlog::EventlogKey) -> QueryResult<Game> {
+    if let Some(tournament_id) = item.tournamentid
+        && let Some(owner) = models::eventlog::read_game_of_eventlog_key(db, tournament_id, key)? {
+        return read(db, owner);
+    }
+    create_update(db, item)
+}
+
+/// Widens the game's actual start and end to include an event that happened at `at`.
+pub fn record_event_time(db: &mut database::Connection, item_id: Uuid, at: DateTime<Utc>) -> QueryResult<usize> {
+    use crate::schema::games::dsl::*;
+
+    let started = diesel::update(games.filter(gid.eq(item_id)).filter(actual_start_time.is_null().or(actual_start_time.gt(at))))
+        .set(actual_start_time.eq(at))
+        .execute(db)?;
+    let ended = diesel::update(games.filter(gid.eq(item_id)).filter(actual_end_time.is_null().or(actual_end_time.lt(at))))
+        .set(actual_end_time.eq(at))
+        .execute(db)?;
+    Ok(started + ended)
+}
+
 pub fn read(db_conn: &mut database::Connection, item_id: Uuid) -> QueryResult<Game> {
     use crate::schema::games::dsl::*;
     games.filter(gid.eq(item_id)).first::<Game>(db_conn)
@@ -0,0 +1,511 @@
+use std::collections::{HashMap, HashSet};
+use crate::database;
+use crate::models::{self, game::Game, gameevent::{GameEvent, GameResult, NewGameEvent}};
+use crate::models::common::invalid;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Games are created by QuizMachine from the room, round and client key it sends, so a quizmaster
+// who picks the wrong round starts a second game. An admin can move a game to the right room or
+// round, or merge the events of the stray game into the real one. Every change is audited.
+
+pub const ACTION_MOVE: &str = "move";
+pub const ACTION_MERGE: &str = "merge";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::game_audits)]
+#[diesel(primary_key(auditid))]
+pub struct GameAudit {
+    pub auditid: Uuid,
+    pub tid: Uuid,
+    pub gid: Uuid,                              // the game that was moved, or merged into
+    pub source_gid: Option<Uuid>,               // the game merged away
+    pub action: String,                         // move or merge
+    pub detail: String,                         // what changed, in JSON
+    pub performed_by: Option<Uuid>,             // None once that user is deleted
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable)]
+#[diesel(table_name = crate::schema::game_audits)]
+pub struct NewGameAudit {
+    pub tid: Uuid,
+    pub gid: Uuid,
+    pub source_gid: Option<Uuid>,
+    pub action: String,
+    pub detail: String,
+    pub performed_by: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameMoveRequest {
+    pub roomid: Option<Uuid>,
+    pub roundid: Option<Uuid>,
+    pub preview: Option<bool>,                  // defaults to true: nothing is written
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct GamePlace {
+    pub divisionid: Uuid,
+    pub roomid: Uuid,
+    pub roundid: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameMove {
+    pub gid: Uuid,
+    pub preview: bool,
+    pub before: GamePlace,
+    pub after: GamePlace,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameMergeRequest {
+    pub from: Uuid,                             // the game whose events move over; it is deleted
+    pub preview: Option<bool>,                  // defaults to true: nothing is written
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameMerge {
+    pub gid: Uuid,
+    pub source_gid: Uuid,
+    pub preview: bool,
+    pub events_moved: i32,
+    pub duplicates_dropped: i32,                // events both games already had
+    pub renumbered: i32,                        // events given a new number to avoid a collision
+    pub result: Option<GameResult>,
+    pub problems: Vec<String>,                  // why the merged stream doesn't score, if it doesn't
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct DuplicateGames {
+    pub gids: Vec<Uuid>,                        // oldest first
+    pub reasons: Vec<String>,
+}
+
+fn teams_of(game: &Game) -> Vec<Uuid> {
+    let mut teams: Vec<Uuid> = [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)].into_iter().flatten().collect();
+    teams.sort();
+    teams
+}
+
+fn place_of(game: &Game) -> GamePlace {
+    GamePlace { divisionid: game.divisionid, roomid: game.roomid, roundid: game.roundid }
+}
+
+fn audit(db: &mut database::Connection, item: &NewGameAudit) -> QueryResult<GameAudit> {
+    use crate::schema::game_audits::dsl::*;
+    diesel::insert_into(game_audits).values(item).get_result::<GameAudit>(db)
+}
+
+/// Moves a game to another room, round or both. The round decides the division.
+pub fn move_game(db: &mut database::Connection, game: &Game, user_id: Uuid, request: &GameMoveRequest) -> QueryResult<GameMove> {
+    let preview = request.preview.unwrap_or(true);
+    let before = place_of(game);
+    let mut after = before.clone();
+
+    if let Some(room_id) = request.roomid {
+        let room = models::room::read(db, room_id).map_err(|_| invalid(format!["Room {} not found", room_id]))?;
+        if room.tid != game.tournamentid {
+            return Err(invalid(format!["Room '{}' is in another tournament", room.name]));
+        }
+        after.roomid = room.roomid;
+    }
+    if let Some(round_id) = request.roundid {
+        let round = models::round::read(db, round_id).map_err(|_| invalid(format!["Round {} not found", round_id]))?;
+        let division = models::division::read(db, round.did)?;
+        if division.tid != game.tournamentid {
+            return Err(invalid(format!["Round {} is in another tournament", round_id]));
+        }
+        after.roundid = round.roundid;
+        after.divisionid = round.did;
+    }
+    if after == before {
+        return Err(invalid("The game is already in that room and round".to_string()));
+    }
+
+    // QuizMachine identifies a game by its room, round and client key, so two games can't share them
+    let taken = {
+        use crate::schema::games::dsl::*;
+        games
+            .filter(gid.ne(game.gid))
+            .filter(org.eq(&game.org))
+            .filter(tournamentid.eq(game.tournamentid))
+            .filter(divisionid.eq(after.divisionid))
+            .filter(roomid.eq(after.roomid))
+            .filter(roundid.eq(after.roundid))
+            .filter(clientkey.eq(&game.clientkey))
+            .select(gid)
+            .first::<Uuid>(db)
+            .optional()?
+    };
+    if let Some(other) = taken {
+        return Err(invalid(format!["Game {} is already in that room and round; merge the two games instead", other]));
+    }
+    // nor can a game take over the key another game was moved away from or merged out of
+    let after_key = (game.clientkey.clone(), after.divisionid.to_string(), after.roomid.to_string(), after.roundid.to_string());
+    if let Some(other) = models::eventlog::read_game_of_eventlog_key(db, game.tournamentid, &after_key)?.filter(|g| *g != game.gid) {
+        return Err(invalid(format!["QuizMachine's events for that room and round go to game {}; merge the two games instead", other]));
+    }
+
+    if !preview {
+        let record = NewGameAudit {
+            tid: game.tournamentid,
+            gid: game.gid,
+            source_gid: None,
+            action: ACTION_MOVE.to_string(),
+            detail: serde_json::json!({ "before": before, "after": after }).to_string(),
+            performed_by: user_id,
+        };
+        db.transaction::<_, diesel::result::Error, _>(|conn| {
+            use crate::schema::games::dsl::*;
+            // QuizMachine keeps sending the game with the room and round it started in
+            models::eventlog::record_eventlog_key(conn, game, game.gid)?;
+            diesel::update(games.filter(gid.eq(game.gid)))
+                .set((divisionid.eq(after.divisionid), roomid.eq(after.roomid), roundid.eq(after.roundid), updated_at.eq(Utc::now())))
+                .execute(conn)?;
+            audit(conn, &record)?;
+            Ok(())
+        })?;
+    }
+
+    Ok(GameMove { gid: game.gid, preview, before, after })
+}
+
+/// Where each of a stray game's events is in the merged game, by question and event number.
+pub type EventPlaces = HashMap<(i32, i32), (i32, i32)>;
+
+/// Adds `source`'s events to `target`'s. An event the target already has on the same question is a
+/// duplicate and dropped; one whose number is taken is renumbered after the question's last event.
+/// Returns the events to insert, the duplicates dropped, the events renumbered and where each source
+/// event is in the merged game: its own place, or that of the target's event it duplicates.
+pub fn merge_events(target_gid: Uuid, target: &[GameEvent], source: &[GameEvent]) -> (Vec<NewGameEvent>, i32, i32, EventPlaces) {
+    let identity = |e: &GameEvent| (e.question, e.event.clone(), e.name.clone(), e.team, e.quizzer, e.parm1.clone(), e.parm2.clone());
+
+    let mut unmatched: HashMap<_, Vec<(i32, i32)>> = HashMap::new();
+    for e in target.iter() {
+        unmatched.entry(identity(e)).or_default().push((e.question, e.eventnum));
+    }
+    let mut taken: HashSet<(i32, i32)> = target.iter().map(|e| (e.question, e.eventnum)).collect();
+    let mut last: HashMap<i32, i32> = HashMap::new();
+    for e in target.iter() {
+        let entry = last.entry(e.question).or_insert(e.eventnum);
+        *entry = (*entry).max(e.eventnum);
+    }
+
+    let mut ordered: Vec<&GameEvent> = source.iter().collect();
+    ordered.sort_by_key(|e| (e.question, e.eventnum));
+
+    let (mut events, mut duplicates, mut renumbered, mut placed) = (vec![], 0, 0, HashMap::new());
+    for e in ordered {
+        if let Some(kept) = unmatched.get_mut(&identity(e)).filter(|k| !k.is_empty()) {
+            placed.insert((e.question, e.eventnum), kept.remove(0));
+            duplicates += 1;
+            continue;
+        }
+        let mut eventnum = e.eventnum;
+        if taken.contains(&(e.question, eventnum)) {
+            eventnum = last.get(&e.question).copied().unwrap_or(0) + 1;
+            renumbered += 1;
+        }
+        taken.insert((e.question, eventnum));
+        placed.insert((e.question, e.eventnum), (e.question, eventnum));
+        let entry = last.entry(e.question).or_insert(eventnum);
+        *entry = (*entry).max(eventnum);
+        events.push(NewGameEvent {
+            gid: target_gid,
+            question: e.question,
+            eventnum,
+            name: e.name.clone(),
+            team: e.team,
+            quizzer: e.quizzer,
+            event: e.event.clone(),
+            parm1: e.parm1.clone(),
+            parm2: e.parm2.clone(),
+            clientts: e.clientts,
+            serverts: e.serverts,
+            md5digest: e.md5digest.clone(),
+        });
+    }
+    (events, duplicates, renumbered, placed)
+}
+
+/// Merges the events of `source` into `target` and deletes `source`. The two games must be in the
+/// same tournament and have the same teams, and can't both have an outcome or a paper scoresheet.
+pub fn merge_games(db: &mut database::Connection, target: &Game, source: &Game, user_id: Uuid, preview: bool) -> QueryResult<GameMerge> {
+    if target.gid == source.gid {
+        return Err(invalid("A game can't be merged into itself".to_string()));
+    }
+    if target.tournamentid != source.tournamentid {
+        return Err(invalid("The games are in different tournaments".to_string()));
+    }
+    if teams_of(target) != teams_of(source) {
+        return Err(invalid("The games have different teams".to_string()));
+    }
+    // an outcome or paper scoresheet goes with the stray game's events, unless the games both have one
+    let outcomes = models::game_outcome::read_all_outcomes_of_games(db, &[target.gid, source.gid])?;
+    if outcomes.len() == 2 {
+        return Err(invalid("Both games have a recorded outcome; delete one before merging".to_string()));
+    }
+    if models::scoresheet::is_manual(db, target.gid)? && models::scoresheet::is_manual(db, source.gid)? {
+        return Err(invalid("Both games were entered from a paper scoresheet; delete one before merging".to_string()));
+    }
+
+    let target_events = models::gameevent::read_all_gameevents_of_games(db, &[target.gid])?;
+    let source_events = models::gameevent::read_all_gameevents_of_games(db, &[source.gid])?;
+    let (new_events, duplicates_dropped, renumbered, placed) = merge_events(target.gid, &target_events, &source_events);
+
+    let mut merged = target_events.clone();
+    merged.extend(new_events.iter().cloned().map(GameEvent::new_from_new_game_event));
+    merged.sort_by_key(|e| (e.question, e.eventnum));
+    let questions_per_game = models::award_setting::read_or_default(db, target.divisionid)?.questions_per_game;
+    let (result, problems) = match models::gameevent::calculate_game_result(target.gid, merged, questions_per_game) {
+        Ok(r) => (Some(r), vec![]),
+        Err(errors) => (None, errors),
+    };
+
+    let report = GameMerge {
+        gid: target.gid,
+        source_gid: source.gid,
+        preview,
+        events_moved: new_events.len() as i32,
+        duplicates_dropped,
+        renumbered,
+        result,
+        problems,
+    };
+
+    if !preview {
+        let record = NewGameAudit {
+            tid: target.tournamentid,
+            gid: target.gid,
+            source_gid: Some(source.gid),
+            action: ACTION_MERGE.to_string(),
+            detail: serde_json::json!({
+                "source": place_of(source),
+                "source_clientkey": source.clientkey,
+                "events_moved": report.events_moved,
+                "duplicates_dropped": duplicates_dropped,
+                "renumbered": renumbered,
+            }).to_string(),
+            performed_by: user_id,
+        };
+        db.transaction::<_, diesel::result::Error, _>(|conn| {
+            use crate::schema::{game_eventlog_keys, game_outcomes, game_questions, gameevents, games, games_statsgroups, manual_scoresheets, rulings};
+            diesel::insert_into(gameevents::table).values(&new_events).execute(conn)?;
+            diesel::delete(gameevents::table.filter(gameevents::gid.eq(source.gid))).execute(conn)?;
+
+            // the merged game takes over the stray game's stats groups
+            let groups: Vec<Uuid> = games_statsgroups::table
+                .filter(games_statsgroups::gameid.eq(source.gid))
+                .select(games_statsgroups::statsgroupid)
+                .load(conn)?;
+            diesel::delete(games_statsgroups::table.filter(games_statsgroups::gameid.eq(source.gid))).execute(conn)?;
+            for group in groups {
+                diesel::insert_into(games_statsgroups::table)
+                    .values((games_statsgroups::gameid.eq(target.gid), games_statsgroups::statsgroupid.eq(group)))
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+
+            // and its challenge and appeal cases, each linked to where its event is in the merged game
+            let cases: Vec<(Uuid, Option<i32>, Option<i32>)> = rulings::table
+                .filter(rulings::gid.eq(source.gid))
+                .select((rulings::rulingid, rulings::event_question, rulings::event_num))
+                .load(conn)?;
+            for (ruling_id, event_question, event_num) in cases {
+                let event = event_question.zip(event_num).and_then(|e| placed.get(&e).copied());
+                diesel::update(rulings::table.filter(rulings::rulingid.eq(ruling_id)))
+                    .set((
+                        rulings::gid.eq(target.gid),
+                        rulings::event_question.eq(event.map(|e| e.0)),
+                        rulings::event_num.eq(event.map(|e| e.1)),
+                    ))
+                    .execute(conn)?;
+            }
+
+            // and its outcome, paper scoresheet and links to the packet's questions, keeping the
+            // merged game's own link where both games read a question
+            diesel::update(game_outcomes::table.filter(game_outcomes::gid.eq(source.gid)))
+                .set(game_outcomes::gid.eq(target.gid))
+                .execute(conn)?;
+            diesel::update(manual_scoresheets::table.filter(manual_scoresheets::gid.eq(source.gid)))
+                .set(manual_scoresheets::gid.eq(target.gid))
+                .execute(conn)?;
+            let taken: Vec<i32> = game_questions::table
+                .filter(game_questions::gid.eq(target.gid))
+                .select(game_questions::question)
+                .load(conn)?;
+            diesel::update(game_questions::table.filter(game_questions::gid.eq(source.gid)).filter(game_questions::question.ne_all(taken)))
+                .set(game_questions::gid.eq(target.gid))
+                .execute(conn)?;
+
+            // and its eventlog, which stays under the keys the stray game was sent with
+            diesel::update(game_eventlog_keys::table.filter(game_eventlog_keys::gid.eq(source.gid)))
+                .set(game_eventlog_keys::gid.eq(target.gid))
+                .execute(conn)?;
+            models::eventlog::record_eventlog_key(conn, source, target.gid)?;
+
+            diesel::delete(games::table.filter(games::gid.eq(source.gid))).execute(conn)?;
+            audit(conn, &record)?;
+            Ok(())
+        })?;
+    }
+
+    Ok(report)
+}
+
+/// Groups a tournament's games that look like the same game entered twice: the same teams in the
+/// same round, or the same teams and quizmaster in the same room.
+pub fn find_duplicates(games: &[Game]) -> Vec<DuplicateGames> {
+    let mut ordered: Vec<&Game> = games.iter().filter(|g| !g.ignore).collect();
+    ordered.sort_by_key(|g| (g.created_at, g.gid));
+
+    let mut found: Vec<DuplicateGames> = vec![];
+    for (idx, first) in ordered.iter().enumerate() {
+        for second in ordered.iter().skip(idx + 1) {
+            if teams_of(first) != teams_of(second) {
+                continue;
+            }
+            let mut reasons = vec![];
+            if first.roundid == second.roundid {
+                reasons.push("same teams in the same round".to_string());
+            }
+            if first.roomid == second.roomid && first.quizmasterid == second.quizmasterid {
+                reasons.push("same teams and quizmaster in the same room".to_string());
+            }
+            if !first.clientkey.is_empty() && first.clientkey == second.clientkey {
+                reasons.push("same QuizMachine client key".to_string());
+            }
+            if reasons.is_empty() {
+                continue;
+            }
+            match found.iter_mut().find(|d| d.gids.contains(&first.gid)) {
+                Some(group) => {
+                    if !group.gids.contains(&second.gid) {
+                        group.gids.push(second.gid);
+                    }
+                    for reason in reasons {
+                        if !group.reasons.contains(&reason) {
+                            group.reasons.push(reason);
+                        }
+                    }
+                },
+                None => found.push(DuplicateGames { gids: vec![first.gid, second.gid], reasons }),
+            }
+        }
+    }
+    found
+}
+
+pub fn read_duplicates_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<DuplicateGames>> {
+    let games = {
+        use crate::schema::games::dsl::*;
+        games.filter(tournamentid.eq(tournament_id)).load::<Game>(db)?
+    };
+    Ok(find_duplicates(&games))
+}
+
+pub fn read_all_audits_of_game(db: &mut database::Connection, game_id: Uuid) -> QueryResult<Vec<GameAudit>> {
+    use crate::schema::game_audits::dsl::*;
+    game_audits
+        .filter(gid.eq(game_id).or(source_gid.eq(game_id)))
+        .order(created_at.asc())
+        .load::<GameAudit>(db)
+}
+
+pub fn read_all_audits_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<GameAudit>> {
+    use crate::schema::game_audits::dsl::*;
+    game_audits
+        .filter(tid.eq(tournament_id))
+        .order(created_at.asc())
+        .load::<GameAudit>(db)
+}
+
+pub fn has_merged(db: &mut database::Connection, game_id: Uuid) -> QueryResult<bool> {
+    use crate::schema::game_audits::dsl::*;
+    diesel::select(diesel::dsl::exists(game_audits.filter(gid.eq(game_id)).filter(action.eq(ACTION_MERGE)))).get_result(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(gid: Uuid, question: i32, eventnum: i32, code: &str, name: &str) -> GameEvent {
+        GameEvent {
+            gid,
+            question,
+            eventnum,
+            name: name.to_string(),
+            team: 0,
+            quizzer: 0,
+            event: code.to_string(),
+            parm1: String::new(),
+            parm2: String::new(),
+            clientts: Utc::now(),
+            serverts: Utc::now(),
+            md5digest: String::new(),
+        }
+    }
+
+    #[test]
+    fn merge_events_works() {
+        let (target_gid, source_gid) = (Uuid::new_v4(), Uuid::new_v4());
+        let target = vec![
+            event(target_gid, 1, 1, "TN", "Team A"),
+            event(target_gid, 1, 2, "TC", "Ann"),
+            event(target_gid, 2, 1, "NJ", ""),
+        ];
+        let source = vec![
+            event(source_gid, 1, 1, "TN", "Team A"),       // the same setup event: dropped
+            event(source_gid, 2, 1, "TC", "Abe"),          // question 2, event 1 is taken: renumbered
+            event(source_gid, 3, 1, "TC", "Ann"),
+        ];
+
+        let (events, duplicates, renumbered, placed) = merge_events(target_gid, &target, &source);
+        assert_eq!((duplicates, renumbered), (1, 1));
+        assert_eq!(placed, HashMap::from([((1, 1), (1, 1)), ((2, 1), (2, 2)), ((3, 1), (3, 1))]));
+        let placed: Vec<(i32, i32, &str)> = events.iter().map(|e| (e.question, e.eventnum, e.name.as_str())).collect();
+        assert_eq!(placed, vec![(2, 2, "Abe"), (3, 1, "Ann")]);
+        assert!(events.iter().all(|e| e.gid == target_gid));
+    }
+
+    #[test]
+    fn find_duplicates_works() {
+        let now = Utc::now();
+        let (left, right, qm, room, round) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let game = |roomid: Uuid, roundid: Uuid, leftteamid: Uuid, rightteamid: Uuid, minutes: i64| Game {
+            gid: Uuid::new_v4(),
+            org: "Nazarene".to_string(),
+            tournamentid: Uuid::nil(),
+            divisionid: Uuid::nil(),
+            roomid,
+            roundid,
+            ignore: false,
+            ruleset: "Tournament".to_string(),
+            leftteamid,
+            centerteamid: None,
+            rightteamid,
+            quizmasterid: qm,
+            contentjudgeid: None,
+            created_at: now + chrono::Duration::minutes(minutes),
+            updated_at: now,
+            clientkey: String::new(),
+            actual_start_time: None,
+            actual_end_time: None,
+            start_offset_minutes: 0,
+        };
+        let real = game(room, round, left, right, 0);
+        let stray = game(room, Uuid::new_v4(), right, left, 5);     // wrong round, teams swapped sides
+        let unrelated = game(Uuid::new_v4(), Uuid::new_v4(), left, right, 10);
+
+        let found = find_duplicates(&[stray.clone(), unrelated, real.clone()]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].gids, vec![real.gid, stray.gid]);
+        assert_eq!(found[0].reasons, vec!["same teams and quizmaster in the same room".to_string()]);
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use crate::database;
+use crate::models::{award_setting::AwardSettings, game::Game, gameevent::{GameResult, TeamGameResult}};
+use crate::models::common::invalid;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// An outcome decides a game without its event stream. A forfeit is a loss for the team that
+// forfeited and a win for the others; a bye is a win for the team that received it (unless the
+// division's award settings say otherwise); a no contest doesn't count for anyone.
+
+pub const REASONS: [&str; 7] = ["no_show", "withdrawn", "ineligible", "misconduct", "technical", "odd_team_count", "other"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutcomeKind {
+    Forfeit,
+    Bye,
+    NoContest,
+}
+
+impl OutcomeKind {
+    pub fn from_param(value: &str) -> Result<Self, String> {
+        match value {
+            "forfeit" => Ok(OutcomeKind::Forfeit),
+            "bye" => Ok(OutcomeKind::Bye),
+            "no_contest" => Ok(OutcomeKind::NoContest),
+            _ => Err(format!["'{}' is not a game outcome; use forfeit, bye or no_contest", value]),
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutcomeKind::Forfeit => "forfeit",
+            OutcomeKind::Bye => "bye",
+            OutcomeKind::NoContest => "no_contest",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::game_outcomes)]
+#[diesel(primary_key(gid))]
+pub struct GameOutcome {
+    pub gid: Uuid,
+    pub outcome: String,                        // forfeit, bye or no_contest
+    pub reason: String,                         // one of REASONS
+    pub teamid: Option<Uuid>,                   // the team that forfeited or received the bye
+    pub note: String,
+    pub recorded_by: Option<Uuid>,              // None once that user is deleted
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::game_outcomes)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewGameOutcome {
+    pub gid: Uuid,
+    pub outcome: String,
+    pub reason: String,
+    pub teamid: Option<Uuid>,
+    pub note: String,
+    pub recorded_by: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameOutcomeRequest {
+    pub outcome: String,
+    pub reason: String,
+    pub teamid: Option<Uuid>,
+    pub note: Option<String>,
+}
+
+impl GameOutcome {
+    pub fn describe(&self) -> String {
+        let kind = match self.outcome.as_str() {
+            "forfeit" => "Forfeit",
+            "bye" => "Bye",
+            _ => "No contest",
+        };
+        let reason = self.reason.replace('_', " ");
+        if self.note.is_empty() {
+            format!["{} ({})", kind, reason]
+        } else {
+            format!["{} ({}): {}", kind, reason, self.note]
+        }
+    }
+}
+
+fn teams_of_game(game: &Game) -> Vec<Uuid> {
+    [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)].into_iter().flatten().collect()
+}
+
+pub fn validate(game: &Game, req: &GameOutcomeRequest) -> Result<OutcomeKind, String> {
+    let kind = OutcomeKind::from_param(&req.outcome)?;
+    if !REASONS.contains(&req.reason.as_str()) {
+        return Err(format!["'{}' is not a reason; use one of {}", req.reason, REASONS.join(", ")]);
+    }
+    match (kind, req.teamid) {
+        (OutcomeKind::NoContest, Some(_)) => Err("A no contest is not recorded against a team".to_string()),
+        (OutcomeKind::NoContest, None) => Ok(kind),
+        (_, None) => Err(format!["A {} needs the teamid it applies to", kind.as_str()]),
+        (_, Some(id)) if !teams_of_game(game).contains(&id) => Err("The team does not play in this game".to_string()),
+        _ => Ok(kind),
+    }
+}
+
+/// Records the game's outcome, replacing any earlier one. Returns whether it was new.
+pub fn record(db: &mut database::Connection, game: &Game, user_id: Uuid, req: &GameOutcomeRequest) -> QueryResult<(GameOutcome, bool)> {
+    use crate::schema::game_outcomes::dsl::*;
+
+    let kind = validate(game, req).map_err(invalid)?;
+    let item = NewGameOutcome {
+        gid: game.gid,
+        outcome: kind.as_str().to_string(),
+        reason: req.reason.clone(),
+        teamid: req.teamid,
+        note: req.note.clone().unwrap_or_default(),
+        recorded_by: user_id,
+    };
+    let existed = read(db, game.gid).is_ok();
+    let saved = diesel::insert_into(game_outcomes)
+        .values(&item)
+        .on_conflict(gid)
+        .do_update()
+        .set((&item, updated_at.eq(Utc::now())))
+        .get_result::<GameOutcome>(db)?;
+    Ok((saved, !existed))
+}
+
+pub fn read(db: &mut database::Connection, game_id: Uuid) -> QueryResult<GameOutcome> {
+    use crate::schema::game_outcomes::dsl::*;
+    game_outcomes.filter(gid.eq(game_id)).first::<GameOutcome>(db)
+}
+
+pub fn read_all_outcomes_of_games(db: &mut database::Connection, game_ids: &[Uuid]) -> QueryResult<HashMap<Uuid, GameOutcome>> {
+    use crate::schema::game_outcomes::dsl::*;
+    let found = game_outcomes.filter(gid.eq_any(game_ids)).load::<GameOutcome>(db)?;
+    Ok(found.into_iter().map(|o| (o.gid, o)).collect())
+}
+
+pub fn delete(db: &mut database::Connection, game_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::game_outcomes::dsl::*;
+    diesel::delete(game_outcomes.filter(gid.eq(game_id))).execute(db)
+}
+
+/// The result the outcome stands for, shaped like one calculated from an event stream (teams are
+/// numbered by seat, without quizzers), together with the team ids in the same order. Err gives
+/// the reason the game doesn't count.
+pub fn decided_result(game: &Game, outcome: &GameOutcome, settings: &AwardSettings, team_names: &HashMap<Uuid, String>) -> Result<(GameResult, Vec<Option<Uuid>>), String> {
+    let seats = teams_of_game(game);
+    let kind = OutcomeKind::from_param(&outcome.outcome)?;
+    let scored: Vec<(usize, Uuid, i32, i32)> = match kind {
+        OutcomeKind::NoContest => return Err(outcome.describe()),
+        OutcomeKind::Bye if !settings.bye_counts_as_win => return Err(format!["{}; byes don't count in this division", outcome.describe()]),
+        OutcomeKind::Bye => seats
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| Some(**id) == outcome.teamid)
+            .map(|(idx, id)| (idx, *id, settings.bye_score, 1))
+            .collect(),
+        OutcomeKind::Forfeit => seats
+            .iter()
+            .enumerate()
+            .map(|(idx, id)| {
+                if Some(*id) == outcome.teamid {
+                    (idx, *id, settings.forfeit_loss_score, seats.len() as i32)
+                } else {
+                    (idx, *id, settings.forfeit_win_score, 1)
+                }
+            })
+            .collect(),
+    };
+    if scored.is_empty() {
+        return Err(format!["{}; the team no longer plays in this game", outcome.describe()]);
+    }
+
+    let teams = scored
+        .iter()
+        .map(|(idx, id, score, rank)| TeamGameResult {
+            team: *idx as i32,
+            name: team_names.get(id).cloned().unwrap_or_default(),
+            score: *score,
+            rank: *rank,
+            quizzers: vec![],
+        })
+        .collect();
+    let teamids = scored.iter().map(|(_, id, _, _)| Some(*id)).collect();
+    Ok((GameResult { gid: game.gid, is_final: true, last_question: 0, teams }, teamids))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::award_setting::AwardSettingsBuilder;
+
+    fn game(center: bool) -> Game {
+        Game {
+            gid: Uuid::new_v4(),
+            org: String::new(),
+            tournamentid: Uuid::new_v4(),
+            divisionid: Uuid::new_v4(),
+            roomid: Uuid::new_v4(),
+            roundid: Uuid::new_v4(),
+            ignore: false,
+            ruleset: String::new(),
+            leftteamid: Uuid::new_v4(),
+            centerteamid: if center { Some(Uuid::new_v4()) } else { None },
+            rightteamid: Uuid::new_v4(),
+            quizmasterid: Uuid::new_v4(),
+            contentjudgeid: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            clientkey: String::new(),
+            actual_start_time: None,
+            actual_end_time: None,
+            start_offset_minutes: 0,
+        }
+    }
+
+    fn outcome(game: &Game, kind: &str, teamid: Option<Uuid>) -> GameOutcome {
+        GameOutcome {
+            gid: game.gid,
+            outcome: kind.to_string(),
+            reason: "no_show".to_string(),
+            teamid,
+            note: String::new(),
+            recorded_by: Some(Uuid::new_v4()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn settings() -> AwardSettings {
+        AwardSettings::from(AwardSettingsBuilder::new_default(Uuid::new_v4()).set_forfeit_win_score(100).build().unwrap())
+    }
+
+    #[test]
+    fn decided_result_works() {
+        let names = HashMap::new();
+        let mut settings = settings();
+
+        // the center team forfeits a three-team game: it places last, the others share the win
+        let three = game(true);
+        let forfeit = outcome(&three, "forfeit", three.centerteamid);
+        let (result, teamids) = decided_result(&three, &forfeit, &settings, &names).unwrap();
+        assert!(result.is_final);
+        assert_eq!(teamids, vec![Some(three.leftteamid), three.centerteamid, Some(three.rightteamid)]);
+        assert_eq!(result.teams.iter().map(|t| (t.team, t.score, t.rank)).collect::<Vec<_>>(), vec![(0, 100, 1), (1, 0, 3), (2, 100, 1)]);
+
+        // a bye only counts for the team that received it
+        let two = game(false);
+        let bye = outcome(&two, "bye", Some(two.rightteamid));
+        let (result, teamids) = decided_result(&two, &bye, &settings, &names).unwrap();
+        assert_eq!(teamids, vec![Some(two.rightteamid)]);
+        assert_eq!((result.teams[0].team, result.teams[0].rank), (1, 1));
+        settings.bye_counts_as_win = false;
+        assert!(decided_result(&two, &bye, &settings, &names).is_err());
+
+        assert!(decided_result(&two, &outcome(&two, "no_contest", None), &settings, &names).is_err());
+    }
+
+    #[test]
+    fn validate_works() {
+        let two = game(false);
+        let request = |kind: &str, reason: &str, teamid| GameOutcomeRequest { outcome: kind.to_string(), reason: reason.to_string(), teamid, note: None };
+        assert_eq!(validate(&two, &request("forfeit", "no_show", Some(two.leftteamid))), Ok(OutcomeKind::Forfeit));
+        assert_eq!(validate(&two, &request("no_contest", "technical", None)), Ok(OutcomeKind::NoContest));
+        assert!(validate(&two, &request("forfeit", "no_show", None)).is_err());
+        assert!(validate(&two, &request("forfeit", "no_show", Some(Uuid::new_v4()))).is_err());
+        assert!(validate(&two, &request("no_contest", "technical", Some(two.leftteamid))).is_err());
+        assert!(validate(&two, &request("bye", "because", Some(two.leftteamid))).is_err());
+        assert!(validate(&two, &request("walkover", "other", Some(two.leftteamid))).is_err());
+    }
+}
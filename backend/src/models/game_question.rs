@@ -0,0 +1,124 @@
+use crate::database;
+use crate::models::{self, round_question::RoundQuestionRole};
+use crate::models::common::invalid;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Which question from the question bank was read for each question number of a game. As a game is
+// played its question numbers are linked to the round's packet: the regular questions in order,
+// then the overtime questions. When an alternate is read instead, the question is set by hand and
+// relinking from the packet leaves it alone.
+
+pub const SOURCE_PACKET: &str = "packet";
+pub const SOURCE_MANUAL: &str = "manual";
+
+// The events that mean a question was read.
+pub const READ_EVENTS: [&str; 3] = ["TC", "TE", "NJ"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::game_questions)]
+#[diesel(primary_key(gid, question))]
+pub struct GameQuestion {
+    pub gid: Uuid,
+    pub question: i32,                          // the game's question number, as in gameevents
+    pub questionid: Uuid,
+    pub source: String,                         // packet or manual
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::game_questions)]
+pub struct NewGameQuestion {
+    pub gid: Uuid,
+    pub question: i32,
+    pub questionid: Uuid,
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameQuestionChangeset {
+    pub questionid: Uuid,
+}
+
+/// The packet question for a game question number: the regular questions come first, then overtime.
+pub fn packet_question(db: &mut database::Connection, round_id: Uuid, number: i32) -> QueryResult<Option<Uuid>> {
+    if number < 1 {
+        return Ok(None);
+    }
+    let regular = models::round_question::read_question_ids(db, round_id, RoundQuestionRole::Regular)?;
+    if let Some(id) = regular.get(number as usize - 1) {
+        return Ok(Some(*id));
+    }
+    let overtime = models::round_question::read_question_ids(db, round_id, RoundQuestionRole::Overtime)?;
+    Ok(overtime.get(number as usize - 1 - regular.len()).copied())
+}
+
+pub fn read_all_of_game(db: &mut database::Connection, game_id: Uuid) -> QueryResult<Vec<GameQuestion>> {
+    use crate::schema::game_questions::dsl::*;
+    game_questions
+        .filter(gid.eq(game_id))
+        .order(question.asc())
+        .load::<GameQuestion>(db)
+}
+
+/// Links a question number of a game to its packet question, unless it's linked already.
+pub fn link(db: &mut database::Connection, game_id: Uuid, number: i32) -> QueryResult<Option<GameQuestion>> {
+    use crate::schema::game_questions::dsl::*;
+    if let Some(linked) = game_questions.find((game_id, number)).first::<GameQuestion>(db).optional()? {
+        return Ok(Some(linked));
+    }
+    let game = models::game::read(db, game_id)?;
+    let Some(packet_id) = packet_question(db, game.roundid, number)? else {
+        return Ok(None);
+    };
+    diesel::insert_into(game_questions)
+        .values(&NewGameQuestion { gid: game_id, question: number, questionid: packet_id, source: SOURCE_PACKET.to_string() })
+        .on_conflict_do_nothing()
+        .execute(db)?;
+    game_questions.find((game_id, number)).first::<GameQuestion>(db).optional()
+}
+
+/// Links every question read in a game to the round's packet again, keeping the questions set by hand.
+pub fn link_all(db: &mut database::Connection, game_id: Uuid) -> QueryResult<Vec<GameQuestion>> {
+    use crate::schema::gameevents;
+    use crate::schema::game_questions::dsl::*;
+
+    let numbers: Vec<i32> = gameevents::table
+        .filter(gameevents::gid.eq(game_id))
+        .filter(gameevents::event.eq_any(READ_EVENTS))
+        .select(gameevents::question)
+        .distinct()
+        .order(gameevents::question.asc())
+        .load::<i32>(db)?;
+
+    db.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(game_questions.filter(gid.eq(game_id)).filter(source.eq(SOURCE_PACKET))).execute(conn)?;
+        for number in numbers {
+            link(conn, game_id, number)?;
+        }
+        read_all_of_game(conn, game_id)
+    })
+}
+
+/// Sets the question read for a question number by hand, e.g. when an alternate was read.
+pub fn set(db: &mut database::Connection, game_id: Uuid, number: i32, item: &GameQuestionChangeset) -> QueryResult<GameQuestion> {
+    use crate::schema::game_questions::dsl::*;
+    if number < 1 {
+        return Err(invalid(format!["{} is not a question number", number]));
+    }
+    if models::question::read(db, item.questionid).is_err() {
+        return Err(invalid(format!["Question {} isn't in the question bank", item.questionid]));
+    }
+    let new_question = NewGameQuestion { gid: game_id, question: number, questionid: item.questionid, source: SOURCE_MANUAL.to_string() };
+    diesel::insert_into(game_questions)
+        .values(&new_question)
+        .on_conflict((gid, question))
+        .do_update()
+        .set((&new_question, updated_at.eq(diesel::dsl::now)))
+        .get_result::<GameQuestion>(db)
+}
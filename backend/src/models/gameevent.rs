@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use crate::{database, models::{common::PaginationParams}};
 use utoipa::ToSchema;
 
-const DEFAULT_QUESTIONS_PER_GAME: i32 = 20;
+pub const DEFAULT_QUESTIONS_PER_GAME: i32 = 20;
 const DEFAULT_SUBSTITUTION_SEAT: i32 = 4;
 const DEFAULT_INTERIM_SUBSTITUTION_SEAT: i32 = 1000;
 const DEFAULT_QUIZ_OUT: i32 = 4;
@@ -1510,7 +1510,18 @@ impl GameEventStreamBuilder {
         )
     }
     pub fn then_add_SB(self, name: &str, team: i32, new_seat: i32) -> Result<Self, Vec<String>> {
-        let errors: Vec<String> = vec![];
+        let mut errors: Vec<String> = vec![];
+        let sub_seat_number = 4;
+
+        // only the quizzer on the substitute's seat can be subbed in
+        if name.trim().is_empty() {
+            errors.push("SB was specified but the name of the quizzer being subbed in was blank.".to_string());
+        } else if self.teams[team as usize].quizzers[sub_seat_number as usize] != name {
+            errors.push(format!["Quizzer '{}' can't be subbed in because they are not the substitute on team '{}'. Substitute: '{}'.", name, team, self.teams[team as usize].quizzers[sub_seat_number as usize]]);
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
         // System Behavior to emulate:
         // 1. Insert "SB" event for *the quizzer being subbed-in* (this is the quizzer reflected in 'name' param)
@@ -1561,24 +1572,26 @@ impl GameEventStreamBuilder {
         new_eventnum += 1;
 
         // For the quizzer subbing-OUT:
-        let sub_seat_number = 4;
         new_events.push(
             GameEventBuilder::new_default(self.gid)
             .set_event(Some(GameEventCode::QN))
             .set_question(Some(new_question))
             .set_eventnum(Some(new_eventnum))
-            .set_name(Some(original_quizzer_name_in_substitution_seat))
+            .set_name(Some(original_quizzer_name_in_substitution_seat.clone()))
             .set_team(Some(team))
             .set_quizzer(Some(sub_seat_number))
             .build()
             .unwrap()
         );
 
-        if errors.len() > 0 {
-            return Err(errors);
-        }
+        // the two quizzers trade seats, so later rulings find the subbed-in quizzer on the new seat
+        let mut new_teams = self.teams.clone();
+        new_teams[team as usize].quizzers[new_seat as usize] = name.to_string();
+        new_teams[team as usize].quizzers[sub_seat_number as usize] = original_quizzer_name_in_substitution_seat;
+
         Ok(
             Self {
+                teams: new_teams,
                 events: new_events,
                 ..self
             }
@@ -2519,6 +2532,169 @@ pub fn create_update_game_event(db: &mut database::Connection, item: &NewGameEve
     }
 }
 
+// Game results (per-game summary of the calculated event stream, used for awards, standings, etc.):
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuizzerGameResult {
+    pub name: String,
+    pub seat: i32,
+    pub correct_tossups: i32,
+    pub errors_on_tossups: i32,
+    pub correct_bonuses: i32,
+    pub errors_on_bonuses: i32,
+    pub fouls: i32,
+    pub quizzed_out: bool,
+    pub errored_out: bool,
+    pub fouled_out: bool,
+    pub points: i32,  // individual points (regulation questions only)
+}
+impl QuizzerGameResult {
+    pub fn is_perfect_game(&self) -> bool {
+        // a "perfect" is quizzing out without a single toss-up error
+        self.quizzed_out && self.errors_on_tossups == 0
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TeamGameResult {
+    pub team: i32,  // 0 = left, 1 = center, 2 = right (as found in the event stream)
+    pub name: String,
+    pub score: i32,
+    pub rank: i32,  // competition ranking; tie-breaker questions are taken into account
+    pub quizzers: Vec<QuizzerGameResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct GameResult {
+    pub gid: Uuid,
+    pub is_final: bool,  // all regulation questions have been quizzed and there is exactly one winner
+    pub last_question: i32,
+    pub teams: Vec<TeamGameResult>,
+}
+
+/// The game's result, with `questions_per_game` regulation questions (the division's award
+/// settings); points on later questions are overtime and don't count for the quizzers.
+pub fn calculate_game_result(game_id: Uuid, game_events: Vec<GameEvent>, questions_per_game: i32) -> Result<GameResult, Vec<String>> {
+    // The calculator assumes a well-formed stream, so check everything it would trip over first.
+    let reference_errors = find_unresolvable_event_references(&game_events);
+    if !reference_errors.is_empty() {
+        return Err(reference_errors);
+    }
+    GameEventStreamValidator::new(game_events.clone())
+        .check_for_has_RM_and_QT()
+        .check_for_min_one_team_and_min_one_quizzer_per_team()
+        .check_for_no_team_name_duplicates()
+        .check_for_no_quizzer_name_duplicates_within_team()
+        .validate()?;
+
+    let mut calculated = GameEventCalculator::new(game_id, game_events)
+        .calculate_current_game_scores_and_counts()?;
+
+    // Rankings are only maintained by the calculator while breaking ties, so rank by score otherwise.
+    let went_to_overtime = calculated.teams.values().any(|t| t.rank != -1);
+    if !went_to_overtime {
+        calculated = calculated.update_team_rankings_using_competitive_ranking();
+    }
+
+    let mut teams: Vec<TeamGameResult> = vec![];
+    for (team_idx, team) in calculated.teams.iter() {
+        let mut quizzers: Vec<QuizzerGameResult> = team.quizzers
+            .iter()
+            .map(|(seat, quizzer)| QuizzerGameResult {
+                name: quizzer.name.clone(),
+                seat: *seat,
+                correct_tossups: quizzer.correct_tossups.len() as i32,
+                errors_on_tossups: quizzer.errors_on_tossups.len() as i32,
+                correct_bonuses: quizzer.correct_bonuses.len() as i32,
+                errors_on_bonuses: quizzer.errors_on_bonuses.len() as i32,
+                fouls: quizzer.fouls_received.len() as i32,
+                quizzed_out: quizzer.question_quizzed_out_on != -1,
+                errored_out: quizzer.question_errored_out_on != -1,
+                fouled_out: quizzer.question_fouled_out_on != -1 || quizzer.fouls_received.len() >= calculated.options.foul_out as usize,
+                points: calculate_individual_points(quizzer, &calculated.options, questions_per_game),
+            })
+            .collect();
+        quizzers.sort_by_key(|q| q.seat);
+        teams.push(TeamGameResult {
+            team: *team_idx,
+            name: team.name.clone(),
+            score: team.score,
+            rank: team.rank,
+            quizzers,
+        });
+    }
+    teams.sort_by_key(|t| t.team);
+
+    let winners = teams.iter().filter(|t| t.rank == 1).count();
+    Ok(GameResult {
+        gid: game_id,
+        is_final: calculated.current_question > questions_per_game && winners == 1,
+        last_question: calculated.current_question - 1,
+        teams,
+    })
+}
+
+fn calculate_individual_points(quizzer: &QuizzerForGameEventCalculator, options: &OptionsForGameEventCalculator, questions_per_game: i32) -> i32 {
+    let correct_in_regulation = quizzer.correct_tossups
+        .iter()
+        .filter(|&&q| q <= questions_per_game)
+        .count() as i32;
+    let mut points = correct_in_regulation * options.point_award_for_correct_tossup;
+    if quizzer.question_quizzed_out_on != -1 && quizzer.errors_on_tossups.is_empty() {
+        points += options.point_award_for_quizzing_out;
+    }
+    for (idx, question) in quizzer.errors_on_tossups.iter().enumerate() {
+        if *question > questions_per_game {
+            continue;
+        }
+        let error_num = (idx + 1) as i32;
+        if *question >= options.start_error_zone_deductions || error_num >= options.individual_error_begin_deduction_count {
+            points -= options.point_deduction_for_error_on_tossup;
+        }
+    }
+    points
+}
+
+fn find_unresolvable_event_references(game_events: &[GameEvent]) -> Vec<String> {
+    let mut errors: Vec<String> = vec![];
+    let mut game_events = game_events.to_vec();
+    game_events.sort();
+    let mut known_teams: Vec<i32> = vec![];
+    let mut known_seats: Vec<(i32, i32)> = vec![];
+    for game_event in game_events.iter() {
+        match game_event.event.as_str() {
+            "RM" | "QT" | "IP" | "OP" | "NJ" | "A+" | "A-" | "DE" => {},
+            "TN" => known_teams.push(game_event.team),
+            "QN" => {
+                if !known_teams.contains(&game_event.team) {
+                    errors.push(format!["Quizzer '{}' was named for team {} before the team was named. Question: {}", game_event.name, game_event.team, game_event.question]);
+                }
+                known_seats.push((game_event.team, game_event.quizzer));
+            },
+            "SC" | "SS" | "C-" | "FC" | "SB" | "TO" => {
+                if !known_teams.contains(&game_event.team) {
+                    errors.push(format!["Event '{}' refers to unknown team {}. Question: {}", game_event.event, game_event.team, game_event.question]);
+                }
+            },
+            "TC" | "TE" | "BC" | "BE" | "QO" | "EO" | "F-" => {
+                if !known_seats.contains(&(game_event.team, game_event.quizzer)) {
+                    errors.push(format!["Event '{}' refers to unknown quizzer seat {} on team {}. Question: {}", game_event.event, game_event.quizzer, game_event.team, game_event.question]);
+                }
+            },
+            unknown => errors.push(format!["Unknown game event code '{}'. Question: {}", unknown, game_event.question]),
+        }
+    }
+    errors
+}
+
+pub fn read_all_gameevents_of_games(db: &mut database::Connection, game_ids: &[Uuid]) -> QueryResult<Vec<GameEvent>> {
+    use crate::schema::gameevents::dsl::*;
+    gameevents
+        .filter(gid.eq_any(game_ids))
+        .order((gid, question, eventnum))
+        .load::<GameEvent>(db)
+}
+
 // Not including a Delete fn until it is apparent that it is needed.
 
 #[cfg(test)]
@@ -4064,4 +4240,85 @@ mod tests {
         assert![game_events_two_names_are_same_specific.is_err()];
         assert![game_events_two_names_are_same_everything.is_err()];
     }
+
+    #[test]
+    fn calculate_game_result_works() {
+
+        // ARRANGE:
+
+        let game_id = Uuid::new_v4();
+
+        let left_team = 0;
+        let center_team = 1;
+
+        let mut builder = GameEventStreamBuilder::new(game_id)
+            .then_add_RM("Tournament")
+            .then_add_QT("Nazarene")
+            .then_add_TN("Red Team", left_team).unwrap()
+            .then_add_QN_plus_if_SC_or_SS("Anna", left_team, 0, true, false).unwrap()
+            .then_add_QN_plus_if_SC_or_SS("Ben", left_team, 1, false, true).unwrap()
+            .then_add_TN("Blue Team", center_team).unwrap()
+            .then_add_QN_plus_if_SC_or_SS("Cara", center_team, 0, true, false).unwrap()
+            .then_add_QN_plus_if_SC_or_SS("Dan", center_team, 1, false, true).unwrap();
+        for _ in 0..4 {
+            builder = builder.then_add_TC("Anna", left_team).unwrap();  // questions 1-4, quizzes out without error
+        }
+        for _ in 0..3 {
+            builder = builder.then_add_TC("Ben", left_team).unwrap();  // questions 5-7
+        }
+        builder = builder.then_add_TE_and_bonuses("Cara", center_team, false, false).unwrap();  // question 8
+        for _ in 0..4 {
+            builder = builder.then_add_TC("Cara", center_team).unwrap();  // questions 9-12, quizzes out after an error
+        }
+        for _ in 0..8 {
+            builder = builder.then_add_NJ().unwrap();  // questions 13-20
+        }
+        let (game_events, _) = builder.to_game_events();
+
+        let (mut incomplete_game_events, _) = GameEventStreamBuilder::new(game_id)
+            .then_add_RM("Tournament")
+            .then_add_QT("Nazarene")
+            .then_add_TN("Red Team", left_team).unwrap()
+            .then_add_QN_plus_if_SC_or_SS("Anna", left_team, 0, true, false).unwrap()
+            .then_add_TC("Anna", left_team).unwrap()
+            .to_game_events();
+        incomplete_game_events.last_mut().unwrap().team = 2;  // refers to a team that was never named
+
+        // ACT:
+
+        let result = calculate_game_result(game_id, game_events.clone(), DEFAULT_QUESTIONS_PER_GAME).unwrap();
+        let short_result = calculate_game_result(game_id, game_events.clone(), 10).unwrap();
+        let long_result = calculate_game_result(game_id, game_events, 30).unwrap();
+        let bad_result = calculate_game_result(game_id, incomplete_game_events, DEFAULT_QUESTIONS_PER_GAME);
+
+        // ASSERT:
+
+        assert![result.is_final];
+        assert_eq![result.last_question, 20];
+        assert_eq![result.teams.len(), 2];
+
+        let red = &result.teams[0];
+        assert_eq![red.name, "Red Team"];
+        assert_eq![red.score, 150];
+        assert_eq![red.rank, 1];
+        assert_eq![red.quizzers[0].name, "Anna"];
+        assert_eq![red.quizzers[0].points, 90];
+        assert![red.quizzers[0].is_perfect_game()];
+        assert_eq![red.quizzers[1].points, 60];
+        assert![!red.quizzers[1].is_perfect_game()];
+
+        let blue = &result.teams[1];
+        assert_eq![blue.score, 80];
+        assert_eq![blue.rank, 2];
+        assert_eq![blue.quizzers[0].points, 80];
+        assert![blue.quizzers[0].quizzed_out];
+        assert![!blue.quizzers[0].is_perfect_game()];
+
+        // with 10 regulation questions, Cara's correct answers on 11 and 12 are overtime
+        assert![short_result.is_final];
+        assert_eq![short_result.teams[1].quizzers[0].points, 40];
+        assert![!long_result.is_final];
+
+        assert![bad_result.is_err()];
+    }
 }
@@ -37,3 +37,32 @@ pub mod role;
 pub mod permission;
 pub mod role_permission;
 pub mod users_roles;pub mod create_tournament_applicant;
+pub mod award_setting;
+pub mod division_rookie;
+pub mod award;
+pub mod certificate_template;
+pub mod certificate;
+pub mod export;
+pub mod eventlog_consistency;
+pub mod round_robin;
+pub mod bracket;
+pub mod conflict;
+pub mod officials;
+pub mod schedule;
+pub mod calendar;
+pub mod timeline;
+pub mod game_outcome;
+pub mod scoresheet;
+pub mod game_merge;
+pub mod question;
+pub mod question_import;
+pub mod packet;
+pub mod packet_export;
+pub mod round_question;
+pub mod ruling;
+pub mod game_question;
+pub mod question_usage;
+pub mod quizzer_strength;
+pub mod scripture;
+pub mod quiz_season;
+pub mod competition_level;
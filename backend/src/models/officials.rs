@@ -0,0 +1,505 @@
+use std::collections::{HashMap, HashSet};
+use crate::database;
+use crate::models::{conflict::{quizzers_of_team, user_name}, game::Game, room::Room, round::Round, team::Team, user::User};
+use crate::models::common::invalid;
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Games are officiated from their round's scheduled start time for `game_minutes`. Games of
+// rounds without a start time are taken to run at the same, unknown time, so nobody officiates two
+// of them and availability windows don't apply. Nobody officiates a game in which a team they
+// coach or quiz on plays, and everyone gets at least `min_break_minutes` between two games. Among
+// the officials left, the one with the fewest games so far is picked, so the workload stays
+// balanced.
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct AvailabilityWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct EligibleOfficial {
+    pub userid: Uuid,
+    pub quizmaster: Option<bool>,                        // defaults to true
+    pub contentjudge: Option<bool>,                      // defaults to true
+    pub availability: Option<Vec<AvailabilityWindow>>,   // defaults to always available
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct OfficialsRequest {
+    pub officials: Vec<EligibleOfficial>,
+    pub game_minutes: Option<i64>,          // defaults to 30
+    pub min_break_minutes: Option<i64>,     // defaults to 0
+    pub contentjudges: Option<bool>,        // defaults to true: every game also gets a content judge
+    pub round_ids: Option<Vec<Uuid>>,       // defaults to every round of the tournament
+    pub preview: Option<bool>,              // defaults to true: nothing is written
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct OfficialAssignment {
+    pub gid: Uuid,
+    pub roundid: Uuid,
+    pub round_name: String,
+    pub roomid: Uuid,
+    pub room_name: String,
+    pub scheduled_start_time: Option<DateTime<Utc>>,
+    pub quizmasterid: Option<Uuid>,
+    pub quizmaster_name: Option<String>,
+    pub contentjudgeid: Option<Uuid>,
+    pub contentjudge_name: Option<String>,
+    pub changed: bool,                      // differs from the officials the game has now
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct OfficialWorkload {
+    pub userid: Uuid,
+    pub name: String,
+    pub quizmaster_games: i32,
+    pub contentjudge_games: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct UnfilledSlot {
+    pub gid: Uuid,
+    pub round_name: String,
+    pub room_name: String,
+    pub scheduled_start_time: Option<DateTime<Utc>>,
+    pub role: String,                       // quizmaster or contentjudge
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct OfficialsAssignment {
+    pub tid: Uuid,
+    pub preview: bool,
+    pub assignments: Vec<OfficialAssignment>,
+    pub workload: Vec<OfficialWorkload>,
+    pub unfilled: Vec<UnfilledSlot>,
+    pub updated: i32,                       // games written; 0 in preview mode
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Quizmaster,
+    ContentJudge,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Quizmaster => "quizmaster",
+            Role::ContentJudge => "contentjudge",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameToOfficiate {
+    pub start: Option<DateTime<Utc>>,
+    pub excluded: HashSet<Uuid>,            // coaches and quizzers of the teams in the game
+    pub contentjudge: Option<Uuid>,         // the content judge the game keeps when none are planned
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub userid: Uuid,
+    pub quizmaster: bool,
+    pub contentjudge: bool,
+    pub windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,   // empty means always available
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfficialsPlan {
+    pub quizmasters: Vec<Option<usize>>,    // candidate index per game
+    pub contentjudges: Vec<Option<usize>>,
+    pub unfilled: Vec<(usize, Role, &'static str)>,
+}
+
+struct Planner<'a> {
+    games: &'a [GameToOfficiate],
+    candidates: &'a [Candidate],
+    game_length: Duration,
+    min_break: Duration,
+    busy: Vec<Vec<DateTime<Utc>>>,          // start times each candidate officiates
+    untimed: Vec<bool>,                     // officiates a game of a round without a start time
+    load: Vec<[usize; 2]>,                  // games per candidate as quizmaster and content judge
+}
+
+impl Planner<'_> {
+    fn can_play_role(&self, c: usize, role: Role) -> bool {
+        match role {
+            Role::Quizmaster => self.candidates[c].quizmaster,
+            Role::ContentJudge => self.candidates[c].contentjudge,
+        }
+    }
+
+    fn is_available(&self, c: usize, start: DateTime<Utc>) -> bool {
+        let windows = &self.candidates[c].windows;
+        let end = start + self.game_length;
+        windows.is_empty() || windows.iter().any(|(from, to)| *from <= start && end <= *to)
+    }
+
+    fn is_free(&self, c: usize, start: DateTime<Utc>) -> bool {
+        let gap = self.game_length + self.min_break;
+        self.busy[c].iter().all(|other| (start - *other).abs() >= gap)
+    }
+
+    // The candidates who can take the slot, or why nobody can.
+    fn candidates_for(&self, g: usize, role: Role, taken: Option<usize>) -> Result<Vec<usize>, &'static str> {
+        let mut left: Vec<usize> = (0..self.candidates.len()).filter(|c| self.can_play_role(*c, role)).collect();
+        if left.is_empty() {
+            return Err("nobody is eligible for this role");
+        }
+        left.retain(|c| !self.games[g].excluded.contains(&self.candidates[*c].userid));
+        if left.is_empty() {
+            return Err("every eligible official coaches or quizzes on a team in this game");
+        }
+        let kept = if role == Role::Quizmaster { self.games[g].contentjudge } else { None };
+        left.retain(|c| Some(self.candidates[*c].userid) != kept);
+        if left.is_empty() {
+            return Err("the only eligible official is the game's content judge");
+        }
+        if let Some(start) = self.games[g].start {
+            left.retain(|c| self.is_available(*c, start));
+            if left.is_empty() {
+                return Err("no eligible official is available at this time");
+            }
+            left.retain(|c| Some(*c) != taken && self.is_free(*c, start));
+        } else {
+            left.retain(|c| Some(*c) != taken && !self.untimed[*c]);
+        }
+        if left.is_empty() {
+            return Err("every available official is officiating or on a break");
+        }
+        Ok(left)
+    }
+
+    fn least_loaded(&self, options: &[usize], role: Role) -> usize {
+        let role_idx = if role == Role::Quizmaster { 0 } else { 1 };
+        *options
+            .iter()
+            .min_by_key(|c| (self.load[**c][0] + self.load[**c][1], self.load[**c][role_idx], **c))
+            .unwrap()
+    }
+}
+
+/// Assigns a quizmaster (and, if `contentjudges`, a content judge) to every game. Games are filled
+/// one start time at a time. Within a start time quizmasters, who every game needs, come before
+/// content judges, and the slot with the fewest options goes first.
+pub fn plan_officials(
+    games: &[GameToOfficiate],
+    candidates: &[Candidate],
+    game_length: Duration,
+    min_break: Duration,
+    contentjudges: bool,
+) -> OfficialsPlan {
+    let mut planner = Planner {
+        games,
+        candidates,
+        game_length,
+        min_break,
+        busy: vec![vec![]; candidates.len()],
+        untimed: vec![false; candidates.len()],
+        load: vec![[0, 0]; candidates.len()],
+    };
+    let mut plan = OfficialsPlan {
+        quizmasters: vec![None; games.len()],
+        contentjudges: vec![None; games.len()],
+        unfilled: vec![],
+    };
+
+    let mut order: Vec<usize> = (0..games.len()).collect();
+    order.sort_by_key(|g| (games[*g].start.is_some(), games[*g].start, *g));
+    let mut groups: Vec<Vec<usize>> = vec![];
+    for g in order {
+        match groups.last_mut() {
+            Some(group) if games[group[0]].start == games[g].start => group.push(g),
+            _ => groups.push(vec![g]),
+        }
+    }
+
+    for group in groups {
+        let mut slots: Vec<(usize, Role)> = group.iter().map(|g| (*g, Role::Quizmaster)).collect();
+        if contentjudges {
+            slots.extend(group.iter().map(|g| (*g, Role::ContentJudge)));
+        }
+        while !slots.is_empty() {
+            let options: Vec<Result<Vec<usize>, &'static str>> = slots
+                .iter()
+                .map(|(g, role)| {
+                    let taken = if *role == Role::ContentJudge { plan.quizmasters[*g] } else { plan.contentjudges[*g] };
+                    planner.candidates_for(*g, *role, taken)
+                })
+                .collect();
+            let next = (0..slots.len())
+                .min_by_key(|i| (slots[*i].1 == Role::ContentJudge, options[*i].as_ref().map(|o| o.len()).unwrap_or(0)))
+                .unwrap();
+            let (g, role) = slots.remove(next);
+            match &options[next] {
+                Ok(available) => {
+                    let c = planner.least_loaded(available, role);
+                    match games[g].start {
+                        Some(start) => planner.busy[c].push(start),
+                        None => planner.untimed[c] = true,
+                    }
+                    match role {
+                        Role::Quizmaster => {
+                            planner.load[c][0] += 1;
+                            plan.quizmasters[g] = Some(c);
+                        }
+                        Role::ContentJudge => {
+                            planner.load[c][1] += 1;
+                            plan.contentjudges[g] = Some(c);
+                        }
+                    }
+                }
+                Err(reason) => plan.unfilled.push((g, role, reason)),
+            }
+        }
+    }
+    plan.unfilled.sort_by_key(|(g, role, _)| (*g, *role == Role::ContentJudge));
+    plan
+}
+
+/// Plans the officials of a tournament's games. Unless `preview` is false nothing is written; an
+/// applied plan must fill every slot.
+pub fn assign(db: &mut database::Connection, tournament_id: Uuid, request: &OfficialsRequest) -> QueryResult<OfficialsAssignment> {
+    use crate::schema::{games, rooms, rounds, teams, users};
+
+    let preview = request.preview.unwrap_or(true);
+    let contentjudges = request.contentjudges.unwrap_or(true);
+    let game_minutes = request.game_minutes.unwrap_or(30);
+    let min_break_minutes = request.min_break_minutes.unwrap_or(0);
+    if game_minutes <= 0 || min_break_minutes < 0 {
+        return Err(invalid("game_minutes must be positive and min_break_minutes can't be negative".to_string()));
+    }
+    if request.officials.is_empty() {
+        return Err(invalid("At least one official is needed".to_string()));
+    }
+
+    let official_ids: Vec<Uuid> = request.officials.iter().map(|o| o.userid).collect();
+    let officials: Vec<User> = users::table.filter(users::id.eq_any(&official_ids)).load::<User>(db)?;
+    let names: HashMap<Uuid, String> = officials.iter().map(|u| (u.id, user_name(u))).collect();
+    let unknown: Vec<String> = official_ids.iter().filter(|id| !names.contains_key(id)).map(|id| id.to_string()).collect();
+    if !unknown.is_empty() {
+        return Err(invalid(format!["Unknown official(s) {}", unknown.join(", ")]));
+    }
+
+    let mut rows = games::table
+        .inner_join(rounds::table)
+        .inner_join(rooms::table)
+        .filter(games::tournamentid.eq(tournament_id))
+        .filter(games::ignore.eq(false))
+        .select((Game::as_select(), Round::as_select(), Room::as_select()))
+        .load::<(Game, Round, Room)>(db)?;
+    if let Some(round_ids) = &request.round_ids {
+        rows.retain(|(_, round, _)| round_ids.contains(&round.roundid));
+    }
+    rows.sort_by(|a, b| (a.1.scheduled_start_time, &a.2.name).cmp(&(b.1.scheduled_start_time, &b.2.name)));
+
+    let team_ids: HashSet<Uuid> = rows
+        .iter()
+        .flat_map(|(game, _, _)| [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)])
+        .flatten()
+        .collect();
+    let team_members: HashMap<Uuid, Vec<Uuid>> = teams::table
+        .filter(teams::teamid.eq_any(team_ids))
+        .load::<Team>(db)?
+        .iter()
+        .map(|t| (t.teamid, [vec![t.coachid], quizzers_of_team(t)].concat()))
+        .collect();
+
+    let to_officiate: Vec<GameToOfficiate> = rows
+        .iter()
+        .map(|(game, round, _)| GameToOfficiate {
+            start: round.scheduled_start_time,
+            excluded: [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)]
+                .into_iter()
+                .flatten()
+                .flat_map(|t| team_members.get(&t).cloned().unwrap_or_default())
+                .collect(),
+            contentjudge: if contentjudges { None } else { game.contentjudgeid },
+        })
+        .collect();
+    let candidates: Vec<Candidate> = request
+        .officials
+        .iter()
+        .map(|o| Candidate {
+            userid: o.userid,
+            quizmaster: o.quizmaster.unwrap_or(true),
+            contentjudge: o.contentjudge.unwrap_or(true),
+            windows: o.availability.iter().flatten().map(|w| (w.start, w.end)).collect(),
+        })
+        .collect();
+
+    let plan = plan_officials(
+        &to_officiate,
+        &candidates,
+        Duration::minutes(game_minutes),
+        Duration::minutes(min_break_minutes),
+        contentjudges,
+    );
+
+    let official_of = |c: Option<usize>| c.map(|c| candidates[c].userid);
+    let name_of = |c: Option<usize>| c.map(|c| names[&candidates[c].userid].clone());
+    let assignments: Vec<OfficialAssignment> = rows
+        .iter()
+        .enumerate()
+        .map(|(idx, (game, round, room))| {
+            let quizmasterid = official_of(plan.quizmasters[idx]);
+            let contentjudgeid = if contentjudges { official_of(plan.contentjudges[idx]) } else { game.contentjudgeid };
+            OfficialAssignment {
+                gid: game.gid,
+                roundid: round.roundid,
+                round_name: round.name.clone(),
+                roomid: room.roomid,
+                room_name: room.name.clone(),
+                scheduled_start_time: round.scheduled_start_time,
+                quizmasterid,
+                quizmaster_name: name_of(plan.quizmasters[idx]),
+                contentjudgeid,
+                contentjudge_name: if contentjudges { name_of(plan.contentjudges[idx]) } else { None },
+                changed: quizmasterid != Some(game.quizmasterid) || contentjudgeid != game.contentjudgeid,
+            }
+        })
+        .collect();
+
+    let workload = candidates
+        .iter()
+        .enumerate()
+        .map(|(c, candidate)| OfficialWorkload {
+            userid: candidate.userid,
+            name: names[&candidate.userid].clone(),
+            quizmaster_games: plan.quizmasters.iter().filter(|q| **q == Some(c)).count() as i32,
+            contentjudge_games: plan.contentjudges.iter().filter(|j| **j == Some(c)).count() as i32,
+        })
+        .collect();
+
+    let unfilled: Vec<UnfilledSlot> = plan
+        .unfilled
+        .iter()
+        .map(|(idx, role, reason)| {
+            let (game, round, room) = &rows[*idx];
+            UnfilledSlot {
+                gid: game.gid,
+                round_name: round.name.clone(),
+                room_name: room.name.clone(),
+                scheduled_start_time: round.scheduled_start_time,
+                role: role.as_str().to_string(),
+                reason: reason.to_string(),
+            }
+        })
+        .collect();
+
+    let mut updated = 0;
+    if !preview {
+        if !unfilled.is_empty() {
+            return Err(invalid(format!["The assignment can't be applied: {} slots are unfilled", unfilled.len()]));
+        }
+        updated = db.transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut updated = 0;
+            for assignment in assignments.iter().filter(|a| a.changed) {
+                updated += diesel::update(games::table.filter(games::gid.eq(assignment.gid)))
+                    .set((
+                        games::quizmasterid.eq(assignment.quizmasterid.unwrap()),
+                        games::contentjudgeid.eq(assignment.contentjudgeid),
+                        games::updated_at.eq(Utc::now()),
+                    ))
+                    .execute(conn)?;
+            }
+            Ok(updated as i32)
+        })?;
+    }
+
+    Ok(OfficialsAssignment { tid: tournament_id, preview, assignments, workload, unfilled, updated })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap()
+    }
+
+    fn candidate(windows: Vec<(DateTime<Utc>, DateTime<Utc>)>) -> Candidate {
+        Candidate { userid: Uuid::new_v4(), quizmaster: true, contentjudge: true, windows }
+    }
+
+    fn game(start: DateTime<Utc>, excluded: &[Uuid]) -> GameToOfficiate {
+        GameToOfficiate { start: Some(start), excluded: excluded.iter().copied().collect(), contentjudge: None }
+    }
+
+    #[test]
+    fn plan_officials_balances_workload_and_respects_breaks() {
+        let candidates: Vec<Candidate> = (0..4).map(|_| candidate(vec![])).collect();
+        let games: Vec<GameToOfficiate> = [at(9, 0), at(9, 0), at(9, 30), at(9, 30), at(10, 0), at(10, 0)]
+            .iter()
+            .map(|start| game(*start, &[]))
+            .collect();
+
+        let plan = plan_officials(&games, &candidates, Duration::minutes(30), Duration::minutes(30), false);
+
+        assert!(plan.unfilled.is_empty());
+        // with a 30 minute break nobody officiates two rounds in a row
+        for (idx, official) in plan.quizmasters.iter().enumerate() {
+            let next_round = if idx < 4 { idx + 2 - idx % 2 } else { continue };
+            assert!(!plan.quizmasters[next_round..next_round + 2].contains(official));
+        }
+        let loads: Vec<usize> = (0..4).map(|c| plan.quizmasters.iter().filter(|q| **q == Some(c)).count()).collect();
+        assert!(loads.iter().all(|l| *l == 1 || *l == 2));
+    }
+
+    #[test]
+    fn plan_officials_reports_unfillable_slots() {
+        let coach = candidate(vec![]);
+        let mut late = candidate(vec![(at(10, 0), at(12, 0))]);
+        late.quizmaster = false;
+        let games = vec![
+            game(at(9, 0), &[coach.userid]),
+            game(at(10, 0), &[]),
+        ];
+        let candidates = vec![coach, late];
+
+        let plan = plan_officials(&games, &candidates, Duration::minutes(30), Duration::zero(), true);
+
+        assert_eq!(plan.quizmasters, vec![None, Some(0)]);
+        assert_eq!(plan.contentjudges, vec![None, Some(1)]);
+        assert_eq!(plan.unfilled, vec![
+            (0, Role::Quizmaster, "every eligible official coaches or quizzes on a team in this game"),
+            (0, Role::ContentJudge, "no eligible official is available at this time"),
+        ]);
+    }
+
+    #[test]
+    fn plan_officials_runs_untimed_games_at_once() {
+        let candidates: Vec<Candidate> = (0..3).map(|_| candidate(vec![(at(9, 0), at(10, 0))])).collect();
+        let untimed = GameToOfficiate { start: None, excluded: HashSet::new(), contentjudge: None };
+        let games = vec![untimed.clone(), untimed.clone(), untimed];
+
+        let plan = plan_officials(&games, &candidates, Duration::minutes(30), Duration::zero(), true);
+
+        let mut officials: Vec<usize> = plan.quizmasters.iter().chain(plan.contentjudges.iter()).flatten().copied().collect();
+        assert_eq!(officials.len(), 3);
+        officials.sort();
+        officials.dedup();
+        assert_eq!(officials.len(), 3);
+        assert_eq!(plan.unfilled.len(), 3);
+    }
+
+    #[test]
+    fn plan_officials_keeps_the_content_judge_off_the_quizmaster_seat() {
+        let candidates: Vec<Candidate> = (0..2).map(|_| candidate(vec![])).collect();
+        let games = vec![GameToOfficiate { start: Some(at(9, 0)), excluded: HashSet::new(), contentjudge: Some(candidates[0].userid) }];
+
+        let plan = plan_officials(&games, &candidates, Duration::minutes(30), Duration::zero(), false);
+
+        assert_eq!(plan.quizmasters, vec![Some(1)]);
+    }
+}
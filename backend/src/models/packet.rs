@@ -0,0 +1,593 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use crate::database;
+use crate::models::{self, question::Question, round::Round, scripture::find_book};
+use crate::models::quiz_season::QuizSeason;
+use crate::models::round_question::RoundQuestionRole;
+use crate::models::common::invalid;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use rand::{rngs::StdRng, seq::{IndexedRandom, SliceRandom}, SeedableRng};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// A round's packet is the questions read in it, kept in round_questions: the regular questions,
+// alternates for a question that's thrown out, and overtime questions. The generator picks them
+// from the question bank following the request's rules; a packet can be regenerated or changed by
+// hand at any time.
+//
+// A question is never used twice in a tournament, nor for a team that heard it at an earlier
+// tournament (a team with the same coach and name). Chapters are covered in proportion to how much
+// of the season's material is in each, counting the questions already used in the tournament.
+//
+// The season and competition level default to the division's (or, for the season, its
+// tournament's), and the questions to those on the season's material.
+
+pub const DEFAULT_QUESTIONS: i32 = 20;
+pub const MAX_QUESTIONS: i32 = 100;
+pub const DEFAULT_ALTERNATES: i32 = 3;
+pub const MAX_ALTERNATES: i32 = 10;
+pub const MAX_OVERTIME: i32 = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, ToSchema)]
+pub struct PacketRequest {
+    pub quiz_season: Option<i32>,               // defaults to the division's season
+    pub competition_lvl: Option<i32>,           // defaults to the division's level, else every level
+    pub questions: Option<i32>,                 // regular questions per packet, defaults to 20
+    pub alternates: Option<i32>,                // per packet, defaults to 3
+    pub overtime: Option<i32>,                  // per packet, defaults to none
+    pub type_distribution: Option<BTreeMap<String, i32>>, // how many regular questions of each type; the rest can be any type
+    pub books: Option<Vec<String>>,             // the season's material, defaults to every book of the season
+    pub seed: Option<i64>,                      // the same seed and rules pick the same questions
+    pub round_ids: Option<Vec<Uuid>>,           // for a division, defaults to every round
+    pub preview: Option<bool>,                  // defaults to true: nothing is written
+}
+
+impl PacketRequest {
+    pub fn questions(&self) -> usize {
+        self.questions.unwrap_or(DEFAULT_QUESTIONS) as usize
+    }
+
+    pub fn alternates(&self) -> usize {
+        self.alternates.unwrap_or(DEFAULT_ALTERNATES) as usize
+    }
+
+    pub fn overtime(&self) -> usize {
+        self.overtime.unwrap_or(0) as usize
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let mut errors = vec![];
+        if self.quiz_season.is_some_and(|s| s < 1) {
+            errors.push("quiz_season must be at least 1".to_string());
+        }
+        if self.questions.is_some_and(|n| !(1..=MAX_QUESTIONS).contains(&n)) {
+            errors.push(format!["questions must be between 1 and {}", MAX_QUESTIONS]);
+        }
+        if self.alternates.is_some_and(|n| !(0..=MAX_ALTERNATES).contains(&n)) {
+            errors.push(format!["alternates must be between 0 and {}", MAX_ALTERNATES]);
+        }
+        if self.overtime.is_some_and(|n| !(0..=MAX_OVERTIME).contains(&n)) {
+            errors.push(format!["overtime must be between 0 and {}", MAX_OVERTIME]);
+        }
+        if let Some(distribution) = &self.type_distribution {
+            if distribution.values().any(|n| *n < 0) {
+                errors.push("type_distribution can't have a negative count".to_string());
+            }
+            let typed: i32 = distribution.values().filter(|n| **n > 0).sum();
+            if typed > self.questions() as i32 {
+                errors.push(format!["type_distribution asks for {} questions but a packet has {}", typed, self.questions()]);
+            }
+        }
+        for book in self.books.iter().flatten() {
+            if find_book(book).is_none() {
+                errors.push(format!["'{}' is not a book of the Bible", book]);
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors.join("; ")) }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::round_packets)]
+#[diesel(primary_key(roundid))]
+pub struct RoundPacket {
+    pub roundid: Uuid,
+    pub rules: Option<String>,                  // the PacketRequest in JSON, none if only entered by hand
+    pub seed: Option<i64>,
+    pub adjusted: bool,                         // changed by hand since it was generated
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::round_packets)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewRoundPacket {
+    pub roundid: Uuid,
+    pub rules: Option<String>,
+    pub seed: Option<i64>,
+    pub adjusted: bool,
+    pub updated_by: Option<Uuid>,
+}
+
+// The questions of a packet in each role, in the order they're read.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Packet {
+    pub roundid: Uuid,
+    pub round_name: String,
+    pub questions: Vec<Question>,
+    pub alternates: Vec<Question>,
+    pub overtime: Vec<Question>,
+    pub rules: Option<PacketRequest>,
+    pub seed: Option<i64>,
+    pub adjusted: bool,
+}
+
+// A packet changed by hand: its regular questions, and the alternates and overtime questions if
+// they change too.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PacketAdjustment {
+    pub questions: Vec<Uuid>,
+    pub alternates: Option<Vec<Uuid>>,
+    pub overtime: Option<Vec<Uuid>>,
+}
+
+// The question ids the generator picked for a packet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PickedPacket {
+    pub questions: Vec<Uuid>,
+    pub alternates: Vec<Uuid>,
+    pub overtime: Vec<Uuid>,
+}
+
+impl PickedPacket {
+    fn by_role(&self) -> [(RoundQuestionRole, Vec<Uuid>); 3] {
+        [
+            (RoundQuestionRole::Regular, self.questions.clone()),
+            (RoundQuestionRole::Alternate, self.alternates.clone()),
+            (RoundQuestionRole::Overtime, self.overtime.clone()),
+        ]
+    }
+
+    fn ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.questions.iter().chain(self.alternates.iter()).chain(self.overtime.iter())
+    }
+}
+
+fn chapter_of(question: &Question) -> (String, i32) {
+    let book = find_book(&question.book).map(|(b, _)| b.to_string()).unwrap_or(question.book.clone());
+    (book, question.chapter)
+}
+
+/// Picks a packet from `material`, leaving out the `used` questions. `covered` counts the questions
+/// already picked from each chapter and is updated, so the packets of a division share one tally.
+pub fn pick_packet(
+    material: &[Question],
+    used: &HashSet<Uuid>,
+    covered: &mut BTreeMap<(String, i32), i32>,
+    request: &PacketRequest,
+    rng: &mut StdRng,
+) -> Result<PickedPacket, String> {
+    let mut weights: BTreeMap<(String, i32), f64> = BTreeMap::new();
+    for question in material.iter() {
+        *weights.entry(chapter_of(question)).or_default() += 1.0 / material.len() as f64;
+    }
+
+    let mut picked: HashSet<Uuid> = HashSet::new();
+    let mut pick_one = |type_: Option<&str>, picked: &mut HashSet<Uuid>| -> Result<Uuid, String> {
+        let pool: Vec<&Question> = material
+            .iter()
+            .filter(|q| !used.contains(&q.id) && !picked.contains(&q.id))
+            .filter(|q| type_.is_none_or(|t| q.type_.eq_ignore_ascii_case(t)))
+            .collect();
+        if pool.is_empty() {
+            return Err(match type_ {
+                Some(t) => format!["there aren't enough unused questions of type {} for the packet", t],
+                None => "there aren't enough unused questions for the packet".to_string(),
+            });
+        }
+
+        // the chapter furthest behind its share of the material, counting this pick
+        let total: i32 = covered.values().sum::<i32>() + 1;
+        let chapters: BTreeSet<(String, i32)> = pool.iter().map(|q| chapter_of(q)).collect();
+        let deficit = |chapter: &(String, i32)| weights[chapter] * total as f64 - *covered.get(chapter).unwrap_or(&0) as f64;
+        let most = chapters.iter().map(deficit).fold(f64::MIN, f64::max);
+        let behind: Vec<&(String, i32)> = chapters.iter().filter(|c| deficit(c) >= most - 1e-9).collect();
+        let chapter = (*behind.choose(rng).unwrap()).clone();
+
+        let candidates: Vec<&&Question> = pool.iter().filter(|q| chapter_of(q) == chapter).collect();
+        let question = candidates.choose(rng).unwrap();
+        *covered.entry(chapter).or_default() += 1;
+        picked.insert(question.id);
+        Ok(question.id)
+    };
+
+    // the typed questions are picked first, so questions of any type don't use them up
+    let mut plan: Vec<Option<&str>> = vec![];
+    for (type_, count) in request.type_distribution.iter().flatten() {
+        plan.extend((0..*count).map(|_| Some(type_.as_str())));
+    }
+    plan.resize(request.questions(), None);
+
+    let mut packet = PickedPacket::default();
+    for type_ in plan {
+        packet.questions.push(pick_one(type_, &mut picked)?);
+    }
+    for _ in 0..request.alternates() {
+        packet.alternates.push(pick_one(None, &mut picked)?);
+    }
+    for _ in 0..request.overtime() {
+        packet.overtime.push(pick_one(None, &mut picked)?);
+    }
+    packet.questions.shuffle(rng);
+    Ok(packet)
+}
+
+pub fn read_packet_record(db: &mut database::Connection, round_id: Uuid) -> QueryResult<Option<RoundPacket>> {
+    use crate::schema::round_packets::dsl::*;
+    round_packets.find(round_id).first::<RoundPacket>(db).optional()
+}
+
+fn read_rounds_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<Round>> {
+    use crate::schema::divisions;
+    use crate::schema::rounds;
+    rounds::table
+        .inner_join(divisions::table)
+        .filter(divisions::tid.eq(tournament_id))
+        .select(Round::as_select())
+        .load::<Round>(db)
+}
+
+/// The questions of the given rounds, with the round each is in.
+fn read_used_questions(db: &mut database::Connection, round_ids: &[Uuid]) -> QueryResult<HashMap<Uuid, Uuid>> {
+    Ok(models::round_question::read_all_of_rounds(db, round_ids)?
+        .into_iter()
+        .map(|rq| (rq.questionid, rq.roundid))
+        .collect())
+}
+
+/// The rounds of other tournaments played by the teams of this round: teams of earlier tournaments
+/// with the same coach and name.
+fn read_rounds_of_same_teams(db: &mut database::Connection, round: &Round, tournament_id: Uuid) -> QueryResult<Vec<Uuid>> {
+    use crate::schema::{games, teams};
+
+    let games_of_round: Vec<models::game::Game> = games::table
+        .filter(games::roundid.eq(round.roundid))
+        .load(db)?;
+    let team_ids: Vec<Uuid> = games_of_round
+        .iter()
+        .flat_map(|g| [Some(g.leftteamid), g.centerteamid, Some(g.rightteamid)])
+        .flatten()
+        .collect();
+    let these: Vec<models::team::Team> = teams::table.filter(teams::teamid.eq_any(&team_ids)).load(db)?;
+    let coaches: Vec<Uuid> = these.iter().map(|t| t.coachid).collect();
+    let same_teams: Vec<Uuid> = teams::table
+        .filter(teams::coachid.eq_any(&coaches))
+        .load::<models::team::Team>(db)?
+        .into_iter()
+        .filter(|t| these.iter().any(|this| this.coachid == t.coachid && this.name.eq_ignore_ascii_case(&t.name)))
+        .map(|t| t.teamid)
+        .collect();
+
+    games::table
+        .filter(games::tournamentid.ne(tournament_id))
+        .filter(games::leftteamid.eq_any(&same_teams).or(games::rightteamid.eq_any(&same_teams)).or(games::centerteamid.eq_any(&same_teams)))
+        .select(games::roundid)
+        .distinct()
+        .load::<Uuid>(db)
+}
+
+fn read_material(db: &mut database::Connection, season: &QuizSeason, request: &PacketRequest) -> QueryResult<Vec<Question>> {
+    use crate::schema::questionsandanswers::dsl::*;
+    let mut query = questionsandanswers
+        .filter(quiz_season.eq(season.season))
+        .into_boxed();
+    if let Some(level) = request.competition_lvl {
+        query = query.filter(competition_lvl.eq(level));
+    }
+    if let Some(books) = &request.books {
+        let mut names: Vec<String> = books.clone();
+        names.extend(books.iter().filter_map(|b| find_book(b)).map(|(b, _)| b.to_string()));
+        query = query.filter(book.eq_any(names));
+    } else {
+        let ranges = season.material_ranges();
+        if !ranges.is_empty() {
+            query = query.filter(models::question::touching_any(&ranges));
+        }
+    }
+    query.order((book.asc(), chapter.asc(), beginning_verse.asc(), id.asc())).load::<Question>(db)
+}
+
+fn read_questions(db: &mut database::Connection, ids: &[Uuid]) -> QueryResult<HashMap<Uuid, Question>> {
+    use crate::schema::questionsandanswers::dsl::*;
+    Ok(questionsandanswers
+        .filter(id.eq_any(ids))
+        .load::<Question>(db)?
+        .into_iter()
+        .map(|q| (q.id, q))
+        .collect())
+}
+
+fn tournament_of_round(db: &mut database::Connection, round: &Round) -> QueryResult<Uuid> {
+    Ok(models::division::read(db, round.did)?.tid)
+}
+
+fn to_packet(round: &Round, record: Option<&RoundPacket>, picked: &PickedPacket, questions: &HashMap<Uuid, Question>) -> Packet {
+    let lookup = |ids: &[Uuid]| ids.iter().filter_map(|q| questions.get(q).cloned()).collect();
+    Packet {
+        roundid: round.roundid,
+        round_name: round.name.clone(),
+        questions: lookup(&picked.questions),
+        alternates: lookup(&picked.alternates),
+        overtime: lookup(&picked.overtime),
+        rules: record.and_then(|p| p.rules.as_ref()).and_then(|r| serde_json::from_str(r).ok()),
+        seed: record.and_then(|p| p.seed),
+        adjusted: record.is_some_and(|p| p.adjusted),
+    }
+}
+
+pub fn read(db: &mut database::Connection, round_id: Uuid) -> QueryResult<Packet> {
+    let round = models::round::read(db, round_id)?;
+    let record = read_packet_record(db, round_id)?;
+    let mut picked = PickedPacket::default();
+    for rq in models::round_question::read_all_of_round(db, round_id)? {
+        match rq.role.as_str() {
+            "alternate" => picked.alternates.push(rq.questionid),
+            "overtime" => picked.overtime.push(rq.questionid),
+            _ => picked.questions.push(rq.questionid),
+        }
+    }
+    let ids: Vec<Uuid> = picked.ids().copied().collect();
+    let questions = read_questions(db, &ids)?;
+    Ok(to_packet(&round, record.as_ref(), &picked, &questions))
+}
+
+fn write_packet(db: &mut database::Connection, round_id: Uuid, picked: &PickedPacket, record: &NewRoundPacket) -> QueryResult<()> {
+    use crate::schema::round_packets;
+    use crate::schema::rounds::dsl::*;
+    models::round_question::replace_all_of_round(db, round_id, &picked.by_role())?;
+    diesel::update(rounds.filter(roundid.eq(round_id)))
+        .set(updated_at.eq(diesel::dsl::now))
+        .execute(db)?;
+    diesel::insert_into(round_packets::table)
+        .values(record)
+        .on_conflict(round_packets::roundid)
+        .do_update()
+        .set((record, round_packets::updated_at.eq(diesel::dsl::now)))
+        .execute(db)?;
+    Ok(())
+}
+
+/// Generates the packets of the given rounds, which must be in one tournament, in order. Unless
+/// previewing, each packet is written to its round, replacing the one it had.
+pub fn generate(db: &mut database::Connection, round_ids: &[Uuid], request: &PacketRequest, user_id: Uuid) -> QueryResult<Vec<Packet>> {
+    request.validate().map_err(invalid)?;
+
+    let mut of_rounds = vec![];
+    for round_id in round_ids.iter() {
+        of_rounds.push(models::round::read(db, *round_id)?);
+    }
+    let Some(first) = of_rounds.first() else {
+        return Err(invalid("There are no rounds to generate packets for".to_string()));
+    };
+    let tournament_id = tournament_of_round(db, first)?;
+    for round in of_rounds.iter() {
+        if tournament_of_round(db, round)? != tournament_id {
+            return Err(invalid(format!["Round {} is in another tournament", round.name]));
+        }
+    }
+
+    // the season and level default to the first round's division's
+    let of_division = models::division::read(db, first.did)?;
+    let season = match request.quiz_season {
+        Some(item_season) => {
+            models::quiz_season::check_exists(db, item_season)?;
+            models::quiz_season::read(db, item_season)?
+        },
+        None => models::quiz_season::season_of_division(db, first.did)?
+            .ok_or(invalid("quiz_season is required when the division and tournament have no season".to_string()))?,
+    };
+    let competition_lvl = request.competition_lvl.or(of_division.competition_lvl);
+    if let Some(level) = competition_lvl {
+        models::competition_level::check_exists(db, level)?;
+    }
+    let request = &PacketRequest { quiz_season: Some(season.season), competition_lvl, ..request.clone() };
+
+    let material = read_material(db, &season, request)?;
+    if material.is_empty() {
+        return Err(invalid(format!["The question bank has no questions for season {}", season.name]));
+    }
+
+    // the other rounds of the tournament keep their packets; these rounds' are replaced
+    let others: Vec<Uuid> = read_rounds_of_tournament(db, tournament_id)?
+        .into_iter()
+        .map(|r| r.roundid)
+        .filter(|r| !round_ids.contains(r))
+        .collect();
+    let mut used: HashSet<Uuid> = read_used_questions(db, &others)?.into_keys().collect();
+    let mut covered: BTreeMap<(String, i32), i32> = BTreeMap::new();
+    for question in material.iter().filter(|q| used.contains(&q.id)) {
+        *covered.entry(chapter_of(question)).or_default() += 1;
+    }
+
+    let seed = request.seed.unwrap_or(rand::random::<u32>() as i64);
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let mut picks = vec![];
+    for round in of_rounds.iter() {
+        let heard_by_teams = read_rounds_of_same_teams(db, round, tournament_id)
+            .and_then(|heard| read_used_questions(db, &heard))?;
+        let excluded: HashSet<Uuid> = used.iter().copied().chain(heard_by_teams.into_keys()).collect();
+        let picked = pick_packet(&material, &excluded, &mut covered, request, &mut rng)
+            .map_err(|e| invalid(format!["Round {}: {}", round.name, e]))?;
+        used.extend(picked.ids());
+        picks.push(picked);
+    }
+
+    let rules = PacketRequest { seed: Some(seed), round_ids: None, preview: None, ..request.clone() };
+    let by_id: HashMap<Uuid, Question> = material.into_iter().map(|q| (q.id, q)).collect();
+    let preview = request.preview.unwrap_or(true);
+
+    db.transaction::<_, diesel::result::Error, _>(|conn| {
+        let mut packets = vec![];
+        for (round, picked) in of_rounds.iter().zip(picks) {
+            let record = NewRoundPacket {
+                roundid: round.roundid,
+                rules: Some(serde_json::to_string(&rules).unwrap()),
+                seed: Some(seed),
+                adjusted: false,
+                updated_by: Some(user_id),
+            };
+            if !preview {
+                write_packet(conn, round.roundid, &picked, &record)?;
+            }
+            packets.push(Packet {
+                rules: Some(rules.clone()),
+                seed: Some(seed),
+                ..to_packet(round, None, &picked, &by_id)
+            });
+        }
+        Ok(packets)
+    })
+}
+
+/// Generates the packets of a division's rounds, or of the rounds the request names.
+pub fn generate_for_division(db: &mut database::Connection, division_id: Uuid, request: &PacketRequest, user_id: Uuid) -> QueryResult<Vec<Packet>> {
+    use crate::schema::rounds::dsl::*;
+    let of_division: Vec<Round> = rounds
+        .filter(did.eq(division_id))
+        .order((scheduled_start_time.asc(), name.asc()))
+        .load::<Round>(db)?;
+    let round_ids: Vec<Uuid> = of_division
+        .iter()
+        .map(|r| r.roundid)
+        .filter(|r| request.round_ids.as_ref().is_none_or(|wanted| wanted.contains(r)))
+        .collect();
+    if let Some(missing) = request.round_ids.iter().flatten().find(|r| !round_ids.contains(r)) {
+        return Err(invalid(format!["Round {} is not in this division", missing]));
+    }
+    generate(db, &round_ids, request, user_id)
+}
+
+/// Replaces a packet's questions by hand. The questions must be in the bank and can't be used
+/// anywhere else in the tournament.
+pub fn adjust(db: &mut database::Connection, round_id: Uuid, adjustment: &PacketAdjustment, user_id: Uuid) -> QueryResult<Packet> {
+    let round = models::round::read(db, round_id)?;
+    let existing = read(db, round_id)?;
+    let picked = PickedPacket {
+        questions: adjustment.questions.clone(),
+        alternates: adjustment.alternates.clone().unwrap_or(existing.alternates.iter().map(|q| q.id).collect()),
+        overtime: adjustment.overtime.clone().unwrap_or(existing.overtime.iter().map(|q| q.id).collect()),
+    };
+    if picked.questions.len() > MAX_QUESTIONS as usize {
+        return Err(invalid(format!["A packet has at most {} questions", MAX_QUESTIONS]));
+    }
+    if picked.alternates.len() > MAX_ALTERNATES as usize {
+        return Err(invalid(format!["A packet has at most {} alternates", MAX_ALTERNATES]));
+    }
+    if picked.overtime.len() > MAX_OVERTIME as usize {
+        return Err(invalid(format!["A packet has at most {} overtime questions", MAX_OVERTIME]));
+    }
+
+    let ids: Vec<Uuid> = picked.ids().copied().collect();
+    let questions = read_questions(db, &ids)?;
+    let tournament_id = tournament_of_round(db, &round)?;
+    let others: Vec<Round> = read_rounds_of_tournament(db, tournament_id)?
+        .into_iter()
+        .filter(|r| r.roundid != round_id)
+        .collect();
+    let used = read_used_questions(db, &others.iter().map(|r| r.roundid).collect::<Vec<_>>())?;
+
+    let mut seen = HashSet::new();
+    let mut errors = vec![];
+    for question in ids.iter() {
+        if !seen.insert(*question) {
+            errors.push(format!["question {} is in the packet twice", question]);
+        } else if !questions.contains_key(question) {
+            errors.push(format!["question {} isn't in the question bank", question]);
+        } else if let Some(other) = used.get(question).and_then(|r| others.iter().find(|o| o.roundid == *r)) {
+            errors.push(format!["question {} is already in round {}", question, other.name]);
+        }
+    }
+    if !errors.is_empty() {
+        return Err(invalid(errors.join("; ")));
+    }
+
+    let record = NewRoundPacket {
+        roundid: round_id,
+        rules: existing.rules.as_ref().map(|r| serde_json::to_string(r).unwrap()),
+        seed: existing.seed,
+        adjusted: true,
+        updated_by: Some(user_id),
+    };
+    db.transaction::<_, diesel::result::Error, _>(|conn| write_packet(conn, round_id, &picked, &record))?;
+    read(db, round_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(type_: &str, book: &str, chapter: i32) -> Question {
+        Question {
+            id: Uuid::new_v4(),
+            competition_lvl: 1,
+            quiz_season: 1,
+            type_: type_.to_string(),
+            question: "A question?".to_string(),
+            answer: "An answer".to_string(),
+            book: book.to_string(),
+            chapter,
+            verses: "1".to_string(),
+            beginning_verse: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            was_imported_from_ui: Some(true),
+            is_quizstuff_authored_question: None,
+        }
+    }
+
+    #[test]
+    fn pick_packet_follows_the_rules() {
+        // John 1 has three times the material of John 2
+        let mut material: Vec<Question> = (0..18).map(|_| question("G", "John", 1)).collect();
+        material.extend((0..6).map(|_| question("G", "John", 2)));
+        material.extend((0..6).map(|_| question("A", "John", 1)));
+        material.extend((0..2).map(|_| question("A", "John", 2)));
+        let used: HashSet<Uuid> = [material[0].id].into_iter().collect();
+        let request = PacketRequest {
+            quiz_season: Some(1),
+            questions: Some(8),
+            alternates: Some(2),
+            overtime: Some(0),
+            type_distribution: Some([("A".to_string(), 4)].into_iter().collect()),
+            ..Default::default()
+        };
+
+        let mut covered = BTreeMap::new();
+        let mut rng = StdRng::seed_from_u64(7);
+        let packet = pick_packet(&material, &used, &mut covered, &request, &mut rng).unwrap();
+        assert_eq!((packet.questions.len(), packet.alternates.len(), packet.overtime.len()), (8, 2, 0));
+        let picked: HashSet<Uuid> = packet.ids().copied().collect();
+        assert_eq!(picked.len(), 10);
+        assert!(!picked.contains(&material[0].id));
+        assert!(material.iter().filter(|q| q.type_ == "A" && packet.questions.contains(&q.id)).count() >= 4);
+
+        // three quarters of the material is in John 1
+        assert_eq!(covered.values().sum::<i32>(), 10);
+        assert!((7..=8).contains(&covered[&("John".to_string(), 1)]));
+
+        // the same seed picks the same packet
+        let mut again = StdRng::seed_from_u64(7);
+        assert_eq!(pick_packet(&material, &used, &mut BTreeMap::new(), &request, &mut again).unwrap(), packet);
+
+        // there aren't nine type A questions
+        let greedy = PacketRequest { type_distribution: Some([("A".to_string(), 9)].into_iter().collect()), questions: Some(10), ..request.clone() };
+        assert_eq!(
+            pick_packet(&material, &used, &mut BTreeMap::new(), &greedy, &mut rng),
+            Err("there aren't enough unused questions of type A for the packet".to_string()),
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use crate::database;
+use crate::models::{self, conflict::user_name, packet::Packet, question_import::{self, QuestionImportRow, QUESTION_COLUMNS}, round::Round, user::User};
+use crate::models::common::invalid;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Packets are written as the question file QuizMachine loads: one line per question, quoted the
+// way QuizMachine quotes its eventlog (see eventlog::to_quizmachine_csv). Regular questions are
+// numbered from 1 and overtime questions carry on from there, as QuizMachine reads them; alternates
+// are numbered A1, A2 and so on. A packet can also be written in the layout the question bank's
+// import reads (question_import::QUESTION_COLUMNS), followed by where each question sits in the
+// packet, so it can be loaded into a bank again. Every download is recorded in packet_exports.
+
+pub const QUIZMACHINE_QUESTION_COLUMNS: [&str; 9] = [
+    "round", "number", "kind", "type", "question", "answer", "book", "chapter", "verses",
+];
+
+pub const PACKET_POSITION_COLUMNS: [&str; 3] = ["round", "kind", "number"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketFileFormat {
+    QuizMachine,
+    Questions,
+}
+
+impl PacketFileFormat {
+    pub fn from_param(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.to_lowercase()).as_deref() {
+            None | Some("quizmachine") => Ok(PacketFileFormat::QuizMachine),
+            Some("questions") => Ok(PacketFileFormat::Questions),
+            Some(other) => Err(format!["'{}' is not a packet file format; use quizmachine or questions", other]),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PacketExportParams {
+    pub format: Option<String>,                 // "quizmachine" (default) or "questions"
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QuizMachineQuestion {
+    pub round: String,
+    pub number: String,
+    pub kind: String,                           // regular, alternate or overtime
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub question: String,
+    pub answer: String,
+    pub book: String,
+    pub chapter: i32,
+    pub verses: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PacketQuestion {
+    pub row: QuestionImportRow,
+    pub round: String,
+    pub kind: String,                           // regular, alternate or overtime
+    pub number: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::packet_exports)]
+pub struct PacketExport {
+    pub id: Uuid,
+    pub roundid: Uuid,
+    pub exported_by: Option<Uuid>,              // None once that user is deleted
+    pub scheduled_start_time: Option<DateTime<Utc>>,
+    pub questions: String,                      // the question ids in the file, in order, in JSON
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable)]
+#[diesel(table_name = crate::schema::packet_exports)]
+pub struct NewPacketExport {
+    pub roundid: Uuid,
+    pub exported_by: Uuid,
+    pub scheduled_start_time: Option<DateTime<Utc>>,
+    pub questions: String,
+}
+
+// One download of a packet, for auditing who had the questions before the round.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PacketExportRecord {
+    pub id: Uuid,
+    pub roundid: Uuid,
+    pub round_name: String,
+    pub exported_by: Option<Uuid>,
+    pub exported_by_name: String,
+    pub exported_at: DateTime<Utc>,
+    pub scheduled_start_time: Option<DateTime<Utc>>,
+    pub before_start: Option<bool>,             // none when the round had no start time
+    pub questions: Vec<Uuid>,
+}
+
+/// The question ids of a packet in the order they're written to the file.
+fn question_ids(packet: &Packet) -> Vec<Uuid> {
+    packet.questions.iter()
+        .chain(packet.overtime.iter())
+        .chain(packet.alternates.iter())
+        .map(|q| q.id)
+        .collect()
+}
+
+/// The packet's questions in file order, each with its kind and number in the packet.
+fn positions(packet: &Packet) -> Vec<(&'static str, String, &models::question::Question)> {
+    let regular = packet.questions.iter()
+        .chain(packet.overtime.iter())
+        .enumerate()
+        .map(|(i, q)| (if i < packet.questions.len() { "regular" } else { "overtime" }, (i + 1).to_string(), q));
+    let alternates = packet.alternates.iter()
+        .enumerate()
+        .map(|(i, q)| ("alternate", format!["A{}", i + 1], q));
+    regular.chain(alternates).collect()
+}
+
+pub fn quizmachine_questions(packet: &Packet) -> Vec<QuizMachineQuestion> {
+    positions(packet)
+        .into_iter()
+        .map(|(kind, number, q)| QuizMachineQuestion {
+            round: packet.round_name.clone(),
+            number,
+            kind: kind.to_string(),
+            type_: q.type_.clone(),
+            question: q.question.clone(),
+            answer: q.answer.clone(),
+            book: q.book.clone(),
+            chapter: q.chapter,
+            verses: q.verses.clone(),
+        })
+        .collect()
+}
+
+pub fn packet_questions(packet: &Packet) -> Vec<PacketQuestion> {
+    positions(packet)
+        .into_iter()
+        .map(|(kind, number, q)| PacketQuestion {
+            row: question_import::to_row(q),
+            round: packet.round_name.clone(),
+            kind: kind.to_string(),
+            number,
+        })
+        .collect()
+}
+
+pub fn to_quizmachine_csv(packets: &[Packet]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b',')
+        .quote(b'\'')
+        .has_headers(false)
+        .from_writer(vec![]);
+    writer.write_record(QUIZMACHINE_QUESTION_COLUMNS).map_err(|e| e.to_string())?;
+    for packet in packets.iter() {
+        for record in quizmachine_questions(packet).iter() {
+            writer.serialize(record).map_err(|e| e.to_string())?;
+        }
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+pub fn to_question_file(packets: &[Packet]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+    writer.write_record(QUESTION_COLUMNS.iter().chain(PACKET_POSITION_COLUMNS.iter())).map_err(|e| e.to_string())?;
+    for packet in packets.iter() {
+        for record in packet_questions(packet).iter() {
+            writer.serialize((&record.row, &record.round, &record.kind, &record.number)).map_err(|e| e.to_string())?;
+        }
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+pub fn to_file(format: PacketFileFormat, packets: &[Packet]) -> Result<Vec<u8>, String> {
+    match format {
+        PacketFileFormat::QuizMachine => to_quizmachine_csv(packets),
+        PacketFileFormat::Questions => to_question_file(packets),
+    }
+}
+
+/// The packets of the given rounds. A round without one can't be exported.
+pub fn read_packets(db: &mut database::Connection, round_ids: &[Uuid]) -> QueryResult<Vec<Packet>> {
+    let mut packets = vec![];
+    for round_id in round_ids {
+        let packet = models::packet::read(db, *round_id)?;
+        if packet.questions.is_empty() {
+            return Err(invalid(format!["Round {} has no packet", packet.round_name]));
+        }
+        packets.push(packet);
+    }
+    Ok(packets)
+}
+
+/// The packets of a division's rounds that have one, in the order the rounds are played.
+pub fn read_packets_of_division(db: &mut database::Connection, division_id: Uuid) -> QueryResult<Vec<Packet>> {
+    use crate::schema::rounds::dsl::*;
+    let of_division: Vec<Round> = rounds
+        .filter(did.eq(division_id))
+        .order((scheduled_start_time.asc(), name.asc()))
+        .load::<Round>(db)?;
+    let mut packets = vec![];
+    for round in of_division {
+        let packet = models::packet::read(db, round.roundid)?;
+        if !packet.questions.is_empty() {
+            packets.push(packet);
+        }
+    }
+    if packets.is_empty() {
+        return Err(invalid("No round of this division has a packet".to_string()));
+    }
+    Ok(packets)
+}
+
+/// Records that `user_id` downloaded the packets.
+pub fn record(db: &mut database::Connection, packets: &[Packet], user_id: Uuid) -> QueryResult<Vec<PacketExport>> {
+    use crate::schema::packet_exports::dsl::*;
+    let mut new_exports = vec![];
+    for packet in packets {
+        let round = models::round::read(db, packet.roundid)?;
+        new_exports.push(NewPacketExport {
+            roundid: packet.roundid,
+            exported_by: user_id,
+            scheduled_start_time: round.scheduled_start_time,
+            questions: serde_json::to_string(&question_ids(packet)).unwrap_or_default(),
+        });
+    }
+    diesel::insert_into(packet_exports)
+        .values(&new_exports)
+        .get_results::<PacketExport>(db)
+}
+
+/// The downloads of the given rounds' packets, newest first.
+pub fn read_all_of_rounds(db: &mut database::Connection, round_ids: &[Uuid]) -> QueryResult<Vec<PacketExportRecord>> {
+    use crate::schema::{packet_exports, rounds, users};
+    let exports = packet_exports::table
+        .inner_join(rounds::table)
+        .left_join(users::table)
+        .filter(packet_exports::roundid.eq_any(round_ids))
+        .order((packet_exports::created_at.desc(), rounds::name.asc()))
+        .select((PacketExport::as_select(), rounds::name, Option::<User>::as_select()))
+        .load::<(PacketExport, String, Option<User>)>(db)?;
+    Ok(exports
+        .into_iter()
+        .map(|(export, round_name, user)| PacketExportRecord {
+            id: export.id,
+            roundid: export.roundid,
+            round_name,
+            exported_by: export.exported_by,
+            exported_by_name: user.as_ref().map(user_name).unwrap_or_default(),
+            exported_at: export.created_at,
+            scheduled_start_time: export.scheduled_start_time,
+            before_start: export.scheduled_start_time.map(|start| export.created_at < start),
+            questions: serde_json::from_str(&export.questions).unwrap_or_default(),
+        })
+        .collect())
+}
+
+pub fn read_all_of_division(db: &mut database::Connection, division_id: Uuid) -> QueryResult<Vec<PacketExportRecord>> {
+    use crate::schema::rounds::dsl::*;
+    let round_ids: Vec<Uuid> = rounds
+        .filter(did.eq(division_id))
+        .select(roundid)
+        .load::<Uuid>(db)?;
+    read_all_of_rounds(db, &round_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::question::Question;
+
+    #[test]
+    fn packet_files_work() {
+        let question = |text: &str, verse: i32| Question {
+            id: Uuid::new_v4(),
+            competition_lvl: 1,
+            quiz_season: 1,
+            type_: "G".to_string(),
+            question: text.to_string(),
+            answer: "Answer, with a comma".to_string(),
+            book: "John".to_string(),
+            chapter: 1,
+            verses: verse.to_string(),
+            beginning_verse: verse,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            was_imported_from_ui: None,
+            is_quizstuff_authored_question: None,
+        };
+        let packet = Packet {
+            roundid: Uuid::new_v4(),
+            round_name: "Round 1".to_string(),
+            questions: vec![question("One?", 1), question("Two?", 2)],
+            alternates: vec![question("Alternate?", 3)],
+            overtime: vec![question("Overtime?", 4)],
+            rules: None,
+            seed: None,
+            adjusted: false,
+        };
+        let numbered: Vec<(String, String)> = packet_questions(&packet).into_iter().map(|q| (q.kind, q.number)).collect();
+        assert_eq!(numbered, vec![
+            ("regular".to_string(), "1".to_string()),
+            ("regular".to_string(), "2".to_string()),
+            ("overtime".to_string(), "3".to_string()),
+            ("alternate".to_string(), "A1".to_string()),
+        ]);
+        assert_eq!(question_ids(&packet)[2], packet.overtime[0].id);
+
+        let csv = String::from_utf8(to_file(PacketFileFormat::QuizMachine, std::slice::from_ref(&packet)).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "round,number,kind,type,question,answer,book,chapter,verses");
+        assert_eq!(lines[1], "Round 1,1,regular,G,One?,'Answer, with a comma',John,1,1");
+        assert_eq!(lines[3], "Round 1,3,overtime,G,Overtime?,'Answer, with a comma',John,1,4");
+        assert_eq!(lines.len(), 5);
+
+        let csv = String::from_utf8(to_file(PacketFileFormat::Questions, std::slice::from_ref(&packet)).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], format!["{},round,kind,number", QUESTION_COLUMNS.join(",")]);
+        assert_eq!(lines[1], "1,1,G,One?,\"Answer, with a comma\",John,1,1,1,John 1:1,,Round 1,regular,1");
+        assert_eq!(lines.len(), 5);
+
+        // the import reads the file back as the packet's questions
+        let rows = question_import::parse_csv(csv.as_bytes()).unwrap();
+        let expected: Vec<QuestionImportRow> = packet.questions.iter()
+            .chain(packet.overtime.iter())
+            .chain(packet.alternates.iter())
+            .map(question_import::to_row)
+            .collect();
+        assert_eq!(rows, expected);
+        assert!(rows.iter().all(|row| question_import::validate_row(row).is_empty()));
+    }
+}
@@ -20,6 +20,7 @@ pub enum AppResource {
     Game,
     Team,
     User,
+    Question,
 }
 
 impl AppResource {
@@ -34,6 +35,7 @@ impl AppResource {
             AppResource::Game         => "game",
             AppResource::Team         => "team",
             AppResource::User         => "user",
+            AppResource::Question     => "question",
         }
     }
 }
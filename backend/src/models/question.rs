@@ -0,0 +1,501 @@
+use crate::database;
+use crate::models::{self, common::PaginationParams, scripture::{self, ScriptureRange}};
+use crate::models::common::{escape_like, invalid};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::*;
+use diesel::{QueryResult,AsChangeset,Insertable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+// The question bank, in the shape of a SetMaker export. A question's competition level and quiz
+// season are the numbers of a competition_level (0 is for practice) and a quiz_season (1 is John in
+// the Nazarene scripture rotation), and must exist.
+
+pub struct QuestionBuilder {
+    competition_lvl: Option<i32>,
+    quiz_season: Option<i32>,
+    type_: Option<String>,
+    question: Option<String>,
+    answer: Option<String>,
+    book: Option<String>,
+    chapter: Option<i32>,
+    verses: Option<String>,
+    beginning_verse: Option<i32>,
+    was_imported_from_ui: Option<bool>,
+    is_quizstuff_authored_question: Option<bool>,
+}
+
+impl QuestionBuilder {
+    pub fn new(question: &str, answer: &str) -> Self {
+        Self {
+            competition_lvl: None,
+            quiz_season: None,
+            type_: None,
+            question: Some(question.to_string()),
+            answer: Some(answer.to_string()),
+            book: None,
+            chapter: None,
+            verses: None,
+            beginning_verse: None,
+            was_imported_from_ui: None,
+            is_quizstuff_authored_question: None,
+        }
+    }
+    pub fn new_default(question: &str, answer: &str) -> Self {
+        Self {
+            competition_lvl: Some(1),
+            quiz_season: Some(1),
+            type_: Some("G".to_string()),
+            question: Some(question.to_string()),
+            answer: Some(answer.to_string()),
+            book: Some("John".to_string()),
+            chapter: Some(1),
+            verses: Some("1".to_string()),
+            beginning_verse: Some(1),
+            was_imported_from_ui: Some(false),
+            is_quizstuff_authored_question: Some(false),
+        }
+    }
+    pub fn set_competition_lvl(mut self, competition_lvl: i32) -> Self {
+        self.competition_lvl = Some(competition_lvl);
+        self
+    }
+    pub fn set_quiz_season(mut self, quiz_season: i32) -> Self {
+        self.quiz_season = Some(quiz_season);
+        self
+    }
+    pub fn set_type(mut self, type_: &str) -> Self {
+        self.type_ = Some(type_.to_string());
+        self
+    }
+    pub fn set_book(mut self, book: &str) -> Self {
+        self.book = Some(book.to_string());
+        self
+    }
+    pub fn set_chapter(mut self, chapter: i32) -> Self {
+        self.chapter = Some(chapter);
+        self
+    }
+    pub fn set_verses(mut self, verses: &str) -> Self {
+        self.verses = Some(verses.to_string());
+        self
+    }
+    pub fn set_beginning_verse(mut self, beginning_verse: i32) -> Self {
+        self.beginning_verse = Some(beginning_verse);
+        self
+    }
+    pub fn set_was_imported_from_ui(mut self, was_imported_from_ui: bool) -> Self {
+        self.was_imported_from_ui = Some(was_imported_from_ui);
+        self
+    }
+    pub fn set_is_quizstuff_authored_question(mut self, is_quizstuff_authored_question: bool) -> Self {
+        self.is_quizstuff_authored_question = Some(is_quizstuff_authored_question);
+        self
+    }
+    fn validate_all_are_some(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.competition_lvl.is_none() {
+            errors.push("competition_lvl is required".to_string());
+        }
+        if self.quiz_season.is_none() {
+            errors.push("quiz_season is required".to_string());
+        }
+        if self.type_.is_none() {
+            errors.push("type is required".to_string());
+        }
+        if self.book.is_none() {
+            errors.push("book is required".to_string());
+        }
+        if self.chapter.is_none() {
+            errors.push("chapter is required".to_string());
+        }
+        if self.verses.is_none() {
+            errors.push("verses is required".to_string());
+        }
+        if self.beginning_verse.is_none() {
+            errors.push("beginning_verse is required".to_string());
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(())
+    }
+    pub fn build(self) -> Result<NewQuestion, Vec<String>> {
+        self.validate_all_are_some()?;
+        let item = NewQuestion {
+            id: Uuid::new_v4(),
+            competition_lvl: self.competition_lvl.unwrap(),
+            quiz_season: self.quiz_season.unwrap(),
+            type_: self.type_.unwrap(),
+            question: self.question.unwrap_or_default(),
+            answer: self.answer.unwrap_or_default(),
+            book: self.book.unwrap(),
+            chapter: self.chapter.unwrap(),
+            verses: self.verses.unwrap(),
+            beginning_verse: self.beginning_verse.unwrap(),
+            was_imported_from_ui: self.was_imported_from_ui,
+            is_quizstuff_authored_question: self.is_quizstuff_authored_question,
+        };
+        item.validate()?;
+        Ok(item)
+    }
+    pub fn build_and_insert(self, db: &mut database::Connection) -> QueryResult<Question> {
+        let new_question = self.build().map_err(|errors| invalid(errors.join(" ")))?;
+        create(db, &new_question)
+    }
+}
+
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Queryable,
+    Selectable,
+    Identifiable,
+    ToSchema
+)]
+#[diesel(table_name = crate::schema::questionsandanswers)]
+#[diesel(primary_key(id))]
+pub struct Question {
+    pub id: Uuid,
+    pub competition_lvl: i32,                   // 0 for practice, higher for higher levels of competition
+    pub quiz_season: i32,                       // a quiz_season, e.g. 1 for John
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub question: String,
+    pub answer: String,
+    pub book: String,
+    pub chapter: i32,
+    pub verses: String,                         // as written, e.g. "16" or "16-18"
+    pub beginning_verse: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub was_imported_from_ui: Option<bool>,     // true when added by a file import, false when entered one at a time
+    pub is_quizstuff_authored_question: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable, ToSchema)]
+#[diesel(table_name = crate::schema::questionsandanswers)]
+pub struct NewQuestion {
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+    pub competition_lvl: i32,
+    pub quiz_season: i32,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub question: String,
+    pub answer: String,
+    pub book: String,
+    pub chapter: i32,
+    pub verses: String,
+    pub beginning_verse: i32,
+    pub was_imported_from_ui: Option<bool>,
+    pub is_quizstuff_authored_question: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, AsChangeset, ToSchema)]
+#[diesel(table_name = crate::schema::questionsandanswers)]
+pub struct QuestionChangeset {
+    pub competition_lvl: Option<i32>,
+    pub quiz_season: Option<i32>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub question: Option<String>,
+    pub answer: Option<String>,
+    pub book: Option<String>,
+    pub chapter: Option<i32>,
+    pub verses: Option<String>,
+    pub beginning_verse: Option<i32>,
+    pub was_imported_from_ui: Option<bool>,
+    pub is_quizstuff_authored_question: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QuestionSearchParams {
+    pub page: i64,
+    pub page_size: i64,
+    pub quiz_season: Option<i32>,
+    pub competition_lvl: Option<i32>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub book: Option<String>,
+    pub from_chapter: Option<i32>,              // the scripture range, from chapter:verse
+    pub from_verse: Option<i32>,
+    pub to_chapter: Option<i32>,                // to chapter:verse, inclusive
+    pub to_verse: Option<i32>,
+    pub text: Option<String>,                   // words in the question
+    pub reference: Option<String>,              // questions touching it, e.g. "Acts 2" or "1 Cor 13:4-7"
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReferenceCheckParams {
+    pub quiz_season: Option<i32>,
+}
+
+/// A question whose scripture columns aren't a reference that exists.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ReferenceProblem {
+    pub id: Uuid,
+    pub quiz_season: i32,
+    pub reference: String,                      // as stored, "book chapter:verses"
+    pub errors: Vec<String>,
+}
+
+impl NewQuestion {
+    /// Checks the fields, and that the scripture columns are a reference that exists.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        QuestionChangeset {
+            competition_lvl: Some(self.competition_lvl),
+            quiz_season: Some(self.quiz_season),
+            type_: Some(self.type_.clone()),
+            question: Some(self.question.clone()),
+            answer: Some(self.answer.clone()),
+            book: Some(self.book.clone()),
+            chapter: Some(self.chapter),
+            verses: Some(self.verses.clone()),
+            beginning_verse: Some(self.beginning_verse),
+            was_imported_from_ui: self.was_imported_from_ui,
+            is_quizstuff_authored_question: self.is_quizstuff_authored_question,
+        }.validate()?;
+        let errors = scripture::validate_question(&self.book, self.chapter, &self.verses, self.beginning_verse);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Writes the book and verses in canonical form, e.g. "1 Cor" and "4 - 7" as "1 Corinthians" and "4-7".
+    pub fn normalize(&mut self) {
+        if let Some((canonical_book, canonical_verses)) = scripture::canonical_question(&self.book, self.chapter, &self.verses) {
+            self.book = canonical_book;
+            self.verses = canonical_verses;
+        }
+    }
+}
+
+impl QuestionChangeset {
+    /// Checks the fields that are set; the others are left as they are.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.competition_lvl.is_some_and(|l| l < 0) {
+            errors.push("competition_lvl can't be negative".to_string());
+        }
+        if self.quiz_season.is_some_and(|s| s < 1) {
+            errors.push("quiz_season must be at least 1".to_string());
+        }
+        if self.type_.as_ref().is_some_and(|t| t.trim().is_empty() || t.len() > 32) {
+            errors.push("type must be between 1 and 32 characters".to_string());
+        }
+        if self.question.as_ref().is_some_and(|q| q.trim().is_empty()) {
+            errors.push("question can't be empty".to_string());
+        }
+        if self.book.as_ref().is_some_and(|b| b.trim().is_empty() || b.len() > 32) {
+            errors.push("book must be between 1 and 32 characters".to_string());
+        }
+        if self.chapter.is_some_and(|c| c < 1) {
+            errors.push("chapter must be at least 1".to_string());
+        }
+        if self.verses.as_ref().is_some_and(|v| v.trim().is_empty() || v.len() > 32) {
+            errors.push("verses must be between 1 and 32 characters".to_string());
+        }
+        if self.beginning_verse.is_some_and(|v| v < 1) {
+            errors.push("beginning_verse must be at least 1".to_string());
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+// The season and level a question names must exist.
+fn check_season_and_level(db: &mut database::Connection, season: Option<i32>, level: Option<i32>) -> QueryResult<()> {
+    if let Some(season) = season {
+        models::quiz_season::check_exists(db, season)?;
+    }
+    if let Some(level) = level {
+        models::competition_level::check_exists(db, level)?;
+    }
+    Ok(())
+}
+
+pub fn create(db: &mut database::Connection, item: &NewQuestion) -> QueryResult<Question> {
+    use crate::schema::questionsandanswers::dsl::*;
+    item.validate().map_err(|errors| invalid(errors.join(" ")))?;
+    check_season_and_level(db, Some(item.quiz_season), Some(item.competition_lvl))?;
+    let mut item = item.clone();
+    item.normalize();
+    insert_into(questionsandanswers).values(&item).get_result::<Question>(db)
+}
+
+pub fn read(db: &mut database::Connection, item_id: Uuid) -> QueryResult<Question> {
+    use crate::schema::questionsandanswers::dsl::*;
+    questionsandanswers.filter(id.eq(item_id)).first::<Question>(db)
+}
+
+// The last verse of a question: its verses are canonical ("16", "16-18" or "16,18"), so the last number.
+const ENDING_VERSE_SQL: &str = "COALESCE((regexp_match(verses, '(\\d+)\\s*$'))[1]::int, beginning_verse)";
+
+pub(crate) type QuestionFilter = Box<dyn BoxableExpression<crate::schema::questionsandanswers::table, Pg, SqlType = sql_types::Bool>>;
+
+// Questions sharing a verse with any of `ranges`, which can't be empty.
+pub(crate) fn touching_any(ranges: &[ScriptureRange]) -> QuestionFilter {
+    let (first, rest) = ranges.split_first().expect("at least one range");
+    rest.iter().fold(touching(first), |condition, range| Box::new(condition.or(touching(range))))
+}
+
+// Questions sharing a verse with `range`. A question with a list of verses counts as all the verses
+// from its first to its last.
+fn touching(range: &ScriptureRange) -> QuestionFilter {
+    use diesel::dsl::sql;
+    use diesel::sql_types::{Integer, Text};
+    Box::new(
+        sql::<sql_types::Bool>("(book = ").bind::<Text, _>(range.book.clone())
+            .sql(" AND (chapter, beginning_verse) <= (").bind::<Integer, _>(range.end_chapter)
+            .sql(", ").bind::<Integer, _>(range.end_verse.unwrap_or(i32::MAX))
+            .sql(&format![") AND (chapter, {}) >= (", ENDING_VERSE_SQL]).bind::<Integer, _>(range.start_chapter)
+            .sql(", ").bind::<Integer, _>(range.start_verse.unwrap_or(0))
+            .sql("))")
+    )
+}
+
+fn search_query(params: &QuestionSearchParams, ranges: &[ScriptureRange]) -> crate::schema::questionsandanswers::BoxedQuery<'static, Pg> {
+    use crate::schema::questionsandanswers::dsl::*;
+
+    let mut query = questionsandanswers.into_boxed();
+    if let Some(season) = params.quiz_season {
+        query = query.filter(quiz_season.eq(season));
+    }
+    if let Some(level) = params.competition_lvl {
+        query = query.filter(competition_lvl.eq(level));
+    }
+    if let Some(kind) = params.type_.clone() {
+        query = query.filter(type_.eq(kind));
+    }
+    if let Some(book_name) = params.book.clone() {
+        query = query.filter(book.ilike(escape_like(&book_name)));
+    }
+    if let Some(from) = params.from_chapter {
+        let verse = params.from_verse.unwrap_or(1);
+        query = query.filter(chapter.gt(from).or(chapter.eq(from).and(beginning_verse.ge(verse))));
+    }
+    if let Some(to) = params.to_chapter {
+        query = match params.to_verse {
+            Some(verse) => query.filter(chapter.lt(to).or(chapter.eq(to).and(beginning_verse.le(verse)))),
+            None => query.filter(chapter.le(to)),
+        };
+    }
+    if let Some(words) = params.text.as_ref().filter(|t| !t.trim().is_empty()) {
+        query = query.filter(question.ilike(format!["%{}%", escape_like(words.trim())]));
+    }
+    if !ranges.is_empty() {
+        query = query.filter(touching_any(ranges));
+    }
+    query
+}
+
+/// Reads a page of the questions matching `params`, in scripture order, and how many match in all.
+pub fn search(db: &mut database::Connection, params: &QuestionSearchParams) -> QueryResult<(Vec<Question>, i64)> {
+    use crate::schema::questionsandanswers::dsl::*;
+
+    let page_size = params.page_size.min(PaginationParams::MAX_PAGE_SIZE as i64);
+    let offset_val = params.page * page_size;
+    let ranges = match params.reference.as_ref().filter(|r| !r.trim().is_empty()) {
+        Some(reference) => scripture::parse(reference).map_err(invalid)?,
+        None => vec![],
+    };
+
+    let items = search_query(params, &ranges)
+        .order((quiz_season.asc(), book.asc(), chapter.asc(), beginning_verse.asc(), created_at.asc()))
+        .limit(page_size)
+        .offset(offset_val)
+        .load::<Question>(db)?;
+    let total = search_query(params, &ranges).count().get_result::<i64>(db)?;
+    Ok((items, total))
+}
+
+pub fn update(db: &mut database::Connection, item_id: Uuid, item: &QuestionChangeset) -> QueryResult<Question> {
+    use crate::schema::questionsandanswers::dsl::*;
+    item.validate().map_err(|errors| invalid(errors.join(" ")))?;
+    check_season_and_level(db, item.quiz_season, item.competition_lvl)?;
+
+    // the scripture columns are checked together, with the ones that aren't changing
+    let mut item = item.clone();
+    if item.book.is_some() || item.chapter.is_some() || item.verses.is_some() || item.beginning_verse.is_some() {
+        let current = read(db, item_id)?;
+        let new_book = item.book.clone().unwrap_or(current.book);
+        let new_chapter = item.chapter.unwrap_or(current.chapter);
+        let new_verses = item.verses.clone().unwrap_or(current.verses);
+        let errors = scripture::validate_question(&new_book, new_chapter, &new_verses, item.beginning_verse.unwrap_or(current.beginning_verse));
+        if !errors.is_empty() {
+            return Err(invalid(errors.join(" ")));
+        }
+        if let Some((canonical_book, canonical_verses)) = scripture::canonical_question(&new_book, new_chapter, &new_verses) {
+            item.book = item.book.map(|_| canonical_book);
+            item.verses = item.verses.map(|_| canonical_verses);
+        }
+    }
+
+    diesel::update(questionsandanswers.filter(id.eq(item_id)))
+        .set((
+            &item,
+            updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(db)
+}
+
+/// The stored questions whose scripture columns don't validate, in scripture order.
+pub fn check_references(db: &mut database::Connection, params: &ReferenceCheckParams) -> QueryResult<Vec<ReferenceProblem>> {
+    use crate::schema::questionsandanswers::dsl::*;
+    let mut query = questionsandanswers.into_boxed();
+    if let Some(season) = params.quiz_season {
+        query = query.filter(quiz_season.eq(season));
+    }
+    let questions = query
+        .order((quiz_season.asc(), book.asc(), chapter.asc(), beginning_verse.asc(), created_at.asc()))
+        .load::<Question>(db)?;
+    Ok(questions
+        .into_iter()
+        .filter_map(|q| {
+            let errors = scripture::validate_question(&q.book, q.chapter, &q.verses, q.beginning_verse);
+            (!errors.is_empty()).then(|| ReferenceProblem {
+                id: q.id,
+                quiz_season: q.quiz_season,
+                reference: format!["{} {}:{}", q.book, q.chapter, q.verses],
+                errors,
+            })
+        })
+        .collect())
+}
+
+pub fn count(db: &mut database::Connection) -> QueryResult<i64> {
+    use crate::schema::questionsandanswers::dsl::*;
+    questionsandanswers.count().get_result(db)
+}
+
+pub fn delete(db: &mut database::Connection, item_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::questionsandanswers::dsl::*;
+    diesel::delete(questionsandanswers.filter(id.eq(item_id))).execute(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_validates_fields() {
+        assert!(QuestionBuilder::new_default("Who was sent from God?", "John").build().is_ok());
+
+        let errors = QuestionBuilder::new_default("  ", "John")
+            .set_quiz_season(0)
+            .set_chapter(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(errors, vec![
+            "quiz_season must be at least 1".to_string(),
+            "question can't be empty".to_string(),
+            "chapter must be at least 1".to_string(),
+        ]);
+
+        let errors = QuestionBuilder::new("Who was sent from God?", "John").build().unwrap_err();
+        assert_eq!(errors.len(), 7);
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::HashSet;
+use crate::database;
+use crate::models;
+use crate::models::question::{NewQuestion, Question, QuestionChangeset};
+use crate::models::scripture::{self, find_book, parse_verses};
+use crate::models::common::invalid;
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Bulk import and export of the question bank, as CSV or JSON. An import is previewed first: each
+// row is checked, its scripture reference compared with its book, chapter and verses, and its
+// question compared with the bank and the rest of the file. Applying it adds the rows that passed.
+
+pub const QUESTION_COLUMNS: [&str; 11] = [
+    "competition_lvl", "quiz_season", "type", "question", "answer", "book", "chapter", "verses",
+    "beginning_verse", "reference", "is_quizstuff_authored_question",
+];
+
+// Questions whose words overlap at least this much are reported as near duplicates.
+pub const NEAR_DUPLICATE_SIMILARITY: f64 = 0.8;
+
+// One question of an import, in QUESTION_COLUMNS. `reference` (e.g. "John 3:16-18") is optional;
+// when given it has to agree with the other scripture columns.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct QuestionImportRow {
+    pub competition_lvl: i32,
+    pub quiz_season: i32,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub question: String,
+    pub answer: String,
+    pub book: String,
+    pub chapter: i32,
+    pub verses: String,
+    pub beginning_verse: i32,
+    #[serde(default)]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub is_quizstuff_authored_question: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct NearDuplicate {
+    pub id: Option<Uuid>,                       // a question already in the bank
+    pub line: Option<u64>,                      // or an earlier line of the import
+    pub question: String,
+    pub similarity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuestionImportLine {
+    pub line: u64,                              // the CSV line, or the JSON array index plus one
+    pub question: String,
+    pub action: String,                         // create, skip (a duplicate) or invalid
+    pub errors: Vec<String>,
+    pub duplicate_of: Option<Uuid>,             // the same question is already in the bank
+    pub near_duplicates: Vec<NearDuplicate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuestionImportReport {
+    pub preview: bool,
+    pub to_create: i32,
+    pub duplicates: i32,
+    pub invalid: i32,
+    pub near_duplicates: i32,                   // lines to create that look like another question
+    pub lines: Vec<QuestionImportLine>,
+    pub created: Vec<Question>,                 // only filled in when applied
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestionFileFormat {
+    Csv,
+    Json,
+}
+
+impl QuestionFileFormat {
+    pub fn from_param(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.to_lowercase()).as_deref() {
+            None | Some("csv") => Ok(QuestionFileFormat::Csv),
+            Some("json") => Ok(QuestionFileFormat::Json),
+            Some(other) => Err(format!["'{}' is not a question file format; use csv or json", other]),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QuestionImportParams {
+    pub format: Option<String>,                 // "csv" (default) or "json"
+    pub preview: Option<bool>,                  // defaults to true: nothing is written
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QuestionExportParams {
+    pub format: Option<String>,                 // "csv" (default) or "json"
+    pub quiz_season: Option<i32>,
+    pub competition_lvl: Option<i32>,
+}
+
+/// Checks a row's fields, and that its book, chapter, verses and reference agree with each other.
+pub fn validate_row(row: &QuestionImportRow) -> Vec<String> {
+    let mut errors = QuestionChangeset {
+        competition_lvl: Some(row.competition_lvl),
+        quiz_season: Some(row.quiz_season),
+        type_: Some(row.type_.clone()),
+        question: Some(row.question.clone()),
+        answer: Some(row.answer.clone()),
+        book: Some(row.book.clone()),
+        chapter: Some(row.chapter),
+        verses: Some(row.verses.clone()),
+        beginning_verse: Some(row.beginning_verse),
+        was_imported_from_ui: None,
+        is_quizstuff_authored_question: row.is_quizstuff_authored_question,
+    }.validate().err().unwrap_or_default();
+
+    if row.answer.trim().is_empty() {
+        errors.push("answer can't be empty".to_string());
+    }
+    errors.extend(scripture::validate_question(&row.book, row.chapter, &row.verses, row.beginning_verse));
+    if let Some(reference) = row.reference.as_ref().filter(|r| !r.trim().is_empty()) {
+        match scripture::parse(reference) {
+            Ok(ranges) => {
+                if scripture::question_ranges(&row.book, row.chapter, &row.verses).ok() != Some(ranges) {
+                    errors.push(format!["reference '{}' doesn't match {} {}:{}", reference, row.book, row.chapter, row.verses]);
+                }
+            },
+            Err(e) => errors.push(e),
+        }
+    }
+    errors
+}
+
+pub fn normalize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect()
+}
+
+/// How much two questions' words overlap, from 0 (nothing in common) to 1 (the same words).
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: HashSet<String> = normalize(a).into_iter().collect();
+    let b: HashSet<String> = normalize(b).into_iter().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+fn same_question(a: &QuestionImportRow, b: &Question) -> bool {
+    normalize(&a.question) == normalize(&b.question)
+        && find_book(&a.book) == find_book(&b.book)
+        && a.chapter == b.chapter
+        && parse_verses(&a.verses).ok() == parse_verses(&b.verses).ok()
+}
+
+pub fn parse_csv(bytes: &[u8]) -> Result<Vec<QuestionImportRow>, String> {
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+    let mut rows = vec![];
+    for result in reader.deserialize::<QuestionImportRow>() {
+        let line = rows.len() as u64 + 2;
+        rows.push(result.map_err(|e| format!["line {}: {}", line, e])?);
+    }
+    Ok(rows)
+}
+
+pub fn parse(format: QuestionFileFormat, bytes: &[u8]) -> Result<Vec<QuestionImportRow>, String> {
+    match format {
+        QuestionFileFormat::Csv => parse_csv(bytes),
+        QuestionFileFormat::Json => serde_json::from_slice::<Vec<QuestionImportRow>>(bytes).map_err(|e| e.to_string()),
+    }
+}
+
+/// Checks every row against the bank (`existing`) and the rows before it.
+pub fn check_rows(rows: &[QuestionImportRow], existing: &[Question], first_line: u64) -> Vec<QuestionImportLine> {
+    let mut lines: Vec<QuestionImportLine> = vec![];
+    for (idx, row) in rows.iter().enumerate() {
+        let line = first_line + idx as u64;
+        let errors = validate_row(row);
+        let duplicate_of = existing.iter().find(|q| same_question(row, q)).map(|q| q.id);
+        let repeated = rows[..idx].iter().enumerate().any(|(earlier, other)| {
+            lines[earlier].action != "invalid"
+                && normalize(&other.question) == normalize(&row.question)
+                && find_book(&other.book) == find_book(&row.book)
+                && other.chapter == row.chapter
+                && parse_verses(&other.verses).ok() == parse_verses(&row.verses).ok()
+        });
+
+        let mut near_duplicates: Vec<NearDuplicate> = existing
+            .iter()
+            .filter(|q| Some(q.id) != duplicate_of && find_book(&q.book) == find_book(&row.book))
+            .map(|q| NearDuplicate { id: Some(q.id), line: None, question: q.question.clone(), similarity: similarity(&q.question, &row.question) })
+            .chain(rows[..idx].iter().enumerate().map(|(earlier, other)| NearDuplicate {
+                id: None,
+                line: Some(first_line + earlier as u64),
+                question: other.question.clone(),
+                similarity: similarity(&other.question, &row.question),
+            }))
+            .filter(|near| near.similarity >= NEAR_DUPLICATE_SIMILARITY && near.similarity < 1.0)
+            .collect();
+        near_duplicates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+        let action = if !errors.is_empty() {
+            "invalid"
+        } else if duplicate_of.is_some() || repeated {
+            "skip"
+        } else {
+            "create"
+        };
+        lines.push(QuestionImportLine {
+            line,
+            question: row.question.clone(),
+            action: action.to_string(),
+            errors,
+            duplicate_of,
+            near_duplicates,
+        });
+    }
+    lines
+}
+
+fn read_all_questions_of_books(db: &mut database::Connection, books: &[String]) -> QueryResult<Vec<Question>> {
+    use crate::schema::questionsandanswers::dsl::*;
+    questionsandanswers
+        .filter(book.eq_any(books))
+        .order(created_at.asc())
+        .load::<Question>(db)
+}
+
+/// Checks an import and, unless previewing, adds the rows that passed in one transaction. Imported
+/// questions are marked `was_imported_from_ui`, whether the file came from the UI or the API.
+pub fn import(db: &mut database::Connection, format: QuestionFileFormat, bytes: &[u8], preview: bool) -> QueryResult<QuestionImportReport> {
+    let rows = parse(format, bytes).map_err(invalid)?;
+    if rows.is_empty() {
+        return Err(invalid("The file has no questions".to_string()));
+    }
+
+    let mut books: Vec<String> = rows.iter().map(|r| r.book.trim().to_string()).collect();
+    books.extend(rows.iter().filter_map(|r| find_book(&r.book)).map(|(book, _)| book.to_string()));
+    let existing = read_all_questions_of_books(db, &books)?;
+    let first_line = if format == QuestionFileFormat::Csv { 2 } else { 1 };
+    let mut lines = check_rows(&rows, &existing, first_line);
+
+    // rows naming a season or level that doesn't exist can't be added
+    let seasons: HashSet<i32> = models::quiz_season::read_all(db)?.into_iter().map(|s| s.season).collect();
+    let levels: HashSet<i32> = models::competition_level::read_all(db)?.into_iter().map(|l| l.level).collect();
+    for (row, line) in rows.iter().zip(lines.iter_mut()) {
+        if !seasons.contains(&row.quiz_season) {
+            line.errors.push(format!["There is no quiz season {}", row.quiz_season]);
+        }
+        if !levels.contains(&row.competition_lvl) {
+            line.errors.push(format!["There is no competition level {}", row.competition_lvl]);
+        }
+        if !line.errors.is_empty() {
+            line.action = "invalid".to_string();
+        }
+    }
+
+    let count = |action: &str| lines.iter().filter(|l| l.action == action).count() as i32;
+    let mut report = QuestionImportReport {
+        preview,
+        to_create: count("create"),
+        duplicates: count("skip"),
+        invalid: count("invalid"),
+        near_duplicates: lines.iter().filter(|l| l.action == "create" && !l.near_duplicates.is_empty()).count() as i32,
+        lines,
+        created: vec![],
+    };
+
+    if !preview {
+        let new_questions: Vec<NewQuestion> = rows
+            .iter()
+            .zip(report.lines.iter())
+            .filter(|(_, line)| line.action == "create")
+            .map(|(row, _)| (row, scripture::canonical_question(&row.book, row.chapter, &row.verses)))
+            .map(|(row, canonical)| NewQuestion {
+                id: Uuid::new_v4(),
+                competition_lvl: row.competition_lvl,
+                quiz_season: row.quiz_season,
+                type_: row.type_.trim().to_string(),
+                question: row.question.trim().to_string(),
+                answer: row.answer.trim().to_string(),
+                book: canonical.as_ref().map(|(book, _)| book.clone()).unwrap_or(row.book.clone()),
+                chapter: row.chapter,
+                verses: canonical.map(|(_, verses)| verses).unwrap_or(row.verses.trim().to_string()),
+                beginning_verse: row.beginning_verse,
+                was_imported_from_ui: Some(true),
+                is_quizstuff_authored_question: row.is_quizstuff_authored_question,
+            })
+            .collect();
+        report.created = db.transaction::<_, diesel::result::Error, _>(|conn| {
+            use crate::schema::questionsandanswers::dsl::*;
+            diesel::insert_into(questionsandanswers).values(&new_questions).get_results::<Question>(conn)
+        })?;
+    }
+
+    Ok(report)
+}
+
+pub(crate) fn to_row(question: &Question) -> QuestionImportRow {
+    QuestionImportRow {
+        competition_lvl: question.competition_lvl,
+        quiz_season: question.quiz_season,
+        type_: question.type_.clone(),
+        question: question.question.clone(),
+        answer: question.answer.clone(),
+        book: question.book.clone(),
+        chapter: question.chapter,
+        verses: question.verses.clone(),
+        beginning_verse: question.beginning_verse,
+        reference: Some(format!["{} {}:{}", question.book, question.chapter, question.verses]),
+        is_quizstuff_authored_question: question.is_quizstuff_authored_question,
+    }
+}
+
+/// Writes questions in the layout the import reads, so an archived season can be imported again.
+pub fn to_file(format: QuestionFileFormat, questions: &[Question]) -> Result<Vec<u8>, String> {
+    let rows: Vec<QuestionImportRow> = questions.iter().map(to_row).collect();
+    match format {
+        QuestionFileFormat::Json => serde_json::to_vec_pretty(&rows).map_err(|e| e.to_string()),
+        QuestionFileFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(vec![]);
+            writer.write_record(QUESTION_COLUMNS).map_err(|e| e.to_string())?;
+            for row in rows.iter() {
+                writer.serialize(row).map_err(|e| e.to_string())?;
+            }
+            writer.into_inner().map_err(|e| e.to_string())
+        },
+    }
+}
+
+pub fn read_for_export(db: &mut database::Connection, params: &QuestionExportParams) -> QueryResult<Vec<Question>> {
+    use crate::schema::questionsandanswers::dsl::*;
+    let mut query = questionsandanswers.into_boxed();
+    if let Some(season) = params.quiz_season {
+        query = query.filter(quiz_season.eq(season));
+    }
+    if let Some(level) = params.competition_lvl {
+        query = query.filter(competition_lvl.eq(level));
+    }
+    query
+        .order((quiz_season.asc(), book.asc(), chapter.asc(), beginning_verse.asc(), created_at.asc()))
+        .load::<Question>(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(question: &str, book: &str, chapter: i32, verses: &str, beginning_verse: i32) -> QuestionImportRow {
+        QuestionImportRow {
+            competition_lvl: 1,
+            quiz_season: 1,
+            type_: "G".to_string(),
+            question: question.to_string(),
+            answer: "An answer".to_string(),
+            book: book.to_string(),
+            chapter,
+            verses: verses.to_string(),
+            beginning_verse,
+            reference: None,
+            is_quizstuff_authored_question: None,
+        }
+    }
+
+    #[test]
+    fn validate_row_works() {
+        assert!(validate_row(&row("Who was sent from God?", "John", 1, "6", 6)).is_empty());
+        assert!(validate_row(&QuestionImportRow { reference: Some("1 Corinthians 13:4-7".to_string()), ..row("What is love?", "1 corinthians", 13, "4-7", 4) }).is_empty());
+
+        assert_eq!(validate_row(&row("Who?", "Hezekiah", 1, "1", 1)), vec!["'Hezekiah' is not a book of the Bible".to_string()]);
+        assert_eq!(validate_row(&row("Who?", "John", 22, "1", 1)), vec!["John has 21 chapters, not 22".to_string()]);
+        assert_eq!(validate_row(&row("Who?", "John", 1, "6-8", 7)), vec!["verses '6-8' don't begin with verse 7".to_string()]);
+        assert_eq!(validate_row(&row("Who?", "John", 1, "8-6", 8)), vec!["'8-6' is not a verse or range of verses".to_string()]);
+        assert_eq!(
+            validate_row(&QuestionImportRow { reference: Some("John 3:16".to_string()), ..row("Who?", "John", 1, "6", 6) }),
+            vec!["reference 'John 3:16' doesn't match John 1:6".to_string()],
+        );
+    }
+
+    #[test]
+    fn check_rows_finds_duplicates() {
+        let rows = vec![
+            row("Who was sent from God?", "John", 1, "6", 6),
+            row("Who was sent from God then?", "John", 1, "6", 6),
+            row("who was sent from God", "John", 1, "6", 6),
+            row("In the beginning was what?", "John", 1, "1", 1),
+            row("Who?", "Hezekiah", 1, "1", 1),
+        ];
+        let existing = Question {
+            id: Uuid::new_v4(),
+            competition_lvl: 1,
+            quiz_season: 1,
+            type_: "G".to_string(),
+            question: "In the beginning was what?".to_string(),
+            answer: "The Word".to_string(),
+            book: "John".to_string(),
+            chapter: 1,
+            verses: "1".to_string(),
+            beginning_verse: 1,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            was_imported_from_ui: Some(true),
+            is_quizstuff_authored_question: None,
+        };
+
+        let lines = check_rows(&rows, std::slice::from_ref(&existing), 2);
+        let actions: Vec<&str> = lines.iter().map(|l| l.action.as_str()).collect();
+        assert_eq!(actions, vec!["create", "create", "skip", "skip", "invalid"]);
+        assert_eq!(lines[1].near_duplicates.iter().map(|n| n.line).collect::<Vec<_>>(), vec![Some(2)]);
+        assert_eq!(lines[3].duplicate_of, Some(existing.id));
+        assert!(similarity("Who was sent from God?", "Who was the man sent from God?") < NEAR_DUPLICATE_SIMILARITY);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::database;
+use crate::models::question::Question;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// How questions from the question bank did when they were read, from the questions linked to each
+// game (see game_question) and the game's events. Ignored games don't count. The time to jump is
+// measured from the last event of the question before, which is the closest the events get to when
+// a question started being read; longer than MAX_SECONDS_TO_JUMP means the clock was stopped.
+
+pub const MAX_SECONDS_TO_JUMP: f64 = 120.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QuestionUsageParams {
+    pub quiz_season: Option<i32>,
+    pub competition_lvl: Option<i32>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub book: Option<String>,
+    pub tournament_id: Option<Uuid>,
+}
+
+/// One reading of a question in a game.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuestionUse {
+    pub jumped: bool,
+    pub correct: bool,
+    pub errors: i64,
+    pub bonuses: i64,
+    pub bonuses_correct: i64,
+    pub seconds_to_jump: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, ToSchema)]
+pub struct UsageStats {
+    pub uses: i64,
+    pub jumps: i64,                             // uses where someone jumped
+    pub correct: i64,                           // uses answered correctly
+    pub errors: i64,                            // wrong answers, counting each one
+    pub no_jumps: i64,
+    pub bonuses: i64,
+    pub bonuses_correct: i64,
+    pub jump_rate: Option<f64>,
+    pub correct_rate: Option<f64>,
+    pub bonus_conversion: Option<f64>,
+    pub average_seconds_to_jump: Option<f64>,
+    #[serde(skip)]
+    timed_jumps: i64,
+    #[serde(skip)]
+    seconds_to_jump: f64,
+}
+
+impl UsageStats {
+    pub fn add(&mut self, item: &QuestionUse) {
+        self.uses += 1;
+        if item.jumped {
+            self.jumps += 1;
+        } else {
+            self.no_jumps += 1;
+        }
+        if item.correct {
+            self.correct += 1;
+        }
+        self.errors += item.errors;
+        self.bonuses += item.bonuses;
+        self.bonuses_correct += item.bonuses_correct;
+        if let Some(seconds) = item.seconds_to_jump {
+            self.timed_jumps += 1;
+            self.seconds_to_jump += seconds;
+        }
+        self.update_rates();
+    }
+
+    fn update_rates(&mut self) {
+        let rate = |part: i64, whole: i64| if whole > 0 { Some(part as f64 / whole as f64) } else { None };
+        self.jump_rate = rate(self.jumps, self.uses);
+        self.correct_rate = rate(self.correct, self.uses);
+        self.bonus_conversion = rate(self.bonuses_correct, self.bonuses);
+        self.average_seconds_to_jump = if self.timed_jumps > 0 { Some(self.seconds_to_jump / self.timed_jumps as f64) } else { None };
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuestionUsage {
+    pub question: Question,
+    pub stats: UsageStats,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct UsageGroup {
+    pub key: String,                            // the type, book, or "book chapter"
+    pub stats: UsageStats,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuestionUsageReport {
+    pub questions: Vec<QuestionUsage>,
+    pub by_type: Vec<UsageGroup>,
+    pub by_book: Vec<UsageGroup>,
+    pub by_chapter: Vec<UsageGroup>,
+}
+
+/// Tallies the events of one game for each linked question number. `events` are
+/// (question number, event code, client timestamp).
+pub fn tally_game(links: &[(i32, Uuid)], events: &[(i32, String, DateTime<Utc>)]) -> Vec<(Uuid, QuestionUse)> {
+    links
+        .iter()
+        .filter_map(|(number, question_id)| {
+            let of_question: Vec<&(i32, String, DateTime<Utc>)> = events.iter().filter(|(n, _, _)| n == number).collect();
+            if of_question.is_empty() {
+                return None;
+            }
+            let count = |code: &str| of_question.iter().filter(|(_, event, _)| event == code).count() as i64;
+            let first_jump = of_question
+                .iter()
+                .filter(|(_, event, _)| event == "TC" || event == "TE")
+                .map(|(_, _, ts)| *ts)
+                .min();
+            let started = events.iter().filter(|(n, _, _)| n < number).map(|(_, _, ts)| *ts).max();
+            let seconds_to_jump = match (first_jump, started) {
+                (Some(jump), Some(start)) => {
+                    let seconds = (jump - start).num_milliseconds() as f64 / 1000.0;
+                    (0.0..=MAX_SECONDS_TO_JUMP).contains(&seconds).then_some(seconds)
+                }
+                _ => None,
+            };
+            let item = QuestionUse {
+                jumped: first_jump.is_some(),
+                correct: count("TC") > 0,
+                errors: count("TE"),
+                bonuses: count("BC") + count("BE"),
+                bonuses_correct: count("BC"),
+                seconds_to_jump,
+            };
+            Some((*question_id, item))
+        })
+        .collect()
+}
+
+fn groups(stats: BTreeMap<String, UsageStats>) -> Vec<UsageGroup> {
+    stats.into_iter().map(|(key, stats)| UsageGroup { key, stats }).collect()
+}
+
+pub fn report(db: &mut database::Connection, params: &QuestionUsageParams) -> QueryResult<QuestionUsageReport> {
+    use crate::schema::{game_questions, gameevents, games, questionsandanswers};
+
+    let mut query = game_questions::table
+        .inner_join(games::table)
+        .inner_join(questionsandanswers::table)
+        .filter(games::ignore.eq(false))
+        .select((game_questions::gid, game_questions::question, Question::as_select()))
+        .into_boxed();
+    if let Some(season) = params.quiz_season {
+        query = query.filter(questionsandanswers::quiz_season.eq(season));
+    }
+    if let Some(level) = params.competition_lvl {
+        query = query.filter(questionsandanswers::competition_lvl.eq(level));
+    }
+    if let Some(of_type) = &params.type_ {
+        query = query.filter(questionsandanswers::type_.eq(of_type.clone()));
+    }
+    if let Some(of_book) = &params.book {
+        query = query.filter(questionsandanswers::book.eq(of_book.clone()));
+    }
+    if let Some(tournament) = params.tournament_id {
+        query = query.filter(games::tournamentid.eq(tournament));
+    }
+    let linked: Vec<(Uuid, i32, Question)> = query.load(db)?;
+
+    let mut links_by_game: HashMap<Uuid, Vec<(i32, Uuid)>> = HashMap::new();
+    let mut questions: HashMap<Uuid, Question> = HashMap::new();
+    for (game_id, number, question) in linked {
+        links_by_game.entry(game_id).or_default().push((number, question.id));
+        questions.insert(question.id, question);
+    }
+
+    let game_ids: Vec<Uuid> = links_by_game.keys().copied().collect();
+    let mut events_by_game: HashMap<Uuid, Vec<(i32, String, DateTime<Utc>)>> = HashMap::new();
+    for (game_id, number, event, ts) in gameevents::table
+        .filter(gameevents::gid.eq_any(&game_ids))
+        .select((gameevents::gid, gameevents::question, gameevents::event, gameevents::clientts))
+        .load::<(Uuid, i32, String, DateTime<Utc>)>(db)?
+    {
+        events_by_game.entry(game_id).or_default().push((number, event, ts));
+    }
+
+    let mut by_question: HashMap<Uuid, UsageStats> = HashMap::new();
+    let mut by_type: BTreeMap<String, UsageStats> = BTreeMap::new();
+    let mut by_book: BTreeMap<String, UsageStats> = BTreeMap::new();
+    let mut by_chapter: BTreeMap<String, UsageStats> = BTreeMap::new();
+    for (game_id, links) in &links_by_game {
+        let events = events_by_game.get(game_id).map(Vec::as_slice).unwrap_or_default();
+        for (question_id, item) in tally_game(links, events) {
+            let question = &questions[&question_id];
+            by_question.entry(question_id).or_default().add(&item);
+            by_type.entry(question.type_.clone()).or_default().add(&item);
+            by_book.entry(question.book.clone()).or_default().add(&item);
+            by_chapter.entry(format!["{} {}", question.book, question.chapter]).or_default().add(&item);
+        }
+    }
+
+    let mut usages: Vec<QuestionUsage> = by_question
+        .into_iter()
+        .map(|(question_id, stats)| QuestionUsage { question: questions[&question_id].clone(), stats })
+        .collect();
+    usages.sort_by(|a, b| {
+        (&a.question.book, a.question.chapter, a.question.beginning_verse, a.question.id)
+            .cmp(&(&b.question.book, b.question.chapter, b.question.beginning_verse, b.question.id))
+    });
+
+    Ok(QuestionUsageReport {
+        questions: usages,
+        by_type: groups(by_type),
+        by_book: groups(by_book),
+        by_chapter: groups(by_chapter),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn tally_game_works() {
+        let at = |seconds: i64| Utc.timestamp_opt(1_700_000_000 + seconds, 0).unwrap();
+        let q1 = Uuid::new_v4();
+        let q2 = Uuid::new_v4();
+        let q3 = Uuid::new_v4();
+        let events = vec![
+            (1, "TE".to_string(), at(0)),
+            (1, "TC".to_string(), at(5)),
+            (1, "BC".to_string(), at(10)),
+            (2, "TC".to_string(), at(18)),
+            (2, "BE".to_string(), at(25)),
+            (3, "NJ".to_string(), at(300)),
+        ];
+        let tallied = tally_game(&[(1, q1), (2, q2), (3, q3), (4, Uuid::new_v4())], &events);
+        assert_eq!(tallied.len(), 3);
+        assert_eq!(tallied[0], (q1, QuestionUse { jumped: true, correct: true, errors: 1, bonuses: 1, bonuses_correct: 1, seconds_to_jump: None }));
+        assert_eq!(tallied[1], (q2, QuestionUse { jumped: true, correct: true, errors: 0, bonuses: 1, bonuses_correct: 0, seconds_to_jump: Some(8.0) }));
+        assert_eq!(tallied[2], (q3, QuestionUse { jumped: false, correct: false, errors: 0, bonuses: 0, bonuses_correct: 0, seconds_to_jump: None }));
+
+        let mut stats = UsageStats::default();
+        for (_, item) in &tallied {
+            stats.add(item);
+        }
+        assert_eq!(stats.uses, 3);
+        assert_eq!(stats.jump_rate, Some(2.0 / 3.0));
+        assert_eq!(stats.bonus_conversion, Some(0.5));
+        assert_eq!(stats.average_seconds_to_jump, Some(8.0));
+    }
+}
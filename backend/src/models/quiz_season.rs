@@ -0,0 +1,221 @@
+use crate::database;
+use crate::models::scripture::{self, ScriptureRange};
+use crate::models::common::invalid;
+use diesel::prelude::*;
+use diesel::*;
+use diesel::{QueryResult,AsChangeset,Insertable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+
+// A quiz season of the scripture rotation. Its number is the quiz_season of the question bank, and
+// its material is the books and chapters quizzed, written as a scripture reference (e.g. "Acts 1-12").
+// A division quizzes its own season if it has one, and its tournament's if it doesn't.
+
+pub struct QuizSeasonBuilder {
+    season: i32,
+    name: String,
+    year: Option<i32>,
+    material: Option<String>,
+}
+
+impl QuizSeasonBuilder {
+    pub fn new(season: i32, name: &str) -> Self {
+        Self {
+            season,
+            name: name.to_string(),
+            year: None,
+            material: None,
+        }
+    }
+    pub fn new_default(season: i32) -> Self {
+        Self {
+            season,
+            name: format!["Season {}", season],
+            year: None,
+            material: None,
+        }
+    }
+    pub fn set_year(mut self, year: i32) -> Self {
+        self.year = Some(year);
+        self
+    }
+    pub fn set_material(mut self, material: &str) -> Self {
+        self.material = Some(material.to_string());
+        self
+    }
+    pub fn build(self) -> Result<NewQuizSeason, Vec<String>> {
+        let item = NewQuizSeason {
+            season: self.season,
+            name: self.name,
+            year: self.year,
+            material: self.material.unwrap_or_default(),
+        };
+        item.validate()?;
+        Ok(item)
+    }
+    pub fn build_and_insert(self, db: &mut database::Connection) -> QueryResult<QuizSeason> {
+        let new_season = self.build().map_err(|errors| invalid(errors.join(" ")))?;
+        create(db, &new_season)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::quiz_seasons)]
+#[diesel(primary_key(season))]
+pub struct QuizSeason {
+    pub season: i32,                            // the quiz_season of the question bank
+    pub name: String,
+    pub year: Option<i32>,                      // the year it's quizzed, e.g. 2026 for 2026-27
+    pub material: String,                       // books and chapters, e.g. "Hebrews 1-13; 1 Peter 1-5"
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable, ToSchema)]
+#[diesel(table_name = crate::schema::quiz_seasons)]
+pub struct NewQuizSeason {
+    pub season: i32,
+    pub name: String,
+    pub year: Option<i32>,
+    #[serde(default)]
+    pub material: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, AsChangeset, ToSchema)]
+#[diesel(table_name = crate::schema::quiz_seasons)]
+pub struct QuizSeasonChangeset {
+    pub name: Option<String>,
+    pub year: Option<i32>,
+    pub material: Option<String>,
+}
+
+fn validate_fields(name: Option<&str>, year: Option<i32>, material: Option<&str>) -> Vec<String> {
+    let mut errors = vec![];
+    if name.is_some_and(|n| n.trim().is_empty() || n.len() > 64) {
+        errors.push("name must be between 1 and 64 characters".to_string());
+    }
+    if year.is_some_and(|y| !(1900..=2200).contains(&y)) {
+        errors.push("year must be between 1900 and 2200".to_string());
+    }
+    if let Some(text) = material.filter(|m| !m.trim().is_empty()) {
+        if text.len() > 255 {
+            errors.push("material must be at most 255 characters".to_string());
+        } else if let Err(e) = scripture::parse(text) {
+            errors.push(format!["material: {}", e]);
+        }
+    }
+    errors
+}
+
+/// The material written in canonical form, e.g. "1 cor; 2 cor" as "1 Corinthians 1-16; 2 Corinthians 1-13".
+fn canonical_material(material: &str) -> String {
+    scripture::parse(material).map(|ranges| scripture::format_ranges(&ranges)).unwrap_or(material.trim().to_string())
+}
+
+impl NewQuizSeason {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = validate_fields(Some(&self.name), self.year, Some(&self.material));
+        if self.season < 1 {
+            errors.push("season must be at least 1".to_string());
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+impl QuizSeasonChangeset {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let errors = validate_fields(self.name.as_deref(), self.year, self.material.as_deref());
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+impl QuizSeason {
+    /// The season's material as ranges; none when it hasn't been set.
+    pub fn material_ranges(&self) -> Vec<ScriptureRange> {
+        if self.material.trim().is_empty() {
+            return vec![];
+        }
+        scripture::parse(&self.material).unwrap_or_default()
+    }
+}
+
+pub fn create(db: &mut database::Connection, item: &NewQuizSeason) -> QueryResult<QuizSeason> {
+    use crate::schema::quiz_seasons::dsl::*;
+    item.validate().map_err(|errors| invalid(errors.join(" ")))?;
+    let item = NewQuizSeason { material: canonical_material(&item.material), ..item.clone() };
+    insert_into(quiz_seasons).values(&item).get_result::<QuizSeason>(db)
+}
+
+pub fn exists(db: &mut database::Connection, item_season: i32) -> bool {
+    use crate::schema::quiz_seasons::dsl::*;
+    quiz_seasons
+        .find(item_season)
+        .get_result::<QuizSeason>(db)
+        .is_ok()
+}
+
+/// Fails with a message for the caller when there's no such season.
+pub fn check_exists(db: &mut database::Connection, item_season: i32) -> QueryResult<()> {
+    if exists(db, item_season) { Ok(()) } else { Err(invalid(format!["There is no quiz season {}", item_season])) }
+}
+
+pub fn read(db: &mut database::Connection, item_season: i32) -> QueryResult<QuizSeason> {
+    use crate::schema::quiz_seasons::dsl::*;
+    quiz_seasons.find(item_season).first::<QuizSeason>(db)
+}
+
+pub fn read_all(db: &mut database::Connection) -> QueryResult<Vec<QuizSeason>> {
+    use crate::schema::quiz_seasons::dsl::*;
+    quiz_seasons.order(season.asc()).load::<QuizSeason>(db)
+}
+
+pub fn update(db: &mut database::Connection, item_season: i32, item: &QuizSeasonChangeset) -> QueryResult<QuizSeason> {
+    use crate::schema::quiz_seasons::dsl::*;
+    item.validate().map_err(|errors| invalid(errors.join(" ")))?;
+    let item = QuizSeasonChangeset { material: item.material.as_deref().map(canonical_material), ..item.clone() };
+    diesel::update(quiz_seasons.find(item_season))
+        .set((
+            &item,
+            updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result(db)
+}
+
+/// Deletes a season nothing refers to; questions, divisions and tournaments keep theirs.
+pub fn delete(db: &mut database::Connection, item_season: i32) -> QueryResult<usize> {
+    use crate::schema::quiz_seasons::dsl::*;
+    diesel::delete(quiz_seasons.find(item_season)).execute(db)
+}
+
+/// The season a division quizzes: its own, or its tournament's.
+pub fn season_of_division(db: &mut database::Connection, division_id: uuid::Uuid) -> QueryResult<Option<QuizSeason>> {
+    use crate::schema::{divisions, tournaments};
+    let (of_division, of_tournament) = divisions::table
+        .inner_join(tournaments::table.on(tournaments::tid.eq(divisions::tid)))
+        .filter(divisions::did.eq(division_id))
+        .select((divisions::quiz_season, tournaments::quiz_season))
+        .first::<(Option<i32>, Option<i32>)>(db)?;
+    match of_division.or(of_tournament) {
+        Some(item_season) => read(db, item_season).optional(),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_works() {
+        assert!(QuizSeasonBuilder::new(9, "Acts").set_material("Acts 1-12").set_year(2027).build().is_ok());
+        assert!(QuizSeasonBuilder::new_default(9).build().is_ok());
+        assert_eq!(QuizSeasonBuilder::new(0, " ").build().unwrap_err().len(), 2);
+        assert_eq!(
+            QuizSeasonBuilder::new(9, "Acts").set_material("Acts 40").build().unwrap_err(),
+            vec!["material: Acts has 28 chapters, not 40".to_string()]
+        );
+        assert_eq!(canonical_material("1 cor; 2 cor"), "1 Corinthians 1-16; 2 Corinthians 1-13");
+        assert_eq!(canonical_material("acts 1-12"), "Acts 1-12");
+    }
+}
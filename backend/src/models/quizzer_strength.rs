@@ -0,0 +1,285 @@
+use crate::database;
+use crate::models::{self, common::normalize_name, conflict::user_name, game::{seat_of_team, Game}, question::Question, user::User};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Which material a quizzer is strong or weak on: their toss-up and bonus answers, joined to the
+// questions linked to each game question (see game_question), by question type, book and chapter.
+// Events name the quizzer the way the scorekeeper typed it, so they're matched to the quizzer's
+// first name or full name, on the seat of a team the quizzer is on. Ignored games don't count.
+
+pub const ANSWER_EVENTS: [&str; 4] = ["TC", "TE", "BC", "BE"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QuizzerStrengthParams {
+    pub tournament_id: Option<Uuid>,
+    pub quiz_season: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, ToSchema)]
+pub struct Accuracy {
+    pub tossups_correct: i64,
+    pub tossup_errors: i64,
+    pub bonuses_correct: i64,
+    pub bonus_errors: i64,
+    pub tossup_accuracy: Option<f64>,
+    pub bonus_accuracy: Option<f64>,
+    pub accuracy: Option<f64>,                  // toss-ups and bonuses together
+}
+
+impl Accuracy {
+    pub fn add(&mut self, event: &str) {
+        match event {
+            "TC" => self.tossups_correct += 1,
+            "TE" => self.tossup_errors += 1,
+            "BC" => self.bonuses_correct += 1,
+            "BE" => self.bonus_errors += 1,
+            _ => return,
+        }
+        let rate = |part: i64, whole: i64| if whole > 0 { Some(part as f64 / whole as f64) } else { None };
+        self.tossup_accuracy = rate(self.tossups_correct, self.tossups_correct + self.tossup_errors);
+        self.bonus_accuracy = rate(self.bonuses_correct, self.bonuses_correct + self.bonus_errors);
+        self.accuracy = rate(
+            self.tossups_correct + self.bonuses_correct,
+            self.tossups_correct + self.tossup_errors + self.bonuses_correct + self.bonus_errors,
+        );
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct StrengthGroup {
+    pub key: String,                            // the type, book, or "book chapter"
+    pub accuracy: Accuracy,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct QuizzerStrengths {
+    pub quizzerid: Uuid,
+    pub quizzer_name: String,
+    pub tournament_id: Option<Uuid>,
+    pub quiz_season: Option<i32>,
+    pub games: i64,                             // games with at least one answer counted
+    pub overall: Accuracy,
+    pub by_type: Vec<StrengthGroup>,
+    pub by_book: Vec<StrengthGroup>,
+    pub by_chapter: Vec<StrengthGroup>,
+}
+
+/// The names a scorekeeper might type for the user: the first name or the full name.
+pub fn names_of_user(user: &User) -> Vec<String> {
+    vec![
+        normalize_name(&user.fname),
+        normalize_name(&format!["{} {}", user.fname, user.lname]),
+        normalize_name(&format!["{} {} {}", user.fname, user.mname, user.lname]),
+    ]
+}
+
+/// The team seats in a game's events that belong to `team_ids` (see game::seat_of_team).
+pub fn seats_of_teams(game: &Game, team_ids: &HashSet<Uuid>) -> Vec<i32> {
+    let mut seats: Vec<i32> = team_ids.iter().filter_map(|id| seat_of_team(game, *id)).collect();
+    seats.sort();
+    seats
+}
+
+fn groups(accuracies: BTreeMap<String, Accuracy>) -> Vec<StrengthGroup> {
+    accuracies.into_iter().map(|(key, accuracy)| StrengthGroup { key, accuracy }).collect()
+}
+
+/// Tallies answers, each an event code with the question it was on.
+pub fn tally(answers: &[(&str, &Question)]) -> (Accuracy, Vec<StrengthGroup>, Vec<StrengthGroup>, Vec<StrengthGroup>) {
+    let mut overall = Accuracy::default();
+    let mut by_type: BTreeMap<String, Accuracy> = BTreeMap::new();
+    let mut by_book: BTreeMap<String, Accuracy> = BTreeMap::new();
+    let mut by_chapter: BTreeMap<String, Accuracy> = BTreeMap::new();
+    for (event, question) in answers {
+        overall.add(event);
+        by_type.entry(question.type_.clone()).or_default().add(event);
+        by_book.entry(question.book.clone()).or_default().add(event);
+        by_chapter.entry(format!["{} {}", question.book, question.chapter]).or_default().add(event);
+    }
+    (overall, groups(by_type), groups(by_book), groups(by_chapter))
+}
+
+fn read_team_ids_of_quizzer(db: &mut database::Connection, user_id: Uuid) -> QueryResult<HashSet<Uuid>> {
+    use crate::schema::teams::dsl::*;
+    let ids: Vec<Uuid> = teams
+        .filter(
+            quizzer_one_id.eq(user_id)
+                .or(quizzer_two_id.eq(user_id))
+                .or(quizzer_three_id.eq(user_id))
+                .or(quizzer_four_id.eq(user_id))
+                .or(quizzer_five_id.eq(user_id))
+                .or(quizzer_six_id.eq(user_id))
+        )
+        .select(teamid)
+        .load::<Uuid>(db)?;
+    Ok(ids.into_iter().collect())
+}
+
+/// Whether `quizzer_id` is on a team in the tournament.
+pub fn is_in_tournament(db: &mut database::Connection, quizzer_id: Uuid, tournament_id: Uuid) -> QueryResult<bool> {
+    use crate::schema::{divisions, teams};
+    let team_ids: Vec<Uuid> = read_team_ids_of_quizzer(db, quizzer_id)?.into_iter().collect();
+    let found = teams::table
+        .inner_join(divisions::table.on(divisions::did.eq(teams::did)))
+        .filter(teams::teamid.eq_any(&team_ids))
+        .filter(divisions::tid.eq(tournament_id))
+        .count()
+        .get_result::<i64>(db)?;
+    Ok(found > 0)
+}
+
+/// Whether `viewer_id` coaches `quizzer_id`, on a team or through a roster.
+pub fn is_coach_of(db: &mut database::Connection, viewer_id: Uuid, quizzer_id: Uuid) -> QueryResult<bool> {
+    use crate::schema::{rosters_coaches, rosters_quizzers, teams};
+    let team_ids: Vec<Uuid> = read_team_ids_of_quizzer(db, quizzer_id)?.into_iter().collect();
+    let coaches_team = teams::table
+        .filter(teams::teamid.eq_any(&team_ids))
+        .filter(teams::coachid.eq(viewer_id))
+        .count()
+        .get_result::<i64>(db)? > 0;
+    if coaches_team {
+        return Ok(true);
+    }
+    let coaches_roster = rosters_coaches::table
+        .inner_join(rosters_quizzers::table.on(rosters_quizzers::rosterid.eq(rosters_coaches::rosterid)))
+        .filter(rosters_coaches::coachid.eq(viewer_id))
+        .filter(rosters_quizzers::quizzerid.eq(quizzer_id))
+        .count()
+        .get_result::<i64>(db)? > 0;
+    Ok(coaches_roster)
+}
+
+pub fn read(db: &mut database::Connection, quizzer_id: Uuid, params: &QuizzerStrengthParams) -> QueryResult<QuizzerStrengths> {
+    use crate::schema::{game_questions, games, questionsandanswers};
+
+    let user = models::user::read(db, quizzer_id)?;
+    let names = names_of_user(&user);
+    let team_ids = read_team_ids_of_quizzer(db, quizzer_id)?;
+    let team_id_list: Vec<Uuid> = team_ids.iter().copied().collect();
+
+    let mut query = games::table
+        .filter(games::ignore.eq(false))
+        .filter(
+            games::leftteamid.eq_any(&team_id_list)
+                .or(games::rightteamid.eq_any(&team_id_list))
+                .or(games::centerteamid.eq_any(&team_id_list))
+        )
+        .into_boxed();
+    if let Some(tournament) = params.tournament_id {
+        query = query.filter(games::tournamentid.eq(tournament));
+    }
+    let played: Vec<Game> = query.load::<Game>(db)?;
+    let game_ids: Vec<Uuid> = played.iter().map(|g| g.gid).collect();
+
+    let mut linked = game_questions::table
+        .inner_join(questionsandanswers::table)
+        .filter(game_questions::gid.eq_any(&game_ids))
+        .select((game_questions::gid, game_questions::question, Question::as_select()))
+        .into_boxed();
+    if let Some(season) = params.quiz_season {
+        linked = linked.filter(questionsandanswers::quiz_season.eq(season));
+    }
+    let questions: HashMap<(Uuid, i32), Question> = linked
+        .load::<(Uuid, i32, Question)>(db)?
+        .into_iter()
+        .map(|(game_id, number, question)| ((game_id, number), question))
+        .collect();
+
+    let seats: HashMap<Uuid, Vec<i32>> = played.iter().map(|g| (g.gid, seats_of_teams(g, &team_ids))).collect();
+    let events = models::gameevent::read_all_gameevents_of_games(db, &game_ids)?;
+    let mut answered_games: HashSet<Uuid> = HashSet::new();
+    let answers: Vec<(&str, &Question)> = events
+        .iter()
+        .filter(|e| ANSWER_EVENTS.contains(&e.event.as_str()))
+        .filter(|e| seats.get(&e.gid).is_some_and(|s| s.contains(&e.team)))
+        .filter(|e| names.contains(&normalize_name(&e.name)))
+        .filter_map(|e| {
+            let question = questions.get(&(e.gid, e.question))?;
+            answered_games.insert(e.gid);
+            Some((e.event.as_str(), question))
+        })
+        .collect();
+
+    let (overall, by_type, by_book, by_chapter) = tally(&answers);
+    Ok(QuizzerStrengths {
+        quizzerid: user.id,
+        quizzer_name: user_name(&user),
+        tournament_id: params.tournament_id,
+        quiz_season: params.quiz_season,
+        games: answered_games.len() as i64,
+        overall,
+        by_type,
+        by_book,
+        by_chapter,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn tally_works() {
+        let question = |type_: &str, chapter: i32| Question {
+            id: Uuid::new_v4(),
+            competition_lvl: 1,
+            quiz_season: 1,
+            type_: type_.to_string(),
+            question: "Question?".to_string(),
+            answer: "Answer".to_string(),
+            book: "John".to_string(),
+            chapter,
+            verses: "1".to_string(),
+            beginning_verse: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            was_imported_from_ui: None,
+            is_quizstuff_authored_question: None,
+        };
+        let finish_verse = question("F", 1);
+        let general = question("G", 3);
+        let (overall, by_type, _, by_chapter) = tally(&[("TC", &finish_verse), ("TE", &finish_verse), ("BC", &general), ("TC", &general), ("NJ", &general)]);
+        assert_eq!((overall.tossups_correct, overall.tossup_errors, overall.bonuses_correct), (2, 1, 1));
+        assert_eq!(overall.accuracy, Some(0.75));
+        assert_eq!(by_type.iter().map(|g| (g.key.as_str(), g.accuracy.accuracy)).collect::<Vec<_>>(), vec![("F", Some(0.5)), ("G", Some(1.0))]);
+        assert_eq!(by_chapter.iter().map(|g| (g.key.as_str(), g.accuracy.tossup_accuracy)).collect::<Vec<_>>(), vec![("John 1", Some(0.5)), ("John 3", Some(1.0))]);
+        assert_eq!(by_chapter[1].accuracy.bonus_accuracy, Some(1.0));
+    }
+
+    #[test]
+    fn seats_of_teams_works() {
+        let game = Game {
+            gid: Uuid::new_v4(),
+            org: String::new(),
+            tournamentid: Uuid::new_v4(),
+            divisionid: Uuid::new_v4(),
+            roomid: Uuid::new_v4(),
+            roundid: Uuid::new_v4(),
+            ignore: false,
+            ruleset: String::new(),
+            leftteamid: Uuid::new_v4(),
+            centerteamid: None,
+            rightteamid: Uuid::new_v4(),
+            quizmasterid: Uuid::new_v4(),
+            contentjudgeid: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            clientkey: String::new(),
+            actual_start_time: None,
+            actual_end_time: None,
+            start_offset_minutes: 0,
+        };
+
+        // with two teams the right team only sits in seat 1
+        assert_eq!(seats_of_teams(&game, &[game.rightteamid].into_iter().collect()), vec![1]);
+        assert_eq!(seats_of_teams(&game, &[game.leftteamid, game.rightteamid].into_iter().collect()), vec![0, 1]);
+        let three = Game { centerteamid: Some(Uuid::new_v4()), ..game.clone() };
+        assert_eq!(seats_of_teams(&three, &[three.rightteamid].into_iter().collect()), vec![2]);
+    }
+}
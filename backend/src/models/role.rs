@@ -15,6 +15,7 @@ pub enum AppRole {
     Member,
     TournamentManager,  // AKA, Tournament Owner/Creator
     TournamentAdmin,
+    QuestionWriter,
     SuperUser,
 }
 
@@ -24,6 +25,7 @@ impl AppRole {
             AppRole::Member            => "member",
             AppRole::TournamentManager => "tournament_manager",
             AppRole::TournamentAdmin   => "tournament_admin",
+            AppRole::QuestionWriter    => "question_writer",
             AppRole::SuperUser         => "super_user",
         }
     }
@@ -33,6 +35,7 @@ impl AppRole {
             AppRole::Member            => "View all resources; no write access",
             AppRole::TournamentManager => "Create, update, and delete tournaments (assign alongside member)",
             AppRole::TournamentAdmin   => "Tournament Manager except for creating Tournaments",
+            AppRole::QuestionWriter    => "Write and edit the question bank, and see its answers (assign alongside member)",
             AppRole::SuperUser         => "Unrestricted access to all resources and actions",
         }
     }
@@ -96,26 +96,6 @@ pub struct Round {
     pub scheduled_start_time: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-    pub scheduled_question_one_id: Option<Uuid>,
-    pub scheduled_question_two_id: Option<Uuid>,
-    pub scheduled_question_three_id: Option<Uuid>,
-    pub scheduled_question_four_id: Option<Uuid>,
-    pub scheduled_question_five_id: Option<Uuid>,
-    pub scheduled_question_six_id: Option<Uuid>,
-    pub scheduled_question_seven_id: Option<Uuid>,
-    pub scheduled_question_eight_id: Option<Uuid>,
-    pub scheduled_question_nine_id: Option<Uuid>,
-    pub scheduled_question_ten_id: Option<Uuid>,
-    pub scheduled_question_eleven_id: Option<Uuid>,
-    pub scheduled_question_twelve_id: Option<Uuid>,
-    pub scheduled_question_thirteen_id: Option<Uuid>,
-    pub scheduled_question_fourteen_id: Option<Uuid>,
-    pub scheduled_question_fifteen_id: Option<Uuid>,
-    pub scheduled_question_sixteen_id: Option<Uuid>,
-    pub scheduled_question_seventeen_id: Option<Uuid>,
-    pub scheduled_question_eighteen_id: Option<Uuid>,
-    pub scheduled_question_nineteen_id: Option<Uuid>,
-    pub scheduled_question_twenty_id: Option<Uuid>,
     pub name: String,
 }
 
@@ -159,6 +139,17 @@ pub fn read(db: &mut database::Connection, item_id: Uuid) -> QueryResult<Round>
     rounds.filter(roundid.eq(item_id)).first::<Round>(db)
 }
 
+/// Whether the user is the quizmaster or content judge of one of the round's games.
+pub fn is_official(db: &mut database::Connection, round_id: Uuid, user_id: Uuid) -> bool {
+    use crate::schema::games::dsl::*;
+    games
+        .filter(roundid.eq(round_id))
+        .filter(quizmasterid.eq(user_id).or(contentjudgeid.eq(user_id)))
+        .select(gid)
+        .first::<Uuid>(db)
+        .is_ok()
+}
+
 pub fn read_all(db: &mut database::Connection, pagination: &PaginationParams) -> QueryResult<Vec<Round>> {
     use crate::schema::rounds::dsl::*;
 
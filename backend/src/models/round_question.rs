@@ -0,0 +1,97 @@
+use crate::database;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// The questions of a round, in the order they're read. Regular questions make up the round;
+// alternates replace a question that's thrown out, and overtime questions break a tie.
+
+/// What a question is for in its round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundQuestionRole {
+    Regular,
+    Alternate,
+    Overtime,
+}
+
+impl RoundQuestionRole {
+    pub const ALL: [RoundQuestionRole; 3] = [RoundQuestionRole::Regular, RoundQuestionRole::Alternate, RoundQuestionRole::Overtime];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundQuestionRole::Regular   => "regular",
+            RoundQuestionRole::Alternate => "alternate",
+            RoundQuestionRole::Overtime  => "overtime",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::round_questions)]
+#[diesel(primary_key(roundid, role, position))]
+pub struct RoundQuestion {
+    pub roundid: Uuid,
+    pub role: String,                           // regular, alternate or overtime
+    pub position: i32,                          // from 1, in the order read
+    pub questionid: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable)]
+#[diesel(table_name = crate::schema::round_questions)]
+pub struct NewRoundQuestion {
+    pub roundid: Uuid,
+    pub role: String,
+    pub position: i32,
+    pub questionid: Uuid,
+}
+
+pub fn read_all_of_round(db: &mut database::Connection, round_id: Uuid) -> QueryResult<Vec<RoundQuestion>> {
+    read_all_of_rounds(db, &[round_id])
+}
+
+pub fn read_all_of_rounds(db: &mut database::Connection, round_ids: &[Uuid]) -> QueryResult<Vec<RoundQuestion>> {
+    use crate::schema::round_questions::dsl::*;
+    round_questions
+        .filter(roundid.eq_any(round_ids))
+        .order((roundid.asc(), role.desc(), position.asc()))
+        .load::<RoundQuestion>(db)
+}
+
+/// The ids of the round's questions in one role, in order.
+pub fn read_question_ids(db: &mut database::Connection, round_id: Uuid, of_role: RoundQuestionRole) -> QueryResult<Vec<Uuid>> {
+    use crate::schema::round_questions::dsl::*;
+    round_questions
+        .filter(roundid.eq(round_id))
+        .filter(role.eq(of_role.as_str()))
+        .order(position.asc())
+        .select(questionid)
+        .load::<Uuid>(db)
+}
+
+/// Replaces all the round's questions with `questions`, numbering each role from 1.
+pub fn replace_all_of_round(
+    db: &mut database::Connection,
+    round_id: Uuid,
+    questions: &[(RoundQuestionRole, Vec<Uuid>)],
+) -> QueryResult<Vec<RoundQuestion>> {
+    use crate::schema::round_questions::dsl::*;
+    let new_questions: Vec<NewRoundQuestion> = questions
+        .iter()
+        .flat_map(|(of_role, ids)| ids.iter().enumerate().map(move |(idx, id)| NewRoundQuestion {
+            roundid: round_id,
+            role: of_role.as_str().to_string(),
+            position: idx as i32 + 1,
+            questionid: *id,
+        }))
+        .collect();
+
+    db.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(round_questions.filter(roundid.eq(round_id))).execute(conn)?;
+        diesel::insert_into(round_questions).values(&new_questions).execute(conn)?;
+        read_all_of_round(conn, round_id)
+    })
+}
@@ -0,0 +1,570 @@
+use std::collections::{BTreeSet, HashSet};
+use crate::database;
+use crate::models::{self, common::PaginationParams, game::{Game, GameBuilder}, room::Room, round::Round, team::Team};
+use crate::models::common::invalid;
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RoundRobinRequest {
+    pub team_size: i32,                      // 2 or 3 teams per game
+    pub quizmasterid: Option<Uuid>,          // used for rooms that don't have their own quizmaster
+    pub room_ids: Option<Vec<Uuid>>,         // defaults to every room of the tournament
+    pub round_ids: Option<Vec<Uuid>>,        // defaults to every round of the division
+    pub preview: Option<bool>,               // defaults to true: nothing is written
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ScheduledGame {
+    pub roundid: Uuid,
+    pub round_name: String,
+    pub roomid: Uuid,
+    pub room_name: String,
+    pub leftteamid: Uuid,
+    pub left_team: String,
+    pub centerteamid: Option<Uuid>,
+    pub center_team: Option<String>,
+    pub rightteamid: Uuid,
+    pub right_team: String,
+    pub quizmasterid: Option<Uuid>,
+    pub contentjudgeid: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TeamScheduleSummary {
+    pub teamid: Uuid,
+    pub name: String,
+    pub games: i32,
+    pub byes: i32,
+    pub left: i32,
+    pub center: i32,
+    pub right: i32,
+    pub back_to_back: i32,   // times the team plays two rounds in a row
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RoundRobinSchedule {
+    pub did: Uuid,
+    pub team_size: i32,
+    pub preview: bool,
+    pub rounds_used: i32,
+    pub games: Vec<ScheduledGame>,
+    pub teams: Vec<TeamScheduleSummary>,
+    pub unscheduled: Vec<Vec<String>>,    // matchups that did not fit into the rounds and rooms given
+    pub warnings: Vec<String>,
+    pub created: Vec<Game>,               // empty in preview mode
+}
+
+// A planned game refers to teams by index; `teams` is in seat order (left, [center,] right).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedGame {
+    pub round: usize,
+    pub slot: usize,       // which of the round's free rooms
+    pub teams: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub games: Vec<PlannedGame>,
+    pub unscheduled: Vec<Vec<usize>>,
+    pub unmet_pairs: usize,
+}
+
+/// Every pairing exactly once, grouped into slots in which each team plays at most once (the
+/// circle method). With an odd number of teams one team sits out each slot.
+pub fn circle_matchups(team_count: usize) -> Vec<(usize, usize)> {
+    let mut ids: Vec<Option<usize>> = (0..team_count).map(Some).collect();
+    if team_count % 2 == 1 {
+        ids.push(None);
+    }
+    let n = ids.len();
+    let mut matchups = vec![];
+    for _ in 0..n.saturating_sub(1) {
+        for i in 0..n / 2 {
+            if let (Some(a), Some(b)) = (ids[i], ids[n - 1 - i]) {
+                matchups.push((a.min(b), a.max(b)));
+            }
+        }
+        // keep the first entry fixed and rotate the rest
+        let last = ids.pop().unwrap();
+        ids.insert(1, last);
+    }
+    matchups
+}
+
+struct Tally {
+    games: Vec<usize>,
+    last_round: Vec<Option<usize>>,
+}
+
+impl Tally {
+    fn new(team_count: usize) -> Self {
+        Tally { games: vec![0; team_count], last_round: vec![None; team_count] }
+    }
+    fn played_previous(&self, team: usize, round: usize) -> bool {
+        round > 0 && self.last_round[team] == Some(round - 1)
+    }
+    fn record(&mut self, teams: &[usize], round: usize) {
+        for team in teams.iter() {
+            self.games[*team] += 1;
+            self.last_round[*team] = Some(round);
+        }
+    }
+}
+
+// Fills each round from the queue of matchups. Teams with fewer games go first, which keeps byes
+// even, then matchups whose teams sat out the previous round, then queue order. Teams in
+// `taken[r]` already have a game in round `r`.
+fn pack_two_team_games(team_count: usize, capacities: &[usize], taken: &[HashSet<usize>]) -> Plan {
+    let mut remaining: Vec<(usize, usize)> = circle_matchups(team_count);
+    let mut tally = Tally::new(team_count);
+    let mut games = vec![];
+    for (round, capacity) in capacities.iter().enumerate() {
+        if remaining.is_empty() {
+            break;
+        }
+        let mut order: Vec<usize> = (0..remaining.len()).collect();
+        order.sort_by_key(|&idx| {
+            let (a, b) = remaining[idx];
+            let back_to_back = tally.played_previous(a, round) as usize + tally.played_previous(b, round) as usize;
+            (tally.games[a] + tally.games[b], back_to_back, idx)
+        });
+        let mut busy = taken[round].clone();
+        let mut picked = vec![];
+        for idx in order {
+            if picked.len() >= *capacity {
+                break;
+            }
+            let (a, b) = remaining[idx];
+            if busy.contains(&a) || busy.contains(&b) {
+                continue;
+            }
+            busy.insert(a);
+            busy.insert(b);
+            picked.push(idx);
+        }
+        picked.sort();
+        for (slot, idx) in picked.iter().enumerate() {
+            let (a, b) = remaining[*idx];
+            tally.record(&[a, b], round);
+            games.push(PlannedGame { round, slot, teams: vec![a, b] });
+        }
+        for idx in picked.iter().rev() {
+            remaining.remove(*idx);
+        }
+    }
+    let unmet_pairs = remaining.len();
+    Plan { games, unscheduled: remaining.into_iter().map(|(a, b)| vec![a, b]).collect(), unmet_pairs }
+}
+
+fn all_pairs_met(meetings: &[Vec<usize>]) -> bool {
+    let n = meetings.len();
+    (0..n).all(|a| (a + 1..n).all(|b| meetings[a][b] > 0))
+}
+
+// Three teams can't all meet each other exactly once for most team counts, so rounds are filled
+// greedily: teams with the fewest games play, and each game is the trio with the fewest previous
+// meetings. It stops once every pair has met and everyone has played the same number of games.
+// Teams in `taken[r]` already have a game in round `r` and sit it out.
+fn plan_three_team_games(team_count: usize, capacities: &[usize], taken: &[HashSet<usize>]) -> Plan {
+    let mut meetings = vec![vec![0usize; team_count]; team_count];
+    let mut tally = Tally::new(team_count);
+    let mut games = vec![];
+    for (round, capacity) in capacities.iter().enumerate() {
+        let balanced = tally.games.iter().all(|g| *g == tally.games[0]);
+        if all_pairs_met(&meetings) && balanced {
+            break;
+        }
+        let mut candidates: Vec<usize> = (0..team_count).filter(|t| !taken[round].contains(t)).collect();
+        let game_count = (*capacity).min(candidates.len() / 3);
+        candidates.sort_by_key(|&t| (tally.games[t], tally.played_previous(t, round), t));
+        let mut pool: Vec<usize> = candidates.into_iter().take(game_count * 3).collect();
+
+        let mut slot = 0;
+        while pool.len() >= 3 {
+            // seat the most-met team first, since it has the fewest fresh opponents left
+            pool.sort_by_key(|&t| (std::cmp::Reverse(meetings[t].iter().filter(|m| **m > 0).count()), t));
+            let first = pool.remove(0);
+            let mut best: Option<(usize, usize, usize)> = None;
+            for i in 0..pool.len() {
+                for j in i + 1..pool.len() {
+                    let (b, c) = (pool[i], pool[j]);
+                    let cost = meetings[first][b] + meetings[first][c] + meetings[b][c];
+                    if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                        best = Some((cost, i, j));
+                    }
+                }
+            }
+            let (_, i, j) = best.unwrap();
+            let (b, c) = (pool[i], pool[j]);
+            pool.remove(j);
+            pool.remove(i);
+            let trio = [first, b, c];
+            for x in trio.iter() {
+                for y in trio.iter() {
+                    if x != y {
+                        meetings[*x][*y] += 1;
+                    }
+                }
+            }
+            tally.record(&trio, round);
+            games.push(PlannedGame { round, slot, teams: trio.to_vec() });
+            slot += 1;
+        }
+    }
+    let unmet_pairs = (0..team_count).map(|a| (a + 1..team_count).filter(|b| meetings[a][*b] == 0).count()).sum();
+    Plan { games, unscheduled: vec![], unmet_pairs }
+}
+
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+    let mut result = vec![];
+    for i in 0..items.len() {
+        let mut rest = items.to_vec();
+        let first = rest.remove(i);
+        for mut perm in permutations(&rest) {
+            perm.insert(0, first);
+            result.push(perm);
+        }
+    }
+    result
+}
+
+fn seats_of(team_size: usize) -> &'static [usize] {
+    if team_size == 2 { &[0, 2] } else { &[0, 1, 2] }
+}
+
+fn seat_counts_of(games: &[PlannedGame], team_count: usize, seats: &[usize]) -> Vec<[usize; 3]> {
+    let mut seat_counts = vec![[0usize; 3]; team_count];
+    for game in games.iter() {
+        for (team, seat) in game.teams.iter().zip(seats.iter()) {
+            seat_counts[*team][*seat] += 1;
+        }
+    }
+    seat_counts
+}
+
+/// Puts each game's teams in the seats they have sat in least, so no team sits in one seat more
+/// than once more than in another. Two-team games use the left and right seats only.
+pub fn balance_seats(games: &mut [PlannedGame], team_count: usize) {
+    let Some(team_size) = games.first().map(|g| g.teams.len()) else { return };
+    let seats = seats_of(team_size);
+    let positions = |seat: usize| seats.iter().position(|s| *s == seat).unwrap();
+
+    // a greedy first pass gets most teams close
+    let mut seat_counts = vec![[0usize; 3]; team_count];
+    for game in games.iter_mut() {
+        game.teams = permutations(&game.teams)
+            .into_iter()
+            .min_by_key(|perm| perm.iter().zip(seats.iter()).map(|(t, s)| seat_counts[*t][*s]).sum::<usize>())
+            .unwrap();
+        for (team, seat) in game.teams.iter().zip(seats.iter()) {
+            seat_counts[*team][*seat] += 1;
+        }
+    }
+
+    // then, while a team has two more games in seat `a` than in seat `b`, follow a path that
+    // leaves each team by an `a` seat and enters the next by a `b` seat, and swap the two seats in
+    // every game along it. Teams inside the path keep their counts; the first one evens up and
+    // the last one was short of `a`, so the imbalance shrinks every time.
+    loop {
+        let uneven = (0..team_count).find_map(|t| {
+            seats.iter().find_map(|a| {
+                seats.iter().find(|b| seat_counts[t][*a] >= seat_counts[t][**b] + 2).map(|b| (t, *a, *b))
+            })
+        });
+        let Some((start, a, b)) = uneven else { break };
+        let mut used = vec![false; games.len()];
+        let mut path = vec![];
+        let mut team = start;
+        while let Some(g) = (0..games.len()).find(|g| !used[*g] && games[*g].teams[positions(a)] == team) {
+            used[g] = true;
+            path.push(g);
+            team = games[g].teams[positions(b)];
+        }
+        for g in path {
+            games[g].teams.swap(positions(a), positions(b));
+        }
+        seat_counts = seat_counts_of(games, team_count, seats);
+    }
+}
+
+/// Plans a round robin for `team_count` teams where round `r` has `capacities[r]` free rooms.
+pub fn plan_round_robin(team_count: usize, team_size: usize, capacities: &[usize]) -> Plan {
+    plan_round_robin_around(team_count, team_size, capacities, &vec![HashSet::new(); capacities.len()])
+}
+
+/// Plans a round robin as `plan_round_robin` does, around the teams in `taken[r]`, which already
+/// have a game in round `r`.
+pub fn plan_round_robin_around(team_count: usize, team_size: usize, capacities: &[usize], taken: &[HashSet<usize>]) -> Plan {
+    let mut plan = if team_size == 3 {
+        plan_three_team_games(team_count, capacities, taken)
+    } else {
+        pack_two_team_games(team_count, capacities, taken)
+    };
+    plan.games.sort_by_key(|g| (g.round, g.slot));
+    balance_seats(&mut plan.games, team_count);
+    plan
+}
+
+pub(crate) fn all_pages() -> PaginationParams {
+    PaginationParams { page: 0, page_size: PaginationParams::MAX_PAGE_SIZE as i64 }
+}
+
+pub(crate) fn occupied_rooms_of_round(db: &mut database::Connection, round_id: Uuid) -> QueryResult<HashSet<Uuid>> {
+    use crate::schema::games::dsl::*;
+    let room_ids: Vec<Uuid> = games.filter(roundid.eq(round_id)).select(roomid).load::<Uuid>(db)?;
+    Ok(room_ids.into_iter().collect())
+}
+
+/// The teams that already have a game in the round.
+pub(crate) fn busy_teams_of_round(db: &mut database::Connection, round_id: Uuid) -> QueryResult<HashSet<Uuid>> {
+    use crate::schema::games::dsl::*;
+    let seated: Vec<(Uuid, Option<Uuid>, Uuid)> = games
+        .filter(roundid.eq(round_id))
+        .select((leftteamid, centerteamid, rightteamid))
+        .load(db)?;
+    Ok(seated
+        .into_iter()
+        .flat_map(|(left, center, right)| [Some(left), center, Some(right)])
+        .flatten()
+        .collect())
+}
+
+/// Builds a round robin for a division from its teams, the tournament's rooms and the division's
+/// rounds. Rooms and teams that already have a game in a round are left alone. Unless `preview` is
+/// false, nothing is written.
+pub fn generate(db: &mut database::Connection, division_id: Uuid, request: &RoundRobinRequest) -> QueryResult<RoundRobinSchedule> {
+    let division = models::division::read(db, division_id)?;
+    if request.team_size != 2 && request.team_size != 3 {
+        return Err(invalid(format!["team_size must be 2 or 3, not {}", request.team_size]));
+    }
+    let team_size = request.team_size as usize;
+    let preview = request.preview.unwrap_or(true);
+
+    let teams: Vec<Team> = models::team::read_all_teams_of_division(db, division_id, &all_pages())?;
+    if teams.len() < team_size {
+        return Err(invalid(format!["Division has {} teams; at least {} are needed", teams.len(), team_size]));
+    }
+
+    let mut rooms: Vec<Room> = models::room::read_all_rooms_of_tournament(db, division.tid, &all_pages())?;
+    if let Some(room_ids) = &request.room_ids {
+        rooms.retain(|r| room_ids.contains(&r.roomid));
+    }
+    let mut rounds: Vec<Round> = models::round::read_all_rounds_of_division(db, division_id, &all_pages())?;
+    if let Some(round_ids) = &request.round_ids {
+        rounds.retain(|r| round_ids.contains(&r.roundid));
+    }
+    if rooms.is_empty() || rounds.is_empty() {
+        return Err(invalid("At least one room and one round are needed".to_string()));
+    }
+
+    let mut free_rooms: Vec<Vec<&Room>> = vec![];
+    let mut taken: Vec<HashSet<usize>> = vec![];
+    for round in rounds.iter() {
+        let occupied = occupied_rooms_of_round(db, round.roundid)?;
+        free_rooms.push(rooms.iter().filter(|r| !occupied.contains(&r.roomid)).collect());
+        let busy = busy_teams_of_round(db, round.roundid)?;
+        taken.push(teams.iter().enumerate().filter(|(_, t)| busy.contains(&t.teamid)).map(|(idx, _)| idx).collect());
+    }
+    let capacities: Vec<usize> = free_rooms.iter().map(|r| r.len().min(teams.len() / team_size)).collect();
+
+    let plan = plan_round_robin_around(teams.len(), team_size, &capacities, &taken);
+
+    let mut warnings = vec![];
+    if !plan.unscheduled.is_empty() {
+        warnings.push(format!["{} games did not fit; add rounds or rooms", plan.unscheduled.len()]);
+    } else if plan.unmet_pairs > 0 {
+        warnings.push(format!["{} pairs of teams never meet; add rounds or rooms", plan.unmet_pairs]);
+    }
+
+    let mut games = vec![];
+    for planned in plan.games.iter() {
+        let round = &rounds[planned.round];
+        let room = free_rooms[planned.round][planned.slot];
+        let quizmasterid = room.quizmaster_id.or(request.quizmasterid);
+        let (left, right) = (&teams[planned.teams[0]], &teams[*planned.teams.last().unwrap()]);
+        let center = if planned.teams.len() == 3 { Some(&teams[planned.teams[1]]) } else { None };
+        games.push(ScheduledGame {
+            roundid: round.roundid,
+            round_name: round.name.clone(),
+            roomid: room.roomid,
+            room_name: room.name.clone(),
+            leftteamid: left.teamid,
+            left_team: left.name.clone(),
+            centerteamid: center.map(|t| t.teamid),
+            center_team: center.map(|t| t.name.clone()),
+            rightteamid: right.teamid,
+            right_team: right.name.clone(),
+            quizmasterid,
+            contentjudgeid: room.contentjudge_id,
+        });
+    }
+    let rooms_without_quizmaster: Vec<String> = games
+        .iter()
+        .filter(|g| g.quizmasterid.is_none())
+        .map(|g| g.room_name.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if !rooms_without_quizmaster.is_empty() {
+        warnings.push(format!["No quizmaster for room(s) {}; pass quizmasterid", rooms_without_quizmaster.join(", ")]);
+    }
+
+    let seat_counts = seat_counts_of(&plan.games, teams.len(), seats_of(team_size));
+    let rounds_used = plan.games.iter().map(|g| g.round + 1).max().unwrap_or(0);
+    let summaries = teams
+        .iter()
+        .enumerate()
+        .map(|(idx, team)| {
+            let mut rounds_played: Vec<usize> = plan.games.iter().filter(|g| g.teams.contains(&idx)).map(|g| g.round).collect();
+            rounds_played.sort();
+            let seats = &seat_counts[idx];
+            TeamScheduleSummary {
+                teamid: team.teamid,
+                name: team.name.clone(),
+                games: rounds_played.len() as i32,
+                byes: (rounds_used - rounds_played.len()) as i32,
+                left: seats[0] as i32,
+                center: seats[1] as i32,
+                right: seats[2] as i32,
+                back_to_back: rounds_played.windows(2).filter(|w| w[1] == w[0] + 1).count() as i32,
+            }
+        })
+        .collect();
+
+    let unscheduled = plan.unscheduled
+        .iter()
+        .map(|matchup| matchup.iter().map(|t| teams[*t].name.clone()).collect())
+        .collect();
+
+    let mut created = vec![];
+    if !preview {
+        if !plan.unscheduled.is_empty() || !rooms_without_quizmaster.is_empty() {
+            return Err(invalid(format!["The schedule can't be written: {}", warnings.join("; ")]));
+        }
+        created = db.transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut created = vec![];
+            for game in games.iter() {
+                let new_game = GameBuilder::new_default(game.roomid, game.roundid)
+                    .set_tournamentid(Some(division.tid))
+                    .set_divisionid(Some(division.did))
+                    .set_leftteamid(game.leftteamid)
+                    .set_centerteamid(game.centerteamid)
+                    .set_rightteamid(game.rightteamid)
+                    .set_quizmasterid(game.quizmasterid.unwrap())
+                    .set_contentjudgeid(game.contentjudgeid)
+                    .build()
+                    .map_err(|e| invalid(e.join(", ")))?;
+                created.push(models::game::create(conn, &new_game)?);
+            }
+            Ok(created)
+        })?;
+    }
+
+    Ok(RoundRobinSchedule {
+        did: division.did,
+        team_size: request.team_size,
+        preview,
+        rounds_used: rounds_used as i32,
+        games,
+        teams: summaries,
+        unscheduled,
+        warnings,
+        created,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs_of(plan: &Plan) -> Vec<(usize, usize)> {
+        let mut pairs = vec![];
+        for game in plan.games.iter() {
+            for i in 0..game.teams.len() {
+                for j in i + 1..game.teams.len() {
+                    let (a, b) = (game.teams[i], game.teams[j]);
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        pairs.sort();
+        pairs
+    }
+
+    fn games_per_team(plan: &Plan, team_count: usize) -> Vec<usize> {
+        (0..team_count).map(|t| plan.games.iter().filter(|g| g.teams.contains(&t)).count()).collect()
+    }
+
+    #[test]
+    fn two_team_round_robin_meets_everyone_once() {
+        for team_count in 2..=9 {
+            let rounds = if team_count % 2 == 0 { team_count - 1 } else { team_count };
+            let plan = plan_round_robin(team_count, 2, &vec![team_count / 2; rounds]);
+            assert!(plan.unscheduled.is_empty(), "{} teams", team_count);
+            let pairs = pairs_of(&plan);
+            let expected: Vec<(usize, usize)> = (0..team_count).flat_map(|a| (a + 1..team_count).map(move |b| (a, b))).collect();
+            assert_eq!(pairs, expected, "{} teams", team_count);
+            // odd team counts: everyone sits out exactly once
+            let byes: Vec<usize> = games_per_team(&plan, team_count).iter().map(|g| rounds - g).collect();
+            assert!(byes.iter().all(|b| *b == byes[0]), "{} teams: {:?}", team_count, byes);
+        }
+    }
+
+    #[test]
+    fn two_team_round_robin_never_double_books_a_round() {
+        let plan = plan_round_robin(8, 2, &[2; 14]);
+        assert!(plan.unscheduled.is_empty());
+        for round in 0..14 {
+            let mut teams: Vec<usize> = plan.games.iter().filter(|g| g.round == round).flat_map(|g| g.teams.clone()).collect();
+            let count = teams.len();
+            teams.sort();
+            teams.dedup();
+            assert_eq!(teams.len(), count);
+        }
+    }
+
+    #[test]
+    fn round_robin_skips_teams_already_playing_in_a_round() {
+        let mut taken = vec![HashSet::new(); 5];
+        taken[0] = HashSet::from([0, 1]);
+        for team_size in [2, 3] {
+            let plan = plan_round_robin_around(6, team_size, &[3; 5], &taken);
+            assert!(plan.games.iter().filter(|g| g.round == 0).all(|g| !g.teams.contains(&0) && !g.teams.contains(&1)), "{} teams per game", team_size);
+        }
+    }
+
+    #[test]
+    fn two_team_round_robin_reports_what_does_not_fit() {
+        let plan = plan_round_robin(6, 2, &[3; 3]);
+        assert_eq!(plan.unscheduled.len(), 6);
+    }
+
+    #[test]
+    fn seats_are_balanced() {
+        let plan = plan_round_robin(6, 2, &[3; 5]);
+        for team in 0..6 {
+            let left = plan.games.iter().filter(|g| g.teams[0] == team).count() as i32;
+            let right = plan.games.iter().filter(|g| g.teams[1] == team).count() as i32;
+            assert!((left - right).abs() <= 1, "team {}: {} left, {} right", team, left, right);
+        }
+    }
+
+    #[test]
+    fn three_team_round_robin_meets_everyone_with_even_byes() {
+        let plan = plan_round_robin(7, 3, &[2; 20]);
+        assert_eq!(plan.unmet_pairs, 0);
+        let games = games_per_team(&plan, 7);
+        assert!(games.iter().all(|g| *g == games[0]), "{:?}", games);
+        for team in 0..7 {
+            let seats: Vec<i32> = (0..3).map(|s| plan.games.iter().filter(|g| g.teams[s] == team).count() as i32).collect();
+            assert!(seats.iter().max().unwrap() - seats.iter().min().unwrap() <= 1, "team {}: {:?}", team, seats);
+        }
+    }
+}
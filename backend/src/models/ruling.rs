@@ -0,0 +1,499 @@
+use std::collections::{HashMap, HashSet};
+use crate::database;
+use crate::models::{self, conflict::user_name, game::{seat_of_team, Game}, gameevent::GameEvent, question::Question};
+use crate::models::common::invalid;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// A content judge's case for a challenge or an appeal. The event stream only records the outcome:
+// 'C-' for a challenge overruled, 'A+' and 'A-' for an appeal accepted or overruled, and a 'DE'
+// erasing the call for a challenge accepted (there's no 'C+'). A decided case is linked to that
+// event, given or found on its question or a later one (an accepted appeal starts a new question
+// number). An accepted case overturned the call on the question.
+
+pub const KIND_CHALLENGE: &str = "challenge";
+pub const KIND_APPEAL: &str = "appeal";
+pub const KINDS: [&str; 2] = [KIND_CHALLENGE, KIND_APPEAL];
+
+pub const RULING_PENDING: &str = "pending";
+pub const RULING_ACCEPTED: &str = "accepted";
+pub const RULING_OVERRULED: &str = "overruled";
+pub const RULINGS: [&str; 3] = [RULING_PENDING, RULING_ACCEPTED, RULING_OVERRULED];
+
+pub const MAX_TEXT: usize = 4000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::rulings)]
+#[diesel(primary_key(rulingid))]
+pub struct Ruling {
+    pub rulingid: Uuid,
+    pub gid: Uuid,
+    pub question: i32,                          // the game's question number, as in gameevents
+    pub questionid: Option<Uuid>,               // the question read, from the question bank
+    pub kind: String,                           // challenge or appeal
+    pub teamid: Uuid,                           // the team that raised it
+    pub argument: String,
+    pub ruling: String,                         // pending, accepted or overruled
+    pub rationale: String,
+    pub event_question: Option<i32>,            // the game event recording the ruling
+    pub event_num: Option<i32>,
+    pub judged_by: Option<Uuid>,                // None once that user is deleted
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable)]
+#[diesel(table_name = crate::schema::rulings)]
+pub struct NewRuling {
+    pub gid: Uuid,
+    pub question: i32,
+    pub questionid: Option<Uuid>,
+    pub kind: String,
+    pub teamid: Uuid,
+    pub argument: String,
+    pub ruling: String,
+    pub rationale: String,
+    pub event_question: Option<i32>,
+    pub event_num: Option<i32>,
+    pub judged_by: Uuid,
+}
+
+// A case as the content judge enters it. The question read defaults to the one recorded for the
+// question number, and the ruling to pending.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RulingRequest {
+    pub question: i32,
+    pub questionid: Option<Uuid>,
+    pub kind: String,
+    pub teamid: Uuid,
+    pub argument: String,
+    pub ruling: Option<String>,
+    pub rationale: Option<String>,
+    pub event_question: Option<i32>,
+    pub event_num: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, AsChangeset, ToSchema)]
+#[diesel(table_name = crate::schema::rulings)]
+pub struct RulingChangeset {
+    pub questionid: Option<Uuid>,
+    pub argument: Option<String>,
+    pub ruling: Option<String>,
+    pub rationale: Option<String>,
+    pub event_question: Option<i32>,
+    pub event_num: Option<i32>,
+}
+
+// A case for review: the ruling with who raised and judged it, the question read, and the events
+// of its question and the one recording the ruling.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct RulingRecord {
+    pub ruling: Ruling,
+    pub team_name: String,
+    pub judged_by_name: String,
+    pub question: Option<Question>,
+    pub events: Vec<GameEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct TeamRulingSummary {
+    pub teamid: Uuid,
+    pub team_name: String,
+    pub challenges: i32,
+    pub challenges_overturned: i32,
+    pub appeals: i32,
+    pub appeals_overturned: i32,
+}
+
+fn validate_fields(argument: Option<&str>, ruling: Option<&str>, rationale: Option<&str>) -> Vec<String> {
+    let mut errors = vec![];
+    if argument.is_some_and(|a| a.trim().is_empty() || a.len() > MAX_TEXT) {
+        errors.push(format!["argument must be between 1 and {} characters", MAX_TEXT]);
+    }
+    if let Some(r) = ruling.filter(|r| !RULINGS.contains(r)) {
+        errors.push(format!["'{}' is not a ruling; expected one of {}", r, RULINGS.join(", ")]);
+    }
+    if rationale.is_some_and(|r| r.len() > MAX_TEXT) {
+        errors.push(format!["rationale must be at most {} characters", MAX_TEXT]);
+    }
+    errors
+}
+
+/// A decided case needs a rationale.
+fn check_decision(ruling: &str, rationale: &str) -> Result<(), String> {
+    if ruling != RULING_PENDING && rationale.trim().is_empty() {
+        return Err(format!["A case {} needs a rationale", ruling]);
+    }
+    Ok(())
+}
+
+/// The event QuizMachine records for a ruling; none while it's pending.
+pub fn event_of_ruling(kind: &str, ruling: &str) -> Option<&'static str> {
+    match (kind, ruling) {
+        (KIND_CHALLENGE, RULING_ACCEPTED) => Some("DE"),
+        (KIND_CHALLENGE, RULING_OVERRULED) => Some("C-"),
+        (KIND_APPEAL, RULING_ACCEPTED) => Some("A+"),
+        (KIND_APPEAL, RULING_OVERRULED) => Some("A-"),
+        _ => None,
+    }
+}
+
+/// The first event on or after `question` recording the ruling that no other case is linked to. A
+/// 'DE' has no team and only erases its own question, so it's matched on the question alone.
+pub fn find_event(events: &[GameEvent], code: &str, question: i32, seat: i32, taken: &HashSet<(i32, i32)>) -> Option<(i32, i32)> {
+    events
+        .iter()
+        .filter(|e| e.event == code && e.question >= question)
+        .filter(|e| if code == "DE" { e.question == question } else { e.team == seat })
+        .filter(|e| !taken.contains(&(e.question, e.eventnum)))
+        .min_by_key(|e| (e.question, e.eventnum))
+        .map(|e| (e.question, e.eventnum))
+}
+
+/// Checks the event a case is linked to, or finds it when none is given.
+fn link_event(
+    db: &mut database::Connection,
+    game: &Game,
+    item: &NewRuling,
+    except: Option<Uuid>,
+) -> QueryResult<(Option<i32>, Option<i32>)> {
+    // a pending case has no event yet
+    let Some(code) = event_of_ruling(&item.kind, &item.ruling) else {
+        return Ok((None, None));
+    };
+    let events = models::gameevent::read_all_gameevents_of_games(db, &[game.gid])?;
+    match (item.event_question, item.event_num) {
+        (Some(q), Some(n)) => match events.iter().find(|e| e.question == q && e.eventnum == n) {
+            Some(e) if e.event == code => Ok((Some(q), Some(n))),
+            Some(e) => Err(invalid(format!["Event {}.{} is a '{}', not the '{}' of this ruling", q, n, e.event, code])),
+            None => Err(invalid(format!["The game has no event {}.{}", q, n])),
+        },
+        (None, None) => {
+            use crate::schema::rulings::dsl::*;
+            let mut others = rulings.filter(gid.eq(game.gid)).into_boxed();
+            if let Some(except) = except {
+                others = others.filter(rulingid.ne(except));
+            }
+            let taken: HashSet<(i32, i32)> = others
+                .select((event_question, event_num))
+                .load::<(Option<i32>, Option<i32>)>(db)?
+                .into_iter()
+                .filter_map(|(q, n)| q.zip(n))
+                .collect();
+            let seat = seat_of_team(game, item.teamid).unwrap_or(-1);
+            Ok(find_event(&events, code, item.question, seat, &taken).unzip())
+        },
+        _ => Err(invalid("event_question and event_num go together".to_string())),
+    }
+}
+
+fn check_item(db: &mut database::Connection, game: &Game, item: &NewRuling) -> QueryResult<()> {
+    let mut errors = validate_fields(Some(&item.argument), Some(&item.ruling), Some(&item.rationale));
+    if !KINDS.contains(&item.kind.as_str()) {
+        errors.push(format!["'{}' is not a kind of case; expected one of {}", item.kind, KINDS.join(", ")]);
+    }
+    if item.question < 1 {
+        errors.push(format!["{} is not a question number", item.question]);
+    }
+    if seat_of_team(game, item.teamid).is_none() {
+        errors.push("The team isn't in this game".to_string());
+    }
+    if let Err(e) = check_decision(&item.ruling, &item.rationale) {
+        errors.push(e);
+    }
+    if !errors.is_empty() {
+        return Err(invalid(errors.join("; ")));
+    }
+    if let Some(question_id) = item.questionid
+        && models::question::read(db, question_id).is_err()
+    {
+        return Err(invalid(format!["Question {} isn't in the question bank", question_id]));
+    }
+    Ok(())
+}
+
+/// Records a case for a game. The question read defaults to the one linked to the question number.
+pub fn create(db: &mut database::Connection, game: &Game, request: &RulingRequest, user_id: Uuid) -> QueryResult<Ruling> {
+    use crate::schema::rulings::dsl::*;
+    let mut item = NewRuling {
+        gid: game.gid,
+        question: request.question,
+        questionid: request.questionid,
+        kind: request.kind.trim().to_lowercase(),
+        teamid: request.teamid,
+        argument: request.argument.trim().to_string(),
+        ruling: request.ruling.as_deref().unwrap_or(RULING_PENDING).trim().to_lowercase(),
+        rationale: request.rationale.as_deref().unwrap_or_default().trim().to_string(),
+        event_question: request.event_question,
+        event_num: request.event_num,
+        judged_by: user_id,
+    };
+    check_item(db, game, &item)?;
+    if item.questionid.is_none() {
+        item.questionid = models::game_question::link(db, game.gid, item.question)?.map(|q| q.questionid);
+    }
+    (item.event_question, item.event_num) = link_event(db, game, &item, None)?;
+    diesel::insert_into(rulings).values(&item).get_result::<Ruling>(db)
+}
+
+/// Changes a case, e.g. to record the ruling once it's made. Its link to the event is checked again.
+pub fn update(db: &mut database::Connection, game: &Game, ruling_id: Uuid, changes: &RulingChangeset, user_id: Uuid) -> QueryResult<Ruling> {
+    use crate::schema::rulings::dsl::*;
+    let existing = rulings.filter(gid.eq(game.gid)).find(ruling_id).first::<Ruling>(db)?;
+    let mut item = NewRuling {
+        gid: existing.gid,
+        question: existing.question,
+        questionid: changes.questionid.or(existing.questionid),
+        kind: existing.kind.clone(),
+        teamid: existing.teamid,
+        argument: changes.argument.as_deref().map(|a| a.trim().to_string()).unwrap_or(existing.argument.clone()),
+        ruling: changes.ruling.as_deref().map(|r| r.trim().to_lowercase()).unwrap_or(existing.ruling.clone()),
+        rationale: changes.rationale.as_deref().map(|r| r.trim().to_string()).unwrap_or(existing.rationale.clone()),
+        event_question: None,
+        event_num: None,
+        judged_by: user_id,
+    };
+    check_item(db, game, &item)?;
+    // a new ruling is linked afresh unless the event is given
+    if changes.event_question.is_some() || changes.event_num.is_some() {
+        (item.event_question, item.event_num) = (changes.event_question, changes.event_num);
+    } else if item.ruling == existing.ruling {
+        (item.event_question, item.event_num) = (existing.event_question, existing.event_num);
+    }
+    (item.event_question, item.event_num) = link_event(db, game, &item, Some(ruling_id))?;
+    diesel::update(rulings.find(ruling_id))
+        .set((
+            questionid.eq(item.questionid),
+            argument.eq(&item.argument),
+            ruling.eq(&item.ruling),
+            rationale.eq(&item.rationale),
+            event_question.eq(item.event_question),
+            event_num.eq(item.event_num),
+            judged_by.eq(user_id),
+            updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result::<Ruling>(db)
+}
+
+pub fn delete(db: &mut database::Connection, game_id: Uuid, ruling_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::rulings::dsl::*;
+    diesel::delete(rulings.filter(gid.eq(game_id)).filter(rulingid.eq(ruling_id))).execute(db)
+}
+
+fn to_records(db: &mut database::Connection, of_games: Vec<Ruling>) -> QueryResult<Vec<RulingRecord>> {
+    let game_ids: Vec<Uuid> = of_games.iter().map(|r| r.gid).collect::<HashSet<_>>().into_iter().collect();
+    let events = models::gameevent::read_all_gameevents_of_games(db, &game_ids)?;
+    let mut team_names: HashMap<Uuid, String> = HashMap::new();
+    let mut judge_names: HashMap<Uuid, String> = HashMap::new();
+    let mut records = vec![];
+    for item in of_games {
+        team_names
+            .entry(item.teamid)
+            .or_insert_with(|| models::team::read(db, item.teamid).map(|t| t.name).unwrap_or_default());
+        let judged_by_name = match item.judged_by {
+            Some(judge) => judge_names
+                .entry(judge)
+                .or_insert_with(|| models::user::read(db, judge).map(|u| user_name(&u)).unwrap_or_default())
+                .clone(),
+            None => String::new(),
+        };
+        let question = match item.questionid {
+            Some(question_id) => models::question::read(db, question_id).ok(),
+            None => None,
+        };
+        let linked = item.event_question.zip(item.event_num);
+        let of_case: Vec<GameEvent> = events
+            .iter()
+            .filter(|e| e.gid == item.gid)
+            .filter(|e| e.question == item.question || Some((e.question, e.eventnum)) == linked)
+            .cloned()
+            .collect();
+        records.push(RulingRecord {
+            team_name: team_names[&item.teamid].clone(),
+            judged_by_name,
+            question,
+            events: of_case,
+            ruling: item,
+        });
+    }
+    Ok(records)
+}
+
+pub fn read_all_of_game(db: &mut database::Connection, game_id: Uuid) -> QueryResult<Vec<RulingRecord>> {
+    use crate::schema::rulings::dsl::*;
+    let of_game = rulings
+        .filter(gid.eq(game_id))
+        .order((question.asc(), created_at.asc()))
+        .load::<Ruling>(db)?;
+    to_records(db, of_game)
+}
+
+pub fn read_all_of_games(db: &mut database::Connection, game_ids: &[Uuid]) -> QueryResult<Vec<Ruling>> {
+    use crate::schema::rulings::dsl::*;
+    rulings
+        .filter(gid.eq_any(game_ids))
+        .order((gid.asc(), question.asc(), created_at.asc()))
+        .load::<Ruling>(db)
+}
+
+/// Every case of a tournament, oldest first, for the tournament's admins to review.
+pub fn read_all_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<RulingRecord>> {
+    use crate::schema::{games, rulings};
+    let of_tournament = rulings::table
+        .inner_join(games::table)
+        .filter(games::tournamentid.eq(tournament_id))
+        .order(rulings::created_at.asc())
+        .select(Ruling::as_select())
+        .load::<Ruling>(db)?;
+    to_records(db, of_tournament)
+}
+
+/// The cases each team raised and how many overturned the call, by team name.
+pub fn summarize(of_games: &[Ruling], team_names: &HashMap<Uuid, String>) -> Vec<TeamRulingSummary> {
+    let mut by_team: HashMap<Uuid, TeamRulingSummary> = HashMap::new();
+    for item in of_games {
+        let summary = by_team.entry(item.teamid).or_insert_with(|| TeamRulingSummary {
+            teamid: item.teamid,
+            team_name: team_names.get(&item.teamid).cloned().unwrap_or_default(),
+            challenges: 0,
+            challenges_overturned: 0,
+            appeals: 0,
+            appeals_overturned: 0,
+        });
+        let overturned = (item.ruling == RULING_ACCEPTED) as i32;
+        if item.kind == KIND_APPEAL {
+            summary.appeals += 1;
+            summary.appeals_overturned += overturned;
+        } else {
+            summary.challenges += 1;
+            summary.challenges_overturned += overturned;
+        }
+    }
+    let mut summaries: Vec<TeamRulingSummary> = by_team.into_values().collect();
+    summaries.sort_by(|a, b| a.team_name.cmp(&b.team_name).then(a.teamid.cmp(&b.teamid)));
+    summaries
+}
+
+pub fn summarize_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<TeamRulingSummary>> {
+    use crate::schema::games::dsl::*;
+    let game_ids: Vec<Uuid> = games.filter(tournamentid.eq(tournament_id)).select(gid).load::<Uuid>(db)?;
+    let of_games = read_all_of_games(db, &game_ids)?;
+    let mut team_names = HashMap::new();
+    for item in of_games.iter() {
+        team_names
+            .entry(item.teamid)
+            .or_insert_with(|| models::team::read(db, item.teamid).map(|t| t.name).unwrap_or_default());
+    }
+    Ok(summarize(&of_games, &team_names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(question: i32, eventnum: i32, code: &str, team: i32) -> GameEvent {
+        GameEvent {
+            gid: Uuid::nil(),
+            question,
+            eventnum,
+            name: String::new(),
+            team,
+            quizzer: 0,
+            event: code.to_string(),
+            parm1: String::new(),
+            parm2: String::new(),
+            clientts: Utc::now(),
+            serverts: Utc::now(),
+            md5digest: String::new(),
+        }
+    }
+
+    #[test]
+    fn find_event_works() {
+        let events = vec![
+            event(3, 0, "TC", 0),
+            event(3, 1, "C-", 2),
+            event(4, 0, "TE", 0),
+            event(4, 1, "C-", 0),
+            event(5, 0, "DE", 0),
+            event(5, 1, "A+", 0),
+        ];
+        let taken = HashSet::new();
+        assert_eq!(find_event(&events, "C-", 3, 2, &taken), Some((3, 1)));
+        assert_eq!(find_event(&events, "C-", 3, 0, &taken), Some((4, 1)));
+        assert_eq!(find_event(&events, "A+", 4, 0, &taken), Some((5, 1)));
+        assert_eq!(find_event(&events, "DE", 4, 0, &taken), None);
+        assert_eq!(find_event(&events, "C-", 3, 2, &[(3, 1)].into_iter().collect()), None);
+        assert_eq!(event_of_ruling(KIND_CHALLENGE, RULING_PENDING), None);
+    }
+
+    #[test]
+    fn seat_of_team_works() {
+        let mut game = Game {
+            gid: Uuid::new_v4(),
+            org: String::new(),
+            tournamentid: Uuid::new_v4(),
+            divisionid: Uuid::new_v4(),
+            roomid: Uuid::new_v4(),
+            roundid: Uuid::new_v4(),
+            ignore: false,
+            ruleset: String::new(),
+            leftteamid: Uuid::new_v4(),
+            centerteamid: None,
+            rightteamid: Uuid::new_v4(),
+            quizmasterid: Uuid::new_v4(),
+            contentjudgeid: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            clientkey: String::new(),
+            actual_start_time: None,
+            actual_end_time: None,
+            start_offset_minutes: 0,
+        };
+
+        // two teams: the right team sits in seat 1
+        assert_eq!(seat_of_team(&game, game.leftteamid), Some(0));
+        assert_eq!(seat_of_team(&game, game.rightteamid), Some(1));
+        assert_eq!(seat_of_team(&game, Uuid::new_v4()), None);
+
+        game.centerteamid = Some(Uuid::new_v4());
+        assert_eq!(seat_of_team(&game, game.centerteamid.unwrap()), Some(1));
+        assert_eq!(seat_of_team(&game, game.rightteamid), Some(2));
+    }
+
+    #[test]
+    fn summarize_works() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let case = |teamid: Uuid, kind: &str, ruling: &str| Ruling {
+            rulingid: Uuid::new_v4(),
+            gid: Uuid::nil(),
+            question: 1,
+            questionid: None,
+            kind: kind.to_string(),
+            teamid,
+            argument: "The answer was complete".to_string(),
+            ruling: ruling.to_string(),
+            rationale: String::new(),
+            event_question: None,
+            event_num: None,
+            judged_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let cases = vec![
+            case(a, KIND_CHALLENGE, RULING_ACCEPTED),
+            case(a, KIND_CHALLENGE, RULING_OVERRULED),
+            case(a, KIND_APPEAL, RULING_PENDING),
+            case(b, KIND_APPEAL, RULING_ACCEPTED),
+        ];
+        let names: HashMap<Uuid, String> = [(a, "Zion".to_string()), (b, "Bethel".to_string())].into_iter().collect();
+        let summaries = summarize(&cases, &names);
+        assert_eq!(summaries.iter().map(|s| s.team_name.as_str()).collect::<Vec<_>>(), vec!["Bethel", "Zion"]);
+        assert_eq!((summaries[1].challenges, summaries[1].challenges_overturned, summaries[1].appeals, summaries[1].appeals_overturned), (2, 1, 1, 0));
+        assert_eq!((summaries[0].appeals, summaries[0].appeals_overturned), (1, 1));
+    }
+}
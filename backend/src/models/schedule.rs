@@ -1,100 +1,670 @@
-// use crate::diesel::*;
-// use crate::schema::*;
-// use crate::models::common::*;
-// use serde::{Deserialize, Serialize};
-// // this import requires this syntax (to appease rustc):
-// use crate::schema::schedules::dsl::{
-//     sid,tid,roundtime,org,tournament,division,room,round,team1,team2,team3,
-//     quizmaster,contentjudge,scorekeeper,stats
-// };
-
-// // #[tsync::tsync]
-// #[derive(Debug, Serialize, Deserialize, Clone, Queryable, Insertable, Identifiable, AsChangeset)]
-// #[diesel(table_name = crate::schema::schedules)]
-// #[diesel(primary_key(sid))]
-// pub struct Schedule {
-//     pub sid: BigId,
-//     pub tid: BigId,
-//     pub roundtime: UTC,
-//     pub org: String,
-//     pub tournament: String,
-//     pub division: String,
-//     pub room: String,
-//     pub round: String,
-//     pub team1: String,
-//     pub team2: String,
-//     pub team3: String,
-//     pub quizmaster: String,
-//     pub contentjudge: String,
-//     pub scorekeeper: String,
-//     pub stats: String,
-// }
-
-// // #[tsync::tsync]
-// #[derive(Debug, Serialize, Deserialize, Clone, Insertable, AsChangeset)]
-// #[diesel(table_name = crate::schema::schedules)]
-// #[diesel(primary_key(sid))]
-// pub struct ScheduleChangeset {   
-//     pub roundtime: UTC,
-//     pub org: String,
-//     pub tournament: String,
-//     pub division: String,
-//     pub room: String,
-//     pub round: String,
-//     pub team1: String,
-//     pub team2: String,
-//     pub team3: String,
-//     pub quizmaster: String,
-//     pub contentjudge: String,
-//     pub scorekeeper: String,
-//     pub stats: String,
-// }
-
-// pub fn empty_changeset() -> ScheduleChangeset {
-//     return ScheduleChangeset {   
-//         roundtime: Utc.now(),
-//         org: "".to_string(),
-//         tournament: "".to_string(),
-//         division: "".to_string(),
-//         room: "".to_string(),
-//         round: "".to_string(),
-//         team1: "".to_string(),
-//         team2: "".to_string(),
-//         team3: "".to_string(),
-//         quizmaster: "".to_string(),
-//         contentjudge: "".to_string(),
-//         scorekeeper: "".to_string(),
-//         stats: "".to_string(),
-//     }
-// }
-
-// pub fn create(db: &mut Connection, item: &ScheduleChangeset) -> QueryResult<Schedule> {
-//     use crate::schema::schedules::dsl::*;
-//     insert_into(schedules).values(item).get_result::<Schedule>(db)
-// }
-
-// pub fn read(db: &mut Connection, item_id: i64) -> QueryResult<Schedule> {
-//     use crate::schema::schedules::dsl::*;
-//     schedules.filter(sid.eq(item_id)).first::<Schedule>(db)
-// }
-
-// pub fn read_all(db: &mut Connection) -> QueryResult<Vec<Schedule>> {
-//     use crate::schema::schedules::dsl::*;
-//     schedules
-//         .order(sid)
-//         .limit(10)
-//         .koffset(44)
-//         .load::<Schedule>(db)
-// }
-
-// pub fn update(db: &mut Connection, item_id: i64, item: &ScheduleChangeset) -> QueryResult<Schedule> {
-//     use crate::schema::schedules::dsl::*;
-//     diesel::update(schedules.filter(sid.eq(item_id)))
-//         .set(item)
-//         .get_result(db)
-// }
-
-// pub fn delete(db: &mut Connection, item_id: i64) -> QueryResult<usize> {
-//     use crate::schema::schedules::dsl::*;
-//     diesel::delete(schedules.filter(sid.eq(item_id))).execute(db)
-// }
+use std::collections::{BTreeSet, HashMap};
+use crate::database;
+use crate::models::{self, conflict::user_name, division::Division, game::{Game, GameBuilder}, room::Room, round::Round, round_robin::all_pages, team::Team, timeline::projected_start, tournament::Tournament, user::User};
+use crate::models::common::{escape_like, invalid};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use diesel::prelude::*;
+use diesel::{insert_into, QueryResult};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Schedules come from other tools as CSV, one game per row. Imported rows are kept in the
+// schedules table with the names as written, resolved against the tournament whenever they're
+// read, and turned into games on confirmation.
+
+pub const SCHEDULE_COLUMNS: [&str; 12] = [
+    "roundtime", "tournament", "division", "room", "round", "team1", "team2", "team3",
+    "quizmaster", "contentjudge", "scorekeeper", "stats",
+];
+
+pub struct ScheduleBuilder {
+    pub tid: Uuid,
+    pub roundtime: Option<DateTime<Utc>>,
+    pub tournament: String,
+    pub division: Option<String>,
+    pub room: Option<String>,
+    pub round: Option<String>,
+    pub teams: Vec<String>,
+    pub quizmaster: Option<String>,
+    pub contentjudge: Option<String>,
+    pub scorekeeper: Option<String>,
+    pub stats: Option<String>,
+}
+
+impl ScheduleBuilder {
+    pub fn new(tid: Uuid) -> Self {
+        Self {
+            tid,
+            roundtime: None,
+            tournament: String::new(),
+            division: None,
+            room: None,
+            round: None,
+            teams: vec![],
+            quizmaster: None,
+            contentjudge: None,
+            scorekeeper: None,
+            stats: None,
+        }
+    }
+    pub fn set_roundtime(mut self, val: Option<DateTime<Utc>>) -> Self {
+        self.roundtime = val;
+        self
+    }
+    pub fn set_tournament(mut self, val: &str) -> Self {
+        self.tournament = val.to_string();
+        self
+    }
+    pub fn set_division(mut self, val: &str) -> Self {
+        self.division = Some(val.to_string());
+        self
+    }
+    pub fn set_room(mut self, val: &str) -> Self {
+        self.room = Some(val.to_string());
+        self
+    }
+    pub fn set_round(mut self, val: &str) -> Self {
+        self.round = Some(val.to_string());
+        self
+    }
+    pub fn set_teams(mut self, val: &[&str]) -> Self {
+        self.teams = val.iter().map(|t| t.to_string()).collect();
+        self
+    }
+    pub fn set_quizmaster(mut self, val: Option<&str>) -> Self {
+        self.quizmaster = val.map(str::to_string);
+        self
+    }
+    pub fn set_contentjudge(mut self, val: Option<&str>) -> Self {
+        self.contentjudge = val.map(str::to_string);
+        self
+    }
+    pub fn set_scorekeeper(mut self, val: Option<&str>) -> Self {
+        self.scorekeeper = val.map(str::to_string);
+        self
+    }
+    pub fn set_stats(mut self, val: Option<&str>) -> Self {
+        self.stats = val.map(str::to_string);
+        self
+    }
+    fn validate_all_are_some(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.division.is_none() {
+            errors.push("division is required".to_string());
+        }
+        if self.room.is_none() {
+            errors.push("room is required".to_string());
+        }
+        if self.round.is_none() {
+            errors.push("round is required".to_string());
+        }
+        if self.teams.len() > 3 {
+            errors.push("at most three teams are allowed".to_string());
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(())
+    }
+    pub fn build(self) -> Result<NewSchedule, Vec<String>> {
+        self.validate_all_are_some()?;
+        let team = |idx: usize| self.teams.get(idx).cloned();
+        Ok(NewSchedule {
+            tid: self.tid,
+            roundtime: self.roundtime,
+            tournament: self.tournament.clone(),
+            division: self.division.clone().unwrap(),
+            room: self.room.clone().unwrap(),
+            round: self.round.clone().unwrap(),
+            team1: team(0),
+            team2: team(1),
+            team3: team(2),
+            quizmaster: self.quizmaster,
+            contentjudge: self.contentjudge,
+            scorekeeper: self.scorekeeper,
+            stats: self.stats,
+        })
+    }
+    pub fn build_and_insert(self, db: &mut database::Connection) -> QueryResult<Schedule> {
+        let new_schedule = self.build();
+        create(db, &new_schedule.unwrap())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::schedules)]
+#[diesel(primary_key(sid))]
+pub struct Schedule {
+    pub sid: Uuid,
+    pub tid: Uuid,                              // id of the tournament the schedule was imported into
+    pub roundtime: Option<DateTime<Utc>>,
+    pub tournament: String,
+    pub division: String,
+    pub room: String,
+    pub round: String,
+    pub team1: Option<String>,
+    pub team2: Option<String>,
+    pub team3: Option<String>,
+    pub quizmaster: Option<String>,
+    pub contentjudge: Option<String>,
+    pub scorekeeper: Option<String>,
+    pub stats: Option<String>,
+    pub gid: Option<Uuid>,                      // the game created on confirmation
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable)]
+#[diesel(table_name = crate::schema::schedules)]
+pub struct NewSchedule {
+    pub tid: Uuid,
+    pub roundtime: Option<DateTime<Utc>>,
+    pub tournament: String,
+    pub division: String,
+    pub room: String,
+    pub round: String,
+    pub team1: Option<String>,
+    pub team2: Option<String>,
+    pub team3: Option<String>,
+    pub quizmaster: Option<String>,
+    pub contentjudge: Option<String>,
+    pub scorekeeper: Option<String>,
+    pub stats: Option<String>,
+}
+
+// One line of the CSV layout, in SCHEDULE_COLUMNS order.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScheduleCsvRow {
+    pub roundtime: Option<String>,
+    pub tournament: Option<String>,
+    pub division: String,
+    pub room: String,
+    pub round: String,
+    pub team1: Option<String>,
+    pub team2: Option<String>,
+    pub team3: Option<String>,
+    pub quizmaster: Option<String>,
+    pub contentjudge: Option<String>,
+    pub scorekeeper: Option<String>,
+    pub stats: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, ToSchema)]
+pub struct ResolvedSchedule {
+    pub divisionid: Option<Uuid>,
+    pub roomid: Option<Uuid>,
+    pub roundid: Option<Uuid>,
+    pub leftteamid: Option<Uuid>,
+    pub centerteamid: Option<Uuid>,
+    pub rightteamid: Option<Uuid>,
+    pub quizmasterid: Option<Uuid>,
+    pub contentjudgeid: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ScheduleRow {
+    pub schedule: Schedule,
+    pub resolved: ResolvedSchedule,
+    pub unresolved: Vec<String>,                // e.g. "room 'Room 9'"; empty when the row can become a game
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ScheduleReport {
+    pub tid: Uuid,
+    pub rows: Vec<ScheduleRow>,
+    pub unresolved: Vec<String>,                // every distinct unresolved name
+    pub created: Vec<Game>,                     // only filled in by a confirmation
+}
+
+pub fn create(db: &mut database::Connection, item: &NewSchedule) -> QueryResult<Schedule> {
+    use crate::schema::schedules::dsl::*;
+    insert_into(schedules).values(item).get_result::<Schedule>(db)
+}
+
+pub fn read(db: &mut database::Connection, item_id: Uuid) -> QueryResult<Schedule> {
+    use crate::schema::schedules::dsl::*;
+    schedules.filter(sid.eq(item_id)).first::<Schedule>(db)
+}
+
+pub fn read_all_schedules_of_tournament(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<Schedule>> {
+    use crate::schema::schedules::dsl::*;
+    schedules
+        .filter(tid.eq(tournament_id))
+        .order((roundtime.asc(), division.asc(), round.asc(), room.asc()))
+        .load::<Schedule>(db)
+}
+
+/// Removes the rows that haven't been confirmed yet; confirmed rows stay as a record of the import.
+pub fn discard(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<usize> {
+    use crate::schema::schedules::dsl::*;
+    diesel::delete(schedules.filter(tid.eq(tournament_id)).filter(gid.is_null())).execute(db)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Accepts RFC 3339 as written by the export, or "YYYY-MM-DD HH:MM[:SS]" taken as UTC.
+pub fn parse_roundtime(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|time| time.and_utc())
+        .ok_or(format!["'{}' is not a date and time", value])
+}
+
+fn check_length(line: u64, column: &str, value: &Option<String>, max: usize) -> Result<(), String> {
+    match value {
+        Some(v) if v.chars().count() > max => Err(format!["line {}: {} is longer than {} characters", line, column, max]),
+        _ => Ok(()),
+    }
+}
+
+/// Reads the CSV layout of SCHEDULE_COLUMNS. The header row is required; columns are matched by
+/// name, so their order doesn't matter.
+pub fn parse_csv(tournament_id: Uuid, bytes: &[u8]) -> Result<Vec<NewSchedule>, String> {
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+    let mut rows = vec![];
+    for result in reader.deserialize::<ScheduleCsvRow>() {
+        let row = result.map_err(|e| e.to_string())?;
+        let line = rows.len() as u64 + 2;
+        let division = non_empty(Some(row.division)).ok_or(format!["line {}: division is required", line])?;
+        let room = non_empty(Some(row.room)).ok_or(format!["line {}: room is required", line])?;
+        let round = non_empty(Some(row.round)).ok_or(format!["line {}: round is required", line])?;
+        let roundtime = match non_empty(row.roundtime) {
+            Some(value) => Some(parse_roundtime(&value).map_err(|e| format!["line {}: {}", line, e])?),
+            None => None,
+        };
+        let schedule = NewSchedule {
+            tid: tournament_id,
+            roundtime,
+            tournament: non_empty(row.tournament).unwrap_or_default(),
+            division,
+            room,
+            round,
+            team1: non_empty(row.team1),
+            team2: non_empty(row.team2),
+            team3: non_empty(row.team3),
+            quizmaster: non_empty(row.quizmaster),
+            contentjudge: non_empty(row.contentjudge),
+            scorekeeper: non_empty(row.scorekeeper),
+            stats: non_empty(row.stats),
+        };
+        for (column, value, max) in [
+            ("tournament", Some(schedule.tournament.clone()), 32),
+            ("division", Some(schedule.division.clone()), 32),
+            ("room", Some(schedule.room.clone()), 32),
+            ("round", Some(schedule.round.clone()), 64),
+            ("team1", schedule.team1.clone(), 128),
+            ("team2", schedule.team2.clone(), 128),
+            ("team3", schedule.team3.clone(), 128),
+            ("quizmaster", schedule.quizmaster.clone(), 128),
+            ("contentjudge", schedule.contentjudge.clone(), 128),
+            ("scorekeeper", schedule.scorekeeper.clone(), 128),
+            ("stats", schedule.stats.clone(), 1024),
+        ] {
+            check_length(line, column, &value, max)?;
+        }
+        rows.push(schedule);
+    }
+    Ok(rows)
+}
+
+pub fn to_csv(rows: &[ScheduleCsvRow]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+    writer.write_record(SCHEDULE_COLUMNS).map_err(|e| e.to_string())?;
+    for row in rows.iter() {
+        writer.serialize(row).map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+// The tournament's divisions, rooms, rounds and teams, plus the officials looked up so far.
+struct Names {
+    tournament: Tournament,
+    divisions: Vec<Division>,
+    rooms: Vec<Room>,
+    rounds: HashMap<Uuid, Vec<Round>>,
+    teams: HashMap<Uuid, Vec<Team>>,
+    users: HashMap<String, Result<Uuid, &'static str>>,
+}
+
+impl Names {
+    fn load(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Self> {
+        let tournament = models::tournament::read(db, tournament_id)?;
+        let divisions = models::division::read_all_divisions_of_tournament(db, tournament_id, &all_pages())?;
+        let rooms = models::room::read_all_rooms_of_tournament(db, tournament_id, &all_pages())?;
+        let mut rounds = HashMap::new();
+        let mut teams = HashMap::new();
+        for division in divisions.iter() {
+            rounds.insert(division.did, models::round::read_all_rounds_of_division(db, division.did, &all_pages())?);
+            teams.insert(division.did, models::team::read_all_teams_of_division(db, division.did, &all_pages())?);
+        }
+        Ok(Names { tournament, divisions, rooms, rounds, teams, users: HashMap::new() })
+    }
+
+    // Officials are matched by email or by "first last", ignoring case.
+    fn user(&mut self, db: &mut database::Connection, name: &str) -> QueryResult<Result<Uuid, &'static str>> {
+        let key = name.trim().to_lowercase();
+        if let Some(found) = self.users.get(&key) {
+            return Ok(*found);
+        }
+        let pattern = escape_like(&key);
+        let found: Vec<User> = {
+            use crate::schema::users::dsl::*;
+            users
+                .filter(email.ilike(&pattern).or(fname.concat(" ").concat(lname).ilike(&pattern)))
+                .limit(2)
+                .load::<User>(db)?
+        };
+        let result = match found.len() {
+            0 => Err("unknown"),
+            1 => Ok(found[0].id),
+            _ => Err("ambiguous"),
+        };
+        self.users.insert(key, result);
+        Ok(result)
+    }
+
+    fn resolve(&mut self, db: &mut database::Connection, schedule: &Schedule) -> QueryResult<(ResolvedSchedule, Vec<String>)> {
+        let mut resolved = ResolvedSchedule::default();
+        let mut unresolved = vec![];
+
+        if !schedule.tournament.is_empty()
+            && !same_name(&schedule.tournament, &self.tournament.tname)
+            && !same_name(&schedule.tournament, &self.tournament.breadcrumb) {
+            unresolved.push(format!["tournament '{}'", schedule.tournament]);
+        }
+
+        let division = self.divisions.iter().find(|d| same_name(&d.dname, &schedule.division)).cloned();
+        let room = self.rooms.iter().find(|r| same_name(&r.name, &schedule.room)).cloned();
+        resolved.divisionid = division.as_ref().map(|d| d.did);
+        resolved.roomid = room.as_ref().map(|r| r.roomid);
+        if room.is_none() {
+            unresolved.push(format!["room '{}'", schedule.room]);
+        }
+
+        let team_names: Vec<&String> = [&schedule.team1, &schedule.team2, &schedule.team3].into_iter().flatten().collect();
+        match &division {
+            None => unresolved.push(format!["division '{}'", schedule.division]),
+            Some(division) => {
+                resolved.roundid = self.rounds[&division.did].iter().find(|r| same_name(&r.name, &schedule.round)).map(|r| r.roundid);
+                if resolved.roundid.is_none() {
+                    unresolved.push(format!["round '{}'", schedule.round]);
+                }
+                let mut team_ids = vec![];
+                for team_name in team_names.iter() {
+                    match self.teams[&division.did].iter().find(|t| same_name(&t.name, team_name)) {
+                        Some(team) => team_ids.push(Some(team.teamid)),
+                        None => {
+                            unresolved.push(format!["team '{}'", team_name]);
+                            team_ids.push(None);
+                        },
+                    }
+                }
+                // two teams sit left and right; a third goes in the center
+                match team_ids.len() {
+                    2 => (resolved.leftteamid, resolved.rightteamid) = (team_ids[0], team_ids[1]),
+                    3 => (resolved.leftteamid, resolved.centerteamid, resolved.rightteamid) = (team_ids[0], team_ids[1], team_ids[2]),
+                    _ => {},
+                }
+            },
+        }
+        if team_names.len() < 2 {
+            unresolved.push("teams: a game needs two or three".to_string());
+        }
+
+        match &schedule.quizmaster {
+            Some(name) => match self.user(db, name)? {
+                Ok(id) => resolved.quizmasterid = Some(id),
+                Err(why) => unresolved.push(format!["quizmaster '{}' ({})", name, why]),
+            },
+            None => {
+                resolved.quizmasterid = room.as_ref().and_then(|r| r.quizmaster_id);
+                if resolved.quizmasterid.is_none() && room.is_some() {
+                    unresolved.push(format!["quizmaster: none given and room '{}' has none", schedule.room]);
+                }
+            },
+        }
+        match &schedule.contentjudge {
+            Some(name) => match self.user(db, name)? {
+                Ok(id) => resolved.contentjudgeid = Some(id),
+                Err(why) => unresolved.push(format!["contentjudge '{}' ({})", name, why]),
+            },
+            None => resolved.contentjudgeid = room.as_ref().and_then(|r| r.contentjudge_id),
+        }
+
+        Ok((resolved, unresolved))
+    }
+}
+
+pub fn read_report(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<ScheduleReport> {
+    let mut names = Names::load(db, tournament_id)?;
+    let mut rows = vec![];
+    let mut all_unresolved = BTreeSet::new();
+    for schedule in read_all_schedules_of_tournament(db, tournament_id)? {
+        let (resolved, unresolved) = names.resolve(db, &schedule)?;
+        all_unresolved.extend(unresolved.iter().cloned());
+        rows.push(ScheduleRow { schedule, resolved, unresolved });
+    }
+    Ok(ScheduleReport { tid: tournament_id, rows, unresolved: all_unresolved.into_iter().collect(), created: vec![] })
+}
+
+/// Replaces the unconfirmed rows of the tournament with the rows of `bytes` and reports how they
+/// resolve. Nothing is created until `confirm`.
+pub fn import_csv(db: &mut database::Connection, tournament_id: Uuid, bytes: &[u8]) -> QueryResult<ScheduleReport> {
+    models::tournament::read(db, tournament_id)?;
+    let new_schedules = parse_csv(tournament_id, bytes).map_err(invalid)?;
+    if new_schedules.is_empty() {
+        return Err(invalid("The file has no schedule rows".to_string()));
+    }
+    db.transaction::<_, diesel::result::Error, _>(|conn| {
+        discard(conn, tournament_id)?;
+        insert_into(crate::schema::schedules::table).values(&new_schedules).execute(conn)
+    })?;
+    read_report(db, tournament_id)
+}
+
+/// Creates a game for every unconfirmed row. Either all of them resolve and are created, or none.
+/// A row's roundtime sets the start of a round that has none yet (the earliest row wins), and
+/// its game is offset from the round's start by the difference.
+pub fn confirm(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<ScheduleReport> {
+    let mut report = read_report(db, tournament_id)?;
+    let pending: Vec<&ScheduleRow> = report.rows.iter().filter(|r| r.schedule.gid.is_none()).collect();
+    if pending.is_empty() {
+        return Err(invalid("There are no imported rows to confirm".to_string()));
+    }
+    let unresolved = pending.iter().filter(|r| !r.unresolved.is_empty()).count();
+    if unresolved > 0 {
+        return Err(invalid(format!["{} rows have unresolved names: {}", unresolved, report.unresolved.join(", ")]));
+    }
+
+    let org = models::tournament::read(db, tournament_id)?.organization;
+    let round_ids: BTreeSet<Uuid> = pending.iter().map(|r| r.resolved.roundid.unwrap()).collect();
+    let mut round_starts: HashMap<Uuid, Option<DateTime<Utc>>> = HashMap::new();
+    for round_id in round_ids {
+        round_starts.insert(round_id, models::round::read(db, round_id)?.scheduled_start_time);
+    }
+    let mut untimed: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+    for row in pending.iter() {
+        let round_id = row.resolved.roundid.unwrap();
+        if let (None, Some(time)) = (round_starts[&round_id], row.schedule.roundtime) {
+            let earliest = untimed.entry(round_id).or_insert(time);
+            *earliest = (*earliest).min(time);
+        }
+    }
+
+    let created = db.transaction::<_, diesel::result::Error, _>(|conn| {
+        for (round_id, start) in untimed.iter() {
+            use crate::schema::rounds::dsl::*;
+            diesel::update(rounds.filter(roundid.eq(round_id)))
+                .set(scheduled_start_time.eq(start))
+                .execute(conn)?;
+            round_starts.insert(*round_id, Some(*start));
+        }
+        let mut created = vec![];
+        for row in pending.iter() {
+            let ids = &row.resolved;
+            let new_game = GameBuilder::new_default(ids.roomid.unwrap(), ids.roundid.unwrap())
+                .set_org(org.clone())
+                .set_tournamentid(Some(tournament_id))
+                .set_divisionid(ids.divisionid)
+                .set_leftteamid(ids.leftteamid.unwrap())
+                .set_centerteamid(ids.centerteamid)
+                .set_rightteamid(ids.rightteamid.unwrap())
+                .set_quizmasterid(ids.quizmasterid.unwrap())
+                .set_contentjudgeid(ids.contentjudgeid)
+                .build()
+                .map_err(|e| invalid(e.join(", ")))?;
+            let mut game = models::game::create(conn, &new_game)?;
+            let round_start = round_starts[&game.roundid];
+            if let (Some(start), Some(time)) = (round_start, row.schedule.roundtime) {
+                use crate::schema::games::dsl::*;
+                let offset = (time - start).num_minutes() as i32;
+                if offset != 0 {
+                    game = diesel::update(games.filter(gid.eq(game.gid)))
+                        .set(start_offset_minutes.eq(offset))
+                        .get_result::<Game>(conn)?;
+                }
+            }
+            {
+                use crate::schema::schedules::dsl::*;
+                diesel::update(schedules.filter(sid.eq(row.schedule.sid)))
+                    .set((gid.eq(game.gid), updated_at.eq(Utc::now())))
+                    .execute(conn)?;
+            }
+            created.push(game);
+        }
+        Ok(created)
+    })?;
+
+    report = read_report(db, tournament_id)?;
+    report.created = created;
+    Ok(report)
+}
+
+/// The tournament's games in the import layout, each at its projected start. Scorekeeper and stats
+/// come from the imported row the game was created from, if any.
+pub fn export_csv(db: &mut database::Connection, tournament_id: Uuid) -> QueryResult<Vec<u8>> {
+    use crate::schema::{divisions, games, rooms, rounds, teams, users};
+
+    let tournament = models::tournament::read(db, tournament_id)?;
+    let mut rows = games::table
+        .inner_join(divisions::table)
+        .inner_join(rooms::table)
+        .inner_join(rounds::table)
+        .filter(games::tournamentid.eq(tournament_id))
+        .filter(games::ignore.eq(false))
+        .select((Game::as_select(), Division::as_select(), Room::as_select(), Round::as_select()))
+        .load::<(Game, Division, Room, Round)>(db)?;
+    rows.sort_by(|a, b| {
+        (projected_start(&a.3, &a.0), &a.1.dname, &a.3.name, &a.2.name).cmp(&(projected_start(&b.3, &b.0), &b.1.dname, &b.3.name, &b.2.name))
+    });
+
+    let team_names: HashMap<Uuid, String> = teams::table
+        .filter(teams::did.eq_any(rows.iter().map(|r| r.1.did).collect::<Vec<Uuid>>()))
+        .load::<Team>(db)?
+        .into_iter()
+        .map(|t| (t.teamid, t.name))
+        .collect();
+    let official_ids: Vec<Uuid> = rows.iter().flat_map(|r| [Some(r.0.quizmasterid), r.0.contentjudgeid]).flatten().collect();
+    let user_names: HashMap<Uuid, String> = users::table
+        .filter(users::id.eq_any(official_ids))
+        .load::<User>(db)?
+        .iter()
+        .map(|u| (u.id, user_name(u)))
+        .collect();
+    let imported: HashMap<Uuid, Schedule> = read_all_schedules_of_tournament(db, tournament_id)?
+        .into_iter()
+        .filter_map(|s| s.gid.map(|g| (g, s)))
+        .collect();
+
+    let team = |id: Option<Uuid>| id.and_then(|id| team_names.get(&id).cloned());
+    let csv_rows: Vec<ScheduleCsvRow> = rows
+        .iter()
+        .map(|(game, division, room, round)| {
+            let teams: Vec<Option<String>> = match game.centerteamid {
+                Some(center) => vec![team(Some(game.leftteamid)), team(Some(center)), team(Some(game.rightteamid))],
+                None => vec![team(Some(game.leftteamid)), team(Some(game.rightteamid)), None],
+            };
+            let source = imported.get(&game.gid);
+            ScheduleCsvRow {
+                roundtime: projected_start(round, game)
+                    .or(source.and_then(|s| s.roundtime))
+                    .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                tournament: Some(tournament.tname.clone()),
+                division: division.dname.clone(),
+                room: room.name.clone(),
+                round: round.name.clone(),
+                team1: teams[0].clone(),
+                team2: teams[1].clone(),
+                team3: teams[2].clone(),
+                quizmaster: user_names.get(&game.quizmasterid).cloned(),
+                contentjudge: game.contentjudgeid.and_then(|id| user_names.get(&id).cloned()),
+                scorekeeper: source.and_then(|s| s.scorekeeper.clone()),
+                stats: source.and_then(|s| s.stats.clone()),
+            }
+        })
+        .collect();
+
+    to_csv(&csv_rows).map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parse_csv_reads_back_the_export() {
+        let row = ScheduleCsvRow {
+            roundtime: Some("2026-06-01T09:00:00Z".to_string()),
+            tournament: Some("Spring Meet".to_string()),
+            division: "Senior".to_string(),
+            room: "Room 1".to_string(),
+            round: "Round 1".to_string(),
+            team1: Some("Eagles, Gold".to_string()),
+            team2: Some("Hawks".to_string()),
+            team3: None,
+            quizmaster: Some("Jane Doe".to_string()),
+            contentjudge: None,
+            scorekeeper: None,
+            stats: Some("prelim".to_string()),
+        };
+        let bytes = to_csv(std::slice::from_ref(&row)).unwrap();
+
+        let tid = Uuid::new_v4();
+        let parsed = parse_csv(tid, &bytes).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].roundtime, Some(Utc.with_ymd_and_hms(2026, 6, 1, 9, 0, 0).unwrap()));
+        assert_eq!(parsed[0].team1.as_deref(), Some("Eagles, Gold"));
+        assert_eq!(parsed[0].team3, None);
+        assert_eq!(parsed[0].contentjudge, None);
+        assert_eq!(parsed[0].stats.as_deref(), Some("prelim"));
+    }
+
+    #[test]
+    fn parse_csv_reports_the_line_of_bad_rows() {
+        let csv = "division,room,round,team1,team2,roundtime\n\
+                   Senior,Room 1,Round 1,A,B,2026-06-01 09:00\n\
+                   Senior,,Round 1,C,D,\n";
+        assert_eq!(parse_csv(Uuid::new_v4(), csv.as_bytes()).unwrap_err(), "line 3: room is required");
+
+        let csv = "division,room,round,roundtime\nSenior,Room 1,Round 1,June 1st\n";
+        assert_eq!(parse_csv(Uuid::new_v4(), csv.as_bytes()).unwrap_err(), "line 2: 'June 1st' is not a date and time");
+    }
+}
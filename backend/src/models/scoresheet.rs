@@ -0,0 +1,317 @@
+use crate::database;
+use crate::models::{self, game::Game, gameevent::{GameEvent, GameEventStreamBuilder, GameResult, NewGameEvent}};
+use crate::models::common::invalid;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// A paper scoresheet is turned into the events QuizMachine would have sent, using the same stream
+// builder the tests use, so the game is scored like any other. Generated events carry
+// MANUAL_DIGEST where QuizMachine's events carry the digest of what it sent.
+
+pub const MANUAL_DIGEST: &str = "manual";
+pub const SEATS: usize = 6;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ScoresheetQuizzer {
+    pub name: String,
+    #[serde(default)]
+    pub captain: bool,
+    #[serde(default)]
+    pub cocaptain: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ScoresheetTeam {
+    pub quizzers: Vec<ScoresheetQuizzer>,       // in seat order; the fifth seat is the substitute's
+}
+
+// One line of the sheet. Teams are numbered by seat like the event stream: left, center (three-team
+// games only), then right. `bonus_correct` has one ruling per other team, in the same order.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScoresheetEntry {
+    Correct { team: i32, quizzer: String },
+    Error { team: i32, quizzer: String, #[serde(default)] bonus_correct: Vec<bool> },
+    NoJump,
+    Timeout { team: i32 },
+    Foul { team: i32, quizzer: String },
+    TeamFoul { team: i32, #[serde(default)] coach: bool },
+    Substitution { team: i32, quizzer: String, seat: i32 },
+    ChallengeOverruled { team: i32, quizzer: String },
+    ChallengeAccepted { team: i32, quizzer: String, #[serde(default)] bonus_correct: Vec<bool> },
+    AppealAccepted { team: i32, quizzer: String },
+    AppealOverruled { team: i32, quizzer: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Scoresheet {
+    pub teams: Vec<ScoresheetTeam>,
+    pub entries: Vec<ScoresheetEntry>,
+    pub replace: Option<bool>,                  // required to overwrite events already recorded for the game
+    pub preview: Option<bool>,                  // defaults to true: nothing is written
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = crate::schema::manual_scoresheets)]
+#[diesel(primary_key(gid))]
+pub struct ManualScoresheet {
+    pub gid: Uuid,
+    pub entered_by: Option<Uuid>,               // None once that user is deleted
+    pub scoresheet: String,                     // the sheet as submitted, in JSON
+    pub events: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::manual_scoresheets)]
+pub struct NewManualScoresheet {
+    pub gid: Uuid,
+    pub entered_by: Uuid,
+    pub scoresheet: String,
+    pub events: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ScoresheetReport {
+    pub gid: Uuid,
+    pub preview: bool,
+    pub replaced: i32,                          // events that were (or would be) overwritten
+    pub result: GameResult,
+    pub events: Vec<GameEvent>,
+}
+
+fn bonuses(bonus_correct: &[bool]) -> (bool, bool) {
+    (bonus_correct.first().copied().unwrap_or(false), bonus_correct.get(1).copied().unwrap_or(false))
+}
+
+/// Builds and validates the event stream of a scoresheet for `game`, without touching the database.
+pub fn to_event_stream(game: &Game, team_names: &[String], sheet: &Scoresheet) -> Result<Vec<NewGameEvent>, Vec<String>> {
+    let team_count = team_names.len();
+    if sheet.teams.len() != team_count {
+        return Err(vec![format!["The game has {} teams but the scoresheet has {}", team_count, sheet.teams.len()]]);
+    }
+    if let Some((idx, _)) = sheet.teams.iter().enumerate().find(|(_, t)| t.quizzers.is_empty() || t.quizzers.len() > SEATS) {
+        return Err(vec![format!["Team {} needs between 1 and {} quizzers", idx, SEATS]]);
+    }
+    // the builder indexes teams and seats directly, so check them before it sees them
+    for (line, entry) in sheet.entries.iter().enumerate() {
+        let (team, seat) = match entry {
+            ScoresheetEntry::NoJump => continue,
+            ScoresheetEntry::Substitution { team, seat, .. } => (*team, Some(*seat)),
+            ScoresheetEntry::Correct { team, .. }
+            | ScoresheetEntry::Error { team, .. }
+            | ScoresheetEntry::Timeout { team }
+            | ScoresheetEntry::Foul { team, .. }
+            | ScoresheetEntry::TeamFoul { team, .. }
+            | ScoresheetEntry::ChallengeOverruled { team, .. }
+            | ScoresheetEntry::ChallengeAccepted { team, .. }
+            | ScoresheetEntry::AppealAccepted { team, .. }
+            | ScoresheetEntry::AppealOverruled { team, .. } => (*team, None),
+        };
+        if team < 0 || team as usize >= team_count {
+            return Err(vec![format!["Line {}: Team {} is not in this game; teams are numbered from 0 to {}", line + 1, team, team_count - 1]]);
+        }
+        if let Some(seat) = seat.filter(|s| *s < 0 || *s as usize >= SEATS) {
+            return Err(vec![format!["Line {}: Seat {} does not exist; seats are numbered from 0 to {}", line + 1, seat, SEATS - 1]]);
+        }
+    }
+
+    let mut stream = GameEventStreamBuilder::new(game.gid)
+        .then_add_RM(&game.ruleset)
+        .then_add_QT(&game.org);
+    for (idx, (name, sheet_team)) in team_names.iter().zip(sheet.teams.iter()).enumerate() {
+        stream = stream.then_add_TN(name, idx as i32)?;
+        for (seat, quizzer) in sheet_team.quizzers.iter().enumerate() {
+            stream = stream.then_add_QN_plus_if_SC_or_SS(&quizzer.name, idx as i32, seat as i32, quizzer.captain, quizzer.cocaptain)?;
+        }
+    }
+    for (line, entry) in sheet.entries.iter().enumerate() {
+        let next = match entry {
+            ScoresheetEntry::Correct { team, quizzer } => stream.then_add_TC(quizzer, *team),
+            ScoresheetEntry::Error { team, quizzer, bonus_correct } => {
+                let (left, right) = bonuses(bonus_correct);
+                stream.then_add_TE_and_bonuses(quizzer, *team, left, right)
+            },
+            ScoresheetEntry::NoJump => stream.then_add_NJ(),
+            ScoresheetEntry::Timeout { team } => stream.then_add_TO(*team),
+            ScoresheetEntry::Foul { team, quizzer } => stream.then_add_Fminus(quizzer, *team),
+            ScoresheetEntry::TeamFoul { team, coach } => stream.then_add_FC(*coach, *team),
+            ScoresheetEntry::Substitution { team, quizzer, seat } => stream.then_add_SB(quizzer, *team, *seat),
+            ScoresheetEntry::ChallengeOverruled { team, quizzer } => stream.then_add_Cminus(quizzer, *team),
+            ScoresheetEntry::ChallengeAccepted { team, quizzer, bonus_correct } => {
+                let (left, right) = bonuses(bonus_correct);
+                stream.then_challenge_accepted(quizzer, *team, left, right)
+            },
+            ScoresheetEntry::AppealAccepted { team, quizzer } => stream.then_add_Aplus(quizzer, *team),
+            ScoresheetEntry::AppealOverruled { team, quizzer } => stream.then_add_Aminus(quizzer, *team),
+        };
+        stream = next.map_err(|errors| errors.into_iter().map(|e| format!["Line {}: {}", line + 1, e]).collect::<Vec<String>>())?;
+    }
+
+    let (events, _) = stream.to_game_events();
+    // only the stream is checked here; enter calculates the result with the division's settings
+    models::gameevent::calculate_game_result(game.gid, events.clone(), models::gameevent::DEFAULT_QUESTIONS_PER_GAME)?;
+    Ok(events
+        .into_iter()
+        .map(|e| NewGameEvent {
+            gid: e.gid,
+            question: e.question,
+            eventnum: e.eventnum,
+            name: e.name,
+            team: e.team,
+            quizzer: e.quizzer,
+            event: e.event,
+            parm1: e.parm1,
+            parm2: e.parm2,
+            clientts: e.clientts,
+            serverts: e.serverts,
+            md5digest: MANUAL_DIGEST.to_string(),
+        })
+        .collect())
+}
+
+fn team_names_of_game(db: &mut database::Connection, game: &Game) -> QueryResult<Vec<String>> {
+    let mut names = vec![];
+    for team_id in [Some(game.leftteamid), game.centerteamid, Some(game.rightteamid)].into_iter().flatten() {
+        names.push(models::team::read(db, team_id)?.name);
+    }
+    Ok(names)
+}
+
+/// Generates the game's events from a paper scoresheet. Unless previewing, the events replace
+/// whatever the game had and the sheet is kept as the record of the manual entry.
+pub fn enter(db: &mut database::Connection, game: &Game, user_id: Uuid, sheet: &Scoresheet) -> QueryResult<ScoresheetReport> {
+    let preview = sheet.preview.unwrap_or(true);
+    let team_names = team_names_of_game(db, game)?;
+    let new_events = to_event_stream(game, &team_names, sheet).map_err(|errors| invalid(errors.join(" ")))?;
+    let events: Vec<GameEvent> = new_events.iter().cloned().map(GameEvent::new_from_new_game_event).collect();
+    let questions_per_game = models::award_setting::read_or_default(db, game.divisionid)?.questions_per_game;
+    let result = models::gameevent::calculate_game_result(game.gid, events.clone(), questions_per_game).map_err(|errors| invalid(errors.join(" ")))?;
+
+    let existing = models::gameevent::read_all_gameevents_of_games(db, &[game.gid])?.len() as i32;
+    if existing > 0 && !sheet.replace.unwrap_or(false) && read(db, game.gid).is_err() {
+        return Err(invalid(format!["The game already has {} events from QuizMachine; pass replace to overwrite them", existing]));
+    }
+
+    if !preview {
+        let record = NewManualScoresheet {
+            gid: game.gid,
+            entered_by: user_id,
+            scoresheet: serde_json::to_string(sheet).unwrap_or_default(),
+            events: new_events.len() as i32,
+        };
+        db.transaction::<_, diesel::result::Error, _>(|conn| {
+            use crate::schema::{gameevents, manual_scoresheets};
+            diesel::delete(gameevents::table.filter(gameevents::gid.eq(game.gid))).execute(conn)?;
+            diesel::insert_into(gameevents::table).values(&new_events).execute(conn)?;
+            diesel::insert_into(manual_scoresheets::table)
+                .values(&record)
+                .on_conflict(manual_scoresheets::gid)
+                .do_update()
+                .set((&record, manual_scoresheets::updated_at.eq(Utc::now())))
+                .execute(conn)?;
+            Ok(())
+        })?;
+    }
+
+    Ok(ScoresheetReport { gid: game.gid, preview, replaced: existing, result, events })
+}
+
+pub fn read(db: &mut database::Connection, game_id: Uuid) -> QueryResult<ManualScoresheet> {
+    use crate::schema::manual_scoresheets::dsl::*;
+    manual_scoresheets.filter(gid.eq(game_id)).first::<ManualScoresheet>(db)
+}
+
+pub fn is_manual(db: &mut database::Connection, game_id: Uuid) -> QueryResult<bool> {
+    read(db, game_id).optional().map(|found| found.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        Game {
+            gid: Uuid::new_v4(),
+            org: "Nazarene".to_string(),
+            tournamentid: Uuid::new_v4(),
+            divisionid: Uuid::new_v4(),
+            roomid: Uuid::new_v4(),
+            roundid: Uuid::new_v4(),
+            ignore: false,
+            ruleset: "Tournament".to_string(),
+            leftteamid: Uuid::new_v4(),
+            centerteamid: None,
+            rightteamid: Uuid::new_v4(),
+            quizmasterid: Uuid::new_v4(),
+            contentjudgeid: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            clientkey: String::new(),
+            actual_start_time: None,
+            actual_end_time: None,
+            start_offset_minutes: 0,
+        }
+    }
+
+    fn sheet(entries: serde_json::Value) -> Scoresheet {
+        serde_json::from_value(serde_json::json!({
+            "teams": [
+                { "quizzers": [{ "name": "Ann", "captain": true }, { "name": "Abe", "cocaptain": true }, { "name": "Amy" }, { "name": "Art" }, { "name": "Ada" }] },
+                { "quizzers": [{ "name": "Bob", "captain": true }, { "name": "Bea" }] },
+            ],
+            "entries": entries,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn to_event_stream_works() {
+        let game = game();
+        let names = vec!["Team A".to_string(), "Team B".to_string()];
+        let mut entries = vec![
+            serde_json::json!({ "kind": "correct", "team": 0, "quizzer": "Ann" }),
+            serde_json::json!({ "kind": "error", "team": 1, "quizzer": "Bob", "bonus_correct": [true] }),
+            serde_json::json!({ "kind": "timeout", "team": 1 }),
+            serde_json::json!({ "kind": "substitution", "team": 0, "quizzer": "Ada", "seat": 2 }),
+            serde_json::json!({ "kind": "correct", "team": 0, "quizzer": "Ada" }),
+        ];
+        entries.extend((0..18).map(|_| serde_json::json!({ "kind": "no_jump" })));
+
+        let events = to_event_stream(&game, &names, &sheet(serde_json::Value::Array(entries))).unwrap();
+        assert!(events.iter().all(|e| e.md5digest == MANUAL_DIGEST));
+        assert!(events.iter().any(|e| e.event == "BC" && e.team == 0 && e.name == "Bob"));
+        let as_events: Vec<GameEvent> = events.into_iter().map(GameEvent::new_from_new_game_event).collect();
+        let result = models::gameevent::calculate_game_result(game.gid, as_events, 20).unwrap();
+        assert!(result.is_final);
+        assert_eq!(result.teams.iter().find(|t| t.name == "Team A").unwrap().rank, 1);
+        let team_a = result.teams.iter().find(|t| t.name == "Team A").unwrap();
+        assert!(team_a.quizzers.iter().any(|q| q.name == "Ada" && q.correct_tossups == 1));
+    }
+
+    #[test]
+    fn to_event_stream_rejects_bad_sheets() {
+        let game = game();
+        let names = vec!["Team A".to_string(), "Team B".to_string()];
+
+        let errors = to_event_stream(&game, &names, &sheet(serde_json::json!([{ "kind": "correct", "team": 2, "quizzer": "Ann" }]))).unwrap_err();
+        assert!(errors[0].starts_with("Line 1: Team 2 is not in this game"));
+
+        let errors = to_event_stream(&game, &names, &sheet(serde_json::json!([{ "kind": "correct", "team": 1, "quizzer": "Ann" }]))).unwrap_err();
+        assert!(errors[0].starts_with("Line 1: Quizzer name 'Ann' not found"));
+
+        let errors = to_event_stream(&game, &names, &sheet(serde_json::json!([{ "kind": "substitution", "team": 0, "quizzer": "Amy", "seat": 0 }]))).unwrap_err();
+        assert!(errors[0].starts_with("Line 1: Quizzer 'Amy' can't be subbed in"));
+
+        let errors = to_event_stream(&game, &names, &sheet(serde_json::json!([{ "kind": "substitution", "team": 0, "quizzer": "", "seat": 0 }]))).unwrap_err();
+        assert!(errors[0].starts_with("Line 1: SB was specified but the name"));
+
+        let errors = to_event_stream(&game, &names[..1], &sheet(serde_json::json!([]))).unwrap_err();
+        assert_eq!(errors, vec!["The game has 1 teams but the scoresheet has 2".to_string()]);
+    }
+}
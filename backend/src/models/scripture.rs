@@ -0,0 +1,390 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+// Scripture references: book names and their aliases, and chapter:verse ranges. A reference is a
+// list of ranges, with ';' before another chapter or book and ',' before more verses of the same
+// chapter, e.g. "1 Cor 13:4-7", "John 3:16,18; 4:2", "Acts 2" or "Acts 2-3; Romans 8:28". Its
+// canonical form writes every book out as in BOOKS, joins verses of the same chapter with ',' and
+// everything else with "; ".
+
+// The books of the Bible and how many chapters each has.
+pub const BOOKS: [(&str, i32); 66] = [
+    ("Genesis", 50), ("Exodus", 40), ("Leviticus", 27), ("Numbers", 36), ("Deuteronomy", 34),
+    ("Joshua", 24), ("Judges", 21), ("Ruth", 4), ("1 Samuel", 31), ("2 Samuel", 24),
+    ("1 Kings", 22), ("2 Kings", 25), ("1 Chronicles", 29), ("2 Chronicles", 36), ("Ezra", 10),
+    ("Nehemiah", 13), ("Esther", 10), ("Job", 42), ("Psalms", 150), ("Proverbs", 31),
+    ("Ecclesiastes", 12), ("Song of Songs", 8), ("Isaiah", 66), ("Jeremiah", 52), ("Lamentations", 5),
+    ("Ezekiel", 48), ("Daniel", 12), ("Hosea", 14), ("Joel", 3), ("Amos", 9),
+    ("Obadiah", 1), ("Jonah", 4), ("Micah", 7), ("Nahum", 3), ("Habakkuk", 3),
+    ("Zephaniah", 3), ("Haggai", 2), ("Zechariah", 14), ("Malachi", 4), ("Matthew", 28),
+    ("Mark", 16), ("Luke", 24), ("John", 21), ("Acts", 28), ("Romans", 16),
+    ("1 Corinthians", 16), ("2 Corinthians", 13), ("Galatians", 6), ("Ephesians", 6), ("Philippians", 4),
+    ("Colossians", 4), ("1 Thessalonians", 5), ("2 Thessalonians", 3), ("1 Timothy", 6), ("2 Timothy", 4),
+    ("Titus", 3), ("Philemon", 1), ("Hebrews", 13), ("James", 5), ("1 Peter", 5),
+    ("2 Peter", 3), ("1 John", 5), ("2 John", 1), ("3 John", 1), ("Jude", 1),
+    ("Revelation", 22),
+];
+
+// Other names and abbreviations of the books, lowercase, after a leading "I", "First" or "1st" has
+// been turned into "1". The normalization migration has the same list.
+pub const ALIASES: [(&str, &str); 121] = [
+    ("gen", "Genesis"), ("ge", "Genesis"), ("gn", "Genesis"),
+    ("exod", "Exodus"), ("exo", "Exodus"), ("ex", "Exodus"),
+    ("lev", "Leviticus"), ("le", "Leviticus"), ("lv", "Leviticus"),
+    ("num", "Numbers"), ("nu", "Numbers"), ("nm", "Numbers"),
+    ("deut", "Deuteronomy"), ("dt", "Deuteronomy"), ("de", "Deuteronomy"),
+    ("josh", "Joshua"), ("jos", "Joshua"),
+    ("judg", "Judges"), ("jdg", "Judges"),
+    ("ru", "Ruth"), ("rth", "Ruth"),
+    ("1 sam", "1 Samuel"), ("1 sa", "1 Samuel"), ("2 sam", "2 Samuel"), ("2 sa", "2 Samuel"),
+    ("1 kgs", "1 Kings"), ("1 ki", "1 Kings"), ("2 kgs", "2 Kings"), ("2 ki", "2 Kings"),
+    ("1 chr", "1 Chronicles"), ("1 chron", "1 Chronicles"), ("2 chr", "2 Chronicles"), ("2 chron", "2 Chronicles"),
+    ("ezr", "Ezra"),
+    ("neh", "Nehemiah"),
+    ("est", "Esther"), ("esth", "Esther"),
+    ("jb", "Job"),
+    ("psalm", "Psalms"), ("ps", "Psalms"), ("psa", "Psalms"), ("pss", "Psalms"),
+    ("prov", "Proverbs"), ("pr", "Proverbs"), ("prv", "Proverbs"),
+    ("eccl", "Ecclesiastes"), ("ecc", "Ecclesiastes"), ("qoh", "Ecclesiastes"),
+    ("song", "Song of Songs"), ("song of solomon", "Song of Songs"), ("sos", "Song of Songs"), ("canticles", "Song of Songs"),
+    ("isa", "Isaiah"), ("is", "Isaiah"),
+    ("jer", "Jeremiah"),
+    ("lam", "Lamentations"),
+    ("ezek", "Ezekiel"), ("eze", "Ezekiel"),
+    ("dan", "Daniel"), ("dn", "Daniel"),
+    ("hos", "Hosea"),
+    ("jl", "Joel"),
+    ("am", "Amos"),
+    ("obad", "Obadiah"), ("ob", "Obadiah"),
+    ("jon", "Jonah"),
+    ("mic", "Micah"),
+    ("nah", "Nahum"),
+    ("hab", "Habakkuk"),
+    ("zeph", "Zephaniah"),
+    ("hag", "Haggai"),
+    ("zech", "Zechariah"),
+    ("mal", "Malachi"),
+    ("matt", "Matthew"), ("mt", "Matthew"),
+    ("mk", "Mark"), ("mrk", "Mark"),
+    ("lk", "Luke"), ("luk", "Luke"),
+    ("jn", "John"), ("jhn", "John"),
+    ("ac", "Acts"),
+    ("rom", "Romans"), ("ro", "Romans"),
+    ("1 cor", "1 Corinthians"), ("1 co", "1 Corinthians"), ("2 cor", "2 Corinthians"), ("2 co", "2 Corinthians"),
+    ("gal", "Galatians"),
+    ("eph", "Ephesians"),
+    ("phil", "Philippians"), ("php", "Philippians"),
+    ("col", "Colossians"),
+    ("1 thess", "1 Thessalonians"), ("1 th", "1 Thessalonians"), ("2 thess", "2 Thessalonians"), ("2 th", "2 Thessalonians"),
+    ("1 tim", "1 Timothy"), ("1 ti", "1 Timothy"), ("2 tim", "2 Timothy"), ("2 ti", "2 Timothy"),
+    ("tit", "Titus"),
+    ("philem", "Philemon"), ("phm", "Philemon"),
+    ("heb", "Hebrews"),
+    ("jas", "James"), ("jm", "James"),
+    ("1 pet", "1 Peter"), ("1 pe", "1 Peter"), ("2 pet", "2 Peter"), ("2 pe", "2 Peter"),
+    ("1 jn", "1 John"), ("1 jo", "1 John"), ("2 jn", "2 John"), ("2 jo", "2 John"), ("3 jn", "3 John"), ("3 jo", "3 John"),
+    ("jud", "Jude"),
+    ("rev", "Revelation"), ("re", "Revelation"), ("revelations", "Revelation"),
+];
+
+/// A range of scripture within one book. A verse that isn't given means the whole chapter.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct ScriptureRange {
+    pub book: String,                           // as in BOOKS
+    pub start_chapter: i32,
+    pub start_verse: Option<i32>,               // None: from the start of the chapter
+    pub end_chapter: i32,
+    pub end_verse: Option<i32>,                 // None: to the end of the chapter
+}
+
+impl ScriptureRange {
+    fn start(&self) -> (i32, i32) {
+        (self.start_chapter, self.start_verse.unwrap_or(0))
+    }
+
+    fn end(&self) -> (i32, i32) {
+        (self.end_chapter, self.end_verse.unwrap_or(i32::MAX))
+    }
+
+    /// Whether the two ranges share at least one verse.
+    pub fn touches(&self, other: &ScriptureRange) -> bool {
+        self.book == other.book && self.start() <= other.end() && other.start() <= self.end()
+    }
+
+    fn is_within_one_chapter(&self) -> bool {
+        self.start_chapter == self.end_chapter
+    }
+}
+
+impl fmt::Display for ScriptureRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.book, place(self))
+    }
+}
+
+// The chapter and verses of a range, without its book.
+fn place(range: &ScriptureRange) -> String {
+    let start = match range.start_verse {
+        Some(verse) => format!["{}:{}", range.start_chapter, verse],
+        None => range.start_chapter.to_string(),
+    };
+    let end = match (range.start_verse, range.end_verse) {
+        (Some(_), Some(verse)) if range.is_within_one_chapter() => verse.to_string(),
+        (_, Some(verse)) => format!["{}:{}", range.end_chapter, verse],
+        (_, None) => range.end_chapter.to_string(),
+    };
+    if range.is_within_one_chapter() && range.start_verse == range.end_verse {
+        start
+    } else {
+        format!["{}-{}", start, end]
+    }
+}
+
+/// Writes ranges in canonical form, e.g. "John 3:16,18; 4:2; Acts 2".
+pub fn format_ranges(ranges: &[ScriptureRange]) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&ScriptureRange> = None;
+    for range in ranges {
+        match previous {
+            None => text.push_str(&range.to_string()),
+            Some(p) if p.book != range.book => text.push_str(&format!["; {}", range]),
+            Some(p) if p.end_verse.is_some() && p.end_chapter == range.start_chapter && range.start_verse.is_some() => {
+                // more verses of the same chapter
+                let verses = place(range);
+                text.push_str(&format![",{}", verses.split_once(':').map(|(_, v)| v).unwrap_or(&verses)]);
+            },
+            Some(_) => text.push_str(&format!["; {}", place(range)]),
+        }
+        previous = Some(range);
+    }
+    text
+}
+
+fn normalize_name(name: &str) -> String {
+    let name = name.replace('.', " ").to_lowercase();
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let mut name = words.join(" ");
+    for (prefixes, numeral) in [(["iii ", "third ", "3rd "], "3 "), (["ii ", "second ", "2nd "], "2 "), (["i ", "first ", "1st "], "1 ")] {
+        if let Some(prefix) = prefixes.iter().find(|p| name.starts_with(*p)) {
+            name = format!["{}{}", numeral, &name[prefix.len()..]];
+            break;
+        }
+    }
+    // "1cor" is "1 cor"
+    if name.len() > 1 && name.as_bytes()[0].is_ascii_digit() && name.as_bytes()[1].is_ascii_alphabetic() {
+        name.insert(1, ' ');
+    }
+    name
+}
+
+/// Finds a book by its name or one of its aliases, e.g. "1 Cor", "I Corinthians" or "Psalm".
+pub fn find_book(name: &str) -> Option<(&'static str, i32)> {
+    let wanted = normalize_name(name);
+    let canonical = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == wanted)
+        .map(|(_, book)| book.to_lowercase())
+        .unwrap_or(wanted);
+    BOOKS.iter().find(|(book, _)| book.to_lowercase() == canonical).copied()
+}
+
+/// Reads verses written as "16", "16-18" or "16,18" into the verse numbers they start and end with.
+pub fn parse_verses(verses: &str) -> Result<(i32, i32), String> {
+    let numbers: Vec<Result<i32, _>> = verses.split(['-', ',']).map(|v| v.trim().parse::<i32>()).collect();
+    let numbers: Vec<i32> = numbers.into_iter().collect::<Result<_, _>>().map_err(|_| format!["'{}' is not a verse or range of verses", verses])?;
+    let (first, last) = (numbers[0], *numbers.last().unwrap());
+    if first < 1 || numbers.windows(2).any(|pair| pair[1] <= pair[0]) {
+        return Err(format!["'{}' is not a verse or range of verses", verses]);
+    }
+    Ok((first, last))
+}
+
+// Splits "1 Corinthians 13:4-7" into its book ("1 Corinthians") and the rest ("13:4-7"). A part
+// without a book (e.g. "4:2" after "John 3:16;") has an empty book.
+fn split_book(part: &str) -> (&str, &str) {
+    let part = part.trim();
+    let leading_numeral = part.chars().take_while(|c| c.is_ascii_digit()).count();
+    let has_book_after_numeral = part[leading_numeral..].trim_start().starts_with(|c: char| c.is_alphabetic());
+    let book_start = if leading_numeral > 0 && has_book_after_numeral { leading_numeral } else { 0 };
+    if book_start == 0 && !part.starts_with(|c: char| c.is_alphabetic()) {
+        return ("", part);
+    }
+    let book_end = part[book_start..]
+        .find(|c: char| c.is_ascii_digit())
+        .map(|idx| idx + book_start)
+        .unwrap_or(part.len());
+    (part[..book_end].trim(), part[book_end..].trim())
+}
+
+fn parse_number(text: &str, reference: &str) -> Result<i32, String> {
+    text.trim().parse::<i32>().map_err(|_| format!["'{}' is not a scripture reference like 'John 3:16'", reference])
+}
+
+// Parses a chapter or chapter:verse into the chapter and the verse, if any.
+fn parse_point(text: &str, reference: &str) -> Result<(i32, Option<i32>), String> {
+    match text.split_once(':') {
+        Some((chapter, verse)) => Ok((parse_number(chapter, reference)?, Some(parse_number(verse, reference)?))),
+        None => Ok((parse_number(text, reference)?, None)),
+    }
+}
+
+fn check_range(range: &ScriptureRange, chapters: i32, reference: &str) -> Result<(), String> {
+    for chapter in [range.start_chapter, range.end_chapter] {
+        if chapter < 1 || chapter > chapters {
+            return Err(format!["{} has {} chapters, not {}", range.book, chapters, chapter]);
+        }
+    }
+    if range.start_verse.is_some_and(|v| v < 1) || range.end_verse.is_some_and(|v| v < 1) {
+        return Err(format!["'{}' has a verse before verse 1", reference]);
+    }
+    if range.end() < range.start() {
+        return Err(format!["'{}' ends before it begins", reference]);
+    }
+    Ok(())
+}
+
+/// Reads a reference into its ranges, e.g. "1 Cor 13:4-7", "John 3:16,18; 4:2" or "Acts 2-3".
+/// A book on its own is the whole book.
+pub fn parse(reference: &str) -> Result<Vec<ScriptureRange>, String> {
+    let dashed = reference.replace(['–', '—'], "-");
+    let mut ranges: Vec<ScriptureRange> = vec![];
+    let mut book: Option<(&'static str, i32)> = None;
+    for part in dashed.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, places) = split_book(part);
+        if !name.is_empty() {
+            book = Some(find_book(name).ok_or_else(|| format!["'{}' is not a book of the Bible", name])?);
+        }
+        let (book_name, chapters) = book.ok_or_else(|| format!["'{}' doesn't say which book it's in", part])?;
+        if places.is_empty() {
+            ranges.push(ScriptureRange { book: book_name.to_string(), start_chapter: 1, start_verse: None, end_chapter: chapters, end_verse: None });
+            continue;
+        }
+
+        // a bare number after a chapter:verse is another verse of that chapter
+        let mut chapter_of_verses: Option<i32> = None;
+        for item in places.split(',').map(str::trim) {
+            let (from, to) = match item.split_once('-') {
+                Some((from, to)) => (from, Some(to)),
+                None => (item, None),
+            };
+            let (start_chapter, start_verse) = match (chapter_of_verses, from.contains(':')) {
+                (Some(chapter), false) => (chapter, Some(parse_number(from, reference)?)),
+                _ => parse_point(from, reference)?,
+            };
+            let (end_chapter, end_verse) = match to {
+                None => (start_chapter, start_verse),
+                Some(to) if to.contains(':') => parse_point(to, reference)?,
+                Some(to) if start_verse.is_some() => (start_chapter, Some(parse_number(to, reference)?)),
+                Some(to) => (parse_number(to, reference)?, None),
+            };
+            let range = ScriptureRange { book: book_name.to_string(), start_chapter, start_verse, end_chapter, end_verse };
+            check_range(&range, chapters, reference)?;
+            chapter_of_verses = end_verse.map(|_| end_chapter);
+            ranges.push(range);
+        }
+    }
+    if ranges.is_empty() {
+        return Err(format!["'{}' is not a scripture reference like 'John 3:16'", reference]);
+    }
+    Ok(ranges)
+}
+
+/// The ranges a question covers, from its book, chapter and verses.
+pub fn question_ranges(book: &str, chapter: i32, verses: &str) -> Result<Vec<ScriptureRange>, String> {
+    parse(&format!["{} {}:{}", book, chapter, verses])
+}
+
+/// Checks a question's scripture columns: a known book, a chapter it has, verses within that
+/// chapter, and verses that begin with `beginning_verse`.
+pub fn validate_question(book: &str, chapter: i32, verses: &str, beginning_verse: i32) -> Vec<String> {
+    let mut errors = vec![];
+    match find_book(book) {
+        None => errors.push(format!["'{}' is not a book of the Bible", book]),
+        Some((name, chapters)) if chapter > chapters => errors.push(format!["{} has {} chapters, not {}", name, chapters, chapter]),
+        Some(_) => match question_ranges(book, chapter, verses) {
+            Ok(ranges) if ranges.iter().any(|r| r.start_chapter != chapter || r.end_chapter != chapter || r.start_verse.is_none()) => {
+                errors.push(format!["verses '{}' aren't all in chapter {}", verses, chapter]);
+            },
+            Ok(ranges) if ranges[0].start_verse != Some(beginning_verse) => {
+                errors.push(format!["verses '{}' don't begin with verse {}", verses, beginning_verse]);
+            },
+            Ok(_) => {},
+            Err(_) => errors.push(format!["'{}' is not a verse or range of verses", verses]),
+        },
+    }
+    errors
+}
+
+/// The canonical book name and verses of a question, e.g. ("1 Corinthians", "4-7") for
+/// ("1 cor", "4 - 7"), or None when they don't parse.
+pub fn canonical_question(book: &str, chapter: i32, verses: &str) -> Option<(String, String)> {
+    let ranges = question_ranges(book, chapter, verses).ok()?;
+    let text = format_ranges(&ranges);
+    let prefix = format!["{} {}:", ranges[0].book, chapter];
+    let canonical_verses = text.strip_prefix(&prefix)?.to_string();
+    Some((ranges[0].book.clone(), canonical_verses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(book: &str, start: (i32, Option<i32>), end: (i32, Option<i32>)) -> ScriptureRange {
+        ScriptureRange { book: book.to_string(), start_chapter: start.0, start_verse: start.1, end_chapter: end.0, end_verse: end.1 }
+    }
+
+    #[test]
+    fn find_book_knows_aliases() {
+        assert_eq!(find_book("1 Cor").map(|(b, _)| b), Some("1 Corinthians"));
+        assert_eq!(find_book("I Corinthians").map(|(b, _)| b), Some("1 Corinthians"));
+        assert_eq!(find_book("1cor.").map(|(b, _)| b), Some("1 Corinthians"));
+        assert_eq!(find_book("Second  Timothy").map(|(b, _)| b), Some("2 Timothy"));
+        assert_eq!(find_book("psalm").map(|(b, _)| b), Some("Psalms"));
+        assert_eq!(find_book("III Jn").map(|(b, _)| b), Some("3 John"));
+        assert_eq!(find_book("Hezekiah"), None);
+    }
+
+    #[test]
+    fn parse_reads_ranges_and_lists() {
+        assert_eq!(parse("1 Corinthians 13:4-7").unwrap(), vec![range("1 Corinthians", (13, Some(4)), (13, Some(7)))]);
+        assert_eq!(parse("Acts 2").unwrap(), vec![range("Acts", (2, None), (2, None))]);
+        assert_eq!(parse("acts 2-3").unwrap(), vec![range("Acts", (2, None), (3, None))]);
+        assert_eq!(parse("John 3:16-4:2").unwrap(), vec![range("John", (3, Some(16)), (4, Some(2)))]);
+        assert_eq!(parse("Jude").unwrap(), vec![range("Jude", (1, None), (1, None))]);
+        let ranges = parse("Jn 3:16, 18; 4:2; 1 Jn 1:9").unwrap();
+        assert_eq!(ranges, vec![
+            range("John", (3, Some(16)), (3, Some(16))),
+            range("John", (3, Some(18)), (3, Some(18))),
+            range("John", (4, Some(2)), (4, Some(2))),
+            range("1 John", (1, Some(9)), (1, Some(9))),
+        ]);
+        assert_eq!(format_ranges(&ranges), "John 3:16,18; 4:2; 1 John 1:9");
+        assert_eq!(format_ranges(&parse("acts 2 ; 4:1-3").unwrap()), "Acts 2; 4:1-3");
+
+        assert_eq!(parse("Jude 2").unwrap_err(), "Jude has 1 chapters, not 2");
+        assert_eq!(parse("John 3:18-16").unwrap_err(), "'John 3:18-16' ends before it begins");
+        assert_eq!(parse("3:16").unwrap_err(), "'3:16' doesn't say which book it's in");
+        assert!(parse("Hezekiah 1:1").is_err());
+    }
+
+    #[test]
+    fn touches_works() {
+        let acts_2 = range("Acts", (2, None), (2, None));
+        assert!(acts_2.touches(&range("Acts", (2, Some(40)), (2, Some(41)))));
+        assert!(acts_2.touches(&range("Acts", (1, Some(26)), (2, Some(1)))));
+        assert!(!acts_2.touches(&range("Acts", (3, Some(1)), (3, Some(1)))));
+        assert!(!acts_2.touches(&range("Romans", (2, Some(1)), (2, Some(1)))));
+    }
+
+    #[test]
+    fn validate_question_works() {
+        assert!(validate_question("John", 3, "16-18", 16).is_empty());
+        assert!(validate_question("Jn", 3, "16,18", 16).is_empty());
+        assert_eq!(validate_question("John", 3, "16-18", 17), vec!["verses '16-18' don't begin with verse 17".to_string()]);
+        assert_eq!(validate_question("John", 3, "36-4:2", 36), vec!["verses '36-4:2' aren't all in chapter 3".to_string()]);
+        assert_eq!(validate_question("John", 22, "1", 1), vec!["John has 21 chapters, not 22".to_string()]);
+        assert_eq!(canonical_question("1 cor", 13, "4 - 7"), Some(("1 Corinthians".to_string(), "4-7".to_string())));
+        assert_eq!(canonical_question("John", 3, "16, 18"), Some(("John".to_string(), "16,18".to_string())));
+    }
+}
@@ -313,6 +313,24 @@ pub fn read_all_teams_where_user_is_quizzer(
         .load::<Team>(db)
 }
 
+/// Whether the user sits in one of the seats of a team in the division.
+pub fn is_seated_in_division(db: &mut database::Connection, division_id: Uuid, user_id: Uuid) -> QueryResult<bool> {
+    use crate::schema::teams::dsl::*;
+    teams
+        .filter(did.eq(division_id))
+        .filter(
+            quizzer_one_id.eq(user_id)
+                .or(quizzer_two_id.eq(user_id))
+                .or(quizzer_three_id.eq(user_id))
+                .or(quizzer_four_id.eq(user_id))
+                .or(quizzer_five_id.eq(user_id))
+                .or(quizzer_six_id.eq(user_id))
+        )
+        .count()
+        .get_result::<i64>(db)
+        .map(|n| n > 0)
+}
+
 pub fn update(db: &mut database::Connection, item_id: Uuid, item: &TeamChangeset) -> QueryResult<Team> {
     use crate::schema::teams::dsl::*;
     diesel::update(teams.filter(teamid.eq(item_id)))
@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::database;
+use crate::models::{calendar::GAME_MINUTES, game::Game, room::Room, round::Round};
+use crate::models::common::invalid;
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::QueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// A game is projected to start at its round's scheduled start plus the game's own offset, which
+// a room shift adds to. Actual times come from the first and last events the room sent.
+
+pub const BEHIND_THRESHOLD_MINUTES: i64 = 5;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TimelineParams {
+    pub now: Option<DateTime<Utc>>,             // defaults to the current time
+    pub threshold_minutes: Option<i64>,         // rooms fewer minutes behind than this aren't listed
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct TimelineEntry {
+    pub gid: Uuid,
+    pub roomid: Uuid,
+    pub room_name: String,
+    pub roundid: Uuid,
+    pub round_name: String,
+    pub divisionid: Uuid,
+    pub scheduled_start: Option<DateTime<Utc>>,
+    pub projected_start: Option<DateTime<Utc>>,
+    pub projected_end: Option<DateTime<Utc>>,
+    pub actual_start: Option<DateTime<Utc>>,
+    pub actual_end: Option<DateTime<Utc>>,
+    pub start_delay_minutes: Option<i64>,       // actual start minus projected start
+    pub status: String,                         // unscheduled, pending or started
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct RoomBehind {
+    pub roomid: Uuid,
+    pub room_name: String,
+    pub gid: Uuid,                              // the room's next game
+    pub projected_start: DateTime<Utc>,
+    pub estimated_start: DateTime<Utc>,
+    pub minutes_behind: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct TimelineReport {
+    pub tid: Uuid,
+    pub now: DateTime<Utc>,
+    pub entries: Vec<TimelineEntry>,
+    pub rooms_behind: Vec<RoomBehind>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ShiftRequest {
+    pub minutes: i32,                           // negative pulls the schedule earlier
+    pub from: Option<DateTime<Utc>>,            // only what starts at or after this moves; defaults to now
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ShiftResult {
+    pub minutes: i32,
+    pub from: DateTime<Utc>,
+    pub roundids: Vec<Uuid>,                    // rounds whose scheduled start moved
+    pub gids: Vec<Uuid>,                        // games whose projected start moved
+}
+
+pub fn projected_start(round: &Round, game: &Game) -> Option<DateTime<Utc>> {
+    round.scheduled_start_time.map(|start| start + Duration::minutes(game.start_offset_minutes.into()))
+}
+
+fn entry(game: &Game, round: Option<&Round>, room: Option<&Room>) -> TimelineEntry {
+    let projected = round.and_then(|r| projected_start(r, game));
+    let status = match (projected, game.actual_start_time) {
+        (_, Some(_)) => "started",
+        (Some(_), None) => "pending",
+        (None, None) => "unscheduled",
+    };
+    TimelineEntry {
+        gid: game.gid,
+        roomid: game.roomid,
+        room_name: room.map(|r| r.name.clone()).unwrap_or_default(),
+        roundid: game.roundid,
+        round_name: round.map(|r| r.name.clone()).unwrap_or_default(),
+        divisionid: game.divisionid,
+        scheduled_start: round.and_then(|r| r.scheduled_start_time),
+        projected_start: projected,
+        projected_end: projected.map(|p| p + Duration::minutes(GAME_MINUTES)),
+        actual_start: game.actual_start_time,
+        actual_end: game.actual_end_time,
+        start_delay_minutes: projected.zip(game.actual_start_time).map(|(p, a)| (a - p).num_minutes()),
+        status: status.to_string(),
+    }
+}
+
+/// How late a room's next game will start: not before now, and not before the game the room
+/// started last has had its full length. None once the room has nothing left to start.
+pub fn room_behind(entries: &[TimelineEntry], now: DateTime<Utc>) -> Option<RoomBehind> {
+    let next = entries
+        .iter()
+        .filter(|e| e.actual_start.is_none())
+        .filter_map(|e| e.projected_start.map(|p| (p, e)))
+        .min_by_key(|(p, _)| *p)?;
+    let last_started = entries.iter().filter_map(|e| e.actual_start).max();
+
+    let mut estimated = now.max(next.0);
+    if let Some(started) = last_started {
+        estimated = estimated.max(started + Duration::minutes(GAME_MINUTES));
+    }
+    Some(RoomBehind {
+        roomid: next.1.roomid,
+        room_name: next.1.room_name.clone(),
+        gid: next.1.gid,
+        projected_start: next.0,
+        estimated_start: estimated,
+        minutes_behind: (estimated - next.0).num_minutes(),
+    })
+}
+
+pub fn read_timeline(db: &mut database::Connection, tournament_id: Uuid, params: &TimelineParams) -> QueryResult<TimelineReport> {
+    use crate::schema::{games, rooms, rounds};
+
+    let now = params.now.unwrap_or_else(Utc::now);
+    let threshold = params.threshold_minutes.unwrap_or(BEHIND_THRESHOLD_MINUTES);
+
+    let games: Vec<Game> = games::table
+        .filter(games::tournamentid.eq(tournament_id))
+        .filter(games::ignore.eq(false))
+        .load::<Game>(db)?;
+    let round_ids: HashSet<Uuid> = games.iter().map(|g| g.roundid).collect();
+    let rounds: HashMap<Uuid, Round> = rounds::table.filter(rounds::roundid.eq_any(round_ids)).load::<Round>(db)?.into_iter().map(|r| (r.roundid, r)).collect();
+    let rooms: HashMap<Uuid, Room> = rooms::table.filter(rooms::tid.eq(tournament_id)).load::<Room>(db)?.into_iter().map(|r| (r.roomid, r)).collect();
+
+    let mut entries: Vec<TimelineEntry> = games.iter().map(|g| entry(g, rounds.get(&g.roundid), rooms.get(&g.roomid))).collect();
+    entries.sort_by(|a, b| (a.projected_start.is_none(), a.projected_start, &a.room_name).cmp(&(b.projected_start.is_none(), b.projected_start, &b.room_name)));
+
+    let mut by_room: BTreeMap<Uuid, Vec<TimelineEntry>> = BTreeMap::new();
+    for e in &entries {
+        by_room.entry(e.roomid).or_default().push(e.clone());
+    }
+    let mut rooms_behind: Vec<RoomBehind> = by_room
+        .values()
+        .filter_map(|room_entries| room_behind(room_entries, now))
+        .filter(|r| r.minutes_behind >= threshold)
+        .collect();
+    rooms_behind.sort_by(|a, b| b.minutes_behind.cmp(&a.minutes_behind).then_with(|| a.room_name.cmp(&b.room_name)));
+
+    Ok(TimelineReport { tid: tournament_id, now, entries, rooms_behind })
+}
+
+fn validate(req: &ShiftRequest) -> QueryResult<()> {
+    if req.minutes == 0 {
+        return Err(invalid("minutes must not be 0".to_string()));
+    }
+    Ok(())
+}
+
+/// Moves the division's rounds that start at or after `from`. A round that already has a game
+/// underway stays where it is, so the rooms still playing it aren't reported behind.
+pub fn shift_division(db: &mut database::Connection, division_id: Uuid, req: &ShiftRequest) -> QueryResult<ShiftResult> {
+    use crate::schema::{games, rounds};
+
+    validate(req)?;
+    let from = req.from.unwrap_or_else(Utc::now);
+
+    db.transaction(|db| {
+        let later: Vec<Round> = rounds::table
+            .filter(rounds::did.eq(division_id))
+            .filter(rounds::scheduled_start_time.ge(from))
+            .load::<Round>(db)?;
+        let later_ids: Vec<Uuid> = later.iter().map(|r| r.roundid).collect();
+        let underway: HashSet<Uuid> = games::table
+            .filter(games::roundid.eq_any(&later_ids))
+            .filter(games::actual_start_time.is_not_null())
+            .select(games::roundid)
+            .load::<Uuid>(db)?
+            .into_iter()
+            .collect();
+        let mut roundids: Vec<Uuid> = later_ids.into_iter().filter(|r| !underway.contains(r)).collect();
+        roundids.sort();
+
+        for round in later.iter().filter(|r| roundids.contains(&r.roundid)) {
+            let Some(start) = round.scheduled_start_time else { continue };
+            diesel::update(rounds::table.find(round.roundid))
+                .set((rounds::scheduled_start_time.eq(start + Duration::minutes(req.minutes.into())), rounds::updated_at.eq(Utc::now())))
+                .execute(db)?;
+        }
+        let mut gids: Vec<Uuid> = games::table
+            .filter(games::roundid.eq_any(&roundids))
+            .select(games::gid)
+            .load::<Uuid>(db)?;
+        gids.sort();
+
+        Ok(ShiftResult { minutes: req.minutes, from, roundids, gids })
+    })
+}
+
+/// Moves only this room: its games that haven't started and are projected at or after `from`
+/// get the minutes added to their offset. The rounds, and the other rooms, stay put.
+pub fn shift_room(db: &mut database::Connection, room_id: Uuid, req: &ShiftRequest) -> QueryResult<ShiftResult> {
+    use crate::schema::{games, rounds};
+
+    validate(req)?;
+    let from = req.from.unwrap_or_else(Utc::now);
+
+    db.transaction(|db| {
+        let pending: Vec<Game> = games::table
+            .filter(games::roomid.eq(room_id))
+            .filter(games::ignore.eq(false))
+            .filter(games::actual_start_time.is_null())
+            .load::<Game>(db)?;
+        let round_ids: HashSet<Uuid> = pending.iter().map(|g| g.roundid).collect();
+        let rounds: HashMap<Uuid, Round> = rounds::table.filter(rounds::roundid.eq_any(round_ids)).load::<Round>(db)?.into_iter().map(|r| (r.roundid, r)).collect();
+
+        let mut gids: Vec<Uuid> = pending
+            .iter()
+            .filter(|g| rounds.get(&g.roundid).and_then(|r| projected_start(r, g)).is_some_and(|p| p >= from))
+            .map(|g| g.gid)
+            .collect();
+        gids.sort();
+
+        diesel::update(games::table.filter(games::gid.eq_any(&gids)))
+            .set((games::start_offset_minutes.eq(games::start_offset_minutes + req.minutes), games::updated_at.eq(Utc::now())))
+            .execute(db)?;
+
+        Ok(ShiftResult { minutes: req.minutes, from, roundids: vec![], gids })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap()
+    }
+
+    fn timeline_entry(roomid: Uuid, projected: DateTime<Utc>, actual: Option<DateTime<Utc>>) -> TimelineEntry {
+        TimelineEntry {
+            gid: Uuid::new_v4(),
+            roomid,
+            room_name: "Room 1".to_string(),
+            roundid: Uuid::new_v4(),
+            round_name: String::new(),
+            divisionid: Uuid::new_v4(),
+            scheduled_start: Some(projected),
+            projected_start: Some(projected),
+            projected_end: Some(projected + Duration::minutes(GAME_MINUTES)),
+            actual_start: actual,
+            actual_end: actual,
+            start_delay_minutes: actual.map(|a| (a - projected).num_minutes()),
+            status: if actual.is_some() { "started" } else { "pending" }.to_string(),
+        }
+    }
+
+    #[test]
+    fn room_behind_works() {
+        let room = Uuid::new_v4();
+        let entries = vec![timeline_entry(room, at(9, 0), Some(at(9, 12))), timeline_entry(room, at(9, 30), None)];
+
+        // the first game started 12 minutes late, so the second can't start on time either
+        let behind = room_behind(&entries, at(9, 20)).unwrap();
+        assert_eq!(behind.gid, entries[1].gid);
+        assert_eq!(behind.estimated_start, at(9, 42));
+        assert_eq!(behind.minutes_behind, 12);
+
+        // still waiting at 9:55
+        assert_eq!(room_behind(&entries, at(9, 55)).unwrap().minutes_behind, 25);
+
+        // on time
+        let on_time = vec![timeline_entry(room, at(9, 0), Some(at(9, 0))), timeline_entry(room, at(9, 30), None)];
+        assert_eq!(room_behind(&on_time, at(9, 10)).unwrap().minutes_behind, 0);
+
+        // nothing left to start
+        assert!(room_behind(&entries[..1], at(9, 20)).is_none());
+    }
+}
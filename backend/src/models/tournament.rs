@@ -27,6 +27,7 @@ pub struct TournamentBuilder {
     info: Option<String>,
     owner_id: Option<Uuid>,
     pairing_code: Option<String>,
+    quiz_season: Option<i32>,
 }
 
 impl TournamentBuilder {
@@ -47,6 +48,7 @@ impl TournamentBuilder {
             info: None,
             owner_id: None,
             pairing_code: None,
+            quiz_season: None,
         }
     }
     pub fn new_default(tname: &str) -> Self {
@@ -66,7 +68,8 @@ impl TournamentBuilder {
             shortinfo: Some("Winter Olympics".to_string()),
             info: Some("Shawn White did excellent in the halfpipe.".to_string()),
             owner_id: None,
-            pairing_code: None
+            pairing_code: None,
+            quiz_season: None,
         }
     }
     
@@ -130,6 +133,10 @@ impl TournamentBuilder {
         self.pairing_code = Some(pairing_code.to_string());
         self
     }
+    pub fn set_quiz_season(mut self, quiz_season: i32) -> Self {
+        self.quiz_season = Some(quiz_season);
+        self
+    }
     fn validate_all_are_some(&self) -> Result<bool, Vec<String>> {
 
         let mut errors = Vec::new();
@@ -209,6 +216,7 @@ impl TournamentBuilder {
                     owner_id: self.owner_id.unwrap(),
                     creator_id: self.owner_id.unwrap(),
                     pairing_code: self.pairing_code.unwrap_or(pairing_code),
+                    quiz_season: self.quiz_season,
                 })
             }
         }
@@ -265,6 +273,7 @@ pub struct Tournament {
     pub registration_is_open: bool,
     pub creator_id: Uuid,
     pub pairing_code: String,
+    pub quiz_season: Option<i32>,               // the season quizzed, unless a division has its own
 }
 
 #[derive(
@@ -293,6 +302,7 @@ pub struct NewTournament {
     pub owner_id: Uuid,
     pub creator_id: Uuid,
     pub pairing_code: String,
+    pub quiz_season: Option<i32>,
 }
 
 /// Payload accepted from the frontend for tournament creation (no owner_id — that is
@@ -312,6 +322,7 @@ pub struct NewTournamentPayload {
     pub contactemail: String,
     pub shortinfo: String,
     pub info: String,
+    pub quiz_season: Option<i32>,
 }
 
 // #[tsync::tsync]
@@ -335,10 +346,14 @@ pub struct TournamentChangeset {
     pub info: Option<String>,
     pub registration_is_open: Option<bool>,
     pub pairing_code: Option<String>,
+    pub quiz_season: Option<i32>,
 }
 
 pub fn create(db: &mut database::Connection, item: &NewTournament) -> QueryResult<Tournament> {
     use crate::schema::tournaments::dsl::*;
+    if let Some(season) = item.quiz_season {
+        crate::models::quiz_season::check_exists(db, season)?;
+    }
     diesel::insert_into(tournaments)
         .values(item)
         .get_result::<Tournament>(db)
@@ -462,6 +477,9 @@ pub fn read_all_tournaments_of_tournamentgroup(db: &mut database::Connection, tg
 
 pub fn update(db: &mut database::Connection, item_id: Uuid, item: &TournamentChangeset) -> QueryResult<Tournament> {
     use crate::schema::tournaments::dsl::*;
+    if let Some(season) = item.quiz_season {
+        crate::models::quiz_season::check_exists(db, season)?;
+    }
     diesel::update(tournaments.filter(tid.eq(item_id)))
         .set((
             item,
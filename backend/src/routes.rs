@@ -51,6 +51,11 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .service(services::users_roles::endpoints(web::scope("/usersroles")))
             .service(services::tournamentgroup::endpoints(web::scope("/tournamentgroups")))
             .service(services::statsgroup::endpoints(web::scope("/statsgroups")))
+            .service(services::bracket::endpoints(web::scope("/brackets")))
+            .service(services::calendar::endpoints(web::scope("/calendar")))
+            .service(services::question::endpoints(web::scope("/questions")))
+            .service(services::quiz_season::endpoints(web::scope("/seasons")))
+            .service(services::competition_level::endpoints(web::scope("/competitionlevels")))
             .service(services::create_tournament_applicant::endpoints(web::scope("/createtournamentapplicants")))
             .service(services::roster::endpoints(web::scope("/rosters")))
             .service(services::equipmentset::endpoints(web::scope("/equipmentsets")))
@@ -47,6 +47,96 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    award_settings (did) {
+        did -> Uuid,
+        individual_scorers_cutoff -> Int4,
+        quiz_outs_cutoff -> Int4,
+        team_placements_cutoff -> Int4,
+        rookies_cutoff -> Int4,
+        include_perfect_games -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        forfeit_win_score -> Int4,
+        forfeit_loss_score -> Int4,
+        bye_counts_as_win -> Bool,
+        bye_score -> Int4,
+        questions_per_game -> Int4,
+    }
+}
+
+diesel::table! {
+    bracket_nodes (nodeid) {
+        nodeid -> Uuid,
+        bracketid -> Uuid,
+        #[max_length = 16]
+        side -> Varchar,
+        round_num -> Int4,
+        position -> Int4,
+        gid -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    bracket_seats (nodeid, seat) {
+        nodeid -> Uuid,
+        seat -> Int4,
+        seed -> Nullable<Int4>,
+        teamid -> Nullable<Uuid>,
+        from_nodeid -> Nullable<Uuid>,
+        from_place -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    brackets (bracketid) {
+        bracketid -> Uuid,
+        did -> Uuid,
+        #[max_length = 64]
+        name -> Varchar,
+        #[max_length = 32]
+        format -> Varchar,
+        team_size -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    calendar_feeds (feedid) {
+        feedid -> Uuid,
+        token -> Text,
+        #[max_length = 16]
+        scope -> Varchar,
+        subject_id -> Uuid,
+        created_by -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    certificate_templates (tid) {
+        tid -> Uuid,
+        #[max_length = 64]
+        name -> Varchar,
+        svg -> Text,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    competition_levels (level) {
+        level -> Int4,
+        #[max_length = 32]
+        name -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     computers (computerid) {
         computerid -> Int8,
@@ -100,6 +190,16 @@ diesel::table! {
         shortinfo -> Varchar,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        quiz_season -> Nullable<Int4>,
+        competition_lvl -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    division_rookies (did, quizzerid) {
+        did -> Uuid,
+        quizzerid -> Uuid,
+        created_at -> Timestamptz,
     }
 }
 
@@ -202,6 +302,63 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    game_audits (auditid) {
+        auditid -> Uuid,
+        tid -> Uuid,
+        gid -> Uuid,
+        source_gid -> Nullable<Uuid>,
+        #[max_length = 16]
+        action -> Varchar,
+        detail -> Text,
+        performed_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    game_eventlog_keys (tid, clientkey, division, room, round) {
+        tid -> Uuid,
+        #[max_length = 64]
+        clientkey -> Varchar,
+        #[max_length = 48]
+        division -> Varchar,
+        #[max_length = 48]
+        room -> Varchar,
+        #[max_length = 48]
+        round -> Varchar,
+        gid -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    game_outcomes (gid) {
+        gid -> Uuid,
+        #[max_length = 16]
+        outcome -> Varchar,
+        #[max_length = 32]
+        reason -> Varchar,
+        teamid -> Nullable<Uuid>,
+        note -> Text,
+        recorded_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    game_questions (gid, question) {
+        gid -> Uuid,
+        question -> Int4,
+        questionid -> Uuid,
+        #[max_length = 16]
+        source -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     gameevents (gid, question, eventnum) {
         gid -> Uuid,
@@ -245,6 +402,9 @@ diesel::table! {
         updated_at -> Timestamptz,
         #[max_length = 64]
         clientkey -> Varchar,
+        actual_start_time -> Nullable<Timestamptz>,
+        actual_end_time -> Nullable<Timestamptz>,
+        start_offset_minutes -> Int4,
     }
 }
 
@@ -280,6 +440,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    manual_scoresheets (gid) {
+        gid -> Uuid,
+        entered_by -> Nullable<Uuid>,
+        scoresheet -> Text,
+        events -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     microphonerecorders (id) {
         id -> Int8,
@@ -307,6 +478,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    packet_exports (id) {
+        id -> Uuid,
+        roundid -> Uuid,
+        exported_by -> Nullable<Uuid>,
+        scheduled_start_time -> Nullable<Timestamptz>,
+        questions -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     password_reset_tokens (token) {
         token -> Text,
@@ -383,6 +565,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    quiz_seasons (season) {
+        season -> Int4,
+        #[max_length = 64]
+        name -> Varchar,
+        year -> Nullable<Int4>,
+        #[max_length = 255]
+        material -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     roles (id) {
         #[max_length = 100]
@@ -450,6 +645,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    round_packets (roundid) {
+        roundid -> Uuid,
+        rules -> Nullable<Text>,
+        seed -> Nullable<Int8>,
+        adjusted -> Bool,
+        updated_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    round_questions (roundid, role, position) {
+        roundid -> Uuid,
+        #[max_length = 16]
+        role -> Varchar,
+        position -> Int4,
+        questionid -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     rounds (roundid) {
         roundid -> Uuid,
@@ -457,58 +675,62 @@ diesel::table! {
         scheduled_start_time -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
-        scheduled_question_one_id -> Nullable<Uuid>,
-        scheduled_question_two_id -> Nullable<Uuid>,
-        scheduled_question_three_id -> Nullable<Uuid>,
-        scheduled_question_four_id -> Nullable<Uuid>,
-        scheduled_question_five_id -> Nullable<Uuid>,
-        scheduled_question_six_id -> Nullable<Uuid>,
-        scheduled_question_seven_id -> Nullable<Uuid>,
-        scheduled_question_eight_id -> Nullable<Uuid>,
-        scheduled_question_nine_id -> Nullable<Uuid>,
-        scheduled_question_ten_id -> Nullable<Uuid>,
-        scheduled_question_eleven_id -> Nullable<Uuid>,
-        scheduled_question_twelve_id -> Nullable<Uuid>,
-        scheduled_question_thirteen_id -> Nullable<Uuid>,
-        scheduled_question_fourteen_id -> Nullable<Uuid>,
-        scheduled_question_fifteen_id -> Nullable<Uuid>,
-        scheduled_question_sixteen_id -> Nullable<Uuid>,
-        scheduled_question_seventeen_id -> Nullable<Uuid>,
-        scheduled_question_eighteen_id -> Nullable<Uuid>,
-        scheduled_question_nineteen_id -> Nullable<Uuid>,
-        scheduled_question_twenty_id -> Nullable<Uuid>,
         #[max_length = 64]
         name -> Varchar,
     }
 }
 
+diesel::table! {
+    rulings (rulingid) {
+        rulingid -> Uuid,
+        gid -> Uuid,
+        question -> Int4,
+        questionid -> Nullable<Uuid>,
+        #[max_length = 16]
+        kind -> Varchar,
+        teamid -> Uuid,
+        argument -> Text,
+        #[max_length = 16]
+        ruling -> Varchar,
+        rationale -> Text,
+        event_question -> Nullable<Int4>,
+        event_num -> Nullable<Int4>,
+        judged_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     schedules (sid) {
-        sid -> Int8,
-        tid -> Int8,
-        roundtime -> Timestamptz,
+        sid -> Uuid,
+        tid -> Uuid,
+        roundtime -> Nullable<Timestamptz>,
         #[max_length = 32]
         tournament -> Varchar,
         #[max_length = 32]
         division -> Varchar,
         #[max_length = 32]
         room -> Varchar,
-        #[max_length = 32]
+        #[max_length = 64]
         round -> Varchar,
-        #[max_length = 32]
+        #[max_length = 128]
         team1 -> Nullable<Varchar>,
-        #[max_length = 32]
+        #[max_length = 128]
         team2 -> Nullable<Varchar>,
-        #[max_length = 32]
+        #[max_length = 128]
         team3 -> Nullable<Varchar>,
-        #[max_length = 32]
+        #[max_length = 128]
         quizmaster -> Nullable<Varchar>,
-        #[max_length = 32]
+        #[max_length = 128]
         contentjudge -> Nullable<Varchar>,
-        #[max_length = 32]
+        #[max_length = 128]
         scorekeeper -> Nullable<Varchar>,
         #[max_length = 1024]
         stats -> Nullable<Varchar>,
+        gid -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -598,6 +820,7 @@ diesel::table! {
         creator_id -> Uuid,
         #[max_length = 64]
         pairing_code -> Varchar,
+        quiz_season -> Nullable<Int4>,
     }
 }
 
@@ -655,6 +878,17 @@ diesel::table! {
 
 diesel::joinable!(activation_tokens -> users (user_id));
 diesel::joinable!(attachments -> attachment_blobs (blob_id));
+diesel::joinable!(award_settings -> divisions (did));
+diesel::joinable!(bracket_nodes -> brackets (bracketid));
+diesel::joinable!(bracket_nodes -> games (gid));
+diesel::joinable!(bracket_seats -> teams (teamid));
+diesel::joinable!(brackets -> divisions (did));
+diesel::joinable!(calendar_feeds -> users (created_by));
+diesel::joinable!(certificate_templates -> tournaments (tid));
+diesel::joinable!(division_rookies -> divisions (did));
+diesel::joinable!(division_rookies -> users (quizzerid));
+diesel::joinable!(divisions -> competition_levels (competition_lvl));
+diesel::joinable!(divisions -> quiz_seasons (quiz_season));
 diesel::joinable!(equipment -> computers (computerid));
 diesel::joinable!(equipment -> equipmentsets (equipmentsetid));
 diesel::joinable!(equipment -> extensioncords (extensioncordid));
@@ -668,6 +902,15 @@ diesel::joinable!(equipmentregistrations -> equipment (equipmentid));
 diesel::joinable!(equipmentregistrations -> rooms (roomid));
 diesel::joinable!(equipmentregistrations -> tournaments (tournamentid));
 diesel::joinable!(equipmentsets -> users (equipmentownerid));
+diesel::joinable!(game_audits -> tournaments (tid));
+diesel::joinable!(game_audits -> users (performed_by));
+diesel::joinable!(game_eventlog_keys -> games (gid));
+diesel::joinable!(game_eventlog_keys -> tournaments (tid));
+diesel::joinable!(game_outcomes -> games (gid));
+diesel::joinable!(game_outcomes -> teams (teamid));
+diesel::joinable!(game_outcomes -> users (recorded_by));
+diesel::joinable!(game_questions -> games (gid));
+diesel::joinable!(game_questions -> questionsandanswers (questionid));
 diesel::joinable!(gameevents -> games (gid));
 diesel::joinable!(games -> divisions (divisionid));
 diesel::joinable!(games -> rooms (roomid));
@@ -675,7 +918,13 @@ diesel::joinable!(games -> rounds (roundid));
 diesel::joinable!(games -> tournaments (tournamentid));
 diesel::joinable!(games_statsgroups -> games (gameid));
 diesel::joinable!(games_statsgroups -> statsgroups (statsgroupid));
+diesel::joinable!(manual_scoresheets -> games (gid));
+diesel::joinable!(manual_scoresheets -> users (entered_by));
+diesel::joinable!(packet_exports -> rounds (roundid));
+diesel::joinable!(packet_exports -> users (exported_by));
 diesel::joinable!(password_reset_tokens -> users (user_id));
+diesel::joinable!(questionsandanswers -> competition_levels (competition_lvl));
+diesel::joinable!(questionsandanswers -> quiz_seasons (quiz_season));
 diesel::joinable!(roles_permissions -> permissions (permission_id));
 diesel::joinable!(roles_permissions -> roles (role_id));
 diesel::joinable!(rooms -> tournaments (tid));
@@ -684,10 +933,21 @@ diesel::joinable!(rosters_coaches -> rosters (rosterid));
 diesel::joinable!(rosters_coaches -> users (coachid));
 diesel::joinable!(rosters_quizzers -> rosters (rosterid));
 diesel::joinable!(rosters_quizzers -> users (quizzerid));
+diesel::joinable!(round_packets -> rounds (roundid));
+diesel::joinable!(round_packets -> users (updated_by));
+diesel::joinable!(round_questions -> questionsandanswers (questionid));
+diesel::joinable!(round_questions -> rounds (roundid));
 diesel::joinable!(rounds -> divisions (did));
+diesel::joinable!(rulings -> games (gid));
+diesel::joinable!(rulings -> questionsandanswers (questionid));
+diesel::joinable!(rulings -> teams (teamid));
+diesel::joinable!(rulings -> users (judged_by));
+diesel::joinable!(schedules -> games (gid));
+diesel::joinable!(schedules -> tournaments (tid));
 diesel::joinable!(teams -> divisions (did));
 diesel::joinable!(tournamentgroups_tournaments -> tournamentgroups (tournamentgroupid));
 diesel::joinable!(tournamentgroups_tournaments -> tournaments (tournamentid));
+diesel::joinable!(tournaments -> quiz_seasons (quiz_season));
 diesel::joinable!(tournaments_admins -> tournaments (tournamentid));
 diesel::joinable!(tournaments_admins -> users (adminid));
 diesel::joinable!(user_sessions -> users (user_id));
@@ -699,33 +959,51 @@ diesel::allow_tables_to_appear_in_same_query!(
     apicalllog,
     attachment_blobs,
     attachments,
+    award_settings,
+    bracket_nodes,
+    bracket_seats,
+    brackets,
+    calendar_feeds,
+    certificate_templates,
+    competition_levels,
     computers,
     create_tournament_applicants,
+    division_rookies,
     divisions,
     equipment,
     equipmentregistrations,
     equipmentsets,
     eventlogs,
     extensioncords,
+    game_audits,
+    game_eventlog_keys,
+    game_outcomes,
+    game_questions,
     gameevents,
     games,
     games_statsgroups,
     interfaceboxes,
     jumppads,
+    manual_scoresheets,
     microphonerecorders,
     monitors,
+    packet_exports,
     password_reset_tokens,
     permissions,
     powerstrips,
     projectors,
     questionsandanswers,
+    quiz_seasons,
     roles,
     roles_permissions,
     rooms,
     rosters,
     rosters_coaches,
     rosters_quizzers,
+    round_packets,
+    round_questions,
     rounds,
+    rulings,
     schedules,
     statsgroups,
     teams,
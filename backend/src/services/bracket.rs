@@ -0,0 +1,133 @@
+use actix_web::{Error, HttpMessage, HttpRequest, HttpResponse, Result, delete, get, post, web::{Data, Json, Path}};
+use crate::{auth::{is_rbac_and_abac_authorized, policies::{division::DivisionPolicyResource, PolicyContext, UserContext}}, models::{self, bracket::AdvanceRequest, permission::{AppAction, AppResource}}, services::common::process_response};
+use crate::database::Database;
+use uuid::Uuid;
+
+#[get("/{id}")]
+async fn read(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    match models::bracket::read_tree(&mut conn, item_id.into_inner()) {
+        Ok(tree) => HttpResponse::Ok().json(tree),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[post("/{id}/advance")]
+async fn advance(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<AdvanceRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let bracket_id = item_id.into_inner();
+
+    let bracket = match models::bracket::read(&mut conn, bracket_id) {
+        Ok(b) => b,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::division::read(&mut conn, bracket.did).and_then(|d| models::tournament::read(&mut conn, d.tid)) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Bracket advance {:?} {:?}", line!(), bracket_id, item);
+
+    let result = models::bracket::advance(&mut conn, bracket_id, &item);
+
+    let response = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[delete("/{id}")]
+async fn destroy(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let bracket_id = item_id.into_inner();
+
+    let bracket = match models::bracket::read(&mut conn, bracket_id) {
+        Ok(b) => b,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::division::read(&mut conn, bracket.did).and_then(|d| models::tournament::read(&mut conn, d.tid)) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Bracket model delete {:?}", line!(), bracket_id);
+
+    // the bracket's games stay; only the bracket structure goes
+    let result = models::bracket::delete(&mut conn, bracket_id);
+
+    if result.is_ok() {
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::InternalServerError().finish())
+    }
+}
+
+pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
+    scope
+        .service(read)
+        .service(advance)
+        .service(destroy)
+}
@@ -0,0 +1,143 @@
+use actix_web::{Error, HttpMessage, HttpRequest, HttpResponse, Result, delete, get, post, web::{Data, Json, Path}};
+use crate::{auth::policies::UserContext, models::{self, calendar::{CalendarFeedRequest, CalendarFeedWithUrl, CalendarScope}}, services::common::process_response};
+use crate::database::Database;
+use uuid::Uuid;
+
+// The .ics endpoint is what calendar apps subscribe to: the token in the URL stands in for a login.
+#[get("/{token}.ics")]
+async fn read_feed(
+    db: Data<Database>,
+    token: Path<String>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let feed = match models::calendar::read_by_token(&mut conn, &token.into_inner()) {
+        Ok(f) => f,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+
+    match models::calendar::feed_ics(&mut conn, &feed) {
+        Ok(ics) => HttpResponse::Ok()
+            .content_type("text/calendar; charset=utf-8")
+            .insert_header(("Content-Disposition", format!["inline; filename=\"{}-{}.ics\"", feed.scope, feed.subject_id]))
+            .body(ics),
+        Err(diesel::result::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(e) => {
+            tracing::error!("{} calendar feed {} failed: {}", line!(), feed.feedid, e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[get("/feeds")]
+async fn read_feeds(
+    db: Data<Database>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    match models::calendar::read_all_feeds_of_user(&mut conn, user_ctx.user_id) {
+        Ok(feeds) => Ok(HttpResponse::Ok().json(feeds.into_iter().map(CalendarFeedWithUrl::from).collect::<Vec<_>>())),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[post("/feeds")]
+async fn create_feed(
+    db: Data<Database>,
+    Json(item): Json<CalendarFeedRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let scope = match CalendarScope::from_param(&item.scope) {
+        Ok(s) => s,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "message": e }))),
+    };
+
+    // team and room schedules are public, but a user's feed is only for that user
+    if scope == CalendarScope::User && item.subject_id != user_ctx.user_id {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+    if models::calendar::subject_name(&mut conn, scope, item.subject_id).is_err() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    tracing::debug!("{} Calendar feed create {:?}", line!(), item);
+
+    let result = models::calendar::find_or_create(&mut conn, user_ctx.user_id, scope, item.subject_id);
+    let method = if matches!(result, Ok((_, true))) { "post" } else { "get" };
+    let response = process_response(result.map(|(feed, _)| CalendarFeedWithUrl::from(feed)), method);
+
+    match response.code {
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response)),
+    }
+}
+
+#[delete("/feeds/{id}")]
+async fn destroy_feed(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let feed = match models::calendar::read(&mut conn, item_id.into_inner()) {
+        Ok(f) => f,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    if feed.created_by != user_ctx.user_id {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    // the old URL stops working; creating the feed again gives a new token
+    let result = models::calendar::delete(&mut conn, feed.feedid);
+
+    if result.is_ok() {
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::InternalServerError().finish())
+    }
+}
+
+pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
+    scope
+        .service(read_feeds)
+        .service(create_feed)
+        .service(destroy_feed)
+        .service(read_feed)
+}
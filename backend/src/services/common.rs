@@ -3,6 +3,9 @@ use std::any::type_name;
 use diesel::QueryResult;
 use serde::{Deserialize, Serialize};
 use diesel::result::Error as DBError;
+use actix_web::HttpResponse;
+use crate::models::{common::{EventlogExportParams, ExportParams}, eventlog::{self, EventlogScope, EventlogSource}, export::{self, ExportFormat, ExportScope}, packet::Packet, packet_export::{self, PacketExportParams, PacketFileFormat}};
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PagedResponse<T> {
@@ -65,4 +68,92 @@ pub fn process_response<T>(result : QueryResult<T>, http_method: &str) -> Entity
     }    
 
     response
-}
\ No newline at end of file
+}
+
+/// Shared by the tournament, division, round and statsgroup export endpoints.
+pub fn export_response(db: &mut crate::database::Connection, scope: ExportScope, params: &ExportParams) -> HttpResponse {
+    let format = match ExportFormat::from_param(&params.format) {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({ "message": e })),
+    };
+    let reports = match export::reports_from_param(&params.report, &format) {
+        Ok(r) => r,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({ "message": e })),
+    };
+
+    match export::export(db, &scope, &format, &reports) {
+        Ok((file_name, bytes)) => HttpResponse::Ok()
+            .content_type(format.content_type())
+            .insert_header(("Content-Disposition", format!["attachment; filename=\"{}\"", file_name]))
+            .body(bytes),
+        Err(e) => {
+            tracing::error!("{} export of {} {} failed: {}", line!(), scope.as_str(), scope.id(), e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+/// Shared by the tournament, room and game eventlog endpoints.
+pub fn eventlog_export_response(db: &mut crate::database::Connection, scope: EventlogScope, params: &EventlogExportParams) -> HttpResponse {
+    let source = match EventlogSource::from_param(&params.source) {
+        Ok(s) => s,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({ "message": e })),
+    };
+
+    let records = match eventlog::read_quizmachine_records(db, &scope, &source) {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("{} eventlog export of {} {} failed: {}", line!(), scope.as_str(), scope.id(), e);
+            return HttpResponse::InternalServerError().finish();
+        },
+    };
+
+    match eventlog::to_quizmachine_csv(&records) {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header(("Content-Disposition", format!["attachment; filename=\"eventlog-{}-{}.csv\"", scope.as_str(), scope.id()]))
+            .body(bytes),
+        Err(e) => {
+            tracing::error!("{} eventlog export of {} {} failed: {}", line!(), scope.as_str(), scope.id(), e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+/// Shared by the round and division packet endpoints: the packets as a QuizMachine question file, or in
+/// the layout the bank imports, with the download recorded for the tournament's audit.
+pub fn packet_export_response(db: &mut crate::database::Connection, packets: QueryResult<Vec<Packet>>, params: &PacketExportParams, user_id: Uuid, file_name: &str) -> HttpResponse {
+    let format = match PacketFileFormat::from_param(params.format.as_deref()) {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({ "message": e })),
+    };
+
+    let packets = match packets {
+        Ok(p) => p,
+        Err(DBError::QueryBuilderError(e)) => return HttpResponse::BadRequest().json(serde_json::json!({ "message": e.to_string() })),
+        Err(DBError::NotFound) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            tracing::error!("{} packet export {} failed: {}", line!(), file_name, e);
+            return HttpResponse::InternalServerError().finish();
+        },
+    };
+
+    let bytes = match packet_export::to_file(format, &packets) {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("{} packet export {} failed: {}", line!(), file_name, e);
+            return HttpResponse::InternalServerError().finish();
+        },
+    };
+
+    // no file without a record of who has it
+    if let Err(e) = packet_export::record(db, &packets, user_id) {
+        tracing::error!("{} packet export {} wasn't recorded: {}", line!(), file_name, e);
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(("Content-Disposition", format!["attachment; filename=\"{}\"", file_name]))
+        .body(bytes)
+}
@@ -0,0 +1,155 @@
+use actix_web::{delete, Error, get, HttpMessage, HttpResponse, HttpRequest, post, put, Result, web::{Data, Json, Path}};
+use crate::{auth::policies::UserContext, database::Database};
+use crate::models::{self, role::AppRole, competition_level::{NewCompetitionLevel, CompetitionLevelChangeset}};
+use crate::services::common::process_response;
+
+// Anyone signed in reads the competition levels; only super users change them.
+
+fn is_super_user(user_ctx: &UserContext) -> bool {
+    user_ctx.roles.iter().any(|r| r == AppRole::SuperUser.as_str())
+}
+
+#[get("")]
+async fn index(
+    db: Data<Database>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    match models::competition_level::read_all(&mut db) {
+        Ok(levels) => HttpResponse::Ok().json(levels),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/{level}")]
+async fn read(
+    db: Data<Database>,
+    level: Path<i32>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    match models::competition_level::read(&mut db, level.into_inner()) {
+        Ok(level) => HttpResponse::Ok().json(level),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[post("")]
+async fn create(
+    db: Data<Database>,
+    Json(item): Json<NewCompetitionLevel>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    tracing::debug!("{} CompetitionLevel model create {:?}", line!(), item);
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    if !is_super_user(user_ctx) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let result = models::competition_level::create(&mut db, &item);
+    let response = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[put("/{level}")]
+async fn update(
+    db: Data<Database>,
+    level: Path<i32>,
+    Json(item): Json<CompetitionLevelChangeset>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    tracing::debug!("{} CompetitionLevel model update {:?} {:?}", line!(), level, item);
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    if !is_super_user(user_ctx) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let level = level.into_inner();
+    if !models::competition_level::exists(&mut db, level) {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    let result = models::competition_level::update(&mut db, level, &item);
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[delete("/{level}")]
+async fn destroy(
+    db: Data<Database>,
+    level: Path<i32>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    tracing::debug!("{} CompetitionLevel model delete {:?}", line!(), level);
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    if !is_super_user(user_ctx) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    // a level with questions or divisions can't be deleted
+    match models::competition_level::delete(&mut db, level.into_inner()) {
+        Ok(0) => Ok(HttpResponse::NotFound().finish()),
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::ForeignKeyViolation, _)) => Ok(HttpResponse::Conflict().finish()),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
+    scope
+        .service(index)
+        .service(read)
+        .service(create)
+        .service(update)
+        .service(destroy)
+}
@@ -1,7 +1,15 @@
 use actix_web::{Error, HttpMessage, HttpRequest, HttpResponse, Result, delete, get, post, put, web::{Data, Json, Path, Query}};
 use serde_json::json;
-use crate::{auth::{is_rbac_and_abac_authorized, policies::{division::DivisionPolicyResource, PolicyContext, UserContext}}, models::{self, division::{Division, DivisionChangeset, NewDivision}, permission::{AppAction, AppResource}}, services::common::{EntityResponse, PagedResponse, process_response}};
-use crate::models::common::PaginationParams;
+use crate::{auth::{is_rbac_and_abac_authorized, policies::{division::DivisionPolicyResource, PolicyContext, UserContext}}, models::{self, award_setting::AwardSettingsChangeset, division::{Division, DivisionChangeset, NewDivision}, division_rookie::{DivisionRookie, NewDivisionRookie}, packet_export::PacketExportParams, permission::{AppAction, AppResource}}, services::common::{EntityResponse, PagedResponse, export_response, packet_export_response, process_response}};
+use crate::models::common::{CertificateParams, ExportParams, PaginationParams};
+use crate::models::export::ExportScope;
+use crate::models::certificate::CertificateFormat;
+use crate::models::round_robin::RoundRobinRequest;
+use crate::models::bracket::BracketRequest;
+use crate::models::timeline::ShiftRequest;
+use crate::models::packet::PacketRequest;
+use crate::auth::policies::question::QuestionPolicyResource;
+use crate::services::question::strip_answers;
 use crate::database::Database;
 use utoipa::OpenApi;
 use diesel::QueryResult;
@@ -112,6 +120,304 @@ async fn read_games(
     }
 }
 
+#[get("/{id}/awards")]
+async fn read_awards(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let division_id = item_id.into_inner();
+
+    if models::division::read(&mut conn, division_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    match models::award::compute_division_awards(&mut conn, division_id) {
+        Ok(awards) => HttpResponse::Ok().json(awards),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/{id}/awards/printable")]
+async fn read_awards_printable(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let division_id = item_id.into_inner();
+
+    if models::division::read(&mut conn, division_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    match models::award::compute_division_awards(&mut conn, division_id) {
+        Ok(awards) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(models::award::to_printable_html(&awards)),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/{id}/certificates")]
+async fn read_certificates(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<CertificateParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let format = match CertificateFormat::from_param(&params.format) {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().json(json!({ "message": e })),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::InternalServerError().finish(),
+    };
+
+    let awards = match models::award::compute_division_awards(&mut conn, division_id) {
+        Ok(a) => a,
+        Err(_) => return HttpResponse::InternalServerError().finish(),
+    };
+
+    let template = models::certificate_template::read_svg_or_default(&mut conn, tournament.tid);
+    let certificates = models::certificate::certificates_of_division_awards(&awards, &tournament);
+
+    match models::certificate::build_archive(&format, &template, &certificates) {
+        Ok(archive) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header(("Content-Disposition", format!["attachment; filename=\"certificates-{}.zip\"", division_id]))
+            .body(archive),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/{id}/awardsettings")]
+async fn read_award_settings(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let division_id = item_id.into_inner();
+
+    if models::division::read(&mut conn, division_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    match models::award_setting::read_or_default(&mut conn, division_id) {
+        Ok(settings) => HttpResponse::Ok().json(settings),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[put("/{id}/awardsettings")]
+async fn update_award_settings(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<AwardSettingsChangeset>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} AwardSettings model update {:?} {:?}", line!(), division_id, item);
+
+    let result = models::award_setting::update(&mut conn, division_id, &item);
+
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[get("/{id}/rookies")]
+async fn read_rookies(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    match models::division_rookie::read_all_rookies_of_division(&mut conn, item_id.into_inner()) {
+        Ok(rookies) => HttpResponse::Ok().json(rookies),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[post("/{id}/rookies")]
+async fn add_rookie(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<NewDivisionRookie>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.get_connection().expect("Failed to get connection");
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let item_to_be_created = NewDivisionRookie {
+        did: division_id,
+        ..item
+    };
+
+    tracing::debug!("{} DivisionRookie model create {:?}", line!(), item_to_be_created);
+
+    let result: QueryResult<DivisionRookie> = models::division_rookie::create(&mut conn, &item_to_be_created);
+
+    let response: EntityResponse<DivisionRookie> = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[delete("/{id}/rookies/{quizzer_id}")]
+async fn remove_rookie(
+    db: Data<Database>,
+    item_ids: Path<(Uuid, Uuid)>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let (division_id, quizzer_id) = item_ids.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} DivisionRookie model delete {:?} {:?}", line!(), division_id, quizzer_id);
+
+    match models::division_rookie::delete(&mut conn, division_id, quizzer_id) {
+        Ok(0) => Ok(HttpResponse::NotFound().finish()),
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
 #[post("")]
 async fn create(
     db: Data<Database>,
@@ -164,6 +470,7 @@ async fn create(
     let response: EntityResponse<Division> = process_response(result, "post");
     
     match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
         409 => Ok(HttpResponse::Conflict().json(response)),
         201 => Ok(HttpResponse::Created().json(response)),
         200 => Ok(HttpResponse::Ok().json(response)),
@@ -219,6 +526,7 @@ async fn update(
     let response = process_response(result, "put");
 
     match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
         409 => Ok(HttpResponse::Conflict().json(response)),
         200 => Ok(HttpResponse::Ok().json(response)),
         _ => Ok(HttpResponse::InternalServerError().json(response))
@@ -275,6 +583,397 @@ async fn destroy(
     }
 }
 
+#[get("/{id}/export")]
+async fn export(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<ExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let division_id = item_id.into_inner();
+
+    if models::division::read(&mut conn, division_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    export_response(&mut conn, ExportScope::Division(division_id), &params)
+}
+
+#[get("/{id}/standings")]
+async fn read_standings(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let division_id = item_id.into_inner();
+
+    if models::division::read(&mut conn, division_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    match models::bracket::read_standings(&mut conn, division_id) {
+        Ok(standings) => HttpResponse::Ok().json(standings),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/{id}/brackets")]
+async fn read_brackets(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let division_id = item_id.into_inner();
+
+    if models::division::read(&mut conn, division_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    match models::bracket::read_all_brackets_of_division(&mut conn, division_id) {
+        Ok(brackets) => HttpResponse::Ok().json(brackets),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[post("/{id}/brackets")]
+async fn create_bracket(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<BracketRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Bracket for division {:?} {:?}", line!(), division_id, item);
+
+    let result = models::bracket::create_bracket(&mut conn, division_id, &item);
+
+    let response = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[post("/{id}/schedule/roundrobin")]
+async fn generate_round_robin(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<RoundRobinRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Round robin for division {:?} {:?}", line!(), division_id, item);
+
+    let result = models::round_robin::generate(&mut conn, division_id, &item);
+
+    // a preview writes nothing, so only an applied schedule answers 201
+    let method = if item.preview.unwrap_or(true) { "get" } else { "post" };
+    let response = process_response(result, method);
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+// Generates the question packets of the division's rounds. Previews unless preview is false.
+#[post("/{id}/packets")]
+async fn generate_packets(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<PacketRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Packets for division {:?} {:?}", line!(), division_id, item);
+
+    let result = models::packet::generate_for_division(&mut conn, division_id, &item, user_ctx.user_id);
+    let method = if item.preview.unwrap_or(true) { "get" } else { "post" };
+    let response = process_response(result, method);
+    let mut body = serde_json::to_value(&response).unwrap();
+    if !PolicyContext::<QuestionPolicyResource>::new(user_ctx).can_view_answers() {
+        strip_answers(&mut body);
+    }
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(body)),
+        200 => Ok(HttpResponse::Ok().json(body)),
+        201 => Ok(HttpResponse::Created().json(body)),
+        _ => Ok(HttpResponse::InternalServerError().json(body))
+    }
+}
+
+// The packets of the division's rounds as one QuizMachine question file, or with format=questions as
+// a file the bank can import. Each download is recorded.
+#[get("/{id}/packets/csv")]
+async fn export_packets(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<PacketExportParams>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Packets export for division {:?}", line!(), division_id);
+
+    let packets = models::packet_export::read_packets_of_division(&mut conn, division_id);
+    let file_name = format!["packets-{}.csv", division_id];
+    Ok(packet_export_response(&mut conn, packets, &params, user_ctx.user_id, &file_name))
+}
+
+// Who downloaded the packets of the division's rounds, and whether it was before each round started.
+#[get("/{id}/packets/exports")]
+async fn read_packet_exports(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::packet_export::read_all_of_division(&mut conn, division_id) {
+        Ok(exports) => Ok(HttpResponse::Ok().json(exports)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[post("/{id}/shift")]
+async fn shift_schedule(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<ShiftRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let division_id = item_id.into_inner();
+
+    let division = match models::division::read(&mut conn, division_id) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: DivisionPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let div_update_permission = format!("{}:{}", AppResource::Division.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &div_update_permission, AppResource::Division.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Shift schedule of division {:?} {:?}", line!(), division_id, item);
+
+    let result = models::timeline::shift_division(&mut conn, division_id, &item);
+
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
 pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
     return scope
         .service(index)
@@ -282,6 +981,23 @@ pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
         .service(read_rounds)
         .service(read_teams)
         .service(read_games)
+        .service(read_awards)
+        .service(read_awards_printable)
+        .service(export)
+        .service(read_certificates)
+        .service(read_award_settings)
+        .service(update_award_settings)
+        .service(read_rookies)
+        .service(add_rookie)
+        .service(remove_rookie)
+        .service(generate_round_robin)
+        .service(generate_packets)
+        .service(export_packets)
+        .service(read_packet_exports)
+        .service(shift_schedule)
+        .service(read_standings)
+        .service(read_brackets)
+        .service(create_bracket)
         .service(create)
         .service(update)
         .service(destroy);
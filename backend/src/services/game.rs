@@ -1,8 +1,8 @@
 use actix_web::{delete, Error, get, HttpMessage, HttpResponse, HttpRequest, post, put, Result, web::{Data, Json, Path, Query}};
 use serde_json::json;
-use crate::{auth::{is_rbac_and_abac_authorized, policies::{game::GamePolicyResource, PolicyContext, UserContext}}, models::{self, common::PaginationParams, game::{NewGame, Game, GameChangeset}, permission::{AppAction, AppResource}}};
+use crate::{auth::{is_rbac_and_abac_authorized, policies::{game::GamePolicyResource, PolicyContext, UserContext}}, models::{self, common::{EventlogExportParams, PaginationParams, RebuildParams}, conflict::GameWithConflicts, eventlog::EventlogScope, game::{Game, NewGame, GameChangeset}, game_merge::{GameMergeRequest, GameMoveRequest}, game_question::GameQuestionChangeset, game_outcome::GameOutcomeRequest, permission::{AppAction, AppResource}, ruling::{RulingChangeset, RulingRequest}, scoresheet::Scoresheet}};
 use crate::database::Database;
-use crate::services::common::{EntityResponse, PagedResponse, process_response};
+use crate::services::common::{EntityResponse, PagedResponse, eventlog_export_response, process_response};
 // use utoipa::OpenApi;
 use diesel::QueryResult;
 use uuid::Uuid;
@@ -164,9 +164,10 @@ async fn create(
         item
     };
 
-    let result: QueryResult<Game> = models::game::create(&mut conn, &item);
+    let result: QueryResult<GameWithConflicts> = models::game::create(&mut conn, &item)
+        .map(|game| models::conflict::with_conflicts(&mut conn, game));
 
-    let response: EntityResponse<Game> = process_response(result, "post");
+    let response: EntityResponse<GameWithConflicts> = process_response(result, "post");
     
     match response.code {
         400 => Ok(HttpResponse::BadRequest().json(response)),
@@ -220,7 +221,8 @@ async fn update(
 
     tracing::debug!("{} Game model update {:?} {:?}", line!(), game_id, item);
 
-    let result = models::game::update(&mut conn, game_id, &item);
+    let result = models::game::update(&mut conn, game_id, &item)
+        .map(|game| models::conflict::with_conflicts(&mut conn, game));
 
     let response = process_response(result, "put");
 
@@ -281,12 +283,751 @@ async fn destroy(
     }
 }
 
+#[get("/{id}/consistency")]
+async fn read_consistency(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::eventlog_consistency::check_game(&mut conn, &game) {
+        Ok(report) => Ok(HttpResponse::Ok().json(report)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[post("/{id}/rebuild")]
+async fn rebuild(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<RebuildParams>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let apply = params.apply.unwrap_or(false);
+    tracing::debug!("{} GameEvent rebuild from eventlog {:?} apply={}", line!(), game_id, apply);
+
+    match models::eventlog_consistency::rebuild_games(&mut conn, &[game], apply) {
+        Ok(report) => Ok(HttpResponse::Ok().json(report)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({ "message": e.to_string() }))),
+    }
+}
+
+#[get("/{id}/outcome")]
+async fn read_outcome(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    match models::game_outcome::read(&mut conn, item_id.into_inner()) {
+        Ok(outcome) => HttpResponse::Ok().json(outcome),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+// Forfeits, byes and no contests are decided by an admin, so the game needs no event stream.
+#[put("/{id}/outcome")]
+async fn record_outcome(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<GameOutcomeRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Game outcome record {:?} {:?}", line!(), game_id, item);
+
+    let result = models::game_outcome::record(&mut conn, &game, user_ctx.user_id, &item);
+    let method = if matches!(result, Ok((_, true))) { "post" } else { "put" };
+    let response = process_response(result.map(|(outcome, _)| outcome), method);
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[delete("/{id}/outcome")]
+async fn destroy_outcome(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Game outcome delete {:?}", line!(), game_id);
+
+    // the game counts by its event stream again
+    match models::game_outcome::delete(&mut conn, game.gid) {
+        Ok(0) => Ok(HttpResponse::NotFound().finish()),
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[get("/{id}/scoresheet")]
+async fn read_scoresheet(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    match models::scoresheet::read(&mut conn, item_id.into_inner()) {
+        Ok(sheet) => HttpResponse::Ok().json(sheet),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+// For games quizzed on paper: the sheet becomes the game's event stream. Previews unless preview is false.
+#[post("/{id}/scoresheet")]
+async fn enter_scoresheet(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<Scoresheet>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Game scoresheet entry {:?} {} lines", line!(), game_id, item.entries.len());
+
+    let result = models::scoresheet::enter(&mut conn, &game, user_ctx.user_id, &item);
+    let method = if item.preview.unwrap_or(true) { "get" } else { "post" };
+    let response = process_response(result, method);
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+// Puts a game QuizMachine started in the wrong room or round where it belongs. Previews unless preview is false.
+#[post("/{id}/move")]
+async fn move_game(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<GameMoveRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Game move {:?} {:?}", line!(), game_id, item);
+
+    let result = models::game_merge::move_game(&mut conn, &game, user_ctx.user_id, &item);
+    let method = if item.preview.unwrap_or(true) { "get" } else { "put" };
+    let response = process_response(result, method);
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+// Moves the events of the stray game `from` into this one and deletes it. Previews unless preview is false.
+#[post("/{id}/merge")]
+async fn merge_game(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<GameMergeRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let source = match models::game::read(&mut conn, item.from) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Game merge {:?} {:?}", line!(), game_id, item);
+
+    let preview = item.preview.unwrap_or(true);
+    let result = models::game_merge::merge_games(&mut conn, &game, &source, user_ctx.user_id, preview);
+    let method = if preview { "get" } else { "put" };
+    let response = process_response(result, method);
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[get("/{id}/questions")]
+async fn read_questions(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    match models::game_question::read_all_of_game(&mut conn, item_id.into_inner()) {
+        Ok(questions) => HttpResponse::Ok().json(questions),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+// Links the questions read in the game to the round's packet again, e.g. after the packet was adjusted.
+#[post("/{id}/questions")]
+async fn link_questions(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game_id = item_id.into_inner();
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::game_question::link_all(&mut conn, game.gid) {
+        Ok(questions) => Ok(HttpResponse::Ok().json(questions)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({ "message": e.to_string() }))),
+    }
+}
+
+// Records the question read for a question number by hand, e.g. when an alternate was read.
+#[put("/{id}/questions/{number}")]
+async fn set_question(
+    db: Data<Database>,
+    path: Path<(Uuid, i32)>,
+    Json(item): Json<GameQuestionChangeset>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let (game_id, number) = path.into_inner();
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, game.tournamentid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let result = models::game_question::set(&mut conn, game.gid, number, &item);
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+// Tournament owners and admins can change a game; its content judge can also record its rulings.
+fn is_game_admin(conn: &mut crate::database::Connection, user_ctx: &UserContext, game: &Game) -> bool {
+    let Ok(tournament) = models::tournament::read(conn, game.tournamentid) else {
+        return false;
+    };
+    let user_is_admin = models::tournament_admin::is_admin(conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: GamePolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let game_update_permission = format!("{}:{}", AppResource::Game.as_str(), AppAction::Update.as_str());
+    is_rbac_and_abac_authorized(&policy_ctx, &game_update_permission, AppResource::Game.as_str()).is_ok()
+}
+
+fn can_judge(conn: &mut crate::database::Connection, user_ctx: &UserContext, game: &Game) -> bool {
+    game.contentjudgeid == Some(user_ctx.user_id) || is_game_admin(conn, user_ctx, game)
+}
+
+#[get("/{id}/rulings")]
+async fn read_rulings(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game = match models::game::read(&mut conn, item_id.into_inner()) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    if !can_judge(&mut conn, user_ctx, &game) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::ruling::read_all_of_game(&mut conn, game.gid) {
+        Ok(rulings) => Ok(HttpResponse::Ok().json(rulings)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+// Records a challenge or appeal, decided or still pending.
+#[post("/{id}/rulings")]
+async fn create_ruling(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<RulingRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    tracing::debug!("{} Ruling model create {:?}", line!(), item);
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game = match models::game::read(&mut conn, item_id.into_inner()) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    if !can_judge(&mut conn, user_ctx, &game) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let result = models::ruling::create(&mut conn, &game, &item, user_ctx.user_id);
+    let response = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[put("/{id}/rulings/{ruling_id}")]
+async fn update_ruling(
+    db: Data<Database>,
+    path: Path<(Uuid, Uuid)>,
+    Json(item): Json<RulingChangeset>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let (game_id, ruling_id) = path.into_inner();
+    let mut conn = db.pool.get().unwrap();
+
+    tracing::debug!("{} Ruling model update {:?} {:?}", line!(), ruling_id, item);
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    if !can_judge(&mut conn, user_ctx, &game) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let result = models::ruling::update(&mut conn, &game, ruling_id, &item, user_ctx.user_id);
+    if let Err(diesel::result::Error::NotFound) = result {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+// Only the tournament's admins can throw a case out.
+#[delete("/{id}/rulings/{ruling_id}")]
+async fn destroy_ruling(
+    db: Data<Database>,
+    path: Path<(Uuid, Uuid)>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let (game_id, ruling_id) = path.into_inner();
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let game = match models::game::read(&mut conn, game_id) {
+        Ok(g) => g,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    if !is_game_admin(&mut conn, user_ctx, &game) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::ruling::delete(&mut conn, game.gid, ruling_id) {
+        Ok(0) => Ok(HttpResponse::NotFound().finish()),
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[get("/{id}/audits")]
+async fn read_audits(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    // a merged game no longer exists, but its history does
+    match models::game_merge::read_all_audits_of_game(&mut conn, item_id.into_inner()) {
+        Ok(audits) => HttpResponse::Ok().json(audits),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[get("/{id}/eventlog")]
+async fn export_eventlog(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<EventlogExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let game_id = item_id.into_inner();
+
+    if models::game::read(&mut conn, game_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    eventlog_export_response(&mut conn, EventlogScope::Game(game_id), &params)
+}
+
 pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
     return scope
         .service(index)
         .service(read)
         .service(read_statsgroups)
         .service(read_gameevents)
+        .service(export_eventlog)
+        .service(read_consistency)
+        .service(rebuild)
+        .service(read_outcome)
+        .service(record_outcome)
+        .service(destroy_outcome)
+        .service(read_scoresheet)
+        .service(enter_scoresheet)
+        .service(move_game)
+        .service(merge_game)
+        .service(read_audits)
+        .service(read_questions)
+        .service(link_questions)
+        .service(set_question)
+        .service(read_rulings)
+        .service(create_ruling)
+        .service(update_ruling)
+        .service(destroy_ruling)
         .service(create)
         .service(update)
         .service(destroy);
@@ -8,7 +8,7 @@ use uuid::Uuid;
 use base64::{self, Engine};
 use sha1::{Sha1, Digest};
 use diesel::result::Error as DBError;
-use crate::models::{eventlog, roominfo};
+use crate::models::{eventlog, game_question, roominfo};
 // use crate::models::gameevent::{self,GameEvent};
 use crate::models::game::{self,GameChangeset};
 use crate::database::{self,Database};
@@ -269,6 +269,8 @@ pub async fn write(
         )
     }
 
+    let eventlog_key = (eventlog_entry.clientkey.clone(), eventlog_entry.division.clone(), eventlog_entry.room.clone(), eventlog_entry.round.clone());
+
     // now lets log all this information to the eventlog table.
     // This is a file on disk in QMServer.  But we'll put it
     // on the database in the eventlog table for Qview
@@ -294,13 +296,18 @@ pub async fn write(
     
     // now let's create an entry in the games table
     // Handle errors while we create the entry
-    match game::create_update(mdb, &game_entry) {
+    match game::create_update_of_eventlog_key(mdb, &game_entry, &eventlog_key) {
         Ok(output) => {
             // update the gameevent gid so we have the correct one to write
             // the gameevent to the Quizzes table
             gameevent_entry.gid = output.gid;
             gid = output.gid;
-            log::info!("Inserted/Updated a Game {:?}",output)            
+            log::info!("Inserted/Updated a Game {:?}",output);
+
+            // the client's timestamp rather than ours: events can arrive late when a room was offline
+            if let Err(e) = game::record_event_time(mdb, gid, ts) {
+                log::error!("{:?} {:?} Failed to record the time of an event of game {}: {:?}", module_path!(), line!(), gid, e);
+            }
         },
         Err(e) => {
             let error_content = format!("Game write failure {}", e);
@@ -340,7 +347,13 @@ pub async fn write(
     // Handle errors while we create the entry - this is a database insert or update
     match gameevent::create_update_game_event(mdb, &gameevent_entry) {
         Ok(output) => {
-            log::info!("Inserted/Updated a Quizevent {:?}",output)
+            log::info!("Inserted/Updated a Quizevent {:?}",output);
+
+            // the question was read, so record which question from the round's packet it was
+            if game_question::READ_EVENTS.contains(&output.event.as_str())
+                && let Err(e) = game_question::link(mdb, output.gid, output.question) {
+                log::error!("{:?} {:?} Failed to link question {} of game {}: {:?}", module_path!(), line!(), output.question, output.gid, e);
+            }
         },
         Err(err) => {
             let error_content = format!("Quizevent write failure {}", err);
@@ -521,6 +534,12 @@ async fn create(
     
     let result: QueryResult<GameEvent> = models::gameevent::create(&mut conn, &item);
 
+    // the client's timestamp rather than ours, as in `write`
+    if let Ok(event) = &result
+        && let Err(e) = game::record_event_time(&mut conn, event.gid, event.clientts) {
+        log::error!("{:?} {:?} Failed to record the time of an event of game {}: {:?}", module_path!(), line!(), event.gid, e);
+    }
+
     let response: EntityResponse<GameEvent> = process_response(result, "post");
     
     match response.code {
@@ -30,3 +30,8 @@ pub mod pingmsg;
 pub mod namelist;
 // pub mod roominfo;
 pub mod create_tournament_applicant;
+pub mod bracket;
+pub mod calendar;
+pub mod question;
+pub mod quiz_season;
+pub mod competition_level;
@@ -0,0 +1,364 @@
+use actix_web::{delete, Error, get, HttpMessage, HttpResponse, HttpRequest, post, put, Result, web::{Data, Json, Path, Query}};
+use crate::{auth::policies::{question::QuestionPolicyResource, PolicyContext, UserContext}, database::Database};
+use crate::models::{self, permission::AppAction, question::{NewQuestion, Question, QuestionChangeset, QuestionSearchParams, ReferenceCheckParams}};
+use crate::models::question_import::{QuestionExportParams, QuestionFileFormat, QuestionImportParams};
+use crate::models::question_usage::QuestionUsageParams;
+use crate::services::common::{PagedResponse, process_response};
+use uuid::Uuid;
+
+// Anyone who can read the question bank sees the questions, but only question writers and users
+// who can edit the bank see the answers (see QuestionPolicyResource).
+
+// Removes the answers from every question in `body` (anything with both a question and an answer).
+pub(crate) fn strip_answers(body: &mut serde_json::Value) {
+    match body {
+        serde_json::Value::Object(obj) => {
+            if obj.contains_key("question") && obj.contains_key("beginning_verse") {
+                obj.remove("answer");
+            }
+            obj.values_mut().for_each(strip_answers);
+        },
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_answers),
+        _ => {},
+    }
+}
+
+fn to_body(question: &Question, show_answer: bool) -> serde_json::Value {
+    let mut body = serde_json::to_value(question).unwrap();
+    if let Some(obj) = body.as_object_mut().filter(|_| !show_answer) {
+        obj.remove("answer");
+    }
+    body
+}
+
+#[get("")]
+async fn index(
+    db: Data<Database>,
+    Query(params): Query<QuestionSearchParams>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Read) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let show_answers = policy_ctx.can_view_answers();
+    match models::question::search(&mut db, &params) {
+        Ok((items, count)) => Ok(HttpResponse::Ok().json(PagedResponse {
+            count,
+            items: items.iter().map(|q| to_body(q, show_answers)).collect(),
+        })),
+        // a reference that doesn't parse
+        Err(diesel::result::Error::QueryBuilderError(e)) => Ok(HttpResponse::BadRequest().json(serde_json::json!({ "message": e.to_string() }))),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[get("/{id}")]
+async fn read(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Read) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::question::read(&mut db, item_id.into_inner()) {
+        Ok(question) => Ok(HttpResponse::Ok().json(to_body(&question, policy_ctx.can_view_answers()))),
+        Err(_) => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+#[post("")]
+async fn create(
+    db: Data<Database>,
+    Json(item): Json<NewQuestion>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    tracing::debug!("{} Question model create {:?}", line!(), item);
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Create) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    // a question entered by hand was not imported, whatever the client says
+    let result = models::question::create(&mut db, &NewQuestion { id: Uuid::new_v4(), was_imported_from_ui: Some(false), ..item });
+    let response = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[put("/{id}")]
+async fn update(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<QuestionChangeset>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    tracing::debug!("{} Question model update {:?} {:?}", line!(), item_id, item);
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Update) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let question_id = item_id.into_inner();
+    if models::question::read(&mut db, question_id).is_err() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    let result = models::question::update(&mut db, question_id, &item);
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[delete("/{id}")]
+async fn destroy(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    tracing::debug!("{} Question model delete {:?}", line!(), item_id);
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Delete) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    // a question scheduled in a round can't be deleted until it's taken out of the round
+    match models::question::delete(&mut db, item_id.into_inner()) {
+        Ok(0) => Ok(HttpResponse::NotFound().finish()),
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::ForeignKeyViolation, _)) => Ok(HttpResponse::Conflict().finish()),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[post("/import")]
+async fn import(
+    db: Data<Database>,
+    Query(params): Query<QuestionImportParams>,
+    body: String,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Create) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let format = match QuestionFileFormat::from_param(params.format.as_deref()) {
+        Ok(f) => f,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(serde_json::json!({ "message": e }))),
+    };
+    let preview = params.preview.unwrap_or(true);
+    tracing::debug!("{} Question import {:?} preview={}", line!(), format, preview);
+
+    let result = models::question_import::import(&mut db, format, body.as_bytes(), preview);
+    let response = process_response(result, if preview { "get" } else { "post" });
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response)),
+    }
+}
+
+#[get("/export")]
+async fn export(
+    db: Data<Database>,
+    Query(params): Query<QuestionExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+    // an export has the answers in it
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Read) || !policy_ctx.can_view_answers() {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    let format = match QuestionFileFormat::from_param(params.format.as_deref()) {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({ "message": e })),
+    };
+    let (content_type, extension) = match format {
+        QuestionFileFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        QuestionFileFormat::Json => ("application/json", "json"),
+    };
+    let file_name = match params.quiz_season {
+        Some(season) => format!["questions-season-{}.{}", season, extension],
+        None => format!["questions.{}", extension],
+    };
+
+    match models::question_import::read_for_export(&mut db, &params).map_err(|e| e.to_string())
+        .and_then(|questions| models::question_import::to_file(format, &questions)) {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header(("Content-Disposition", format!["attachment; filename=\"{}\"", file_name]))
+            .body(bytes),
+        Err(e) => {
+            tracing::error!("{} question export failed: {}", line!(), e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+// How each question did when it was read, for question writers judging how hard their questions are.
+#[get("/usage")]
+async fn usage(
+    db: Data<Database>,
+    Query(params): Query<QuestionUsageParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+    // the report has the answers in it
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Read) || !policy_ctx.can_view_answers() {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match models::question_usage::report(&mut db, &params) {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => {
+            tracing::error!("{} question usage report failed: {}", line!(), e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+// The questions whose book, chapter and verses aren't a reference that exists, for writers to fix.
+#[get("/references")]
+async fn check_references(
+    db: Data<Database>,
+    Query(params): Query<ReferenceCheckParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+    let policy_ctx = PolicyContext::<QuestionPolicyResource>::new(user_ctx);
+    if !policy_ctx.is_authorized(AppAction::Read) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match models::question::check_references(&mut db, &params) {
+        Ok(problems) => HttpResponse::Ok().json(problems),
+        Err(e) => {
+            tracing::error!("{} question reference check failed: {}", line!(), e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
+    scope
+        .service(index)
+        .service(export)
+        .service(usage)
+        .service(check_references)
+        .service(import)
+        .service(read)
+        .service(create)
+        .service(update)
+        .service(destroy)
+}
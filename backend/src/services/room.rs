@@ -5,8 +5,8 @@ use serde_json::json;
 use std::collections::HashMap;
 use crate::{auth::{is_rbac_and_abac_authorized, policies::{PolicyContext, UserContext, room::RoomPolicyResource}}, models::common::ReadGamesDetailedParams};
 use crate::database::Database;
-use crate::models::{self, common::PaginationParams, permission::{AppAction, AppResource}, room::{NewRoom, Room, RoomChangeset}};
-use crate::services::common::{EntityResponse, PagedResponse, process_response};
+use crate::models::{self, common::{EventlogExportParams, PaginationParams}, eventlog::EventlogScope, permission::{AppAction, AppResource}, room::{NewRoom, Room, RoomChangeset}, timeline::ShiftRequest};
+use crate::services::common::{EntityResponse, PagedResponse, eventlog_export_response, process_response};
 // use utoipa::OpenApi;
 use diesel::QueryResult;
 use uuid::Uuid;
@@ -437,6 +437,80 @@ async fn destroy(
     }
 }
 
+#[post("/{id}/shift")]
+async fn shift_schedule(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<ShiftRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let room_id = item_id.into_inner();
+
+    let room = match models::room::read(&mut conn, room_id) {
+        Ok(r) => r,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, room.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: RoomPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let room_update_permission = format!("{}:{}", AppResource::Room.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &room_update_permission, AppResource::Room.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Shift schedule of room {:?} {:?}", line!(), room_id, item);
+
+    let result = models::timeline::shift_room(&mut conn, room_id, &item);
+
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[get("/{id}/eventlog")]
+async fn export_eventlog(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<EventlogExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let room_id = item_id.into_inner();
+
+    if models::room::read(&mut db, room_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    eventlog_export_response(&mut db, EventlogScope::Room(room_id), &params)
+}
+
 pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
     return scope
         .service(index)
@@ -444,6 +518,8 @@ pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
         .service(read_games)
         .service(read_games_detailed)
         .service(read_equipmentregistrations)
+        .service(export_eventlog)
+        .service(shift_schedule)
         .service(create)
         .service(update)
         .service(destroy);
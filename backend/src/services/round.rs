@@ -1,6 +1,9 @@
 use actix_web::{delete, Error, get, HttpMessage, HttpResponse, HttpRequest, post, put, Result, web::{Data, Json, Path, Query}};
 use serde_json::json;
-use crate::{auth::{is_rbac_and_abac_authorized, policies::{round::RoundPolicyResource, PolicyContext, UserContext}}, models::{self, common::PaginationParams, permission::{AppAction, AppResource}, round::{NewRound, Round, RoundChangeset}}, services::common::{EntityResponse, PagedResponse, process_response}};
+use crate::{auth::{is_rbac_and_abac_authorized, policies::{round::RoundPolicyResource, PolicyContext, UserContext}}, models::{self, common::{ExportParams, PaginationParams}, export::ExportScope, packet_export::PacketExportParams, permission::{AppAction, AppResource}, round::{NewRound, Round, RoundChangeset}}, services::common::{EntityResponse, PagedResponse, export_response, packet_export_response, process_response}};
+use crate::models::packet::{PacketAdjustment, PacketRequest};
+use crate::auth::policies::question::QuestionPolicyResource;
+use crate::services::question::strip_answers;
 use crate::database::Database;
 use diesel::QueryResult;
 use uuid::Uuid;
@@ -249,11 +252,367 @@ async fn destroy(
     }
 }
 
+#[get("/{id}/export")]
+async fn export(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<ExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let round_id = item_id.into_inner();
+
+    if models::round::read(&mut db, round_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    export_response(&mut db, ExportScope::Round(round_id), &params)
+}
+
+// The ids of the round's questions, by role and position. Like the packet, only for the people
+// running the round.
+#[get("/{id}/questions")]
+async fn read_questions(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+
+    let round_id = item_id.into_inner();
+
+    let round = match models::round::read(&mut db, round_id) {
+        Ok(r) => r,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    if !can_read_packet(&mut db, &round, user_ctx) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match models::round_question::read_all_of_round(&mut db, round_id) {
+        Ok(items) => HttpResponse::Ok().json(items),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+// A round's packet is only for the people running the round: whoever can update it, and the
+// quizmasters and content judges of its games.
+fn can_read_packet(conn: &mut crate::database::Connection, round: &Round, user_ctx: &UserContext) -> bool {
+    if models::round::is_official(conn, round.roundid, user_ctx.user_id) {
+        return true;
+    }
+    let tournament = match models::division::read(conn, round.did).and_then(|division| models::tournament::read(conn, division.tid)) {
+        Ok(t) => t,
+        Err(_) => return false,
+    };
+    let user_is_admin = models::tournament_admin::is_admin(conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: RoundPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let round_update_permission = format!("{}:{}", AppResource::Round.as_str(), AppAction::Update.as_str());
+    is_rbac_and_abac_authorized(&policy_ctx, &round_update_permission, AppResource::Round.as_str()).is_ok()
+}
+
+// The round's question packet. Only question writers see the answers.
+#[get("/{id}/packet")]
+async fn read_packet(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let round = match models::round::read(&mut conn, item_id.into_inner()) {
+        Ok(r) => r,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    if !can_read_packet(&mut conn, &round, user_ctx) {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let packet = match models::packet::read(&mut conn, round.roundid) {
+        Ok(p) => p,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    // reading the questions is a download like any other
+    if let Err(e) = models::packet_export::record(&mut conn, std::slice::from_ref(&packet), user_ctx.user_id) {
+        tracing::error!("{} packet of round {} wasn't recorded: {}", line!(), round.roundid, e);
+        return Ok(HttpResponse::InternalServerError().finish());
+    }
+    let mut body = serde_json::to_value(&packet).unwrap();
+    if !PolicyContext::<QuestionPolicyResource>::new(user_ctx).can_view_answers() {
+        strip_answers(&mut body);
+    }
+    Ok(HttpResponse::Ok().json(body))
+}
+
+// Generates (or regenerates) the round's packet from the question bank. Previews unless preview is false.
+#[post("/{id}/packet")]
+async fn generate_packet(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<PacketRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let round_id = item_id.into_inner();
+
+    let round = match models::round::read(&mut conn, round_id) {
+        Ok(r) => r,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let division = match models::division::read(&mut conn, round.did) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: RoundPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let round_update_permission = format!("{}:{}", AppResource::Round.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &round_update_permission, AppResource::Round.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Round packet generate {:?} {:?}", line!(), round_id, item);
+
+    let result = models::packet::generate(&mut conn, &[round_id], &item, user_ctx.user_id)
+        .map(|mut packets| packets.remove(0));
+    let method = if item.preview.unwrap_or(true) { "get" } else { "post" };
+    let response = process_response(result, method);
+    let mut body = serde_json::to_value(&response).unwrap();
+    if !PolicyContext::<QuestionPolicyResource>::new(user_ctx).can_view_answers() {
+        strip_answers(&mut body);
+    }
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(body)),
+        200 => Ok(HttpResponse::Ok().json(body)),
+        201 => Ok(HttpResponse::Created().json(body)),
+        _ => Ok(HttpResponse::InternalServerError().json(body))
+    }
+}
+
+// Changes the round's packet by hand.
+#[put("/{id}/packet")]
+async fn adjust_packet(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<PacketAdjustment>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let round_id = item_id.into_inner();
+
+    let round = match models::round::read(&mut conn, round_id) {
+        Ok(r) => r,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let division = match models::division::read(&mut conn, round.did) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: RoundPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let round_update_permission = format!("{}:{}", AppResource::Round.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &round_update_permission, AppResource::Round.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Round packet adjust {:?} {:?}", line!(), round_id, item);
+
+    let result = models::packet::adjust(&mut conn, round_id, &item, user_ctx.user_id);
+    let response = process_response(result, "put");
+    let mut body = serde_json::to_value(&response).unwrap();
+    if !PolicyContext::<QuestionPolicyResource>::new(user_ctx).can_view_answers() {
+        strip_answers(&mut body);
+    }
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(body)),
+        200 => Ok(HttpResponse::Ok().json(body)),
+        _ => Ok(HttpResponse::InternalServerError().json(body))
+    }
+}
+
+// The round's packet, alternates and overtime questions included, as a QuizMachine question file, or
+// with format=questions as a file the bank can import. Each download is recorded.
+#[get("/{id}/packet/csv")]
+async fn export_packet(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<PacketExportParams>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let round_id = item_id.into_inner();
+
+    let round = match models::round::read(&mut conn, round_id) {
+        Ok(r) => r,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let division = match models::division::read(&mut conn, round.did) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: RoundPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let round_update_permission = format!("{}:{}", AppResource::Round.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &round_update_permission, AppResource::Round.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Round packet export {:?}", line!(), round_id);
+
+    let packets = models::packet_export::read_packets(&mut conn, &[round_id]);
+    let file_name = format!["packet-{}.csv", round_id];
+    Ok(packet_export_response(&mut conn, packets, &params, user_ctx.user_id, &file_name))
+}
+
+// Who downloaded the round's packet, and whether it was before the round started.
+#[get("/{id}/packet/exports")]
+async fn read_packet_exports(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut conn = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut conn, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish()),
+    };
+
+    let round_id = item_id.into_inner();
+
+    let round = match models::round::read(&mut conn, round_id) {
+        Ok(r) => r,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    let division = match models::division::read(&mut conn, round.did) {
+        Ok(d) => d,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let tournament = match models::tournament::read(&mut conn, division.tid) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::InternalServerError().finish()),
+    };
+
+    let user_is_admin = models::tournament_admin::is_admin(&mut conn, tournament.tid, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: RoundPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    let round_update_permission = format!("{}:{}", AppResource::Round.as_str(), AppAction::Update.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &round_update_permission, AppResource::Round.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::packet_export::read_all_of_rounds(&mut conn, &[round_id]) {
+        Ok(exports) => Ok(HttpResponse::Ok().json(exports)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
 pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
     return scope
         .service(index)
         .service(read)
         .service(read_games)
+        .service(read_questions)
+        .service(read_packet)
+        .service(generate_packet)
+        .service(adjust_packet)
+        .service(export_packet)
+        .service(read_packet_exports)
+        .service(export)
         .service(create)
         .service(update)
         .service(destroy);
@@ -1,7 +1,7 @@
 use actix_web::{delete, Error, get, HttpResponse, HttpRequest, post, put, Result, web::{Data, Json, Path, Query}};
 use crate::{database::Database, models::game_statsgroup::{GameStatsGroup, NewGameStatsGroup}};
-use crate::models::{self, common::PaginationParams, statsgroup::{NewStatsGroup, StatsGroup, StatsGroupChangeset}};
-use crate::services::common::{EntityResponse, PagedResponse, process_response};
+use crate::models::{self, common::{ExportParams, PaginationParams}, export::ExportScope, statsgroup::{NewStatsGroup, StatsGroup, StatsGroupChangeset}};
+use crate::services::common::{EntityResponse, PagedResponse, export_response, process_response};
 use diesel::QueryResult;
 use uuid::Uuid;
 
@@ -205,11 +205,33 @@ async fn remove_game(
     }
 }
 
+#[get("/{id}/export")]
+async fn export(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<ExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let statsgroup_id = item_id.into_inner();
+
+    if models::statsgroup::read(&mut db, statsgroup_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    export_response(&mut db, ExportScope::StatsGroup(statsgroup_id), &params)
+}
+
 pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
     return scope
         .service(index)
         .service(read)
         .service(read_games)
+        .service(export)
         .service(create)
         .service(add_game)
         .service(update)
@@ -1,10 +1,16 @@
 use actix_web::{Error, HttpMessage, HttpRequest, HttpResponse, Result, delete, get, post, put, web::{Data, Json, Path, Query}};
 use serde::{Deserialize, Serialize};
-use crate::{auth::{is_rbac_and_abac_authorized, policies::{PolicyContext, UserContext}}, models::{self, permission::{AppAction, AppResource}, role::AppRole, room::Room, tournament_admin::{NewTournamentAdmin, TournamentAdmin}, users_roles::NewUsersRole}};
+use serde_json::json;
+use crate::{auth::{is_rbac_and_abac_authorized, policies::{tournament::TournamentReviewPolicyResource, PolicyContext, UserContext}}, models::{self, permission::{AppAction, AppResource}, role::AppRole, room::Room, tournament_admin::{NewTournamentAdmin, TournamentAdmin}, users_roles::NewUsersRole}};
 use crate::models::tournament::{NewTournament, NewTournamentPayload, Tournament, TournamentChangeset};
 use crate::models::tournament_admin::TournamentAdminChangeset;
-use crate::models::common::{PaginationParams,SearchDateParams};
-use crate::services::common::{EntityResponse, PagedResponse, process_response};
+use crate::models::common::{CertificateParams,EventlogExportParams,ExportParams,PaginationParams,RebuildParams,SearchDateParams};
+use crate::models::{eventlog::EventlogScope, export::ExportScope};
+use crate::models::certificate::CertificateFormat;
+use crate::models::officials::OfficialsRequest;
+use crate::models::timeline::TimelineParams;
+use crate::models::certificate_template::CertificateTemplateChangeset;
+use crate::services::common::{EntityResponse, PagedResponse, eventlog_export_response, export_response, process_response};
 use chrono::Utc;
 use utoipa::OpenApi;
 use diesel::{QueryResult};
@@ -432,6 +438,7 @@ async fn create(
         owner_id: user_ctx.user_id,
         creator_id: user_ctx.user_id,
         pairing_code,
+        quiz_season: payload.quiz_season,
     };
 
     let result : QueryResult<Tournament> = models::tournament::create(&mut db, &item);
@@ -439,6 +446,7 @@ async fn create(
     let response: EntityResponse<Tournament> = process_response(result, "post");
     
     match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
         409 => Ok(HttpResponse::Conflict().json(response)),
         201 => Ok(HttpResponse::Created().json(response)),
         200 => Ok(HttpResponse::Ok().json(response)),
@@ -538,6 +546,7 @@ async fn update(
     let response = process_response(result, "put");
     
     match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
         409 => Ok(HttpResponse::Conflict().json(response)),
         200 => Ok(HttpResponse::Ok().json(response)),
         _ => Ok(HttpResponse::InternalServerError().json(response))
@@ -626,6 +635,700 @@ async fn remove_admin(
     }
 }
 
+#[get("/{id}/certificatetemplate")]
+async fn read_certificate_template(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    match models::certificate_template::read(&mut db, item_id.into_inner()) {
+        Ok(template) => HttpResponse::Ok().json(template),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[get("/{id}/certificatetemplate/preview")]
+async fn preview_certificate_template(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<CertificateParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let format = match CertificateFormat::from_param(&params.format) {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().json(json!({ "message": e })),
+    };
+
+    let tournament = match models::tournament::read(&mut db, item_id.into_inner()) {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+
+    let template = models::certificate_template::read_svg_or_default(&mut db, tournament.tid);
+    let certificate = models::certificate::sample_certificate(&tournament);
+
+    // the template is the tournament's own markup, so the browser must not run or fetch anything
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(("Content-Security-Policy", "default-src 'none'"))
+        .body(models::certificate::render(&format, &template, &certificate))
+}
+
+#[put("/{id}/certificatetemplate")]
+async fn update_certificate_template(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<CertificateTemplateChangeset>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} CertificateTemplate model update {:?} {:?}", line!(), item_id, item.name);
+
+    let result = models::certificate_template::create_or_update(&mut db, item_id.into_inner(), &item);
+
+    let response = process_response(result, "put");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response))
+    }
+}
+
+#[delete("/{id}/certificatetemplate")]
+async fn destroy_certificate_template(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return HttpResponse::Unauthorized().finish()
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    tracing::debug!("{} CertificateTemplate model delete {:?}", line!(), item_id);
+
+    let result = models::certificate_template::delete(&mut db, item_id.into_inner());
+
+    if result.is_ok() {
+        HttpResponse::Ok().finish()
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+#[get("/{id}/export")]
+async fn export(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<ExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let tournament_id = item_id.into_inner();
+
+    if models::tournament::read(&mut db, tournament_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    export_response(&mut db, ExportScope::Tournament(tournament_id), &params)
+}
+
+#[get("/{id}/consistency")]
+async fn read_consistency(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::eventlog_consistency::check_tournament(&mut db, item_id.into_inner()) {
+        Ok(report) => Ok(HttpResponse::Ok().json(report)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[get("/{id}/conflicts")]
+async fn read_conflicts(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::conflict::check_tournament(&mut db, item_id.into_inner()) {
+        Ok(report) => Ok(HttpResponse::Ok().json(report)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[get("/{id}/timeline")]
+async fn read_timeline(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<TimelineParams>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    if models::tournament::read(&mut db, *item_id).is_err() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    match models::timeline::read_timeline(&mut db, item_id.into_inner(), &params) {
+        Ok(report) => Ok(HttpResponse::Ok().json(report)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+// Games that look like the same game started twice, usually by picking the wrong round in QuizMachine.
+#[get("/{id}/duplicategames")]
+async fn read_duplicate_games(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    if models::tournament::read(&mut db, *item_id).is_err() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    match models::game_merge::read_duplicates_of_tournament(&mut db, item_id.into_inner()) {
+        Ok(duplicates) => Ok(HttpResponse::Ok().json(duplicates)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[get("/{id}/gameaudits")]
+async fn read_game_audits(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    if models::tournament::read(&mut db, *item_id).is_err() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    match models::game_merge::read_all_audits_of_tournament(&mut db, item_id.into_inner()) {
+        Ok(audits) => Ok(HttpResponse::Ok().json(audits)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+// The challenge and appeal cases of every game, for the tournament's owner and admins to review.
+#[get("/{id}/rulings")]
+async fn read_rulings(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+
+    let tour_read_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Read.as_str()];
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let tournament_id = tournament.tid;
+    let user_is_admin = models::tournament_admin::is_admin(&mut db, tournament_id, user_ctx.user_id);
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: TournamentReviewPolicyResource { tournament, user_is_tournament_admin: user_is_admin },
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, &tour_read_permission, AppResource::Tournament.as_str()).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    match models::ruling::read_all_of_tournament(&mut db, tournament_id) {
+        Ok(rulings) => Ok(HttpResponse::Ok().json(rulings)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+// How many challenges and appeals each team raised and how many were overturned.
+#[get("/{id}/rulings/summary")]
+async fn read_rulings_summary(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    if models::tournament::read(&mut db, *item_id).is_err() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    match models::ruling::summarize_tournament(&mut db, item_id.into_inner()) {
+        Ok(summary) => Ok(HttpResponse::Ok().json(summary)),
+        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
+    }
+}
+
+#[post("/{id}/officials")]
+async fn assign_officials(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Json(item): Json<OfficialsRequest>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Officials assignment for tournament {:?} {:?}", line!(), item_id, item);
+
+    let result = models::officials::assign(&mut db, item_id.into_inner(), &item);
+
+    // a preview writes nothing, so it answers like a read
+    let method = if item.preview.unwrap_or(true) { "get" } else { "put" };
+    let response = process_response(result, method);
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        404 => Ok(HttpResponse::NotFound().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response)),
+    }
+}
+
+#[post("/{id}/schedules")]
+async fn import_schedule(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    body: String,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Schedule import for tournament {:?}", line!(), item_id);
+
+    let result = models::schedule::import_csv(&mut db, item_id.into_inner(), body.as_bytes());
+    let response = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        404 => Ok(HttpResponse::NotFound().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response)),
+    }
+}
+
+#[get("/{id}/schedules")]
+async fn read_schedule(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let result = models::schedule::read_report(&mut db, item_id.into_inner());
+    let response = process_response(result, "get");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        404 => Ok(HttpResponse::NotFound().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response)),
+    }
+}
+
+#[post("/{id}/schedules/confirm")]
+async fn confirm_schedule(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    tracing::debug!("{} Schedule confirmation for tournament {:?}", line!(), item_id);
+
+    let result = models::schedule::confirm(&mut db, item_id.into_inner());
+    let response = process_response(result, "post");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        404 => Ok(HttpResponse::NotFound().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response)),
+    }
+}
+
+#[delete("/{id}/schedules")]
+async fn discard_schedule(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let result = models::schedule::discard(&mut db, item_id.into_inner());
+    let response = process_response(result, "delete");
+
+    match response.code {
+        400 => Ok(HttpResponse::BadRequest().json(response)),
+        404 => Ok(HttpResponse::NotFound().json(response)),
+        409 => Ok(HttpResponse::Conflict().json(response)),
+        200 => Ok(HttpResponse::Ok().json(response)),
+        201 => Ok(HttpResponse::Created().json(response)),
+        _ => Ok(HttpResponse::InternalServerError().json(response)),
+    }
+}
+
+#[get("/{id}/schedules/export")]
+async fn export_schedule(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return HttpResponse::Unauthorized().finish()
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament_id = item_id.into_inner();
+    let tournament = match models::tournament::read(&mut db, tournament_id) {
+        Ok(t) => t,
+        Err(_) => return HttpResponse::NotFound().finish(),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match models::schedule::export_csv(&mut db, tournament_id) {
+        Ok(bytes) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header(("Content-Disposition", format!["attachment; filename=\"schedule-{}.csv\"", tournament_id]))
+            .body(bytes),
+        Err(e) => {
+            tracing::error!("{} schedule export of tournament {} failed: {}", line!(), tournament_id, e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[post("/{id}/rebuild")]
+async fn rebuild(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<RebuildParams>,
+    req: HttpRequest
+) -> Result<HttpResponse, Error> {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return Ok(HttpResponse::Unauthorized().finish())
+    };
+    let tour_update_permission = format!["{}:{}", AppResource::Tournament.as_str(), AppAction::Update.as_str()];
+    let resource_name = "tournament";
+
+    let tournament = match models::tournament::read(&mut db, *item_id) {
+        Ok(t) => t,
+        Err(_) => return Ok(HttpResponse::NotFound().finish()),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: tournament
+    };
+    if is_rbac_and_abac_authorized(&policy_ctx, tour_update_permission.as_str(), resource_name).is_err() {
+        return Ok(HttpResponse::Unauthorized().finish());
+    }
+
+    let apply = params.apply.unwrap_or(false);
+    tracing::debug!("{} GameEvent rebuild from eventlog for tournament {:?} apply={}", line!(), item_id, apply);
+
+    match models::eventlog_consistency::rebuild_tournament(&mut db, item_id.into_inner(), apply) {
+        Ok(report) => Ok(HttpResponse::Ok().json(report)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({ "message": e.to_string() }))),
+    }
+}
+
+#[get("/{id}/eventlog")]
+async fn export_eventlog(
+    db: Data<Database>,
+    item_id: Path<Uuid>,
+    Query(params): Query<EventlogExportParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let tournament_id = item_id.into_inner();
+
+    if models::tournament::read(&mut db, tournament_id).is_err() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    eventlog_export_response(&mut db, EventlogScope::Tournament(tournament_id), &params)
+}
+
 pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
     return scope
         .service(index)
@@ -641,10 +1344,30 @@ pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
         .service(read_admins)
         .service(read_tournamentgroups)
         .service(read_equipmentregistrations)
+        .service(export)
+        .service(export_eventlog)
+        .service(read_consistency)
+        .service(read_conflicts)
+        .service(read_timeline)
+        .service(read_duplicate_games)
+        .service(read_game_audits)
+        .service(read_rulings_summary)
+        .service(read_rulings)
+        .service(assign_officials)
+        .service(export_schedule)
+        .service(read_schedule)
+        .service(import_schedule)
+        .service(confirm_schedule)
+        .service(discard_schedule)
+        .service(rebuild)
+        .service(read_certificate_template)
+        .service(preview_certificate_template)
+        .service(update_certificate_template)
         .service(create)
         .service(add_admin)
         .service(update)
         .service(update_admin)
         .service(destroy)
+        .service(destroy_certificate_template)
         .service(remove_admin);
 }
@@ -1,5 +1,5 @@
-use actix_web::{delete, Error, get, HttpResponse, HttpRequest, post, put, Result, web::{Data, Json, Path, Query}};
-use crate::{models::{self, roster::{NewRoster, Roster}, roster_coach::{RosterCoach, RosterCoachBuilder}, user::{NewUser, User, UserChangeset}}, services::common::{EntityResponse, PagedResponse, process_response}};
+use actix_web::{delete, Error, get, HttpMessage, HttpResponse, HttpRequest, post, put, Result, web::{Data, Json, Path, Query}};
+use crate::{auth::{is_rbac_and_abac_authorized, policies::{quizzer::QuizzerPolicyResource, PolicyContext, UserContext}}, models::{self, permission::{AppAction, AppResource}, quizzer_strength::QuizzerStrengthParams, roster::{NewRoster, Roster}, roster_coach::{RosterCoach, RosterCoachBuilder}, user::{NewUser, User, UserChangeset}}, services::common::{EntityResponse, PagedResponse, process_response}};
 use crate::models::common::PaginationParams;
 use crate::database::Database;
 use diesel::QueryResult;
@@ -258,6 +258,60 @@ async fn read_rosters_containing_quizzer(
     }
 }
 
+// Which material the quizzer is strong or weak on, for the quizzer, their coaches, and the owner and
+// admins of a tournament they played in only (see QuizzerPolicyResource).
+#[get("/{id}/strengths")]
+async fn read_strengths(
+    db: Data<Database>,
+    user_id: Path<Uuid>,
+    Query(params): Query<QuizzerStrengthParams>,
+    req: HttpRequest
+) -> HttpResponse {
+    let mut db = db.pool.get().unwrap();
+
+    // log this api call
+    models::apicalllog::create(&mut db, &req);
+
+    let extensions = req.extensions();
+    let user_ctx = match extensions.get::<UserContext>() {
+        Some(u_ctx) => u_ctx,
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+
+    let quizzer_id = user_id.into_inner();
+    let tournament = params.tournament_id.and_then(|tid| models::tournament::read(&mut db, tid).ok());
+    let (user_is_admin, quizzer_is_in_tournament) = match &tournament {
+        Some(t) => (
+            models::tournament_admin::is_admin(&mut db, t.tid, user_ctx.user_id),
+            models::quizzer_strength::is_in_tournament(&mut db, quizzer_id, t.tid).unwrap_or(false),
+        ),
+        None => (false, false),
+    };
+    let policy_ctx = PolicyContext {
+        user_ctx: user_ctx.clone(),
+        resource: QuizzerPolicyResource {
+            quizzer_id,
+            user_is_coach: models::quizzer_strength::is_coach_of(&mut db, user_ctx.user_id, quizzer_id).unwrap_or(false),
+            tournament,
+            user_is_tournament_admin: user_is_admin,
+            quizzer_is_in_tournament,
+        },
+    };
+    let user_read_permission = format!("{}:{}", AppResource::User.as_str(), AppAction::Read.as_str());
+    if is_rbac_and_abac_authorized(&policy_ctx, &user_read_permission, AppResource::User.as_str()).is_err() {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match models::quizzer_strength::read(&mut db, quizzer_id, &params) {
+        Ok(strengths) => HttpResponse::Ok().json(strengths),
+        Err(diesel::result::Error::NotFound) => HttpResponse::NotFound().finish(),
+        Err(e) => {
+            tracing::error!("{} quizzer strengths failed: {}", line!(), e);
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
 #[post("")]
 async fn create(
     db: Data<Database>,
@@ -437,6 +491,7 @@ pub fn endpoints(scope: actix_web::Scope) -> actix_web::Scope {
         .service(read_equipmentsets_of_owner)
         .service(read_rosters_of_coach)
         .service(read_rosters_containing_quizzer)
+        .service(read_strengths)
         .service(create)
         .service(create_roster)
         .service(update)
@@ -0,0 +1,295 @@
+mod common;
+mod fixtures;
+
+use actix_http::StatusCode;
+use actix_web::{App, test, web};
+use backend::database::Database;
+use backend::models::bracket::{AdvanceReport, BracketTree, Standing};
+use backend::models;
+use backend::models::game_outcome::GameOutcomeRequest;
+use backend::models::room::RoomBuilder;
+use backend::routes::configure_routes;
+use backend::services::common::EntityResponse;
+use serde_json::json;
+use crate::common::{TEST_DB_URL, clean_database, make_token};
+
+#[actix_web::test]
+async fn single_elimination_bracket_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, teams, rounds, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let brackets_uri = format!("/api/divisions/{}/brackets", division.did);
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+    let payload = json!({ "name": "Championship", "format": "single_elimination" });
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let no_token_req = test::TestRequest::post()
+        .uri(&brackets_uri)
+        .set_json(&payload)
+        .to_request();
+    let no_token_resp = test::call_service(&app, no_token_req).await;
+    assert_eq!(no_token_resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Fail: top_two with two-team games ────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&brackets_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "name": "Top Two", "format": "top_two", "team_size": 2 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: seeded from the standings ───────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&brackets_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(&payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<BracketTree> = test::read_body_json(resp).await;
+    let tree = body.data.unwrap();
+    assert_eq!(tree.roots.len(), 1);
+    let final_node = &tree.roots[0];
+    assert_eq!(final_node.side, "final");
+    assert_eq!(final_node.status, "waiting");
+    assert_eq!(final_node.children.len(), 2);
+    // 1 v 4 and 2 v 3
+    let semifinal_teams: Vec<Vec<Option<String>>> = final_node.children
+        .iter()
+        .map(|n| n.seats.iter().map(|s| s.team_name.clone()).collect())
+        .collect();
+    assert_eq!(semifinal_teams, vec![
+        vec![Some("Team A".to_string()), Some("Team D".to_string())],
+        vec![Some("Team C".to_string()), Some("Team B".to_string())],
+    ]);
+    assert!(final_node.children.iter().all(|n| n.status == "ready"));
+    let bracket_uri = format!("/api/brackets/{}", tree.bracket.bracketid);
+
+    // ── Success: nothing is scheduled into a round where the teams already play ──
+
+    RoomBuilder::new_default("Room 3", division.tid)
+        .set_quizmaster_id(Some(quizmaster.id))
+        .build_and_insert(&mut conn)
+        .unwrap();
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/advance", bracket_uri))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "roundid": rounds[0].roundid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<AdvanceReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert!(report.created.is_empty());
+    assert_eq!(report.warnings.iter().filter(|w| w.contains("already has a game in round Prelims")).count(), 4);
+
+    // ── Success: semifinals are scheduled ────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/advance", bracket_uri))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "roundid": rounds[1].roundid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<AdvanceReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!(report.created.len(), 2);
+    assert_eq!(report.created[0].leftteamid, teams[0].teamid);
+    assert_eq!(report.created[0].rightteamid, teams[3].teamid);
+
+    // Team A beats Team D; Team C forfeits to Team B without a question being read
+    fixtures::brackets::seed_finished_game_stream(&mut conn, &report.created[0], &teams[0], &teams[3], 1, 0);
+    let forfeit = GameOutcomeRequest {
+        outcome: "forfeit".to_string(),
+        reason: "no_show".to_string(),
+        teamid: Some(teams[2].teamid),
+        note: None,
+    };
+    models::game_outcome::record(&mut conn, &report.created[1], owner.id, &forfeit).unwrap();
+
+    // ── Success: bracket games don't change the standings ────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/standings", division.did))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let standings: Vec<Standing> = test::read_body_json(resp).await;
+    assert_eq!(standings[0].name, "Team A");
+    assert_eq!(standings[1].name, "Team C");
+    assert_eq!(standings[1].wins, 1);
+
+    // ── Success: the final is scheduled ──────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/advance", bracket_uri))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "roundid": rounds[2].roundid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<AdvanceReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!(report.created.len(), 1);
+    let final_game = &report.created[0];
+    assert_eq!(final_game.leftteamid, teams[0].teamid);
+    assert_eq!(final_game.rightteamid, teams[1].teamid);
+
+    fixtures::brackets::seed_finished_game_stream(&mut conn, final_game, &teams[0], &teams[1], 0, 2);
+
+    // ── Success: the tree shows the champion ─────────────────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&bracket_uri)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let tree: BracketTree = test::read_body_json(resp).await;
+    assert_eq!(tree.champion, Some(teams[1].teamid));
+    assert_eq!(tree.champion_name, Some("Team B".to_string()));
+    assert_eq!(tree.roots[0].status, "finished");
+    assert_eq!(tree.roots[0].seats[1].place, Some(1));
+
+    // ── Success: nothing left to schedule ────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/advance", bracket_uri))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "roundid": rounds[2].roundid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<AdvanceReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert!(report.created.is_empty());
+    assert!(!report.warnings.is_empty());
+
+    // ── Success: delete ──────────────────────────────────────────────────────
+
+    let req = test::TestRequest::delete()
+        .uri(&bracket_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri(&bracket_uri)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn power_match_bracket_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, teams, rounds, owner, _) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/divisions/{}/brackets", division.did))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "name": "Power", "format": "power_match" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<BracketTree> = test::read_body_json(resp).await;
+    let tree = body.data.unwrap();
+    assert!(tree.roots.is_empty());
+    let advance_uri = format!("/api/brackets/{}/advance", tree.bracket.bracketid);
+
+    // ── Success: 1 v 2 and 3 v 4 ─────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&advance_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "roundid": rounds[1].roundid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<AdvanceReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!(report.created.len(), 2);
+    assert_eq!((report.created[0].leftteamid, report.created[0].rightteamid), (teams[0].teamid, teams[2].teamid));
+    assert_eq!((report.created[1].leftteamid, report.created[1].rightteamid), (teams[1].teamid, teams[3].teamid));
+
+    // ── Success: the next round waits for this one to finish ─────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&advance_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "roundid": rounds[2].roundid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<AdvanceReport> = test::read_body_json(resp).await;
+    assert!(body.data.unwrap().created.is_empty());
+
+    // Team C beats Team A; Team D beats Team B
+    fixtures::brackets::seed_finished_game_stream(&mut conn, &report.created[0], &teams[0], &teams[2], 0, 3);
+    fixtures::brackets::seed_finished_game_stream(&mut conn, &report.created[1], &teams[1], &teams[3], 0, 1);
+
+    // ── Success: paired again from the new standings ─────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&advance_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "roundid": rounds[2].roundid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<AdvanceReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    // C: 2 wins, 80; A: 1 win, 40; D: 1 win, 20; B: none
+    assert_eq!(report.created.len(), 2);
+    assert_eq!((report.created[0].leftteamid, report.created[0].rightteamid), (teams[2].teamid, teams[0].teamid));
+    assert_eq!((report.created[1].leftteamid, report.created[1].rightteamid), (teams[3].teamid, teams[1].teamid));
+    assert_eq!(report.tree.roots.len(), 4);
+}
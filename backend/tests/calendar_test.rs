@@ -0,0 +1,166 @@
+mod common;
+mod fixtures;
+
+use actix_http::StatusCode;
+use actix_web::{App, test, web::{self, Bytes}};
+use backend::database::Database;
+use backend::models::{self, calendar::CalendarFeedWithUrl};
+use backend::routes::configure_routes;
+use backend::services::common::EntityResponse;
+use serde_json::json;
+use crate::common::{PAGE_NUM, PAGE_SIZE, TEST_DB_URL, clean_database, make_token};
+
+#[actix_web::test]
+async fn calendar_feeds_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Two prelim games at 9:00 (A vs B, C vs D); the same quizmaster runs both.
+    let (division, teams, _, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let games = models::game::read_all_games_of_division(&mut conn, division.did, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+    let game_of_team_a = games.iter().find(|g| g.leftteamid == teams[0].teamid).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(owner.id, vec![], vec![]);
+    let quizmaster_token = make_token(quizmaster.id, vec![], vec![]);
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri("/api/calendar/feeds")
+        .set_json(json!({ "scope": "team", "subject_id": teams[0].teamid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Fail: unknown scope ──────────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri("/api/calendar/feeds")
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "scope": "division", "subject_id": division.did }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Fail: another user's schedule ────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri("/api/calendar/feeds")
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "scope": "user", "subject_id": quizmaster.id }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: a team feed, created once ───────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri("/api/calendar/feeds")
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "scope": "team", "subject_id": teams[0].teamid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<CalendarFeedWithUrl> = test::read_body_json(resp).await;
+    let team_feed = body.data.unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/calendar/feeds")
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "scope": "team", "subject_id": teams[0].teamid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<CalendarFeedWithUrl> = test::read_body_json(resp).await;
+    assert_eq!(body.data.unwrap().url, team_feed.url);
+
+    let req = test::TestRequest::get().uri(&team_feed.url).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "text/calendar; charset=utf-8");
+    let ics = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    assert!(ics.contains("X-WR-CALNAME:Team A\r\n"));
+    assert!(ics.contains(&format!["UID:{}@qview\r\n", game_of_team_a.gid]));
+    assert!(ics.contains("DTSTART:20260601T090000Z\r\n"));
+    assert!(ics.contains("SUMMARY:Prelims: Team A vs Team B\r\n"));
+    assert!(ics.contains("LOCATION:Room 1\\, Building 451\r\n"));
+
+    // ── Success: a user feed lists every game the user officiates ────────────
+
+    let req = test::TestRequest::post()
+        .uri("/api/calendar/feeds")
+        .insert_header(("Authorization", format!("Bearer {}", quizmaster_token)))
+        .set_json(json!({ "scope": "user", "subject_id": quizmaster.id }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<CalendarFeedWithUrl> = test::read_body_json(resp).await;
+    let user_feed = body.data.unwrap();
+
+    let req = test::TestRequest::get().uri(&user_feed.url).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let ics: Bytes = test::read_body(resp).await;
+    let ics = String::from_utf8(ics.to_vec()).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert_eq!(ics.replace("\r\n ", "").matches("Your part: quizmaster").count(), 2);
+
+    // ── Success: a room feed ─────────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri("/api/calendar/feeds")
+        .insert_header(("Authorization", format!("Bearer {}", quizmaster_token)))
+        .set_json(json!({ "scope": "room", "subject_id": game_of_team_a.roomid }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<CalendarFeedWithUrl> = test::read_body_json(resp).await;
+    let room_feed = body.data.unwrap();
+
+    let req = test::TestRequest::get().uri(&room_feed.url).to_request();
+    let resp = test::call_service(&app, req).await;
+    let ics = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+
+    let req = test::TestRequest::get()
+        .uri("/api/calendar/feeds")
+        .insert_header(("Authorization", format!("Bearer {}", quizmaster_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let feeds: Vec<CalendarFeedWithUrl> = test::read_body_json(resp).await;
+    assert_eq!(feeds.len(), 2);
+
+    // ── Fail: only the creator can revoke a feed ─────────────────────────────
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/calendar/feeds/{}", team_feed.feed.feedid))
+        .insert_header(("Authorization", format!("Bearer {}", quizmaster_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: a revoked feed's URL stops working ──────────────────────────
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/calendar/feeds/{}", team_feed.feed.feedid))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get().uri(&team_feed.url).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,55 @@
+mod common;
+mod fixtures;
+
+use actix_http::StatusCode;
+use actix_web::{App, test, web};
+use backend::{database::Database, models::{self, competition_level::CompetitionLevel, user::UserBuilder}};
+use backend::routes::configure_routes;
+use serde_json::json;
+use crate::common::{TEST_DB_URL, clean_database, make_token};
+
+// Levels are reference data that clean_database keeps, so these tests use numbers nothing else
+// does and delete what they create.
+
+#[actix_web::test]
+async fn competition_levels_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+    let _ = models::competition_level::delete(&mut conn, 91);
+
+    let admin = UserBuilder::new_default("Admin").set_hash_password("AdminPwd123!").build_and_insert(&mut conn).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let admin_token = make_token(admin.id, vec!["super_user".to_string()], vec![]);
+    let send = |req: test::TestRequest, token: &str| req.insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+
+    // ── Fail: no token, and a name that's taken ──────────────────────────────
+
+    let resp = test::call_service(&app, test::TestRequest::post().uri("/api/competitionlevels").set_json(json!({ "level": 91, "name": "Masters" })).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri("/api/competitionlevels").set_json(json!({ "level": 91, "name": "Novice" })), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    // ── Success ──────────────────────────────────────────────────────────────
+
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri("/api/competitionlevels").set_json(json!({ "level": 91, "name": "Masters" })), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let resp = test::call_service(&app, send(test::TestRequest::put().uri("/api/competitionlevels/91").set_json(json!({ "name": "Grand Masters" })), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let level: CompetitionLevel = test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/competitionlevels/91").to_request()).await;
+    assert_eq!(level.name, "Grand Masters");
+
+    let resp = test::call_service(&app, send(test::TestRequest::delete().uri("/api/competitionlevels/91"), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
@@ -5,7 +5,7 @@ mod fixtures;
 use actix_http::StatusCode;
 use actix_web::{App, test, web::{self,Bytes}};
 use backend::{database::Database, models::{self, apicalllog::ApiCalllog, division::DivisionBuilder, game::Game}, services::common::PagedResponse};
-use backend::models::{division::Division,round::Round,team::Team};
+use backend::models::{award::DivisionAwards,award_setting::AwardSettings,division::Division,division_rookie::DivisionRookie,round::Round,round_question::RoundQuestionRole,round_robin::RoundRobinSchedule,team::Team,timeline::{ShiftRequest, ShiftResult, TimelineReport}};
 use backend::routes::configure_routes;
 use backend::services::common::EntityResponse;
 use chrono::{TimeZone, Utc};
@@ -645,3 +645,776 @@ async fn get_all_games_of_division_works() {
     assert_ne!(game_1_idx, 10);
     assert_ne!(game_2_idx, 10);
 }
+
+#[actix_web::test]
+async fn read_awards_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, _, _) = fixtures::divisions::arrange_division_awards_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/divisions/{}/awards", division.did);
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .to_request();
+
+    // Act:
+
+    let resp = test::call_service(&app, req).await;
+
+    // Assert:
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let awards: DivisionAwards = test::read_body_json(resp).await;
+    assert_eq!(awards.did, division.did);
+    assert_eq!(awards.games_counted, 2);
+    assert_eq!(awards.games_skipped.len(), 1);
+
+    // Top individual scorers: Anna 180, Cara 170, Ben 80, Dan 0
+    assert_eq!(awards.individual_scorers.len(), 4);
+    assert_eq!(awards.individual_scorers[0].quizzer.quizzer_name, "Anna");
+    assert_eq!(awards.individual_scorers[0].value, 180);
+    assert_eq!(awards.individual_scorers[0].place, 1);
+    assert_eq!(awards.individual_scorers[1].quizzer.quizzer_name, "Cara");
+    assert_eq!(awards.individual_scorers[1].value, 170);
+    assert_eq!(awards.individual_scorers[1].quizzer.team_name, "Team 2");
+    assert_eq!(awards.individual_scorers[3].value, 0);
+
+    // Perfect games: Anna 2, Cara 1
+    assert_eq!(awards.perfect_games.len(), 2);
+    assert_eq!(awards.perfect_games[0].quizzer.quizzer_name, "Anna");
+    assert_eq!(awards.perfect_games[0].value, 2);
+    assert_eq!(awards.perfect_games[1].value, 1);
+
+    // Quiz-outs: Anna and Cara are tied
+    assert_eq!(awards.quiz_outs.len(), 2);
+    for award in awards.quiz_outs.iter() {
+        assert_eq!(award.place, 1);
+        assert!(award.is_tied);
+        assert_eq!(award.value, 2);
+    }
+
+    // Team placements:
+    assert_eq!(awards.team_placements.len(), 2);
+    assert_eq!(awards.team_placements[0].team.team_name, "Team 1");
+    assert_eq!(awards.team_placements[0].team.wins, 2);
+    assert_eq!(awards.team_placements[0].team.total_score, 260);
+    assert!(!awards.team_placements[0].is_tied);
+    assert_eq!(awards.team_placements[1].place, 2);
+
+    // Rookies:
+    assert_eq!(awards.rookies.len(), 1);
+    assert_eq!(awards.rookies[0].quizzer.quizzer_name, "Cara");
+    assert_eq!(awards.rookies[0].place, 1);
+
+    // Check that ApiCalllog is recording API calls for this endpoint:
+    let apicalllog_get_result = models::apicalllog::read_all(&mut conn);
+    assert!(apicalllog_get_result.is_ok());
+    let apicalllog_records: Vec<ApiCalllog> = apicalllog_get_result.unwrap();
+    assert_eq!(apicalllog_records.len(), 1);
+    assert_eq!(apicalllog_records.first().unwrap().method.as_str(), "GET");
+    assert_eq!(apicalllog_records.first().unwrap().uri, uri);
+}
+
+#[actix_web::test]
+async fn read_awards_printable_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, _, _) = fixtures::divisions::arrange_division_awards_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/divisions/{}/awards/printable", division.did);
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .to_request();
+
+    // Act:
+
+    let resp = test::call_service(&app, req).await;
+
+    // Assert:
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get("content-type").unwrap().to_str().unwrap().starts_with("text/html"));
+
+    let body: Bytes = test::read_body(resp).await;
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("Top 10 Individual Scorers"));
+    assert!(html.contains("Rookie Leaderboard"));
+    assert!(html.contains("<td>T-1</td><td>Anna</td>"));
+    assert!(html.contains("<td>T-1</td><td>Cara</td>"));
+}
+
+#[actix_web::test]
+async fn read_certificates_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, _, _) = fixtures::divisions::arrange_division_awards_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/divisions/{}/certificates", division.did);
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .to_request();
+
+    // Act:
+
+    let resp = test::call_service(&app, req).await;
+
+    // Assert:
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap().to_str().unwrap(), "application/zip");
+
+    let body: Bytes = test::read_body(resp).await;
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(body.to_vec())).unwrap();
+    assert!(!archive.is_empty());
+
+    let mut names: Vec<String> = archive.file_names().map(|n| n.to_string()).collect();
+    names.sort();
+    assert!(names.iter().all(|n| n.ends_with(".svg")));
+    assert!(names.iter().any(|n| n.contains("Team_Placement") && n.contains("Team_1")));
+
+    let mut svg = String::new();
+    let first_name = names.first().unwrap().clone();
+    std::io::Read::read_to_string(&mut archive.by_name(&first_name).unwrap(), &mut svg).unwrap();
+    assert!(svg.contains("<svg"));
+    assert!(svg.contains(&division.dname));
+
+    let pdf_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/certificates?format=pdf", division.did))
+        .to_request();
+    let pdf_resp = test::call_service(&app, pdf_req).await;
+    assert_eq!(pdf_resp.status(), StatusCode::OK);
+    let body: Bytes = test::read_body(pdf_resp).await;
+    let archive = zip::ZipArchive::new(std::io::Cursor::new(body.to_vec())).unwrap();
+    assert_eq!(archive.len(), names.len());
+    assert!(archive.file_names().all(|n| n.ends_with(".pdf")));
+
+    let bad_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/certificates?format=png", division.did))
+        .to_request();
+    let bad_resp = test::call_service(&app, bad_req).await;
+    assert_eq!(bad_resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn read_export_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, _, _) = fixtures::divisions::arrange_division_awards_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/divisions/{}/export?report=quizzers", division.did);
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .to_request();
+
+    // Act:
+
+    let resp = test::call_service(&app, req).await;
+
+    // Assert:
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap().to_str().unwrap(), "text/csv; charset=utf-8");
+
+    let body: Bytes = test::read_body(resp).await;
+    assert!(body.starts_with(&[0xEF, 0xBB, 0xBF]));
+    let csv = String::from_utf8(body[3..].to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "division,quizzer,team,team_id,games_played,points,average_points,correct_tossups,errors_on_tossups,quiz_outs,perfect_games");
+    assert_eq!(lines.len(), 5);
+    assert!(lines[1].contains(",Anna,"));
+    assert!(lines[1].contains(",2,180,90.00,"));
+
+    let games_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/export?report=games", division.did))
+        .to_request();
+    let games_resp = test::call_service(&app, games_req).await;
+    assert_eq!(games_resp.status(), StatusCode::OK);
+    let body: Bytes = test::read_body(games_resp).await;
+    let csv = String::from_utf8(body[3..].to_vec()).unwrap();
+    assert_eq!(csv.lines().count(), 4);
+    assert_eq!(csv.lines().filter(|l| l.contains(",final,")).count(), 2);
+    assert_eq!(csv.lines().filter(|l| l.contains(",in progress,")).count(), 1);
+
+    let xlsx_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/export?format=xlsx", division.did))
+        .to_request();
+    let xlsx_resp = test::call_service(&app, xlsx_req).await;
+    assert_eq!(xlsx_resp.status(), StatusCode::OK);
+    let body: Bytes = test::read_body(xlsx_resp).await;
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(body.to_vec())).unwrap();
+    let mut workbook = String::new();
+    std::io::Read::read_to_string(&mut archive.by_name("xl/workbook.xml").unwrap(), &mut workbook).unwrap();
+    for sheet in ["games", "standings", "quizzers", "events"] {
+        assert!(workbook.contains(&format!("<sheet name=\"{}\"", sheet)));
+    }
+    let mut standings = String::new();
+    std::io::Read::read_to_string(&mut archive.by_name("xl/worksheets/sheet2.xml").unwrap(), &mut standings).unwrap();
+    assert!(standings.contains("<v>260</v>"));
+
+    let bad_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/export", division.did))
+        .to_request();
+    let bad_resp = test::call_service(&app, bad_req).await;
+    assert_eq!(bad_resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn update_award_settings_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, owner, _) = fixtures::divisions::arrange_division_awards_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let settings_uri = format!("/api/divisions/{}/awardsettings", division.did);
+    let payload = json!({
+        "individual_scorers_cutoff": 1,
+        "quiz_outs_cutoff": 0,
+        "include_perfect_games": false
+    });
+
+    // ── Success: reading the defaults doesn't save them ──────────────────────
+
+    let defaults: AwardSettings = test::call_and_read_body_json(&app, test::TestRequest::get().uri(&settings_uri).to_request()).await;
+    assert_eq!(defaults.questions_per_game, 20);
+    assert!(!models::award_setting::exists(&mut conn, division.did));
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let no_token_req = test::TestRequest::put()
+        .uri(&settings_uri)
+        .set_json(&payload)
+        .to_request();
+    let no_token_resp = test::call_service(&app, no_token_req).await;
+    assert_eq!(no_token_resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: tournament owner with division:update ────────────────────────
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+    let req = test::TestRequest::put()
+        .uri(&settings_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(&payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<AwardSettings> = test::read_body_json(resp).await;
+    let settings = body.data.unwrap();
+    assert_eq!(settings.individual_scorers_cutoff, 1);
+    assert_eq!(settings.quiz_outs_cutoff, 0);
+    assert!(!settings.include_perfect_games);
+    assert_eq!(settings.team_placements_cutoff, 3);
+
+    // ── Fail: negative cutoff ────────────────────────────────────────────────
+
+    let bad_req = test::TestRequest::put()
+        .uri(&settings_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "rookies_cutoff": -1 }))
+        .to_request();
+    let bad_resp = test::call_service(&app, bad_req).await;
+    assert_eq!(bad_resp.status(), StatusCode::BAD_REQUEST);
+
+    // Awards now reflect the new settings:
+    let awards_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/awards", division.did))
+        .to_request();
+    let awards: DivisionAwards = test::call_and_read_body_json(&app, awards_req).await;
+    assert_eq!(awards.individual_scorers.len(), 1);
+    assert_eq!(awards.individual_scorers[0].quizzer.quizzer_name, "Anna");
+    assert_eq!(awards.quiz_outs.len(), 0);
+    assert_eq!(awards.perfect_games.len(), 0);
+
+    // ── Games are final once the division's regulation questions are quizzed ─
+
+    let put = |payload: serde_json::Value| test::TestRequest::put()
+        .uri(&settings_uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(payload)
+        .to_request();
+    let resp = test::call_service(&app, put(json!({ "questions_per_game": 0 }))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = test::call_service(&app, put(json!({ "questions_per_game": 30 }))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let awards_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/awards", division.did))
+        .to_request();
+    let awards: DivisionAwards = test::call_and_read_body_json(&app, awards_req).await;
+    assert_eq!(awards.games_counted, 0);
+}
+
+#[actix_web::test]
+async fn add_and_remove_rookie_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, owner, rookie) = fixtures::divisions::arrange_division_awards_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+
+    // Act & Assert:
+
+    let delete_req = test::TestRequest::delete()
+        .uri(&format!("/api/divisions/{}/rookies/{}", division.did, rookie.id))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .to_request();
+    let delete_resp = test::call_service(&app, delete_req).await;
+    assert_eq!(delete_resp.status(), StatusCode::OK);
+
+    let list_req = test::TestRequest::get()
+        .uri(&format!("/api/divisions/{}/rookies", division.did))
+        .to_request();
+    let rookies: Vec<DivisionRookie> = test::call_and_read_body_json(&app, list_req).await;
+    assert_eq!(rookies.len(), 1);  // the other Anna is still a rookie
+
+    let add_req = test::TestRequest::post()
+        .uri(&format!("/api/divisions/{}/rookies", division.did))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "did": division.did, "quizzerid": rookie.id }))
+        .to_request();
+    let add_resp = test::call_service(&app, add_req).await;
+    assert_eq!(add_resp.status(), StatusCode::CREATED);
+
+    let duplicate_req = test::TestRequest::post()
+        .uri(&format!("/api/divisions/{}/rookies", division.did))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "did": division.did, "quizzerid": rookie.id }))
+        .to_request();
+    let duplicate_resp = test::call_service(&app, duplicate_req).await;
+    assert_eq!(duplicate_resp.status(), StatusCode::CONFLICT);
+
+    // ── Fail: a rookie has to be on a team in the division ───────────────────
+
+    let benched = models::user::UserBuilder::new_default("Benched").set_hash_password("BenchedPwd123!").build_and_insert(&mut conn).unwrap();
+    let benched_req = test::TestRequest::post()
+        .uri(&format!("/api/divisions/{}/rookies", division.did))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "did": division.did, "quizzerid": benched.id }))
+        .to_request();
+    let benched_resp = test::call_service(&app, benched_req).await;
+    assert_eq!(benched_resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn generate_round_robin_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, owner, quizmaster) = fixtures::divisions::arrange_round_robin_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/divisions/{}/schedule/roundrobin", division.did);
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let no_token_req = test::TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({ "team_size": 2 }))
+        .to_request();
+    let no_token_resp = test::call_service(&app, no_token_req).await;
+    assert_eq!(no_token_resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: preview of a two-team round robin ───────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "team_size": 2 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<RoundRobinSchedule> = test::read_body_json(resp).await;
+    let schedule = body.data.unwrap();
+    assert!(schedule.preview);
+    assert_eq!(schedule.games.len(), 10);
+    assert_eq!(schedule.rounds_used, 5);
+    assert!(schedule.unscheduled.is_empty());
+    assert!(schedule.created.is_empty());
+    for team in schedule.teams.iter() {
+        assert_eq!(team.games, 4);
+        assert_eq!(team.byes, 1);
+        assert_eq!(team.left + team.right, 4);
+        assert!((team.left - team.right).abs() <= 1);
+    }
+    // Room B has no quizmaster
+    assert_eq!(schedule.warnings.len(), 1);
+    for game in schedule.games.iter().filter(|g| g.room_name == "Room A") {
+        assert_eq!(game.quizmasterid, Some(quizmaster.id));
+    }
+
+    let games = models::game::read_all_games_of_division(&mut conn, division.did, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+    assert!(games.is_empty());
+
+    // ── Fail: writing a schedule without a quizmaster for every room ──────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "team_size": 2, "preview": false }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Fail: four teams per game ────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "team_size": 4 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: writing the schedule ────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "team_size": 2, "preview": false, "quizmasterid": quizmaster.id }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<RoundRobinSchedule> = test::read_body_json(resp).await;
+    let schedule = body.data.unwrap();
+    assert_eq!(schedule.created.len(), 10);
+
+    let games = models::game::read_all_games_of_division(&mut conn, division.did, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+    assert_eq!(games.len(), 10);
+
+    // ── Success: a new room gets no games, since every team is already playing ──
+
+    let room_c = models::room::RoomBuilder::new_default("Room C", division.tid)
+        .set_quizmaster_id(Some(quizmaster.id))
+        .build_and_insert(&mut conn)
+        .unwrap();
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "team_size": 2 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<RoundRobinSchedule> = test::read_body_json(resp).await;
+    let schedule = body.data.unwrap();
+    assert!(schedule.games.is_empty());
+    models::room::delete(&mut conn, room_c.roomid).unwrap();
+
+    // ── Success: every room is taken now, so nothing more fits ───────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "team_size": 3 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<RoundRobinSchedule> = test::read_body_json(resp).await;
+    let schedule = body.data.unwrap();
+    assert!(schedule.games.is_empty());
+    assert!(!schedule.warnings.is_empty());
+}
+
+#[actix_web::test]
+async fn generate_packets_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, rounds, owner, _, _, questions) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+    let generate = |payload: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/api/divisions/{}/packets", division.did))
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(payload)
+            .to_request()
+    };
+
+    // ── Fail: a round from somewhere else ────────────────────────────────────
+
+    let resp = test::call_service(&app, generate(json!({ "quiz_season": 1, "round_ids": [uuid::Uuid::new_v4()] }))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Fail: no season, and a division season that doesn't exist ───────────
+
+    let resp = test::call_service(&app, generate(json!({ "questions": 2 }))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let set_season = |season: i32| {
+        test::TestRequest::put()
+            .uri(&format!("/api/divisions/{}", division.did))
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(json!({ "quiz_season": season }))
+            .to_request()
+    };
+    let resp = test::call_service(&app, set_season(99)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: every round gets its own questions from the division's season ─
+
+    let resp = test::call_service(&app, set_season(1)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, generate(json!({ "questions": 2, "alternates": 0, "preview": false }))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 3);
+
+    let mut picked = vec![];
+    for round in rounds.iter() {
+        picked.extend(models::round_question::read_question_ids(&mut conn, round.roundid, RoundQuestionRole::Regular).unwrap());
+    }
+    picked.sort();
+    let mut season_one: Vec<uuid::Uuid> = questions.iter().filter(|q| q.quiz_season == 1).map(|q| q.id).collect();
+    season_one.sort();
+    assert_eq!(picked, season_one);
+}
+
+#[actix_web::test]
+async fn export_packets_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, rounds, owner, writer, _, _) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+    let get = |uri: String, token: &str| {
+        test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let export_uri = format!("/api/divisions/{}/packets/csv", division.did);
+    let exports_uri = format!("/api/divisions/{}/packets/exports", division.did);
+
+    // ── Fail: no round has a packet yet ──────────────────────────────────────
+
+    let resp = test::call_service(&app, get(export_uri.clone(), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/api/divisions/{}/packets", division.did))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(json!({ "quiz_season": 1, "questions": 1, "alternates": 0, "overtime": 1, "preview": false, "round_ids": [rounds[0].roundid, rounds[1].roundid] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // ── Fail: someone who doesn't run the tournament ─────────────────────────
+
+    let writer_token = make_token(writer.id, vec!["question_writer".to_string()], vec!["division:update".to_string()]);
+    let resp = test::call_service(&app, get(export_uri.clone(), &writer_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: one file with the packets of the rounds that have one ───────
+
+    let resp = test::call_service(&app, get(export_uri, &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Bytes = test::read_body(resp).await;
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(csv.lines().count(), 5);
+    assert_eq!(csv.lines().filter(|l| l.contains(",overtime,")).count(), 2);
+
+    // ── Success: each round's download is on the division's audit ────────────
+
+    let resp = test::call_service(&app, get(exports_uri, &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Vec<serde_json::Value> = test::read_body_json(resp).await;
+    let mut exported: Vec<String> = body.iter().map(|e| e["roundid"].as_str().unwrap().to_string()).collect();
+    exported.sort();
+    let mut expected = vec![rounds[0].roundid.to_string(), rounds[1].roundid.to_string()];
+    expected.sort();
+    assert_eq!(exported, expected);
+    assert!(body.iter().all(|e| e["exported_by"] == json!(owner.id)));
+}
+
+#[actix_web::test]
+async fn shift_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, rounds, owner, [game_1, _, semifinal_1, semifinal_2]) = fixtures::brackets::arrange_timeline_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/divisions/{}/shift", division.did);
+    let token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["division:update".to_string()],
+    );
+    let at = |hour: u32, minute: u32| Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap();
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({ "minutes": 15 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Fail: a shift of no minutes ──────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "minutes": 0 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: the division moves its later rounds, keeping room offsets ──
+
+    // Room 1 has already pushed its semifinal back 10 minutes
+    models::timeline::shift_room(&mut conn, game_1.roomid, &ShiftRequest { minutes: 10, from: Some(at(9, 30)) }).unwrap();
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "minutes": 15, "from": "2026-06-01T09:30:00Z" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<ShiftResult> = test::read_body_json(resp).await;
+    let shifted = body.data.unwrap();
+    assert_eq!(shifted.roundids.len(), 2);
+    assert!(!shifted.roundids.contains(&rounds[0].roundid));
+    assert_eq!(shifted.gids.len(), 2);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/timeline?now=2026-06-01T09:50:00Z", division.tid))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let report: TimelineReport = test::read_body_json(resp).await;
+    let projected_start = |gid| report.entries.iter().find(|e| e.gid == gid).unwrap().projected_start;
+    assert_eq!(projected_start(game_1.gid), Some(at(9, 0)));
+    assert_eq!(projected_start(semifinal_1.gid), Some(at(10, 25)));
+    assert_eq!(projected_start(semifinal_2.gid), Some(at(10, 15)));
+}
@@ -0,0 +1,137 @@
+use backend::{database, models};
+use backend::models::division::Division;
+use backend::models::game::{Game, GameBuilder};
+use backend::models::gameevent::GameEventStreamBuilder;
+use backend::models::room::{Room, RoomBuilder};
+use backend::models::round::{Round, RoundBuilder};
+use backend::models::team::{Team, TeamBuilder};
+use backend::models::user::{User, UserBuilder};
+use chrono::{TimeZone, Utc};
+
+use crate::fixtures::{divisions::seed_division, tournaments::seed_tournament};
+
+/// Returns `(division, teams, rounds, owner, quizmaster)` for a division with four teams, two
+/// rooms and three rounds ("Prelims", "Semifinals" and "Final"). In the prelims Team A beat
+/// Team B 40-0 and Team C beat Team D 20-0, so the standings are A, C, then B and D tied.
+pub fn arrange_bracket_works_integration_test(
+    db: &mut database::Connection,
+) -> (Division, Vec<Team>, Vec<Round>, User, User) {
+    let tour = seed_tournament(db, "Bracket Tournament");
+    let division = seed_division(db, tour.tid);
+
+    let coach = UserBuilder::new_default("Coach")
+        .set_hash_password("CoachPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+    let teams: Vec<Team> = ["Team A", "Team B", "Team C", "Team D"]
+        .iter()
+        .map(|team_name| {
+            TeamBuilder::new_default(division.did)
+                .set_name(team_name)
+                .set_coachid(coach.id)
+                .build_and_insert(db)
+                .unwrap()
+        })
+        .collect();
+
+    let rounds: Vec<Round> = ["Prelims", "Semifinals", "Final"]
+        .iter()
+        .enumerate()
+        .map(|(idx, round_name)| {
+            RoundBuilder::new_default(division.did)
+                .set_name(round_name)
+                .set_scheduled_start_time(Utc.with_ymd_and_hms(2026, 6, 1, 9 + idx as u32, 0, 0).unwrap())
+                .build_and_insert(db)
+                .unwrap()
+        })
+        .collect();
+
+    let quizmaster = UserBuilder::new_default("Quizmaster")
+        .set_hash_password("QuizmasterPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+    let rooms: Vec<Room> = ["Room 1", "Room 2"]
+        .iter()
+        .map(|room_name| {
+            RoomBuilder::new_default(room_name, tour.tid)
+                .set_quizmaster_id(Some(quizmaster.id))
+                .build_and_insert(db)
+                .unwrap()
+        })
+        .collect();
+
+    let game_1 = seed_game(db, &rooms[0], &rounds[0], &teams[0], &teams[1], &quizmaster);
+    seed_finished_game_stream(db, &game_1, &teams[0], &teams[1], 2, 0);
+    let game_2 = seed_game(db, &rooms[1], &rounds[0], &teams[2], &teams[3], &quizmaster);
+    seed_finished_game_stream(db, &game_2, &teams[2], &teams[3], 1, 0);
+
+    let owner = models::user::read(db, tour.owner_id).unwrap();
+
+    (division, teams, rounds, owner, quizmaster)
+}
+
+/// Returns `(division, rounds, owner, [prelim_1, prelim_2, semifinal_1, semifinal_2])` for
+/// testing the timeline and shifts. Prelims start at 9:00 and Semifinals at 10:00; Room 1
+/// started 40 minutes late and its events arrived out of order, while Room 2 was on time.
+pub fn arrange_timeline_works_integration_test(
+    db: &mut database::Connection,
+) -> (Division, Vec<Round>, User, [Game; 4]) {
+    let (division, teams, rounds, owner, quizmaster) = arrange_bracket_works_integration_test(db);
+    let prelims = models::game::read_all_games_of_round(db, rounds[0].roundid, &models::common::PaginationParams { page: 0, page_size: 10 }).unwrap();
+    let game_1 = prelims.iter().find(|g| g.leftteamid == teams[0].teamid).unwrap().clone();
+    let game_2 = prelims.iter().find(|g| g.leftteamid == teams[2].teamid).unwrap().clone();
+    let semifinal_1 = GameBuilder::new_default(game_1.roomid, rounds[1].roundid)
+        .set_leftteamid(teams[0].teamid)
+        .set_rightteamid(teams[3].teamid)
+        .set_quizmasterid(quizmaster.id)
+        .build_and_insert(db)
+        .unwrap();
+    let semifinal_2 = GameBuilder::new_default(game_2.roomid, rounds[1].roundid)
+        .set_leftteamid(teams[2].teamid)
+        .set_rightteamid(teams[1].teamid)
+        .set_quizmasterid(quizmaster.id)
+        .build_and_insert(db)
+        .unwrap();
+
+    let at = |hour: u32, minute: u32| Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap();
+    models::game::record_event_time(db, game_1.gid, at(9, 55)).unwrap();
+    models::game::record_event_time(db, game_1.gid, at(9, 40)).unwrap();
+    models::game::record_event_time(db, game_1.gid, at(9, 50)).unwrap();
+    models::game::record_event_time(db, game_2.gid, at(9, 0)).unwrap();
+    models::game::record_event_time(db, game_2.gid, at(9, 28)).unwrap();
+
+    (division, rounds, owner, [game_1, game_2, semifinal_1, semifinal_2])
+}
+
+fn seed_game(db: &mut database::Connection, room: &Room, round: &Round, left: &Team, right: &Team, quizmaster: &User) -> Game {
+    GameBuilder::new_default(room.roomid, round.roundid)
+        .set_leftteamid(left.teamid)
+        .set_rightteamid(right.teamid)
+        .set_quizmasterid(quizmaster.id)
+        .build_and_insert(db)
+        .unwrap()
+}
+
+/// Plays all twenty questions of a two-team game: each correct answer is worth 20 points and
+/// every other question is a no jump.
+pub fn seed_finished_game_stream(db: &mut database::Connection, game: &Game, left: &Team, right: &Team, left_correct: i32, right_correct: i32) {
+    let left_quizzer = format!["{} Quizzer", left.name];
+    let right_quizzer = format!["{} Quizzer", right.name];
+    let mut stream = GameEventStreamBuilder::new(game.gid)
+        .then_add_RM("Tournament")
+        .then_add_QT("Nazarene")
+        .then_add_TN(&left.name, 0).unwrap()
+        .then_add_QN_plus_if_SC_or_SS(&left_quizzer, 0, 0, true, false).unwrap()
+        .then_add_TN(&right.name, 1).unwrap()
+        .then_add_QN_plus_if_SC_or_SS(&right_quizzer, 1, 0, true, false).unwrap();
+    for _ in 0..left_correct {
+        stream = stream.then_add_TC(&left_quizzer, 0).unwrap();
+    }
+    for _ in 0..right_correct {
+        stream = stream.then_add_TC(&right_quizzer, 1).unwrap();
+    }
+    for _ in 0..(20 - left_correct - right_correct) {
+        stream = stream.then_add_NJ().unwrap();
+    }
+    stream.build_and_insert(db).unwrap();
+}
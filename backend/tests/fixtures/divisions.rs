@@ -1,13 +1,18 @@
-use backend::database;
+use backend::{database, models};
 use backend::models::division::{Division, DivisionBuilder, NewDivision};
-use backend::models::team::{Team};
+use backend::models::division_rookie::DivisionRookieBuilder;
+use backend::models::game::GameBuilder;
+use backend::models::gameevent::GameEventStreamBuilder;
+use backend::models::room::RoomBuilder;
+use backend::models::round::RoundBuilder;
+use backend::models::team::{Team, TeamBuilder};
 use backend::models::tournament::{Tournament, TournamentBuilder};
 use backend::models::tournament_admin::TournamentAdminBuilder;
 use backend::models::user::{User, UserBuilder};
 use chrono::{TimeZone, Utc};
 use uuid::Uuid;
 
-use crate::fixtures::{rounds::seed_rounds_with_sched_start_times, teams::seed_teams_with_names, tournaments::seed_tournament};
+use crate::fixtures::{games::seed_1_game_with_minimum_required_dependencies, rounds::seed_rounds_with_sched_start_times, teams::seed_teams_with_names, tournaments::seed_tournament};
 
 /// Returns `(tournament, division_1, division_2, owner, admin_user, unrelated_user)` for testing
 /// division delete ABAC: owner and admin should be allowed, unrelated user should not.
@@ -255,3 +260,161 @@ pub fn seed_rounds_in_division(db: &mut database::Connection, tid: Uuid) -> Divi
 
     div_1.clone()
 }
+
+/// Returns `(division, owner, rookie)` for testing division awards. Three games are seeded in the
+/// division: two finalized games between "Team 1" and "Team 2" and one game that stops at question 1
+/// (which must not be counted). Anna quizzes out without error in both finalized games and Cara
+/// quizzes out in both, so they tie for most quiz-outs. Cara is marked as a rookie, and so is a
+/// second Anna on Team 2, who shares Team 1's Anna's name but never quizzed.
+pub fn arrange_division_awards_works_integration_test(
+    db: &mut database::Connection,
+) -> (Division, User, User) {
+    let (game_1, tour, division, _, room, team_1, team_2, _, _, quizmaster) =
+        seed_1_game_with_minimum_required_dependencies(db);
+
+    let round_2 = RoundBuilder::new_default(division.did)
+        .set_name("2")
+        .build_and_insert(db)
+        .unwrap();
+    let game_2 = GameBuilder::new_default(room.roomid, round_2.roundid)
+        .set_leftteamid(team_1.teamid)
+        .set_rightteamid(team_2.teamid)
+        .set_quizmasterid(quizmaster.id)
+        .build_and_insert(db)
+        .unwrap();
+    let round_3 = RoundBuilder::new_default(division.did)
+        .set_name("3")
+        .build_and_insert(db)
+        .unwrap();
+    let game_3 = GameBuilder::new_default(room.roomid, round_3.roundid)
+        .set_leftteamid(team_1.teamid)
+        .set_rightteamid(team_2.teamid)
+        .set_quizmasterid(quizmaster.id)
+        .build_and_insert(db)
+        .unwrap();
+
+    // Game 1: Team 1 = 150 (Anna 90, Ben 60), Team 2 = 80 (Cara 80)
+    let mut game_1_stream = seed_awards_game_stream_start(game_1.gid);
+    for _ in 0..4 {
+        game_1_stream = game_1_stream.then_add_TC("Anna", 0).unwrap();
+    }
+    for _ in 0..3 {
+        game_1_stream = game_1_stream.then_add_TC("Ben", 0).unwrap();
+    }
+    game_1_stream = game_1_stream.then_add_TE_and_bonuses("Cara", 1, false, false).unwrap();
+    for _ in 0..4 {
+        game_1_stream = game_1_stream.then_add_TC("Cara", 1).unwrap();
+    }
+    for _ in 0..8 {
+        game_1_stream = game_1_stream.then_add_NJ().unwrap();
+    }
+    game_1_stream.build_and_insert(db).unwrap();
+
+    // Game 2: Team 1 = 110 (Anna 90, Ben 20), Team 2 = 90 (Cara 90)
+    let mut game_2_stream = seed_awards_game_stream_start(game_2.gid);
+    for _ in 0..4 {
+        game_2_stream = game_2_stream.then_add_TC("Anna", 0).unwrap();
+    }
+    for _ in 0..4 {
+        game_2_stream = game_2_stream.then_add_TC("Cara", 1).unwrap();
+    }
+    game_2_stream = game_2_stream.then_add_TC("Ben", 0).unwrap();
+    for _ in 0..11 {
+        game_2_stream = game_2_stream.then_add_NJ().unwrap();
+    }
+    game_2_stream.build_and_insert(db).unwrap();
+
+    // Game 3: unfinished
+    seed_awards_game_stream_start(game_3.gid)
+        .then_add_TC("Dan", 1).unwrap()
+        .build_and_insert(db)
+        .unwrap();
+
+    let rookie = UserBuilder::new_default("Cara")
+        .set_hash_password("CaraPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+    let other_anna = UserBuilder::new_default("Anna")
+        .set_hash_password("AnnaPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+    let seats = models::team::TeamChangeset {
+        coachid: None,
+        name: None,
+        quizzer_one_id: Some(Some(rookie.id)),
+        quizzer_two_id: Some(Some(other_anna.id)),
+        quizzer_three_id: None,
+        quizzer_four_id: None,
+        quizzer_five_id: None,
+        quizzer_six_id: None,
+    };
+    models::team::update(db, team_2.teamid, &seats).unwrap();
+    for quizzer in [&rookie, &other_anna] {
+        DivisionRookieBuilder::new(division.did, quizzer.id)
+            .build_and_insert(db)
+            .unwrap();
+    }
+
+    let owner = models::tournament::read(db, tour.tid)
+        .and_then(|t| models::user::read(db, t.owner_id))
+        .unwrap();
+
+    (division, owner, rookie)
+}
+
+/// Returns `(division, owner, quizmaster)` for a division with five teams, five rounds and two
+/// rooms. "Room A" has a quizmaster and "Room B" doesn't.
+pub fn arrange_round_robin_works_integration_test(
+    db: &mut database::Connection,
+) -> (Division, User, User) {
+    let tour = seed_tournament(db, "Round Robin Tournament");
+    let division = seed_division(db, tour.tid);
+
+    let coach = UserBuilder::new_default("Coach")
+        .set_hash_password("CoachPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+    for team_name in ["Team 1", "Team 2", "Team 3", "Team 4", "Team 5"] {
+        TeamBuilder::new_default(division.did)
+            .set_name(team_name)
+            .set_coachid(coach.id)
+            .build_and_insert(db)
+            .unwrap();
+    }
+
+    for round_num in 1..=5 {
+        RoundBuilder::new_default(division.did)
+            .set_name(&round_num.to_string())
+            .set_scheduled_start_time(Utc.with_ymd_and_hms(2026, 6, 1, 8 + round_num, 0, 0).unwrap())
+            .build_and_insert(db)
+            .unwrap();
+    }
+
+    let quizmaster = UserBuilder::new_default("Quizmaster")
+        .set_hash_password("QuizmasterPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+    RoomBuilder::new_default("Room A", tour.tid)
+        .set_quizmaster_id(Some(quizmaster.id))
+        .build_and_insert(db)
+        .unwrap();
+    RoomBuilder::new_default("Room B", tour.tid)
+        .build_and_insert(db)
+        .unwrap();
+
+    let owner = models::user::read(db, tour.owner_id).unwrap();
+
+    (division, owner, quizmaster)
+}
+
+fn seed_awards_game_stream_start(gid: Uuid) -> GameEventStreamBuilder {
+    GameEventStreamBuilder::new(gid)
+        .then_add_RM("Tournament")
+        .then_add_QT("Nazarene")
+        .then_add_TN("Team 1", 0).unwrap()
+        .then_add_QN_plus_if_SC_or_SS("Anna", 0, 0, true, false).unwrap()
+        .then_add_QN_plus_if_SC_or_SS("Ben", 0, 1, false, true).unwrap()
+        .then_add_TN("Team 2", 1).unwrap()
+        .then_add_QN_plus_if_SC_or_SS("Cara", 1, 0, true, false).unwrap()
+        .then_add_QN_plus_if_SC_or_SS("Dan", 1, 1, false, true).unwrap()
+}
@@ -1,4 +1,4 @@
-use backend::{database, models::{division::{Division, DivisionBuilder}, game::{Game, GameBuilder, NewGame}, game_statsgroup::GameStatsGroupBuilder, gameevent::{GameEvent, GameEventCode, GameEventBuilder}, room::{Room, RoomBuilder}, round::{Round, RoundBuilder}, statsgroup::{StatsGroup, StatsGroupBuilder}, team::{Team, TeamBuilder}, tournament::{Tournament, TournamentBuilder}, tournament_admin::TournamentAdminBuilder, user::{User, UserBuilder}}};
+use backend::{database, models::{division::{Division, DivisionBuilder}, eventlog, game::{Game, GameBuilder, NewGame}, game_statsgroup::GameStatsGroupBuilder, gameevent::{GameEvent, GameEventCode, GameEventBuilder}, room::{Room, RoomBuilder}, round::{Round, RoundBuilder}, statsgroup::{StatsGroup, StatsGroupBuilder}, team::{Team, TeamBuilder}, tournament::{Tournament, TournamentBuilder}, tournament_admin::TournamentAdminBuilder, user::{User, UserBuilder}}};
 use chrono::TimeZone;
 use diesel::prelude::*;
 use uuid::Uuid;
@@ -1289,3 +1289,80 @@ pub fn arrange_game_create_works_integration_test(
 
     (tournament, owner, admin_user, unrelated_user, round.roundid, room.roomid, division.did, teams.0.teamid, teams.1.teamid, teams.2.teamid, quizmaster.id, content_judge.id)
 }
+
+/// Returns `(game_1, game1_event1, game1_event2)` from `arrange_get_gameevents_of_game_works_integration_test`
+/// with the two game 1 events also written to `eventlogs`, as the scorekeeping endpoint would have
+/// recorded them. An eventlog entry for a different round is added so game filtering can be checked.
+pub fn arrange_export_eventlog_of_game_works_integration_test(db: &mut database::Connection) -> (Game, GameEvent, GameEvent) {
+    let (game_1, game1_event1, game1_event2) = arrange_get_gameevents_of_game_works_integration_test(db);
+
+    for (game_event, ts, round) in [
+        (&game1_event1, "1748009109", game_1.roundid.to_string()),
+        (&game1_event2, "1748009169", game_1.roundid.to_string()),
+        (&game1_event2, "1748009229", Uuid::new_v4().to_string()),
+    ] {
+        let mut entry = eventlog::empty_changeset();
+        entry.clientkey = game_1.clientkey.clone();
+        entry.tournament = game_1.tournamentid.to_string();
+        entry.division = game_1.divisionid.to_string();
+        entry.room = game_1.roomid.to_string();
+        entry.round = round;
+        entry.question = game_event.question;
+        entry.eventnum = game_event.eventnum;
+        entry.name = game_event.name.clone();
+        entry.team = game_event.team;
+        entry.quizzer = game_event.quizzer;
+        entry.event = game_event.event.clone();
+        entry.ts = ts.to_string();
+        eventlog::create(db, &entry).unwrap();
+    }
+
+    (game_1, game1_event1, game1_event2)
+}
+
+/// Returns `(game, owner_id)` for testing the eventlog/gameevent consistency check and rebuild.
+/// The eventlog holds four events (question 2 was resent with a corrected name). Of those,
+/// gameevents have question 1 as logged, question 2 with the name from before the resend and no
+/// question 3, plus a question 4 event that was never logged. One more eventlog entry comes from a
+/// client key that has no game.
+pub fn arrange_eventlog_consistency_works_integration_test(db: &mut database::Connection) -> (Game, Uuid) {
+    let (game, tour, _, _, _, _, _, _, _, _) = seed_1_game_with_minimum_required_dependencies(db);
+
+    for (clientkey, question, name, event, ts) in [
+        (game.clientkey.as_str(), 1, "Tori", "TC", "1748009109"),
+        (game.clientkey.as_str(), 2, "Kevn", "TC", "1748009169"),
+        (game.clientkey.as_str(), 2, "Kevin", "TC", "1748009170"),
+        (game.clientkey.as_str(), 3, "Tori", "TE", "1748009229"),
+        ("unknown-client", 1, "Nobody", "TC", "1748009109"),
+    ] {
+        let mut entry = eventlog::empty_changeset();
+        entry.clientkey = clientkey.to_string();
+        entry.tournament = game.tournamentid.to_string();
+        entry.division = game.divisionid.to_string();
+        entry.room = game.roomid.to_string();
+        entry.round = game.roundid.to_string();
+        entry.question = question;
+        entry.eventnum = 1;
+        entry.name = name.to_string();
+        entry.team = 0;
+        entry.quizzer = 1;
+        entry.event = event.to_string();
+        entry.ts = ts.to_string();
+        eventlog::create(db, &entry).unwrap();
+    }
+
+    for (question, name, ts) in [(1, "Tori", 1748009109), (2, "Kevn", 1748009169), (4, "Ghost", 1748009289)] {
+        GameEventBuilder::new_default(game.gid)
+            .set_question(Some(question))
+            .set_eventnum(Some(1))
+            .set_name(Some(name.to_string()))
+            .set_team(Some(0))
+            .set_quizzer(Some(1))
+            .set_event(Some(GameEventCode::TC))
+            .set_clientts(Some(chrono::Utc.timestamp_opt(ts, 0).unwrap()))
+            .build_and_insert(db)
+            .unwrap();
+    }
+
+    (game, tour.owner_id)
+}
@@ -25,3 +25,5 @@ pub mod extensioncords;
 pub mod apicalllogs;
 pub mod roles;
 pub mod permissions;pub mod create_tournament_applicants;
+pub mod brackets;
+pub mod questions;
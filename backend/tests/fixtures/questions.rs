@@ -0,0 +1,78 @@
+use backend::{database, models::{self, division::Division, question::{Question, QuestionBuilder}, quiz_season::NewQuizSeason, round::Round, user::{User, UserBuilder}}};
+
+/// Returns `(writer, member, questions)` for a question bank with five questions: three from
+/// John 1 and 3 in season 1 (one of them for practice) and two from Acts 2 in season 2.
+pub fn arrange_question_bank_works_integration_test(
+    db: &mut database::Connection,
+) -> (User, User, Vec<Question>) {
+    let writer = UserBuilder::new_default("Question Writer")
+        .set_hash_password("WriterPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+    let member = UserBuilder::new_default("Member")
+        .set_hash_password("MemberPwd123!")
+        .build_and_insert(db)
+        .unwrap();
+
+    // seasons are reference data clean_database keeps, so Acts is only added the first time
+    if !models::quiz_season::exists(db, 2) {
+        models::quiz_season::create(db, &NewQuizSeason { season: 2, name: "Acts".to_string(), year: None, material: "Acts 1-28".to_string() }).unwrap();
+    }
+
+    let questions = vec![
+        QuestionBuilder::new_default("In the beginning was what?", "The Word")
+            .build_and_insert(db)
+            .unwrap(),
+        QuestionBuilder::new_default("Who was sent from God?", "John")
+            .set_verses("6")
+            .set_beginning_verse(6)
+            .set_type("A")
+            .build_and_insert(db)
+            .unwrap(),
+        QuestionBuilder::new_default("How did God love the world?", "He gave his one and only Son")
+            .set_chapter(3)
+            .set_verses("16")
+            .set_beginning_verse(16)
+            .set_competition_lvl(0)
+            .build_and_insert(db)
+            .unwrap(),
+        QuestionBuilder::new_default("When had the day of Pentecost come?", "When they were all together in one place")
+            .set_quiz_season(2)
+            .set_book("Acts")
+            .set_chapter(2)
+            .build_and_insert(db)
+            .unwrap(),
+        QuestionBuilder::new_default("What came from heaven?", "A sound like the blowing of a violent wind")
+            .set_quiz_season(2)
+            .set_book("Acts")
+            .set_chapter(2)
+            .set_verses("2-3")
+            .set_beginning_verse(2)
+            .build_and_insert(db)
+            .unwrap(),
+    ];
+
+    (writer, member, questions)
+}
+
+/// Returns `(division, rounds, owner, writer, member, questions)`: the bracket division with its
+/// three rounds, and the question bank with three more questions from John 1 in season 1, so
+/// season 1 has six questions (one of them type A) and season 2 has two.
+pub fn arrange_packet_works_integration_test(
+    db: &mut database::Connection,
+) -> (Division, Vec<Round>, User, User, User, Vec<Question>) {
+    let (division, _, rounds, owner, _) = super::brackets::arrange_bracket_works_integration_test(db);
+    let (writer, member, mut questions) = arrange_question_bank_works_integration_test(db);
+
+    for (verse, question, answer) in [(3, "Through whom were all things made?", "Through him"), (4, "What was in him?", "Life"), (5, "Where does the light shine?", "In the darkness")] {
+        questions.push(
+            QuestionBuilder::new_default(question, answer)
+                .set_verses(&verse.to_string())
+                .set_beginning_verse(verse)
+                .build_and_insert(db)
+                .unwrap(),
+        );
+    }
+
+    (division, rounds, owner, writer, member, questions)
+}
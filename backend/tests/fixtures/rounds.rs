@@ -1,5 +1,6 @@
 use backend::{database, models::{division::{Division, DivisionBuilder}, game::Game, round::{NewRound, Round, RoundBuilder}, tournament::{Tournament, TournamentBuilder}, tournament_admin::TournamentAdminBuilder, user::{User, UserBuilder}}};
 use chrono::{DateTime, TimeZone, Utc};
+use backend::schema::rounds;
 use diesel::prelude::*;
 use uuid::Uuid;
 use crate::fixtures;
@@ -152,6 +153,18 @@ pub fn seed_round(db: &mut database::Connection, did: Uuid) -> Round {
         .unwrap()
 }
 
+/// A round whose start time hasn't been set yet.
+pub fn seed_untimed_round(db: &mut database::Connection, did: Uuid, name: &str) -> Round {
+    let round = RoundBuilder::new_default(did)
+        .set_name(name)
+        .build_and_insert(db)
+        .unwrap();
+    diesel::update(rounds::table.filter(rounds::roundid.eq(round.roundid)))
+        .set(rounds::scheduled_start_time.eq(None::<DateTime<Utc>>))
+        .get_result::<Round>(db)
+        .unwrap()
+}
+
 pub fn seed_rounds(
     db: &mut database::Connection,
     did: Uuid
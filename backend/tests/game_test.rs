@@ -5,7 +5,7 @@ mod fixtures;
 use actix_http::StatusCode;
 use actix_web::{App, test, web::{self,Bytes}};
 use backend::{database::Database, models::{self, apicalllog::ApiCalllog, gameevent::GameEvent, statsgroup::StatsGroup}, services::common::PagedResponse};
-use backend::models::game::Game;
+use backend::models::{bracket::Standing, conflict::GameWithConflicts, eventlog_consistency::{GameConsistencyReport, RebuildReport}, game::Game, game_merge::{DuplicateGames, GameAudit, GameMerge, GameMove}, game_outcome::GameOutcome, game_question::GameQuestion, round_question::RoundQuestionRole, ruling::{Ruling, RulingRecord}, scoresheet::{ManualScoresheet, ScoresheetReport}};
 use backend::routes::configure_routes;
 use backend::services::common::EntityResponse;
 use serde_json::json;
@@ -639,3 +639,824 @@ async fn get_gameevents_of_game_works() {
     assert_eq!(apicalllog_records.first().unwrap().uri, uri);
 }
 
+
+#[actix_web::test]
+async fn export_eventlog_of_game_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (game, _, _) = fixtures::games::arrange_export_eventlog_of_game_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/games/{}/eventlog", game.gid);
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .to_request();
+
+    // Act:
+
+    let resp = test::call_service(&app, req).await;
+
+    // Assert:
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: Bytes = test::read_body(resp).await;
+    let eventlog = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<&str> = eventlog.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "clientkey,tournament,division,room,round,question,eventnum,name,team,quizzer,event,parm1,parm2,clientts");
+    assert_eq!(
+        lines[1],
+        format!("{},{},{},{},{},1,1,Tori,0,2,TC,,,2025-05-23-14.05.09.000000", game.clientkey, game.tournamentid, game.divisionid, game.roomid, game.roundid)
+    );
+    assert!(lines[2].contains(",2,1,Kevin,1,2,TC,,,2025-05-23-14.06.09.000000"));
+
+    let gameevents_req = test::TestRequest::get()
+        .uri(&format!("/api/games/{}/eventlog?source=gameevents", game.gid))
+        .to_request();
+    let gameevents_resp = test::call_service(&app, gameevents_req).await;
+    assert_eq!(gameevents_resp.status(), StatusCode::OK);
+    let body: Bytes = test::read_body(gameevents_resp).await;
+    let eventlog = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<&str> = eventlog.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with(&format!("{},{},{},{},{},1,1,Tori,0,2,TC,", game.clientkey, game.tournamentid, game.divisionid, game.roomid, game.roundid)));
+
+    let bad_req = test::TestRequest::get()
+        .uri(&format!("/api/games/{}/eventlog?source=disk", game.gid))
+        .to_request();
+    let bad_resp = test::call_service(&app, bad_req).await;
+    assert_eq!(bad_resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn check_and_rebuild_gameevents_from_eventlog_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (game, owner_id) = fixtures::games::arrange_eventlog_consistency_works_integration_test(&mut conn);
+    let token = make_token(
+        owner_id,
+        vec!["tournament_manager".to_string()],
+        vec!["game:update".to_string()],
+    );
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let check_req = test::TestRequest::get()
+        .uri(&format!("/api/games/{}/consistency", game.gid))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    // Act:
+
+    let check_resp = test::call_service(&app, check_req).await;
+
+    // Assert:
+
+    assert_eq!(check_resp.status(), StatusCode::OK);
+    let report: GameConsistencyReport = test::read_body_json(check_resp).await;
+    assert!(!report.is_consistent);
+    assert_eq!(report.eventlog_events, 3);
+    assert_eq!(report.gameevents, 3);
+    assert_eq!(report.missing.len(), 1);
+    assert_eq!(report.missing[0].question, 3);
+    assert_eq!(report.extra.len(), 1);
+    assert_eq!(report.extra[0].name.as_str(), "Ghost");
+    assert_eq!(report.conflicting.len(), 1);
+    assert_eq!(report.conflicting[0].question, 2);
+    assert_eq!(report.conflicting[0].differences.len(), 2);  // name, clientts
+    assert_eq!(report.conflicting[0].differences[0].eventlog.as_str(), "Kevin");
+    assert_eq!(report.conflicting[0].differences[0].gameevent.as_str(), "Kevn");
+
+    // dry run changes nothing
+    let dry_run_req = test::TestRequest::post()
+        .uri(&format!("/api/games/{}/rebuild", game.gid))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let dry_run_resp = test::call_service(&app, dry_run_req).await;
+    assert_eq!(dry_run_resp.status(), StatusCode::OK);
+    let dry_run: RebuildReport = test::read_body_json(dry_run_resp).await;
+    assert!(!dry_run.applied);
+    assert_eq!(dry_run.games[0].action.as_str(), "would rebuild");
+    assert_eq!(models::gameevent::read_all_gameevents_of_games(&mut conn, &[game.gid]).unwrap().len(), 3);
+
+    let apply_req = test::TestRequest::post()
+        .uri(&format!("/api/games/{}/rebuild?apply=true", game.gid))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let apply_resp = test::call_service(&app, apply_req).await;
+    assert_eq!(apply_resp.status(), StatusCode::OK);
+    let applied: RebuildReport = test::read_body_json(apply_resp).await;
+    assert!(applied.applied);
+    assert_eq!(applied.games[0].action.as_str(), "rebuilt");
+
+    let rebuilt = models::gameevent::read_all_gameevents_of_games(&mut conn, &[game.gid]).unwrap();
+    let names: Vec<&str> = rebuilt.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["Tori", "Kevin", "Tori"]);
+    assert!(models::eventlog_consistency::check_game(&mut conn, &game).unwrap().is_consistent);
+
+    // without a token the check is refused
+    let anonymous_req = test::TestRequest::get()
+        .uri(&format!("/api/games/{}/consistency", game.gid))
+        .to_request();
+    let anonymous_resp = test::call_service(&app, anonymous_req).await;
+    assert_eq!(anonymous_resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn consistency_check_follows_moved_games() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (game, owner_id) = fixtures::games::arrange_eventlog_consistency_works_integration_test(&mut conn);
+    let other_round = models::round::RoundBuilder::new_default(game.divisionid).set_name("Moved To").build_and_insert(&mut conn).unwrap();
+    let token = make_token(
+        owner_id,
+        vec!["tournament_manager".to_string()],
+        vec!["game:update".to_string()],
+    );
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let move_req = test::TestRequest::post()
+        .uri(&format!("/api/games/{}/move", game.gid))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "roundid": other_round.roundid, "preview": false }))
+        .to_request();
+    let move_resp = test::call_service(&app, move_req).await;
+    assert_eq!(move_resp.status(), StatusCode::OK);
+
+    // Act:
+
+    let check_req = test::TestRequest::get()
+        .uri(&format!("/api/games/{}/consistency", game.gid))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let check_resp = test::call_service(&app, check_req).await;
+
+    // Assert: the log sent under the old round still belongs to the game
+
+    assert_eq!(check_resp.status(), StatusCode::OK);
+    let report: GameConsistencyReport = test::read_body_json(check_resp).await;
+    assert_eq!(report.eventlog_events, 3);
+    assert_eq!(report.missing.len(), 1);
+    assert_eq!(report.extra.len(), 1);
+
+    let tournament_report = models::eventlog_consistency::check_tournament(&mut conn, game.tournamentid).unwrap();
+    assert_eq!(tournament_report.orphaned_eventlogs.len(), 1);
+    assert_eq!(tournament_report.orphaned_eventlogs[0].clientkey.as_str(), "unknown-client");
+
+    // QuizMachine keeps sending the game under the old round, which must not recreate it there
+    let mut sent = models::game::GameChangeset::empty();
+    sent.org = Some(game.org.clone());
+    sent.tournamentid = Some(game.tournamentid);
+    sent.divisionid = Some(game.divisionid);
+    sent.roomid = Some(game.roomid);
+    sent.roundid = Some(game.roundid);
+    sent.clientkey = Some(game.clientkey.clone());
+    let games_before = models::game::count_by_tournament(&mut conn, game.tournamentid).unwrap();
+    let written = models::game::create_update_of_eventlog_key(&mut conn, &sent, &models::eventlog::eventlog_key_of_game(&game)).unwrap();
+    assert_eq!((written.gid, written.roundid), (game.gid, other_round.roundid));
+    assert_eq!(models::game::count_by_tournament(&mut conn, game.tournamentid).unwrap(), games_before);
+}
+
+#[actix_web::test]
+async fn create_reports_schedule_conflicts() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Both prelim games share the quizmaster, who is also the standing quizmaster of both rooms.
+    let (division, teams, rounds, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let rooms = models::room::read_all_rooms_of_tournament(&mut conn, division.tid, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["game:create".to_string()],
+    );
+
+    // ── Success: a game without conflicts has none ───────────────────────────
+
+    let payload = fixtures::games::get_game_payload(division.tid, division.did, rooms[0].roomid, rounds[1].roundid, teams[0].teamid, None, teams[1].teamid, quizmaster.id);
+    let req = test::TestRequest::post()
+        .uri("/api/games")
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(&payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<GameWithConflicts> = test::read_body_json(resp).await;
+    let first = body.data.unwrap();
+    assert!(first.conflicts.is_empty());
+
+    // ── Success: double-booking Team A is created with warnings ──────────────
+
+    let payload = fixtures::games::get_game_payload(division.tid, division.did, rooms[1].roomid, rounds[1].roundid, teams[0].teamid, None, teams[2].teamid, quizmaster.id);
+    let req = test::TestRequest::post()
+        .uri("/api/games")
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(&payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<GameWithConflicts> = test::read_body_json(resp).await;
+    let second = body.data.unwrap();
+    let kinds: Vec<&str> = second.conflicts.iter().map(|c| c.kind.as_str()).collect();
+    assert!(kinds.contains(&"team"));
+    assert!(kinds.contains(&"official"));
+    let team_conflict = second.conflicts.iter().find(|c| c.kind == "team").unwrap();
+    assert_eq!(team_conflict.subject_id, teams[0].teamid);
+    assert_eq!(team_conflict.subject_name, "Team A");
+    assert!(team_conflict.gids.contains(&first.game.gid));
+    assert!(team_conflict.gids.contains(&second.game.gid));
+}
+
+#[actix_web::test]
+async fn game_outcomes_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // In the prelims Team A beat Team B 40-0 and Team C beat Team D 20-0.
+    let (division, teams, rounds, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let rooms = models::room::read_all_rooms_of_tournament(&mut conn, division.tid, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+    let semifinal = |conn: &mut _, room: usize, left: usize, right: usize| {
+        models::game::GameBuilder::new_default(rooms[room].roomid, rounds[1].roundid)
+            .set_leftteamid(teams[left].teamid)
+            .set_rightteamid(teams[right].teamid)
+            .set_quizmasterid(quizmaster.id)
+            .build_and_insert(conn)
+            .unwrap()
+    };
+    let game_3 = semifinal(&mut conn, 0, 3, 0);
+    let game_4 = semifinal(&mut conn, 1, 1, 2);
+    let settings = models::award_setting::AwardSettingsChangeset {
+        individual_scorers_cutoff: None,
+        quiz_outs_cutoff: None,
+        team_placements_cutoff: None,
+        rookies_cutoff: None,
+        include_perfect_games: None,
+        forfeit_win_score: Some(50),
+        forfeit_loss_score: None,
+        bye_counts_as_win: None,
+        bye_score: None,
+        questions_per_game: None,
+    };
+    models::award_setting::update(&mut conn, division.did, &settings).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["game:update".to_string()],
+    );
+    let record = |game_id, payload: serde_json::Value, token: Option<&str>| {
+        let mut req = test::TestRequest::put()
+            .uri(&format!("/api/games/{}/outcome", game_id))
+            .set_json(payload);
+        if let Some(token) = token {
+            req = req.insert_header(("Authorization", format!("Bearer {}", token)));
+        }
+        req.to_request()
+    };
+    let standings_uri = format!("/api/divisions/{}/standings", division.did);
+    let read_standings = || test::TestRequest::get().uri(&standings_uri).to_request();
+    let standing = |standings: &Vec<Standing>, idx: usize| standings.iter().find(|s| s.teamid == teams[idx].teamid).unwrap().clone();
+
+    // ── Fail: no token, and a forfeit without the team that forfeited ────────
+
+    let resp = test::call_service(&app, record(game_3.gid, json!({ "outcome": "forfeit", "reason": "no_show", "teamid": teams[0].teamid }), None)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, record(game_3.gid, json!({ "outcome": "forfeit", "reason": "no_show" }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: Team A forfeits to Team D, without any events ───────────────
+
+    let resp = test::call_service(&app, record(game_3.gid, json!({ "outcome": "forfeit", "reason": "no_show", "teamid": teams[0].teamid }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<GameOutcome> = test::read_body_json(resp).await;
+    assert_eq!(body.data.unwrap().recorded_by, Some(owner.id));
+
+    let resp = test::call_service(&app, read_standings()).await;
+    let standings: Vec<Standing> = test::read_body_json(resp).await;
+    assert_eq!(standings[0].teamid, teams[3].teamid);
+    assert_eq!((standing(&standings, 3).wins, standing(&standings, 3).total_score), (1, 50));
+    assert_eq!((standing(&standings, 0).games_played, standing(&standings, 0).wins), (2, 1));
+
+    // ── Success: correcting it to Team D forfeiting replaces the outcome ─────
+
+    let resp = test::call_service(&app, record(game_3.gid, json!({ "outcome": "forfeit", "reason": "withdrawn", "teamid": teams[3].teamid, "note": "Left after the prelims" }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, read_standings()).await;
+    let standings: Vec<Standing> = test::read_body_json(resp).await;
+    assert_eq!(standings[0].teamid, teams[0].teamid);
+    assert_eq!((standing(&standings, 0).wins, standing(&standings, 0).total_score), (2, 90));
+    assert_eq!((standing(&standings, 3).games_played, standing(&standings, 3).wins), (2, 0));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&format!("/api/games/{}/outcome", game_3.gid)).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let outcome: GameOutcome = test::read_body_json(resp).await;
+    assert_eq!(outcome.describe(), "Forfeit (withdrawn): Left after the prelims");
+
+    // ── Success: a no contest counts for nobody ──────────────────────────────
+
+    let resp = test::call_service(&app, record(game_4.gid, json!({ "outcome": "no_contest", "reason": "technical" }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let resp = test::call_service(&app, read_standings()).await;
+    let standings: Vec<Standing> = test::read_body_json(resp).await;
+    assert_eq!(standing(&standings, 1).games_played, 1);
+    assert_eq!(standing(&standings, 2).games_played, 1);
+    let awards = models::award::compute_division_awards(&mut conn, division.did).unwrap();
+    let skipped = awards.games_skipped.iter().find(|s| s.gid == game_4.gid).unwrap();
+    assert_eq!(skipped.reasons, vec!["No contest (technical)".to_string()]);
+
+    // ── Success: removing the outcome, then there is nothing to remove ───────
+
+    let delete = || test::TestRequest::delete()
+        .uri(&format!("/api/games/{}/outcome", game_3.gid))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .to_request();
+    let resp = test::call_service(&app, delete()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, delete()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = test::call_service(&app, read_standings()).await;
+    let standings: Vec<Standing> = test::read_body_json(resp).await;
+    assert_eq!(standing(&standings, 0).games_played, 1);
+}
+
+#[actix_web::test]
+async fn manual_scoresheet_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // In the prelims Team A beat Team B 40-0 and Team C beat Team D 20-0, both from QuizMachine.
+    let (division, teams, rounds, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    let rooms = models::room::read_all_rooms_of_tournament(&mut conn, division.tid, &pagination).unwrap();
+    let prelim = models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap()
+        .into_iter()
+        .find(|g| g.leftteamid == teams[0].teamid)
+        .unwrap();
+    let semifinal = models::game::GameBuilder::new_default(rooms[0].roomid, rounds[1].roundid)
+        .set_leftteamid(teams[3].teamid)
+        .set_rightteamid(teams[0].teamid)
+        .set_quizmasterid(quizmaster.id)
+        .build_and_insert(&mut conn)
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["game:update".to_string()],
+    );
+    let mut entries = vec![
+        json!({ "kind": "correct", "team": 0, "quizzer": "Dee" }),
+        json!({ "kind": "error", "team": 1, "quizzer": "Ann", "bonus_correct": [true] }),
+        json!({ "kind": "timeout", "team": 1 }),
+    ];
+    entries.extend((0..18).map(|_| json!({ "kind": "no_jump" })));
+    let sheet = |entries: &Vec<serde_json::Value>, preview: bool| json!({
+        "teams": [
+            { "quizzers": [{ "name": "Dee", "captain": true }, { "name": "Dan" }] },
+            { "quizzers": [{ "name": "Ann", "captain": true }, { "name": "Abe" }] },
+        ],
+        "entries": entries,
+        "preview": preview,
+    });
+    let enter = |game_id, payload: serde_json::Value, token: Option<&str>| {
+        let mut req = test::TestRequest::post()
+            .uri(&format!("/api/games/{}/scoresheet", game_id))
+            .set_json(payload);
+        if let Some(token) = token {
+            req = req.insert_header(("Authorization", format!("Bearer {}", token)));
+        }
+        req.to_request()
+    };
+    let standings_uri = format!("/api/divisions/{}/standings", division.did);
+    let standing = |standings: &Vec<Standing>, idx: usize| standings.iter().find(|s| s.teamid == teams[idx].teamid).unwrap().clone();
+
+    // ── Fail: no token, a team the game doesn't have, and a quizzer not on the sheet ──
+
+    let resp = test::call_service(&app, enter(semifinal.gid, sheet(&entries, true), None)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, enter(semifinal.gid, sheet(&vec![json!({ "kind": "correct", "team": 2, "quizzer": "Dee" })], true), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = test::call_service(&app, enter(semifinal.gid, sheet(&vec![json!({ "kind": "correct", "team": 0, "quizzer": "Ann" })], true), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: the preview scores the sheet without writing anything ───────
+
+    let resp = test::call_service(&app, enter(semifinal.gid, sheet(&entries, true), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<ScoresheetReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert!(report.preview && report.result.is_final);
+    assert!(models::gameevent::read_all_gameevents_of_games(&mut conn, &[semifinal.gid]).unwrap().is_empty());
+
+    // ── Success: applying it writes the events, and the standings count the game ──
+
+    let resp = test::call_service(&app, enter(semifinal.gid, sheet(&entries, false), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<ScoresheetReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    let written = models::gameevent::read_all_gameevents_of_games(&mut conn, &[semifinal.gid]).unwrap();
+    assert_eq!(written.len(), report.events.len());
+    assert!(written.iter().all(|e| e.md5digest == "manual"));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&standings_uri).to_request()).await;
+    let standings: Vec<Standing> = test::read_body_json(resp).await;
+    assert_eq!((standing(&standings, 3).games_played, standing(&standings, 3).wins), (2, 1));
+    assert_eq!((standing(&standings, 0).games_played, standing(&standings, 0).wins), (2, 1));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&format!("/api/games/{}/scoresheet", semifinal.gid)).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let record: ManualScoresheet = test::read_body_json(resp).await;
+    assert_eq!((record.entered_by, record.events), (Some(owner.id), written.len() as i32));
+
+    // a manually entered game is left alone when rebuilding from the eventlog
+    let rebuild = models::eventlog_consistency::rebuild_game(&mut conn, &semifinal, true).unwrap();
+    assert_eq!(rebuild.action, "skipped: entered manually");
+
+    // ── Fail, then success: QuizMachine's events are only overwritten on request ──
+
+    let resp = test::call_service(&app, enter(prelim.gid, sheet(&entries, true), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let mut replacing = sheet(&entries, true);
+    replacing["replace"] = json!(true);
+    let resp = test::call_service(&app, enter(prelim.gid, replacing, Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<ScoresheetReport> = test::read_body_json(resp).await;
+    assert!(body.data.unwrap().replaced > 0);
+}
+
+#[actix_web::test]
+async fn game_moves_and_merges_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // The semifinal between Team D and Team A was started in the right round, but QuizMachine sent
+    // the whole game to a stray game in the final.
+    let (division, teams, rounds, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    let rooms = models::room::read_all_rooms_of_tournament(&mut conn, division.tid, &pagination).unwrap();
+    let prelim = models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap()
+        .into_iter()
+        .find(|g| g.leftteamid == teams[0].teamid)
+        .unwrap();
+    let semifinal = |conn: &mut _, round: usize| {
+        models::game::GameBuilder::new_default(rooms[0].roomid, rounds[round].roundid)
+            .set_leftteamid(teams[3].teamid)
+            .set_rightteamid(teams[0].teamid)
+            .set_quizmasterid(quizmaster.id)
+            .build_and_insert(conn)
+            .unwrap()
+    };
+    let real = semifinal(&mut conn, 1);
+    let stray = semifinal(&mut conn, 2);
+    fixtures::brackets::seed_finished_game_stream(&mut conn, &stray, &teams[3], &teams[0], 1, 0);
+    let stray_events = models::gameevent::read_all_gameevents_of_games(&mut conn, &[stray.gid]).unwrap().len();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["game:update".to_string()],
+    );
+    let post = |uri: String, payload: serde_json::Value, token: Option<&str>| {
+        let mut req = test::TestRequest::post().uri(&uri).set_json(payload);
+        if let Some(token) = token {
+            req = req.insert_header(("Authorization", format!("Bearer {}", token)));
+        }
+        req.to_request()
+    };
+    let move_uri = |game_id: uuid::Uuid| format!("/api/games/{}/move", game_id);
+    let merge_uri = format!("/api/games/{}/merge", real.gid);
+
+    // ── Success: the two games are detected as duplicates ────────────────────
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&format!("/api/tournaments/{}/duplicategames", division.tid)).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let duplicates: Vec<DuplicateGames> = test::read_body_json(resp).await;
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].gids, vec![real.gid, stray.gid]);
+
+    // ── Fail: no token, a room and round taken by the real game, and teams that differ ──
+
+    let resp = test::call_service(&app, post(move_uri(stray.gid), json!({ "roundid": rounds[1].roundid }), None)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, post(move_uri(stray.gid), json!({ "roundid": rounds[1].roundid }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = test::call_service(&app, post(merge_uri.clone(), json!({ "from": prelim.gid }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: moving the real game to Room 2, previewed first ─────────────
+
+    let resp = test::call_service(&app, post(move_uri(real.gid), json!({ "roomid": rooms[1].roomid }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(models::game::read(&mut conn, real.gid).unwrap().roomid, rooms[0].roomid);
+
+    let resp = test::call_service(&app, post(move_uri(real.gid), json!({ "roomid": rooms[1].roomid, "preview": false }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<GameMove> = test::read_body_json(resp).await;
+    let moved = body.data.unwrap();
+    assert_eq!((moved.before.roomid, moved.after.roomid), (rooms[0].roomid, rooms[1].roomid));
+    assert_eq!(models::game::read(&mut conn, real.gid).unwrap().roomid, rooms[1].roomid);
+
+    // ── Fail: both games have an outcome ─────────────────────────────────────
+
+    let forfeit = models::game_outcome::GameOutcomeRequest {
+        outcome: "forfeit".to_string(),
+        reason: "no_show".to_string(),
+        teamid: Some(teams[0].teamid),
+        note: None,
+    };
+    models::game_outcome::record(&mut conn, &real, owner.id, &forfeit).unwrap();
+    models::game_outcome::record(&mut conn, &stray, owner.id, &forfeit).unwrap();
+    let resp = test::call_service(&app, post(merge_uri.clone(), json!({ "from": stray.gid }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    models::game_outcome::delete(&mut conn, real.gid).unwrap();
+
+    // ── Success: merging the stray game's events, previewed first ────────────
+
+    let resp = test::call_service(&app, post(merge_uri.clone(), json!({ "from": stray.gid }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(models::game::read(&mut conn, stray.gid).is_ok());
+
+    let resp = test::call_service(&app, post(merge_uri.clone(), json!({ "from": stray.gid, "preview": false }), Some(&owner_token))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<GameMerge> = test::read_body_json(resp).await;
+    let merged = body.data.unwrap();
+    assert_eq!((merged.events_moved as usize, merged.duplicates_dropped), (stray_events, 0));
+    assert!(merged.result.unwrap().is_final);
+    assert!(models::game::read(&mut conn, stray.gid).is_err());
+    assert_eq!(models::gameevent::read_all_gameevents_of_games(&mut conn, &[real.gid]).unwrap().len(), stray_events);
+    assert_eq!(models::game_outcome::read(&mut conn, real.gid).unwrap().teamid, Some(teams[0].teamid));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&format!("/api/divisions/{}/standings", division.did)).to_request()).await;
+    let standings: Vec<Standing> = test::read_body_json(resp).await;
+    let team_d = standings.iter().find(|s| s.teamid == teams[3].teamid).unwrap();
+    assert_eq!((team_d.games_played, team_d.wins), (2, 1));
+
+    // a merged game is left alone when rebuilding from the eventlog
+    let real = models::game::read(&mut conn, real.gid).unwrap();
+    assert_eq!(models::eventlog_consistency::rebuild_game(&mut conn, &real, true).unwrap().action, "skipped: merged");
+
+    // ── Success: both operations are in the audit history ────────────────────
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&format!("/api/games/{}/audits", real.gid)).to_request()).await;
+    let audits: Vec<GameAudit> = test::read_body_json(resp).await;
+    assert_eq!(audits.iter().map(|a| a.action.as_str()).collect::<Vec<_>>(), vec!["move", "merge"]);
+    assert!(audits.iter().all(|a| a.performed_by == Some(owner.id)));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&format!("/api/games/{}/audits", stray.gid)).to_request()).await;
+    let audits: Vec<GameAudit> = test::read_body_json(resp).await;
+    assert_eq!((audits.len(), audits[0].source_gid), (1, Some(stray.gid)));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&format!("/api/tournaments/{}/gameaudits", division.tid)).to_request()).await;
+    let audits: Vec<GameAudit> = test::read_body_json(resp).await;
+    assert_eq!(audits.len(), 2);
+}
+
+#[actix_web::test]
+async fn game_questions_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Team A's prelim read two questions correctly and then nothing but no-jumps.
+    let (_, rounds, owner, _, member, questions) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+    let packet: Vec<uuid::Uuid> = questions[0..2].iter().map(|q| q.id).collect();
+    models::round_question::replace_all_of_round(&mut conn, rounds[0].roundid, &[(RoundQuestionRole::Regular, packet), (RoundQuestionRole::Overtime, vec![questions[2].id])]).unwrap();
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    let games = models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap();
+    let game = games.iter().find(|g| models::gameevent::read_all_gameevents_of_games(&mut conn, &[g.gid]).unwrap().iter().filter(|e| e.event == "TC").count() == 2).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(owner.id, vec!["tournament_manager".to_string()], vec!["game:update".to_string()]);
+    let member_token = make_token(member.id, vec!["member".to_string()], vec![]);
+    let questions_uri = format!("/api/games/{}/questions", game.gid);
+    let link = |token: &str| {
+        test::TestRequest::post()
+            .uri(&questions_uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let set = |number: i32, payload: serde_json::Value, token: &str| {
+        test::TestRequest::put()
+            .uri(&format!("{}/{}", questions_uri, number))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(payload)
+            .to_request()
+    };
+    let linked = |questions: &[GameQuestion]| questions.iter().map(|q| (q.question, q.questionid, q.source.clone())).collect::<Vec<_>>();
+
+    // ── Fail: only those who can update the game link its questions ──────────
+
+    let resp = test::call_service(&app, link(&member_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: questions are linked to the packet, then to overtime ────────
+
+    let resp = test::call_service(&app, link(&owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Vec<GameQuestion> = test::read_body_json(resp).await;
+    assert_eq!(linked(&body), vec![
+        (1, questions[0].id, "packet".to_string()),
+        (2, questions[1].id, "packet".to_string()),
+        (3, questions[2].id, "packet".to_string()),
+    ]);
+
+    // ── Fail: the question has to be in the question bank ────────────────────
+
+    let resp = test::call_service(&app, set(2, json!({ "questionid": uuid::Uuid::new_v4() }), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: an alternate set by hand survives relinking ─────────────────
+
+    let resp = test::call_service(&app, set(2, json!({ "questionid": questions[5].id }), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, link(&owner_token)).await;
+    let body: Vec<GameQuestion> = test::read_body_json(resp).await;
+    assert_eq!(linked(&body)[1], (2, questions[5].id, "manual".to_string()));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&questions_uri).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Vec<GameQuestion> = test::read_body_json(resp).await;
+    assert_eq!(body.len(), 3);
+}
+
+#[actix_web::test]
+async fn rulings_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (_, rounds, owner, _, member, questions) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+    models::round_question::replace_all_of_round(&mut conn, rounds[0].roundid, &[(RoundQuestionRole::Regular, vec![questions[0].id, questions[1].id])]).unwrap();
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    let game = models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap().remove(0);
+
+    // the left team's challenge of question 2 was overruled
+    models::gameevent::GameEventBuilder::new_default(game.gid)
+        .set_question(Some(2))
+        .set_eventnum(Some(90))
+        .set_name(Some("Left".to_string()))
+        .set_team(Some(0))
+        .set_quizzer(Some(0))
+        .set_event_using_string("C-".to_string())
+        .build_and_insert(&mut conn)
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(owner.id, vec!["tournament_manager".to_string()], vec!["game:update".to_string()]);
+    let member_token = make_token(member.id, vec!["member".to_string()], vec![]);
+    let rulings_uri = format!("/api/games/{}/rulings", game.gid);
+    let send = |req: test::TestRequest, token: &str| req.insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    let challenge = json!({ "question": 2, "kind": "challenge", "teamid": game.leftteamid, "argument": "The answer given was in the verse." });
+
+    // ── Fail: only the content judge or those who can update the game ────────
+
+    let resp = test::call_service(&app, test::TestRequest::post().uri(&rulings_uri).set_json(&challenge).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri(&rulings_uri).set_json(&challenge), &member_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Fail: a team that isn't playing, and a ruling without a rationale ────
+
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri(&rulings_uri).set_json(json!({ "question": 2, "kind": "challenge", "teamid": uuid::Uuid::new_v4(), "argument": "Nope" })), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri(&rulings_uri).set_json(json!({ "question": 2, "kind": "challenge", "teamid": game.leftteamid, "argument": "Nope", "ruling": "overruled" })), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: a pending case refers to the question read ──────────────────
+
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri(&rulings_uri).set_json(&challenge), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<Ruling> = test::read_body_json(resp).await;
+    let ruling = body.data.unwrap();
+    assert_eq!((ruling.ruling.as_str(), ruling.questionid, ruling.event_num), ("pending", Some(questions[1].id), None));
+
+    // ── Fail: the event linked has to record the ruling ──────────────────────
+
+    let resp = test::call_service(&app, send(test::TestRequest::put().uri(&format!("{}/{}", rulings_uri, ruling.rulingid)).set_json(json!({ "ruling": "accepted", "rationale": "It was.", "event_question": 2, "event_num": 90 })), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: overruling it links the challenge's event ───────────────────
+
+    let resp = test::call_service(&app, send(test::TestRequest::put().uri(&format!("{}/{}", rulings_uri, ruling.rulingid)).set_json(json!({ "ruling": "overruled", "rationale": "The answer wasn't complete." })), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<Ruling> = test::read_body_json(resp).await;
+    let ruling = body.data.unwrap();
+    assert_eq!((ruling.event_question, ruling.event_num), (Some(2), Some(90)));
+
+    let resp = test::call_service(&app, send(test::TestRequest::put().uri(&format!("{}/{}", rulings_uri, uuid::Uuid::new_v4())).set_json(json!({ "argument": "Lost" })), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // an appeal by the same team, accepted
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri(&rulings_uri).set_json(json!({ "question": 2, "kind": "appeal", "teamid": game.leftteamid, "argument": "The quizzer was interrupted.", "ruling": "accepted", "rationale": "They were." })), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let records: Vec<RulingRecord> = test::call_and_read_body_json(&app, send(test::TestRequest::get().uri(&rulings_uri), &owner_token)).await;
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().any(|r| r.ruling.kind == "challenge" && r.question.as_ref().map(|q| q.id) == Some(questions[1].id) && r.events.iter().any(|e| e.event == "C-")));
+
+    // ── Only game admins delete a case ───────────────────────────────────────
+
+    let resp = test::call_service(&app, send(test::TestRequest::delete().uri(&format!("{}/{}", rulings_uri, ruling.rulingid)), &member_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = test::call_service(&app, send(test::TestRequest::delete().uri(&format!("{}/{}", rulings_uri, ruling.rulingid)), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, send(test::TestRequest::delete().uri(&format!("{}/{}", rulings_uri, ruling.rulingid)), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
@@ -8,6 +8,7 @@ use backend::{database::Database, models::{self, apicalllog::ApiCalllog}};
 use backend::models::gameevent::GameEvent;
 use backend::routes::configure_routes;
 use backend::services::common::EntityResponse;
+use chrono::{TimeZone, Utc};
 use crate::common::{PAGE_NUM, PAGE_SIZE, TEST_DB_URL, clean_database};
 
 #[actix_web::test]
@@ -117,3 +118,46 @@ async fn get_all_works() {
     assert_eq!(apicalllog_records.first().unwrap().method.as_str(), "GET");
     assert_eq!(apicalllog_records.first().unwrap().uri, uri);
 }
+
+#[actix_web::test]
+async fn create_records_event_times() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let first = fixtures::gameevents::arrange_create_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    // The room was offline, so the later event arrives first.
+    let at = |hour: u32, minute: u32| Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap();
+    let mut later = first.clone();
+    later.question = 2;
+    later.clientts = at(9, 40);
+    let mut earlier = first;
+    earlier.clientts = at(9, 20);
+
+    // Act:
+
+    for event in [&later, &earlier] {
+        let req = test::TestRequest::post()
+            .uri("/scoreevent")
+            .set_json(event)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+
+    // Assert:
+
+    let game = models::game::read(&mut conn, earlier.gid).unwrap();
+    assert_eq!(game.actual_start_time, Some(at(9, 20)));
+    assert_eq!(game.actual_end_time, Some(at(9, 40)));
+}
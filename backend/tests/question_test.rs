@@ -0,0 +1,515 @@
+mod common;
+mod fixtures;
+
+use actix_http::StatusCode;
+use actix_web::{App, test, web};
+use backend::{database::Database, models::{self, question::Question, question_import::{QuestionImportReport, QuestionImportRow}, question_usage::QuestionUsageReport, round_question::RoundQuestionRole}, services::common::PagedResponse};
+use backend::routes::configure_routes;
+use backend::services::common::EntityResponse;
+use serde_json::json;
+use crate::common::{PAGE_NUM, PAGE_SIZE, TEST_DB_URL, clean_database, make_token};
+
+fn writer_token(writer: &models::user::User) -> String {
+    make_token(
+        writer.id,
+        vec!["member".to_string(), "question_writer".to_string()],
+        vec!["question:read".to_string(), "question:create".to_string(), "question:update".to_string(), "question:delete".to_string()],
+    )
+}
+
+fn member_token(member: &models::user::User) -> String {
+    make_token(member.id, vec!["member".to_string()], vec!["question:read".to_string()])
+}
+
+#[actix_web::test]
+async fn create_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (writer, member, _) = fixtures::questions::arrange_question_bank_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let payload = json!({
+        "competition_lvl": 1,
+        "quiz_season": 1,
+        "type": "Q",
+        "question": "Quote John 1:1.",
+        "answer": "In the beginning was the Word, and the Word was with God, and the Word was God.",
+        "book": "John",
+        "chapter": 1,
+        "verses": "1",
+        "beginning_verse": 1,
+        "was_imported_from_ui": true,
+        "is_quizstuff_authored_question": false
+    });
+    let create = |payload: serde_json::Value, token: Option<String>| {
+        let mut req = test::TestRequest::post().uri("/api/questions").set_json(payload);
+        if let Some(token) = token {
+            req = req.insert_header(("Authorization", format!("Bearer {}", token)));
+        }
+        req.to_request()
+    };
+
+    // ── Fail: no token, and a member who can only read ───────────────────────
+
+    let resp = test::call_service(&app, create(payload.clone(), None)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, create(payload.clone(), Some(member_token(&member)))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Fail: a season outside the rotation ──────────────────────────────────
+
+    let mut bad_payload = payload.clone();
+    bad_payload["quiz_season"] = json!(9);
+    let resp = test::call_service(&app, create(bad_payload, Some(writer_token(&writer)))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: a question writer ───────────────────────────────────────────
+
+    let resp = test::call_service(&app, create(payload.clone(), Some(writer_token(&writer)))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<Question> = test::read_body_json(resp).await;
+    let question = body.data.unwrap();
+    assert_eq!((question.type_.as_str(), question.was_imported_from_ui), ("Q", Some(false)));
+    assert_eq!(models::question::count(&mut conn).unwrap(), 6);
+
+    // ── Fail: the same question for the same verses again ────────────────────
+
+    let resp = test::call_service(&app, create(payload, Some(writer_token(&writer)))).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn search_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (writer, member, questions) = fixtures::questions::arrange_question_bank_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let search = |query: &str, token: Option<String>| {
+        let mut req = test::TestRequest::get().uri(&format!("/api/questions?page=0&{}", query));
+        if let Some(token) = token {
+            req = req.insert_header(("Authorization", format!("Bearer {}", token)));
+        }
+        req.to_request()
+    };
+    let questions_of = |body: &PagedResponse<serde_json::Value>| body.items.iter().map(|q| q["question"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let resp = test::call_service(&app, search("page_size=10", None)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: a page of everything, in scripture order ────────────────────
+
+    let resp = test::call_service(&app, search("page_size=2", Some(writer_token(&writer)))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(body.count, 5);
+    assert_eq!(questions_of(&body), vec![questions[0].question.clone(), questions[1].question.clone()]);
+
+    // ── Success: filtered by season, level, type and scripture range ─────────
+
+    let resp = test::call_service(&app, search("page_size=10&quiz_season=2", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(body.count, 2);
+
+    let resp = test::call_service(&app, search("page_size=10&competition_lvl=0", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(questions_of(&body), vec![questions[2].question.clone()]);
+
+    let resp = test::call_service(&app, search("page_size=10&type=A", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(questions_of(&body), vec![questions[1].question.clone()]);
+
+    let resp = test::call_service(&app, search("page_size=10&book=john&from_chapter=1&from_verse=2&to_chapter=3&to_verse=16", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(questions_of(&body), vec![questions[1].question.clone(), questions[2].question.clone()]);
+
+    let resp = test::call_service(&app, search("page_size=10&text=heaven", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(questions_of(&body), vec![questions[4].question.clone()]);
+
+    // a wildcard in the search text only matches itself
+    let resp = test::call_service(&app, search("page_size=10&text=%25", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert!(body.items.is_empty());
+
+    let resp = test::call_service(&app, search("page_size=10&book=j%25", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert!(body.items.is_empty());
+
+    // ── Success: writers see answers, members don't ──────────────────────────
+
+    let resp = test::call_service(&app, search("page_size=10&quiz_season=2", Some(writer_token(&writer)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert!(body.items.iter().all(|q| q.get("answer").is_some()));
+
+    let resp = test::call_service(&app, search("page_size=10&quiz_season=2", Some(member_token(&member)))).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(body.count, 2);
+    assert!(body.items.iter().all(|q| q.get("answer").is_none()));
+
+    let read = |token: String| test::TestRequest::get()
+        .uri(&format!("/api/questions/{}", questions[0].id))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, read(member_token(&member))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body.get("answer").is_none());
+
+    let super_token = make_token(member.id, vec!["super_user".to_string()], vec![]);
+    let resp = test::call_service(&app, read(super_token)).await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["answer"], json!("The Word"));
+}
+
+#[actix_web::test]
+async fn update_and_delete_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (writer, member, questions) = fixtures::questions::arrange_question_bank_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/questions/{}", questions[1].id);
+    let update = |payload: serde_json::Value, token: String| test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(payload)
+        .to_request();
+    let delete = |token: String| test::TestRequest::delete()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    // ── Fail: a member, and a chapter that doesn't exist ─────────────────────
+
+    let resp = test::call_service(&app, update(json!({ "answer": "John the Baptist" }), member_token(&member))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, update(json!({ "chapter": 0 }), writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: a question writer ───────────────────────────────────────────
+
+    let resp = test::call_service(&app, update(json!({ "answer": "John the Baptist", "type": "G" }), writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let question = models::question::read(&mut conn, questions[1].id).unwrap();
+    assert_eq!((question.answer.as_str(), question.type_.as_str(), question.chapter), ("John the Baptist", "G", 1));
+
+    // ── Fail, then success: delete ───────────────────────────────────────────
+
+    let resp = test::call_service(&app, delete(member_token(&member))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, delete(writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(models::question::read(&mut conn, questions[1].id).is_err());
+
+    let resp = test::call_service(&app, delete(writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn import_and_export_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (writer, member, questions) = fixtures::questions::arrange_question_bank_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let csv = "\
+competition_lvl,quiz_season,type,question,answer,book,chapter,verses,beginning_verse,reference,is_quizstuff_authored_question
+1,1,G,Who was sent from God then?,John,John,1,6,6,John 1:6,false
+1,1,G,In the beginning was what?,The Word,John,1,1,1,,
+1,1,G,What did the Word become?,Flesh,John,1,14,14,John 1:15,
+1,1,G,Who testified concerning him?,John,john,1,15,15,John 1:15,
+";
+    let import = |query: &str, body: &str, token: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/api/questions/import?{}", query))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_payload(body.to_string())
+            .to_request()
+    };
+    let export = |query: &str, token: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/api/questions/export?{}", query))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+
+    // ── Fail: a member who can only read, and an unknown format ──────────────
+
+    let resp = test::call_service(&app, import("", csv, &member_token(&member))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, import("format=xml", csv, &writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: the preview reports each line and writes nothing ────────────
+
+    let resp = test::call_service(&app, import("", csv, &writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<QuestionImportReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert!(report.preview);
+    assert_eq!((report.to_create, report.duplicates, report.invalid, report.near_duplicates), (2, 1, 1, 1));
+    assert_eq!(report.lines.iter().map(|l| l.action.as_str()).collect::<Vec<_>>(), vec!["create", "skip", "invalid", "create"]);
+    assert_eq!(report.lines[0].near_duplicates[0].id, Some(questions[1].id));
+    assert_eq!(report.lines[1].duplicate_of, Some(questions[0].id));
+    assert_eq!(report.lines[2].errors, vec!["reference 'John 1:15' doesn't match John 1:14".to_string()]);
+    assert_eq!(models::question::count(&mut conn).unwrap(), 5);
+
+    // ── Success: applying adds the lines that passed, marked as imported ─────
+
+    let resp = test::call_service(&app, import("preview=false", csv, &writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<QuestionImportReport> = test::read_body_json(resp).await;
+    let created = body.data.unwrap().created;
+    assert_eq!(created.iter().map(|q| (q.book.as_str(), q.was_imported_from_ui)).collect::<Vec<_>>(), vec![("John", Some(true)), ("John", Some(true))]);
+    assert_eq!(models::question::count(&mut conn).unwrap(), 7);
+
+    // ── Success: JSON works the same way ─────────────────────────────────────
+
+    let rows = json!([{
+        "competition_lvl": 1, "quiz_season": 2, "type": "G", "question": "What did they see?",
+        "answer": "Tongues of fire", "book": "Acts", "chapter": 2, "verses": "3", "beginning_verse": 3
+    }]);
+    let resp = test::call_service(&app, import("format=json&preview=false", &rows.to_string(), &writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(models::question::count(&mut conn).unwrap(), 8);
+
+    // ── Fail: only those who see answers can export ──────────────────────────
+
+    let resp = test::call_service(&app, export("quiz_season=2", &member_token(&member))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: an archived season imports again as nothing but duplicates ─
+
+    let resp = test::call_service(&app, export("quiz_season=2", &writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("Content-Disposition").unwrap().to_str().unwrap(), "attachment; filename=\"questions-season-2.csv\"");
+    let archive = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(archive.lines().count(), 4);
+    assert!(archive.lines().nth(1).unwrap().contains("Acts 2:1"));
+
+    let resp = test::call_service(&app, import("", &archive, &writer_token(&writer))).await;
+    let body: EntityResponse<QuestionImportReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!((report.to_create, report.duplicates, report.invalid), (0, 3, 0));
+
+    let resp = test::call_service(&app, export("format=json&quiz_season=2", &writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let rows: Vec<QuestionImportRow> = test::read_body_json(resp).await;
+    assert_eq!(rows.len(), 3);
+}
+
+#[actix_web::test]
+async fn usage_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Both prelims read the same three questions: Team A got the first two and Team C the first one.
+    let (division, rounds, _, writer, member, questions) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+    let packet: Vec<uuid::Uuid> = questions[0..3].iter().map(|q| q.id).collect();
+    models::round_question::replace_all_of_round(&mut conn, rounds[0].roundid, &[(RoundQuestionRole::Regular, packet)]).unwrap();
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    let games = models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap();
+    assert_eq!(games.len(), 2);
+    for game in games {
+        assert_eq!(models::game_question::link_all(&mut conn, game.gid).unwrap().len(), 3);
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let usage = |query: &str, token: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/api/questions/usage?{}", query))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+
+    // ── Fail: only those who see answers see the report ──────────────────────
+
+    let resp = test::call_service(&app, usage("", &member_token(&member))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: each question and group has its rates ───────────────────────
+
+    let resp = test::call_service(&app, usage("quiz_season=1", &writer_token(&writer))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let report: QuestionUsageReport = test::read_body_json(resp).await;
+    let rate_of = |id: uuid::Uuid| report.questions.iter().find(|u| u.question.id == id).map(|u| (u.stats.uses, u.stats.jump_rate, u.stats.correct_rate));
+    assert_eq!(rate_of(questions[0].id), Some((2, Some(1.0), Some(1.0))));
+    assert_eq!(rate_of(questions[1].id), Some((2, Some(0.5), Some(0.5))));
+    assert_eq!(rate_of(questions[2].id), Some((2, Some(0.0), Some(0.0))));
+    assert_eq!(report.by_book.iter().map(|g| (g.key.as_str(), g.stats.uses, g.stats.jumps)).collect::<Vec<_>>(), vec![("John", 6, 3)]);
+    assert_eq!(report.by_chapter.iter().map(|g| (g.key.as_str(), g.stats.uses)).collect::<Vec<_>>(), vec![("John 1", 4), ("John 3", 2)]);
+    assert_eq!(report.by_type.iter().find(|g| g.key == "A").map(|g| g.stats.jump_rate), Some(Some(0.5)));
+
+    // ── Success: the filters narrow the report ───────────────────────────────
+
+    let resp = test::call_service(&app, usage("quiz_season=2", &writer_token(&writer))).await;
+    let report: QuestionUsageReport = test::read_body_json(resp).await;
+    assert!(report.questions.is_empty());
+
+    let resp = test::call_service(&app, usage(&format!("tournament_id={}", division.tid), &writer_token(&writer))).await;
+    let report: QuestionUsageReport = test::read_body_json(resp).await;
+    assert_eq!(report.questions.len(), 3);
+}
+
+#[actix_web::test]
+async fn references_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (writer, member, questions) = fixtures::questions::arrange_question_bank_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let search = |reference: &str| test::TestRequest::get()
+        .uri(&format!("/api/questions?page=0&page_size=10&reference={}", reference))
+        .insert_header(("Authorization", format!("Bearer {}", writer_token(&writer))))
+        .to_request();
+    let questions_of = |body: &PagedResponse<serde_json::Value>| body.items.iter().map(|q| q["question"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+
+    // ── Success: the questions touching a chapter, a range, or a list of ranges ──
+
+    let resp = test::call_service(&app, search("Acts%202")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(questions_of(&body), vec![questions[3].question.clone(), questions[4].question.clone()]);
+
+    let resp = test::call_service(&app, search("ac%202:3")).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(questions_of(&body), vec![questions[4].question.clone()]);
+
+    let resp = test::call_service(&app, search("Jn%201:2-6;%203:16")).await;
+    let body: PagedResponse<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(questions_of(&body), vec![questions[1].question.clone(), questions[2].question.clone()]);
+
+    // ── Fail: a reference that isn't one ─────────────────────────────────────
+
+    let resp = test::call_service(&app, search("Hezekiah%201")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = test::call_service(&app, search("John%201:9-3")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: a new question's reference is written in canonical form ─────
+
+    let create = |payload: serde_json::Value| test::TestRequest::post()
+        .uri("/api/questions")
+        .insert_header(("Authorization", format!("Bearer {}", writer_token(&writer))))
+        .set_json(payload)
+        .to_request();
+    let payload = json!({
+        "competition_lvl": 1,
+        "quiz_season": 1,
+        "type": "G",
+        "question": "What did the Word become?",
+        "answer": "Flesh",
+        "book": "Jn.",
+        "chapter": 1,
+        "verses": "14 - 15",
+        "beginning_verse": 14
+    });
+    let resp = test::call_service(&app, create(payload.clone())).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<Question> = test::read_body_json(resp).await;
+    let question = body.data.unwrap();
+    assert_eq!((question.book.as_str(), question.verses.as_str()), ("John", "14-15"));
+
+    // ── Fail: verses that don't begin with the beginning verse, and a chapter the book doesn't have ──
+
+    let mut bad_payload = payload.clone();
+    bad_payload["verses"] = json!("16-18");
+    let resp = test::call_service(&app, create(bad_payload)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let mut bad_payload = payload;
+    bad_payload["chapter"] = json!(22);
+    let resp = test::call_service(&app, create(bad_payload)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: stored questions that aren't a reference are reported ───────
+
+    {
+        use diesel::RunQueryDsl;
+        diesel::sql_query("UPDATE questionsandanswers SET chapter = 30 WHERE id = $1")
+            .bind::<diesel::sql_types::Uuid, _>(questions[1].id)
+            .execute(&mut conn)
+            .unwrap();
+    }
+
+    let check = |token: String| test::TestRequest::get()
+        .uri("/api/questions/references?quiz_season=1")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, check(member_token(&member))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Vec<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(body.len(), 1);
+    assert_eq!(body[0]["id"], json!(questions[1].id));
+    assert_eq!(body[0]["reference"], json!("John 30:6"));
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/questions/references").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,138 @@
+mod common;
+mod fixtures;
+
+use actix_http::StatusCode;
+use actix_web::{App, test, web};
+use backend::{database::Database, models::{self, competition_level::CompetitionLevel, division::DivisionBuilder, division_rookie::NewDivisionRookie, quiz_season::QuizSeason, team::TeamBuilder, tournament::TournamentBuilder, user::UserBuilder}};
+use backend::routes::configure_routes;
+use backend::services::common::EntityResponse;
+use serde_json::json;
+use crate::common::{TEST_DB_URL, clean_database, make_token};
+
+// Seasons and levels are reference data that clean_database keeps, so these tests use numbers
+// nothing else does and delete what they create.
+
+#[actix_web::test]
+async fn seasons_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+    let _ = models::quiz_season::delete(&mut conn, 91);
+
+    let member = UserBuilder::new_default("Member").set_hash_password("MemberPwd123!").build_and_insert(&mut conn).unwrap();
+    let admin = UserBuilder::new_default("Admin").set_hash_password("AdminPwd123!").build_and_insert(&mut conn).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let member_token = make_token(member.id, vec!["member".to_string()], vec![]);
+    let admin_token = make_token(admin.id, vec!["super_user".to_string()], vec![]);
+    let send = |req: test::TestRequest, token: &str| req.insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+
+    // ── Success: the rotation is seeded ──────────────────────────────────────
+
+    let seasons: Vec<QuizSeason> = test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/seasons").to_request()).await;
+    assert!(seasons.iter().any(|s| s.season == 1 && s.material == "John 1-21"));
+    assert!(!seasons.iter().any(|s| s.name == format!("Season {}", s.season) && s.material.is_empty()));
+    let levels: Vec<CompetitionLevel> = test::call_and_read_body_json(&app, test::TestRequest::get().uri("/api/competitionlevels").to_request()).await;
+    assert!(levels.iter().any(|l| l.level == 1 && l.name == "Novice"));
+
+    // ── Fail: only super users add seasons, with material that's scripture ───
+
+    let payload = json!({ "season": 91, "name": "Acts", "year": 2027, "material": "acts 1-12" });
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri("/api/seasons").set_json(&payload), &member_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri("/api/seasons").set_json(json!({ "season": 91, "name": "Acts", "material": "Acts 40" })), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: the material is written canonically ─────────────────────────
+
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri("/api/seasons").set_json(&payload), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<QuizSeason> = test::read_body_json(resp).await;
+    assert_eq!(body.data.unwrap().material, "Acts 1-12");
+
+    let resp = test::call_service(&app, send(test::TestRequest::post().uri("/api/seasons").set_json(&payload), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = test::call_service(&app, send(test::TestRequest::put().uri("/api/seasons/91").set_json(json!({ "material": "Acts" })), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<QuizSeason> = test::read_body_json(resp).await;
+    assert_eq!(body.data.unwrap().material, "Acts 1-28");
+
+    let resp = test::call_service(&app, send(test::TestRequest::put().uri("/api/seasons/92").set_json(json!({ "name": "None" })), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // ── Fail: a season a tournament quizzes can't be deleted ─────────────────
+
+    let tournament = TournamentBuilder::new_default("Season Tournament").set_owner_id(admin.id).set_quiz_season(91).build_and_insert(&mut conn).unwrap();
+    let resp = test::call_service(&app, send(test::TestRequest::delete().uri("/api/seasons/91"), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    // ── Success ──────────────────────────────────────────────────────────────
+
+    models::tournament::delete(&mut conn, tournament.tid).unwrap();
+    let resp = test::call_service(&app, send(test::TestRequest::delete().uri("/api/seasons/91"), &admin_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = test::call_service(&app, test::TestRequest::get().uri("/api/seasons/91").to_request()).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn rookies_are_checked_against_earlier_seasons() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+    for season in [93, 94] {
+        let _ = models::quiz_season::delete(&mut conn, season);
+    }
+    models::quiz_season::QuizSeasonBuilder::new(93, "Last Year").set_year(2025).build_and_insert(&mut conn).unwrap();
+    models::quiz_season::QuizSeasonBuilder::new(94, "This Year").set_year(2026).build_and_insert(&mut conn).unwrap();
+
+    let coach = UserBuilder::new_default("Coach").set_hash_password("CoachPwd123!").build_and_insert(&mut conn).unwrap();
+    let veteran = UserBuilder::new_default("Veteran").set_hash_password("VeteranPwd123!").build_and_insert(&mut conn).unwrap();
+    let rookie = UserBuilder::new_default("Rookie").set_hash_password("RookiePwd123!").build_and_insert(&mut conn).unwrap();
+
+    // last year's season is the tournament's; this year's is the division's own
+    let last_year = TournamentBuilder::new_default("Last Year").set_owner_id(coach.id).set_quiz_season(93).build_and_insert(&mut conn).unwrap();
+    let last_year_division = DivisionBuilder::new_default("Last Year Div", last_year.tid).build_and_insert(&mut conn).unwrap();
+    TeamBuilder::new_default(last_year_division.did)
+        .set_name("Veterans")
+        .set_coachid(coach.id)
+        .set_quizzer_two_id(veteran.id)
+        .build_and_insert(&mut conn)
+        .unwrap();
+    let this_year = TournamentBuilder::new_default("This Year").set_owner_id(coach.id).build_and_insert(&mut conn).unwrap();
+    let this_year_division = DivisionBuilder::new_default("This Year Div", this_year.tid).set_quiz_season(94).build_and_insert(&mut conn).unwrap();
+    TeamBuilder::new_default(this_year_division.did)
+        .set_name("Mixed")
+        .set_coachid(coach.id)
+        .set_quizzer_one_id(rookie.id)
+        .set_quizzer_two_id(veteran.id)
+        .build_and_insert(&mut conn)
+        .unwrap();
+
+    // Act & Assert:
+
+    let err = models::division_rookie::create(&mut conn, &NewDivisionRookie { did: this_year_division.did, quizzerid: veteran.id }).unwrap_err();
+    assert_eq!(err.to_string(), "The quizzer quizzed in Last Year and isn't a rookie");
+    assert!(models::division_rookie::create(&mut conn, &NewDivisionRookie { did: this_year_division.did, quizzerid: rookie.id }).is_ok());
+
+    // a division can only quiz a season that exists
+    assert!(DivisionBuilder::new_default("Bad Div", this_year.tid).set_quiz_season(95).build_and_insert(&mut conn).is_err());
+
+    clean_database();
+    for season in [93, 94] {
+        models::quiz_season::delete(&mut conn, season).unwrap();
+    }
+}
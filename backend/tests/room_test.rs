@@ -6,8 +6,10 @@ use actix_http::StatusCode;
 use actix_web::{App, test, web::{self,Bytes}};
 use backend::{database::Database, models::{self, apicalllog::ApiCalllog, equipmentregistration::EquipmentRegistration, game::Game, room::RoomBuilder}, services::{common::PagedResponse, room::RoomGame}};
 use backend::models::room::Room;
+use backend::models::timeline::{ShiftResult, TimelineReport};
 use backend::routes::configure_routes;
 use backend::services::common::EntityResponse;
+use chrono::{TimeZone, Utc};
 use serde_json::json;
 use crate::common::{PAGE_NUM, PAGE_SIZE, TEST_DB_URL, clean_database, make_token};
 
@@ -723,3 +725,61 @@ async fn get_all_equipmentregistrations_of_room_works() {
     assert_eq!(apicalllog_records.first().unwrap().method.as_str(), "GET");
     assert_eq!(apicalllog_records.first().unwrap().uri, uri);
 }
+
+#[actix_web::test]
+async fn shift_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Room 1 is 10 minutes behind: its prelim ran until 9:55 and its semifinal is due at 10:00.
+    let (division, _, owner, [game_1, _, semifinal_1, semifinal_2]) = fixtures::brackets::arrange_timeline_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/rooms/{}/shift", game_1.roomid);
+    let token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["room:update".to_string()],
+    );
+    let at = |hour: u32, minute: u32| Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap();
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({ "minutes": 10, "from": "2026-06-01T09:30:00Z" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: Room 1 pushes back its unstarted games ─────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "minutes": 10, "from": "2026-06-01T09:30:00Z" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<ShiftResult> = test::read_body_json(resp).await;
+    assert_eq!(body.data.unwrap().gids, vec![semifinal_1.gid]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/timeline?now=2026-06-01T09:50:00Z", division.tid))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let report: TimelineReport = test::read_body_json(resp).await;
+    let projected_start = |gid| report.entries.iter().find(|e| e.gid == gid).unwrap().projected_start;
+    assert_eq!(projected_start(semifinal_1.gid), Some(at(10, 10)));
+    assert_eq!(projected_start(semifinal_2.gid), Some(at(10, 0)));
+    assert!(report.rooms_behind.is_empty());
+}
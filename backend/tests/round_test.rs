@@ -5,7 +5,9 @@ mod fixtures;
 use actix_http::StatusCode;
 use actix_web::{App, test, web::{self,Bytes}};
 use backend::{database::Database, models::{self, apicalllog::ApiCalllog, game::Game, round::RoundBuilder}, services::common::PagedResponse};
+use backend::models::question_import::QuestionImportReport;
 use backend::models::round::Round;
+use backend::models::round_question::RoundQuestionRole;
 use backend::routes::configure_routes;
 use backend::services::common::EntityResponse;
 use chrono::{TimeZone, Utc};
@@ -530,3 +532,283 @@ async fn get_all_games_of_round_works() {
     assert_eq!(apicalllog_records.first().unwrap().method.as_str(), "GET");
     assert_eq!(apicalllog_records.first().unwrap().uri, uri);
 }
+
+#[actix_web::test]
+async fn packet_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (_, rounds, owner, writer, member, questions) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["round:update".to_string(), "question:read".to_string()],
+    );
+    let writer_token = make_token(
+        writer.id,
+        vec!["question_writer".to_string()],
+        vec!["round:update".to_string(), "question:read".to_string(), "question:update".to_string()],
+    );
+    let member_token = make_token(member.id, vec!["member".to_string()], vec!["question:read".to_string()]);
+    let packet_uri = |round: &Round| format!("/api/rounds/{}/packet", round.roundid);
+    let generate = |round: &Round, payload: serde_json::Value, token: &str| {
+        test::TestRequest::post()
+            .uri(&packet_uri(round))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(payload)
+            .to_request()
+    };
+    let slots = |round: &Round, conn: &mut backend::database::Connection| {
+        models::round_question::read_question_ids(conn, round.roundid, RoundQuestionRole::Regular).unwrap()
+    };
+
+    // ── Fail: a question writer who doesn't run the tournament ───────────────
+
+    let payload = json!({ "quiz_season": 1, "questions": 2, "alternates": 1, "overtime": 1, "type_distribution": { "A": 1 }, "seed": 11 });
+    let resp = test::call_service(&app, generate(&rounds[0], payload.clone(), &writer_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: the preview picks a packet without answers and writes nothing ─
+
+    let resp = test::call_service(&app, generate(&rounds[0], payload.clone(), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let packet = &body["data"];
+    assert_eq!(packet["questions"].as_array().unwrap().len(), 2);
+    assert_eq!(packet["questions"].as_array().unwrap().iter().filter(|q| q["type"] == "A").count(), 1);
+    assert_eq!((packet["alternates"].as_array().unwrap().len(), packet["overtime"].as_array().unwrap().len()), (1, 1));
+    assert!(packet["questions"][0].get("answer").is_none());
+    assert!(slots(&rounds[0], &mut conn).is_empty());
+
+    // ── Success: applied, the packet is written to the round's slots ─────────
+
+    let mut apply = payload.clone();
+    apply["preview"] = json!(false);
+    let resp = test::call_service(&app, generate(&rounds[0], apply, &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let first = slots(&rounds[0], &mut conn);
+    assert_eq!(first.len(), 2);
+    let first_packet = models::packet::read(&mut conn, rounds[0].roundid).unwrap();
+    assert_eq!((first_packet.alternates.len(), first_packet.overtime.len()), (1, 1));
+    assert_eq!(first_packet.rules.unwrap().seed, Some(11));
+
+    // ── Success: the next round only gets questions not used in the tournament ─
+
+    let resp = test::call_service(&app, generate(&rounds[1], json!({ "quiz_season": 1, "questions": 2, "alternates": 0, "preview": false }), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let second = slots(&rounds[1], &mut conn);
+    assert_eq!(second.len(), 2);
+    assert!(second.iter().all(|q| !first.contains(q) && !first_packet.alternates.iter().chain(first_packet.overtime.iter()).any(|a| a.id == *q)));
+
+    // ── Fail: season 1 has no unused questions left ──────────────────────────
+
+    let resp = test::call_service(&app, generate(&rounds[2], json!({ "quiz_season": 1, "questions": 1, "preview": false }), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: regenerating a round can reuse its own questions ────────────
+
+    let resp = test::call_service(&app, generate(&rounds[1], json!({ "quiz_season": 1, "questions": 2, "alternates": 0, "preview": false }), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // ── Fail: by hand, a question already used in another round ──────────────
+
+    let adjust = |round: &Round, payload: serde_json::Value| {
+        test::TestRequest::put()
+            .uri(&packet_uri(round))
+            .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+            .set_json(payload)
+            .to_request()
+    };
+    let resp = test::call_service(&app, adjust(&rounds[2], json!({ "questions": [first[0]] }))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["message"].as_str().unwrap().contains(&format!["question {} is already in round Prelims", first[0]]));
+
+    // ── Success: by hand, a question from season 2 and an overtime question ──
+
+    let resp = test::call_service(&app, adjust(&rounds[2], json!({ "questions": [questions[3].id], "overtime": [questions[4].id] }))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let packet = models::packet::read(&mut conn, rounds[2].roundid).unwrap();
+    assert_eq!(packet.questions.iter().map(|q| q.id).collect::<Vec<_>>(), vec![questions[3].id]);
+    assert_eq!((packet.alternates.len(), packet.overtime.len(), packet.adjusted), (0, 1, true));
+
+    let questions_uri = format!("/api/rounds/{}/questions", rounds[2].roundid);
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&questions_uri).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&questions_uri).insert_header(("Authorization", format!("Bearer {}", member_token))).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = test::call_and_read_body_json(&app, test::TestRequest::get().uri(&questions_uri).insert_header(("Authorization", format!("Bearer {}", owner_token))).to_request()).await;
+    assert_eq!(body.as_array().unwrap().iter().map(|rq| (rq["role"].as_str().unwrap(), rq["position"].as_i64().unwrap())).collect::<Vec<_>>(), vec![("regular", 1), ("overtime", 1)]);
+
+    // ── Fail: the packet before the member officiates the round, and no token ──
+
+    let read = |token: &str| {
+        test::TestRequest::get()
+            .uri(&packet_uri(&rounds[2]))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let resp = test::call_service(&app, read(&member_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&packet_uri(&rounds[2])).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: the round's officials read it, and only question writers see the answers ──
+
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    let prelim = models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap().remove(0);
+    models::game::GameBuilder::new_default(prelim.roomid, rounds[2].roundid)
+        .set_leftteamid(prelim.leftteamid)
+        .set_rightteamid(prelim.rightteamid)
+        .set_quizmasterid(member.id)
+        .set_contentjudgeid(Some(writer.id))
+        .build_and_insert(&mut conn)
+        .unwrap();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, read(&member_token)).await;
+    assert!(body["questions"][0].get("answer").is_none());
+    let body: serde_json::Value = test::call_and_read_body_json(&app, read(&writer_token)).await;
+    assert_eq!(body["questions"][0]["answer"], questions[3].answer);
+    let body: serde_json::Value = test::call_and_read_body_json(&app, read(&owner_token)).await;
+    assert!(body["questions"][0].get("answer").is_none());
+
+    // every read is on the round's download audit
+    let downloads = models::packet_export::read_all_of_rounds(&mut conn, &[rounds[2].roundid]).unwrap();
+    assert_eq!(downloads.len(), 3);
+}
+
+#[actix_web::test]
+async fn packet_export_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (_, rounds, owner, writer, _, questions) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["round:update".to_string(), "question:read".to_string()],
+    );
+    let writer_token = make_token(
+        writer.id,
+        vec!["question_writer".to_string()],
+        vec!["round:update".to_string(), "question:read".to_string()],
+    );
+    let get = |uri: String, token: &str| {
+        test::TestRequest::get()
+            .uri(&uri)
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    let export_uri = format!("/api/rounds/{}/packet/csv", rounds[0].roundid);
+    let exports_uri = format!("/api/rounds/{}/packet/exports", rounds[0].roundid);
+
+    // ── Fail: a round without a packet ───────────────────────────────────────
+
+    let resp = test::call_service(&app, get(export_uri.clone(), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let adjustment = json!({
+        "questions": [questions[0].id, questions[1].id],
+        "alternates": [questions[5].id],
+        "overtime": [questions[6].id],
+    });
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/rounds/{}/packet", rounds[0].roundid))
+        .insert_header(("Authorization", format!("Bearer {}", owner_token)))
+        .set_json(adjustment)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // ── Fail: someone who doesn't run the tournament, and no token ───────────
+
+    let resp = test::call_service(&app, get(export_uri.clone(), &writer_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, test::TestRequest::get().uri(&export_uri).to_request()).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, get(exports_uri.clone(), &writer_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Fail: a file format that isn't one ─────────────────────────────────
+
+    let resp = test::call_service(&app, get(format!("{}?format=pdf", export_uri), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: the QuizMachine file, regular then overtime then alternates ──
+
+    let resp = test::call_service(&app, get(export_uri.clone(), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "text/csv; charset=utf-8");
+    let body: Bytes = test::read_body(resp).await;
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0], "round,number,kind,type,question,answer,book,chapter,verses");
+    assert!(lines[1].starts_with(&format!("{},1,regular,G,In the beginning was what?,The Word,John,1,1", rounds[0].name)));
+    assert!(lines[3].contains(",3,overtime,") && lines[3].contains(&questions[6].question));
+    assert!(lines[4].contains(",A1,alternate,") && lines[4].contains(&questions[5].question));
+
+    // ── Success: the question file, in the layout the bank imports ──────────
+
+    let resp = test::call_service(&app, get(format!("{}?format=questions", export_uri), &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Bytes = test::read_body(resp).await;
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0], "competition_lvl,quiz_season,type,question,answer,book,chapter,verses,beginning_verse,reference,is_quizstuff_authored_question,round,kind,number");
+    assert!(lines[1].starts_with("1,1,G,In the beginning was what?,The Word,John,1,1,1,John 1:1,"));
+    assert!(lines[1].ends_with(&format!(",{},regular,1", rounds[0].name)));
+    assert!(lines[3].ends_with(",overtime,3") && lines[3].contains(&questions[6].question));
+    assert!(lines[4].ends_with(",alternate,A1") && lines[4].contains(&questions[5].question));
+
+    // ── Success: the file imports again, every question a duplicate of the bank's ──
+
+    let importer_token = make_token(writer.id, vec!["question_writer".to_string()], vec!["question:create".to_string()]);
+    let req = test::TestRequest::post()
+        .uri("/api/questions/import")
+        .insert_header(("Authorization", format!("Bearer {}", importer_token)))
+        .set_payload(csv.clone())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<QuestionImportReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!((report.to_create, report.invalid), (0, 0));
+    let imported: Vec<Option<uuid::Uuid>> = report.lines.iter().map(|l| l.duplicate_of).collect();
+    assert_eq!(imported, vec![Some(questions[0].id), Some(questions[1].id), Some(questions[6].id), Some(questions[5].id)]);
+
+    // ── Success: both downloads are on the round's audit ──────────────────────
+
+    let resp = test::call_service(&app, get(exports_uri, &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Vec<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(body.len(), 2);
+    assert_eq!(body[0]["exported_by"], json!(owner.id));
+    assert_eq!(body[0]["round_name"], json!(rounds[0].name));
+    assert_eq!(body[0]["questions"], json!([questions[0].id, questions[1].id, questions[6].id, questions[5].id]));
+    assert_eq!(body[0]["before_start"], json!(rounds[0].scheduled_start_time.map(|start| Utc::now() < start)));
+}
@@ -438,3 +438,44 @@ async fn remove_game_from_statsgroup_works() {
     assert_eq!(apicalllog_records.first().unwrap().method.as_str(), "DELETE");
     assert_eq!(apicalllog_records.first().unwrap().uri, delete_uri);
 }
+
+#[actix_web::test]
+async fn export_games_of_statsgroup_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (statsgroup, game_1, game_2) =
+        fixtures::statsgroups::arrange_get_all_games_of_statsgroup_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/statsgroups/{}/export?report=games", statsgroup.sgid);
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .to_request();
+
+    // Act:
+
+    let resp = test::call_service(&app, req).await;
+
+    // Assert:
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().get("content-disposition").unwrap().to_str().unwrap().contains(&format!("games-statsgroup-{}.csv", statsgroup.sgid)));
+
+    let body: Bytes = test::read_body(resp).await;
+    let csv = String::from_utf8(body[3..].to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("game_id,division,round,room,ignored,status,"));
+    assert!(lines.iter().any(|l| l.starts_with(&game_1.gid.to_string()) && l.contains(",not started,")));
+    assert!(lines.iter().any(|l| l.starts_with(&game_2.gid.to_string()) && l.contains(",not started,")));
+}
@@ -5,7 +5,7 @@ mod fixtures;
 use actix_web::{test, App, web::{self,Bytes}, http::StatusCode};
 use chrono::{Duration, Local, NaiveDate, TimeZone, Utc};
 use backend::{database::seed_data::system_default_data::insert_system_default_data, models::{self, apicalllog::ApiCalllog, equipmentregistration::EquipmentRegistration, game::Game, role::AppRole, room::Room, round::Round, team::TeamWithCoach, tournament_admin::{TournamentAdmin, TournamentAdminChangeset}, tournamentgroup::TournamentGroup, user::User}, routes::configure_routes, services::{common::{EntityResponse, PagedResponse}, tournament::TournamentWithRooms}};
-use backend::models::{division::Division, tournament::Tournament};
+use backend::models::{certificate_template::CertificateTemplate, conflict::ConflictReport, question::QuestionBuilder, ruling::{RulingRecord, RulingRequest, TeamRulingSummary}, user::UserBuilder, division::Division, eventlog_consistency::{RebuildReport, TournamentConsistencyReport}, officials::OfficialsAssignment, schedule::ScheduleReport, timeline::TimelineReport, tournament::Tournament};
 use backend::database::Database;
 use serde_json::json;
 use crate::common::{PAGE_NUM, PAGE_SIZE, TEST_DB_URL, clean_database};
@@ -1352,3 +1352,664 @@ async fn get_today_works() {
     assert_eq!(apicalllog_records.first().unwrap().method.as_str(), "GET");
     assert_eq!(apicalllog_records.first().unwrap().uri.as_str(), uri);
 }
+
+#[actix_web::test]
+async fn update_certificate_template_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (tournament, owner) = fixtures::tournaments::arrange_update_works_integration_test(&mut conn);
+    let token = common::make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["tournament:update".to_string()],
+    );
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let svg = "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"800\" height=\"600\"><text x=\"10\" y=\"50\">{{recipient}} - {{placement}} - {{tname}}</text></svg>";
+    let put_payload = json!({
+        "name": "Plain",
+        "svg": svg
+    });
+
+    let put_uri = format!("/api/tournaments/{}/certificatetemplate", &tournament.tid);
+    let put_req = test::TestRequest::put()
+        .uri(&put_uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(&put_payload)
+        .to_request();
+
+    // Act:
+
+    let put_resp = test::call_service(&app, put_req).await;
+
+    // Assert:
+
+    assert_eq!(put_resp.status(), StatusCode::OK);
+
+    let put_resp_body: EntityResponse<CertificateTemplate> = test::read_body_json(put_resp).await;
+    let template = put_resp_body.data.unwrap();
+    assert_eq!(template.tid, tournament.tid);
+    assert_eq!(template.name.as_str(), "Plain");
+    assert_eq!(template.svg.as_str(), svg);
+
+    let preview_req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/certificatetemplate/preview", &tournament.tid))
+        .to_request();
+    let preview_resp = test::call_service(&app, preview_req).await;
+    assert_eq!(preview_resp.status(), StatusCode::OK);
+    assert_eq!(preview_resp.headers().get("content-type").unwrap().to_str().unwrap(), "image/svg+xml");
+    assert_eq!(preview_resp.headers().get("content-security-policy").unwrap().to_str().unwrap(), "default-src 'none'");
+    let body: Bytes = test::read_body(preview_resp).await;
+    let rendered = String::from_utf8(body.to_vec()).unwrap();
+    assert!(rendered.contains("Sample Quizzer - 1st Place - Test Tour"));
+
+    let pdf_req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/certificatetemplate/preview?format=pdf", &tournament.tid))
+        .to_request();
+    let pdf_resp = test::call_service(&app, pdf_req).await;
+    assert_eq!(pdf_resp.status(), StatusCode::OK);
+    let body: Bytes = test::read_body(pdf_resp).await;
+    assert!(body.starts_with(b"%PDF-1.4"));
+    assert!(body.ends_with(b"%%EOF\n"));
+}
+
+#[actix_web::test]
+async fn update_certificate_template_rejects_scripts_and_external_references() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (tournament, owner) = fixtures::tournaments::arrange_update_works_integration_test(&mut conn);
+    let token = common::make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["tournament:update".to_string()],
+    );
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let templates = [
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"><image href=\"https://example.com/logo.png\"/></svg>",
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" onload=\"alert(document.cookie)\"/>",
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"><use href=\"javascript:alert(1)\"/></svg>",
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"><set attributeName=\"onclick\" to=\"alert(1)\"/></svg>",
+    ];
+
+    for svg in templates {
+        let put_req = test::TestRequest::put()
+            .uri(&format!("/api/tournaments/{}/certificatetemplate", &tournament.tid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "name": "Unsafe", "svg": svg }))
+            .to_request();
+
+        // Act:
+
+        let put_resp = test::call_service(&app, put_req).await;
+
+        // Assert:
+
+        assert_eq!(put_resp.status(), StatusCode::BAD_REQUEST, "{} was accepted", svg);
+    }
+    assert!(!models::certificate_template::exists(&mut conn, tournament.tid));
+}
+
+#[actix_web::test]
+async fn check_and_rebuild_tournament_gameevents_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (game, owner_id) = fixtures::games::arrange_eventlog_consistency_works_integration_test(&mut conn);
+    let token = common::make_token(
+        owner_id,
+        vec!["tournament_manager".to_string()],
+        vec!["tournament:update".to_string()],
+    );
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let check_req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/consistency", game.tournamentid))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+
+    // Act:
+
+    let check_resp = test::call_service(&app, check_req).await;
+
+    // Assert:
+
+    assert_eq!(check_resp.status(), StatusCode::OK);
+    let report: TournamentConsistencyReport = test::read_body_json(check_resp).await;
+    assert_eq!(report.games_checked, 1);
+    assert_eq!(report.games_inconsistent, 1);
+    assert_eq!(report.orphaned_eventlogs.len(), 1);
+    assert_eq!(report.orphaned_eventlogs[0].clientkey.as_str(), "unknown-client");
+    assert_eq!(report.orphaned_eventlogs[0].entries, 1);
+
+    let apply_req = test::TestRequest::post()
+        .uri(&format!("/api/tournaments/{}/rebuild?apply=true", game.tournamentid))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let apply_resp = test::call_service(&app, apply_req).await;
+    assert_eq!(apply_resp.status(), StatusCode::OK);
+    let applied: RebuildReport = test::read_body_json(apply_resp).await;
+    assert!(applied.applied);
+    assert_eq!(applied.games.len(), 1);
+    assert_eq!(applied.games[0].action.as_str(), "rebuilt");
+
+    let after = models::eventlog_consistency::check_tournament(&mut conn, game.tournamentid).unwrap();
+    assert_eq!(after.games_inconsistent, 0);
+}
+
+#[actix_web::test]
+async fn read_conflicts_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Both prelim games share the quizmaster, who is also the standing quizmaster of both rooms.
+    // Team A is double-booked in the semifinals.
+    let (division, teams, rounds, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let rooms = models::room::read_all_rooms_of_tournament(&mut conn, division.tid, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+    for (room, opponent) in [(&rooms[0], &teams[1]), (&rooms[1], &teams[2])] {
+        models::game::GameBuilder::new_default(room.roomid, rounds[1].roundid)
+            .set_leftteamid(teams[0].teamid)
+            .set_rightteamid(opponent.teamid)
+            .set_quizmasterid(quizmaster.id)
+            .build_and_insert(&mut conn)
+            .unwrap();
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/tournaments/{}/conflicts", division.tid);
+    let token = common::make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["tournament:update".to_string()],
+    );
+    let outsider_token = common::make_token(
+        quizmaster.id,
+        vec!["quizmaster".to_string()],
+        vec!["tournament:update".to_string()],
+    );
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Fail: not an admin of the tournament ─────────────────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", outsider_token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: the report lists every conflict ─────────────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let report: ConflictReport = test::read_body_json(resp).await;
+    assert_eq!(report.games_checked, 4);
+    let count_of = |kind: &str| report.conflicts.iter().filter(|c| c.kind == kind).count();
+    assert_eq!(count_of("team"), 1);
+    assert_eq!(count_of("official"), 2);
+    assert_eq!(count_of("room_official"), 2);
+    assert_eq!(count_of("room"), 0);
+
+    // ── Fail: unknown tournament ─────────────────────────────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/conflicts", uuid::Uuid::new_v4()))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn assign_officials_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Two prelim games at 9:00, both with the same quizmaster. One coach coaches every team.
+    let (division, teams, _, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let coach_id = teams[0].coachid;
+    let volunteer = models::user::UserBuilder::new_default("Volunteer")
+        .set_hash_password("VolunteerPwd123!")
+        .build_and_insert(&mut conn)
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/tournaments/{}/officials", division.tid);
+    let token = common::make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["tournament:update".to_string()],
+    );
+    let officials = json!([
+        { "userid": quizmaster.id },
+        { "userid": coach_id },
+        { "userid": volunteer.id, "contentjudge": false },
+    ]);
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .set_json(json!({ "officials": officials }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: preview with content judges reports what can't be filled ────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "officials": officials }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<OfficialsAssignment> = test::read_body_json(resp).await;
+    let plan = body.data.unwrap();
+    assert!(plan.preview);
+    assert_eq!(plan.assignments.len(), 2);
+    assert_eq!(plan.unfilled.len(), 2);
+    assert!(plan.unfilled.iter().all(|u| u.role == "contentjudge"));
+    let coach_load = plan.workload.iter().find(|w| w.userid == coach_id).unwrap();
+    assert_eq!(coach_load.quizmaster_games + coach_load.contentjudge_games, 0);
+
+    // ── Fail: a plan with unfilled slots can't be applied ────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "officials": officials, "preview": false }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // A tiebreak whose round has no start time yet, officiated by the standing content judge
+    let tiebreak = fixtures::rounds::seed_untimed_round(&mut conn, division.did, "Tiebreak");
+    let rooms = models::room::read_all_rooms_of_tournament(&mut conn, division.tid, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+    let tiebreak_game = models::game::GameBuilder::new_default(rooms[0].roomid, tiebreak.roundid)
+        .set_tournamentid(Some(division.tid))
+        .set_divisionid(Some(division.did))
+        .set_leftteamid(teams[0].teamid)
+        .set_rightteamid(teams[1].teamid)
+        .set_quizmasterid(volunteer.id)
+        .set_contentjudgeid(Some(quizmaster.id))
+        .build_and_insert(&mut conn)
+        .unwrap();
+
+    // ── Success: quizmasters only, applied ───────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "officials": officials, "contentjudges": false, "preview": false }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: EntityResponse<OfficialsAssignment> = test::read_body_json(resp).await;
+    let plan = body.data.unwrap();
+    assert!(!plan.preview);
+    assert!(plan.unfilled.is_empty());
+    assert_eq!(plan.assignments.len(), 3);
+    let tiebreak_assignment = plan.assignments.iter().find(|a| a.gid == tiebreak_game.gid).unwrap();
+    assert_eq!(tiebreak_assignment.quizmasterid, Some(volunteer.id));
+    assert_eq!(tiebreak_assignment.contentjudgeid, Some(quizmaster.id));
+    assert_eq!(plan.updated, 1);
+
+    let games = models::game::read_all_games_of_division(&mut conn, division.did, &models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE }).unwrap();
+    let games: Vec<&Game> = games.iter().filter(|g| g.gid != tiebreak_game.gid).collect();
+    let mut quizmasters: Vec<uuid::Uuid> = games.iter().map(|g| g.quizmasterid).collect();
+    quizmasters.sort();
+    let mut expected = vec![quizmaster.id, volunteer.id];
+    expected.sort();
+    assert_eq!(quizmasters, expected);
+
+    // ── Fail: unknown official ───────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(json!({ "officials": [{ "userid": uuid::Uuid::new_v4() }] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn import_and_export_schedule_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Two prelim games are already scheduled; both rooms have a standing quizmaster. Semifinals
+    // start at 10:00; the tiebreak has no start time yet.
+    let (division, _, rounds, owner, quizmaster) = fixtures::brackets::arrange_bracket_works_integration_test(&mut conn);
+    let tiebreak = fixtures::rounds::seed_untimed_round(&mut conn, division.did, "Tiebreak");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let uri = format!("/api/tournaments/{}/schedules", division.tid);
+    let token = common::make_token(
+        owner.id,
+        vec!["tournament_manager".to_string()],
+        vec!["tournament:update".to_string()],
+    );
+    let header = "roundtime,tournament,division,room,round,team1,team2,team3,quizmaster,contentjudge,scorekeeper,stats";
+
+    // ── Fail: no token ───────────────────────────────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .set_payload(header)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: an import with an unknown team reports it ───────────────────
+
+    let csv = format!["{}\n\
+        2026-06-01 10:00,Bracket Tournament,Test Div 3276,Room 1,Semifinals,Team A,Team C,,,,Sam,\n\
+        2026-06-01 10:00,Bracket Tournament,Test Div 3276,room 2,semifinals,Team B,Team Z,,,,,\n", header];
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_payload(csv)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<ScheduleReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!(report.rows.len(), 2);
+    assert_eq!(report.unresolved, vec!["team 'Team Z'".to_string()]);
+    assert_eq!(report.rows[0].resolved.quizmasterid, Some(quizmaster.id));
+
+    // ── Fail: rows with unresolved names can't be confirmed ──────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/confirm", uri))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Success: a corrected import replaces the unconfirmed rows ────────────
+
+    let csv = format!["{}\n\
+        2026-06-01 10:00,Bracket Tournament,Test Div 3276,Room 1,Semifinals,Team A,Team C,,,,Sam,\n\
+        2026-06-01 10:20,Bracket Tournament,Test Div 3276,room 2,semifinals,Team B,Team D,,quizmaster den,,,\n\
+        2026-06-01 12:15,Bracket Tournament,Test Div 3276,Room 1,Tiebreak,Team C,Team B,,,,,\n\
+        2026-06-01 12:00,Bracket Tournament,Test Div 3276,Room 2,Tiebreak,Team A,Team D,,,,,\n", header];
+    let req = test::TestRequest::post()
+        .uri(&uri)
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_payload(csv)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<ScheduleReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!(report.rows.len(), 4);
+    assert!(report.unresolved.is_empty());
+
+    // ── Success: confirmation creates the games ──────────────────────────────
+
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/confirm", uri))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: EntityResponse<ScheduleReport> = test::read_body_json(resp).await;
+    let report = body.data.unwrap();
+    assert_eq!(report.created.len(), 4);
+    assert!(report.rows.iter().all(|r| r.schedule.gid.is_some()));
+    // the roundtimes become the untimed round's start and the games' offsets
+    let offsets: Vec<(uuid::Uuid, i32)> = report.created.iter().map(|g| (g.roundid, g.start_offset_minutes)).collect();
+    assert!(offsets.contains(&(rounds[1].roundid, 0)));
+    assert!(offsets.contains(&(rounds[1].roundid, 20)));
+    assert!(offsets.contains(&(tiebreak.roundid, 15)));
+    assert!(offsets.contains(&(tiebreak.roundid, 0)));
+    let tiebreak = models::round::read(&mut conn, tiebreak.roundid).unwrap();
+    assert_eq!(tiebreak.scheduled_start_time, Some(Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap()));
+
+    let req = test::TestRequest::post()
+        .uri(&format!("{}/confirm", uri))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // ── Fail: the export needs a token ───────────────────────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{}/export", uri))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: the export has every game in the same layout ────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{}/export", uri))
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes: Bytes = test::read_body(resp).await;
+    let csv = String::from_utf8(bytes.to_vec()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], header);
+    assert!(lines.contains(&"2026-06-01T10:00:00Z,Bracket Tournament,Test Div 3276,Room 1,Semifinals,Team A,Team C,,Quizmaster Den,,Sam,"));
+    assert!(lines.contains(&"2026-06-01T10:20:00Z,Bracket Tournament,Test Div 3276,Room 2,Semifinals,Team B,Team D,,Quizmaster Den,,,"));
+    assert!(lines.contains(&"2026-06-01T12:15:00Z,Bracket Tournament,Test Div 3276,Room 1,Tiebreak,Team C,Team B,,Quizmaster Den,,,"));
+
+    let parsed = models::schedule::parse_csv(division.tid, &bytes).unwrap();
+    assert_eq!(parsed.len(), 6);
+}
+
+#[actix_web::test]
+async fn read_timeline_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, _, _, [game_1, _, semifinal_1, _]) = fixtures::brackets::arrange_timeline_works_integration_test(&mut conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let at = |hour: u32, minute: u32| Utc.with_ymd_and_hms(2026, 6, 1, hour, minute, 0).unwrap();
+    let entry = |report: &TimelineReport, gid| report.entries.iter().find(|e| e.gid == gid).unwrap().clone();
+
+    // ── Success: projected vs actual, and Room 1 is behind ──────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/timeline?now=2026-06-01T09:50:00Z", division.tid))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let report: TimelineReport = test::read_body_json(resp).await;
+    assert_eq!(report.entries.len(), 4);
+    let started = entry(&report, game_1.gid);
+    assert_eq!(started.status, "started");
+    assert_eq!(started.actual_start, Some(at(9, 40)));
+    assert_eq!(started.actual_end, Some(at(9, 55)));
+    assert_eq!(started.start_delay_minutes, Some(40));
+    assert_eq!(entry(&report, semifinal_1.gid).status, "pending");
+    assert_eq!(report.rooms_behind.len(), 1);
+    assert_eq!(report.rooms_behind[0].room_name, "Room 1");
+    assert_eq!(report.rooms_behind[0].gid, semifinal_1.gid);
+    assert_eq!(report.rooms_behind[0].minutes_behind, 10);
+
+    // ── Fail: unknown tournament ─────────────────────────────────────────────
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/tournaments/{}/timeline", uuid::Uuid::new_v4()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn read_rulings_works() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    let (division, rounds, owner, _, member, _) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    let game = models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap().remove(0);
+    assert!(game.centerteamid.is_none());
+
+    // both teams' challenges were overruled; with two teams the right team is in seat 1
+    for (question, team) in [(2, 0), (3, 1)] {
+        models::gameevent::GameEventBuilder::new_default(game.gid)
+            .set_question(Some(question))
+            .set_eventnum(Some(90))
+            .set_name(Some("Challenger".to_string()))
+            .set_team(Some(team))
+            .set_quizzer(Some(0))
+            .set_event_using_string("C-".to_string())
+            .build_and_insert(&mut conn)
+            .unwrap();
+    }
+    let case = |question: i32, kind: &str, teamid: uuid::Uuid, ruling: &str| RulingRequest {
+        question,
+        questionid: None,
+        kind: kind.to_string(),
+        teamid,
+        argument: "The answer was complete.".to_string(),
+        ruling: Some(ruling.to_string()),
+        rationale: Some("It wasn't.".to_string()),
+        event_question: None,
+        event_num: None,
+    };
+    models::ruling::create(&mut conn, &game, &case(2, "challenge", game.leftteamid, "overruled"), owner.id).unwrap();
+    models::ruling::create(&mut conn, &game, &RulingRequest { rationale: Some("It was.".to_string()), ..case(2, "appeal", game.leftteamid, "accepted") }, owner.id).unwrap();
+
+    // the right team's case was judged by someone since deleted, on a question since deleted
+    let judge = UserBuilder::new_default("Judge").set_hash_password("JudgePwd123!").build_and_insert(&mut conn).unwrap();
+    let read = QuestionBuilder::new_default("Where was the Word?", "With God").build_and_insert(&mut conn).unwrap();
+    let right = models::ruling::create(&mut conn, &game, &RulingRequest { questionid: Some(read.id), ..case(3, "challenge", game.rightteamid, "overruled") }, judge.id).unwrap();
+    assert_eq!((right.event_question, right.event_num), (Some(3), Some(90)));
+    models::user::delete(&mut conn, judge.id).unwrap();
+    models::question::delete(&mut conn, read.id).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let owner_token = common::make_token(owner.id, vec!["tournament_manager".to_string()], vec!["tournament:read".to_string()]);
+    let member_token = common::make_token(member.id, vec!["member".to_string()], vec!["tournament:read".to_string()]);
+    let rulings_uri = format!("/api/tournaments/{}/rulings", division.tid);
+    let send = |uri: &str, token: &str| test::TestRequest::get().uri(uri).insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+
+    // ── Fail: only tournament admins review the cases ────────────────────────
+
+    let resp = test::call_service(&app, send(&rulings_uri, &member_token)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: a tournament admin reviews them ─────────────────────────────
+
+    models::tournament_admin::create(&mut conn, &models::tournament_admin::NewTournamentAdmin {
+        tournamentid: division.tid,
+        adminid: member.id,
+        role_description: "content judge".to_string(),
+        access_lvl: 1,
+    }).unwrap();
+    let resp = test::call_service(&app, send(&rulings_uri, &member_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // ── Success ──────────────────────────────────────────────────────────────
+
+    let resp = test::call_service(&app, send(&rulings_uri, &owner_token)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let records: Vec<RulingRecord> = test::read_body_json(resp).await;
+    assert_eq!(records.len(), 3);
+    let right = records.iter().find(|r| r.ruling.teamid == game.rightteamid).unwrap();
+    assert_eq!((right.ruling.judged_by, right.judged_by_name.as_str(), right.ruling.questionid), (None, "", None));
+    assert!(right.events.iter().any(|e| e.event == "C-" && e.team == 1));
+
+    // ── Success: the summary is public ───────────────────────────────────────
+
+    let summary: Vec<TeamRulingSummary> = test::call_and_read_body_json(&app, test::TestRequest::get().uri(&format!("{}/summary", rulings_uri)).to_request()).await;
+    let totals = |teamid| summary.iter().find(|s| s.teamid == teamid).map(|s| (s.challenges, s.challenges_overturned, s.appeals, s.appeals_overturned)).unwrap();
+    assert_eq!(totals(game.leftteamid), (1, 0, 1, 1));
+    assert_eq!(totals(game.rightteamid), (1, 0, 0, 0));
+}
@@ -4,11 +4,11 @@ mod fixtures;
 
 use actix_http::StatusCode;
 use actix_web::{App, test, web::{self,Bytes}};
-use backend::{database::Database, models::{self, apicalllog::ApiCalllog, equipmentset::EquipmentSet, game::Game, roster::Roster, team::Team, tournament::Tournament, user::User}, services::common::PagedResponse};
+use backend::{database::Database, models::{self, apicalllog::ApiCalllog, equipmentset::EquipmentSet, game::Game, quizzer_strength::QuizzerStrengths, roster::Roster, round_question::RoundQuestionRole, team::Team, tournament::Tournament, user::User}, services::common::PagedResponse};
 use backend::routes::configure_routes;
 use backend::services::common::EntityResponse;
 use serde_json::json;
-use crate::common::{PAGE_NUM, PAGE_SIZE, TEST_DB_URL, clean_database};
+use crate::common::{PAGE_NUM, PAGE_SIZE, TEST_DB_URL, clean_database, make_token};
 
 #[actix_web::test]
 async fn create_works() {
@@ -826,3 +826,110 @@ async fn get_all_equipmentsets_of_owner_works() {
     assert_eq!(apicalllog_records.first().unwrap().method.as_str(), "GET");
     assert_eq!(apicalllog_records.first().unwrap().uri, uri);
 }
+
+#[actix_web::test]
+async fn strengths_work() {
+
+    // Arrange:
+
+    clean_database();
+    let db = Database::new(TEST_DB_URL);
+    let mut conn = db.get_connection().expect("Failed to get connection.");
+
+    // Team A's quizzer answered the first two prelim questions, from John 1: one general, one type A.
+    let (division, rounds, owner, _, member, questions) = fixtures::questions::arrange_packet_works_integration_test(&mut conn);
+    let packet: Vec<uuid::Uuid> = questions[0..3].iter().map(|q| q.id).collect();
+    models::round_question::replace_all_of_round(&mut conn, rounds[0].roundid, &[(RoundQuestionRole::Regular, packet)]).unwrap();
+    let pagination = models::common::PaginationParams { page: PAGE_NUM, page_size: PAGE_SIZE };
+    for game in models::game::read_all_games_of_round(&mut conn, rounds[0].roundid, &pagination).unwrap() {
+        models::game_question::link_all(&mut conn, game.gid).unwrap();
+    }
+    let team_a = models::team::read_all_teams_of_division(&mut conn, division.did, &pagination).unwrap()
+        .into_iter()
+        .find(|t| t.name == "Team A")
+        .unwrap();
+    let quizzer = models::user::UserBuilder::new_default("Team A Quizzer")
+        .set_email("quizzer@fakeemail.com")
+        .build_and_insert(&mut conn)
+        .unwrap();
+    models::team::update(&mut conn, team_a.teamid, &models::team::TeamChangeset {
+        coachid: None,
+        name: None,
+        quizzer_one_id: Some(Some(quizzer.id)),
+        quizzer_two_id: None,
+        quizzer_three_id: None,
+        quizzer_four_id: None,
+        quizzer_five_id: None,
+        quizzer_six_id: None,
+    }).unwrap();
+    let roster_coach = models::user::UserBuilder::new_default("Roster Coach")
+        .set_email("rostercoach@fakeemail.com")
+        .build_and_insert(&mut conn)
+        .unwrap();
+    let roster = models::roster::RosterBuilder::new_default("Home Church", roster_coach.id).build_and_insert(&mut conn).unwrap();
+    models::roster_coach::RosterCoachBuilder::new_default(roster_coach.id, roster.rosterid).build_and_insert(&mut conn).unwrap();
+    models::roster_quizzer::RosterQuizzerBuilder::new_default(quizzer.id, roster.rosterid).build_and_insert(&mut conn).unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .configure(configure_routes)
+    ).await;
+
+    let token = |user_id: uuid::Uuid| make_token(user_id, vec!["member".to_string()], vec!["user:read".to_string()]);
+    let strengths = |query: &str, token: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/api/users/{}/strengths?{}", quizzer.id, query))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+
+    // ── Fail: someone who isn't the quizzer, a coach or an admin ─────────────
+
+    let resp = test::call_service(&app, strengths("", &token(member.id))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = test::call_service(&app, strengths("", &token(owner.id))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // the owner of a tournament the quizzer didn't play in
+    let other = models::tournament::TournamentBuilder::new_default("Other Tournament")
+        .set_owner_id(member.id)
+        .build_and_insert(&mut conn)
+        .unwrap();
+    let resp = test::call_service(&app, strengths(&format!("tournament_id={}", other.tid), &token(member.id))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // without user:read
+    let resp = test::call_service(&app, strengths("", &make_token(quizzer.id, vec!["member".to_string()], vec![]))).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ── Success: the quizzer sees their accuracy by type, book and chapter ───
+
+    let resp = test::call_service(&app, strengths("", &token(quizzer.id))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: QuizzerStrengths = test::read_body_json(resp).await;
+    assert_eq!((body.games, body.overall.tossups_correct, body.overall.accuracy), (1, 2, Some(1.0)));
+    assert_eq!(body.by_type.iter().map(|g| (g.key.as_str(), g.accuracy.tossups_correct)).collect::<Vec<_>>(), vec![("A", 1), ("G", 1)]);
+    assert_eq!(body.by_book.iter().map(|g| g.key.as_str()).collect::<Vec<_>>(), vec!["John"]);
+    assert_eq!(body.by_chapter.iter().map(|g| (g.key.as_str(), g.accuracy.tossups_correct)).collect::<Vec<_>>(), vec![("John 1", 2)]);
+
+    // ── Success: the team's coach, a roster coach and the tournament's owner ─
+
+    let resp = test::call_service(&app, strengths("", &token(team_a.coachid))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, strengths("", &token(roster_coach.id))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = test::call_service(&app, strengths(&format!("tournament_id={}", division.tid), &token(owner.id))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: QuizzerStrengths = test::read_body_json(resp).await;
+    assert_eq!(body.overall.tossups_correct, 2);
+
+    // ── Success: another season has nothing yet ──────────────────────────────
+
+    let resp = test::call_service(&app, strengths("quiz_season=2", &token(quizzer.id))).await;
+    let body: QuizzerStrengths = test::read_body_json(resp).await;
+    assert_eq!((body.games, body.overall.accuracy), (0, None));
+}